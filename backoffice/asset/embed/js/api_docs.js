@@ -0,0 +1,175 @@
+const METHODS = ["get", "post", "put", "patch", "delete"];
+
+function element(name, text, className) {
+    let el = document.createElement(name);
+    if (text !== undefined && text !== null) {
+        el.textContent = text;
+    }
+    if (className !== undefined) {
+        el.className = className;
+    }
+    return el;
+}
+
+function resolve(spec, schema) {
+    while (schema && schema.$ref) {
+        let name = schema.$ref.split("/").pop();
+        schema = spec.components.schemas[name];
+    }
+    return schema || {};
+}
+
+function sketch(spec, schema, depth) {
+    schema = resolve(spec, schema);
+    if (depth > 6) {
+        return "...";
+    }
+    let variants = schema.oneOf || schema.anyOf;
+    if (variants) {
+        let parts = variants.map(function (variant) {
+            return sketch(spec, variant, depth + 1);
+        });
+        if (parts.length === 1) {
+            return parts[0];
+        }
+        if (parts.length === 2 && parts.includes("null")) {
+            let other = parts.find(function (part) {
+                return part !== "null";
+            });
+            return typeof other === "string" ? other + " | null" : other;
+        }
+        return parts;
+    }
+    if (schema.enum) {
+        return schema.enum.join(" | ");
+    }
+    let types = Array.isArray(schema.type) ? schema.type : [schema.type];
+    let type = types.find(function (value) {
+        return value !== "null";
+    });
+    if (type === "array") {
+        return [sketch(spec, schema.items, depth + 1)];
+    }
+    if (type === "object" || schema.properties) {
+        let required = schema.required || [];
+        let output = {};
+        for (let [key, value] of Object.entries(schema.properties || {})) {
+            output[required.includes(key) ? key : key + "?"] = sketch(spec, value, depth + 1);
+        }
+        return output;
+    }
+    let output = type || "any";
+    if (schema.format) {
+        output += " (" + schema.format + ")";
+    }
+    if (types.includes("null")) {
+        output += " | null";
+    }
+    return output;
+}
+
+function schemaBlock(spec, content) {
+    if (!content || !content["application/json"]) {
+        return element("span", "-");
+    }
+    let value = sketch(spec, content["application/json"].schema, 0);
+    return element("pre", JSON.stringify(value, null, 2), "pre");
+}
+
+function table(headings, rows) {
+    let tableElement = element("table", null, "table-full mt-3");
+    let head = element("tr");
+    for (let heading of headings) {
+        head.appendChild(element("th", heading));
+    }
+    tableElement.appendChild(element("thead")).appendChild(head);
+    let body = tableElement.appendChild(element("tbody"));
+    for (let row of rows) {
+        let tr = body.appendChild(element("tr"));
+        for (let cell of row) {
+            let td = tr.appendChild(element("td"));
+            if (cell instanceof Node) {
+                td.appendChild(cell);
+            } else {
+                td.textContent = cell;
+            }
+        }
+    }
+    return tableElement;
+}
+
+function renderOperation(spec, path, method, operation) {
+    let section = element("div", null, "mt-3");
+    let heading = section.appendChild(element("p"));
+    heading.appendChild(element("strong", method, "uppercase"));
+    heading.appendChild(document.createTextNode(" "));
+    heading.appendChild(element("code", path));
+    if (operation.description) {
+        section.appendChild(element("p", operation.description));
+    }
+    if (Array.isArray(operation.security) && operation.security.every(function (item) {
+        return Object.keys(item).length === 0;
+    })) {
+        section.appendChild(element("p", "No authentication required."));
+    }
+    if (operation.parameters && operation.parameters.length > 0) {
+        section.appendChild(table(["Parameter", "In", "Type", "Required"], operation.parameters.map(function (parameter) {
+            return [
+                parameter.name,
+                parameter.in,
+                JSON.stringify(sketch(spec, parameter.schema, 0)),
+                parameter.required ? "yes" : "no"
+            ];
+        })));
+    }
+    if (operation.requestBody) {
+        section.appendChild(element("p", "Request body", "mt-3"));
+        section.appendChild(schemaBlock(spec, operation.requestBody.content));
+    }
+    section.appendChild(table(["Status", "Description", "Body"], Object.entries(operation.responses || {}).map(function ([status, response]) {
+        response = resolve(spec, response);
+        return [status, response.description || "", schemaBlock(spec, response.content)];
+    })));
+    return section;
+}
+
+export async function renderApiDocs(target) {
+    if (target === null) {
+        return;
+    }
+    let response = await fetch(target.dataset.specUrl, {headers: {"Accept": "application/json"}});
+    let spec = await response.json();
+    let tags = (spec.tags || []).map(function (tag) {
+        return tag.name;
+    });
+    let grouped = {};
+    for (let [path, item] of Object.entries(spec.paths || {})) {
+        for (let method of METHODS) {
+            let operation = item[method];
+            if (operation === undefined) {
+                continue;
+            }
+            let tag = (operation.tags || ["other"])[0];
+            if (!tags.includes(tag)) {
+                tags.push(tag);
+            }
+            (grouped[tag] = grouped[tag] || []).push(renderOperation(spec, path, method, operation));
+        }
+    }
+    target.replaceChildren();
+    for (let tag of tags) {
+        if (grouped[tag] === undefined) {
+            continue;
+        }
+        let tagInfo = (spec.tags || []).find(function (value) {
+            return value.name === tag;
+        });
+        target.appendChild(element("h2", tag, "mt-3 capitalize"));
+        if (tagInfo && tagInfo.description) {
+            target.appendChild(element("p", tagInfo.description));
+        }
+        for (let operation of grouped[tag]) {
+            target.appendChild(operation);
+        }
+    }
+}
@@ -0,0 +1,2 @@
+api-docs-title = API Documentation
+api-docs-download = Download the OpenAPI document
\ No newline at end of file
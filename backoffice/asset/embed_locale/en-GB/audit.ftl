@@ -0,0 +1,44 @@
+audit-route-title = Audit Log
+
+audit-route-filter-user = User
+audit-route-filter-action = Action
+audit-route-filter-from = From
+audit-route-filter-to = To
+audit-route-filter-any = Any
+audit-route-filter-submit = Filter
+
+audit-route-head-created-at = When
+audit-route-head-actor = User
+audit-route-head-action = Action
+audit-route-head-target = Target
+audit-route-head-diff = Changes
+audit-route-head-client-ip = IP
+
+audit-route-command-line = Command line
+audit-route-unknown = Unknown
+audit-route-empty = No entries found
+
+audit-route-page-previous = Previous
+audit-route-page-next = Next
+audit-route-page-summary = Page { $page } of { $pages } ({ $total } entries)
+
+audit-action-link-add = Link added
+audit-action-link-edit = Link edited
+audit-action-link-delete = Link deleted
+audit-action-link-restore = Link restored
+audit-action-link-purge = Link purged from the trash
+audit-action-link-rollback = Link rolled back
+audit-action-link-alias-add = Link alias added
+audit-action-link-alias-remove = Link alias removed
+audit-action-tag-remove = Tag removed
+audit-action-user-add = User added
+audit-action-user-edit = User edited
+audit-action-user-password = Password changed
+audit-action-user-sign-out = Signed out everywhere
+audit-action-user-reset-two-factor = Two-factor login reset
+audit-action-two-factor-enable = Two-factor login enabled
+audit-action-two-factor-disable = Two-factor login disabled
+audit-action-two-factor-recovery-codes = Recovery codes regenerated
+audit-action-session-revoke = Session signed out
+audit-action-api-token-add = API token created
+audit-action-api-token-revoke = API token revoked
\ No newline at end of file
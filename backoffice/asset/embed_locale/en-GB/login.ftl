@@ -7,6 +7,17 @@ login-confirm-button = Login
 # Login Post Locale
 login-post-flash-success = Login success
 login-post-flash-failed = Login failed
+login-post-flash-throttled = Too many failed attempts, try again in { $seconds } seconds
+
+# Two-Factor Login Locale
+login-two-factor-title = Two-Factor Login
+login-two-factor-code = Code from your app, or a recovery code
+login-two-factor-confirm-button = Verify
+
+# Two-Factor Login Post Locale
+login-two-factor-post-flash-failed = Invalid code
+login-two-factor-post-flash-expired = The login has expired, please sign in again
+login-two-factor-api-required = A two-factor code is required
 
 # Logout Locale
 login-logout-post-success = Logout success
\ No newline at end of file
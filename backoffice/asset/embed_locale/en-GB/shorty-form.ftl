@@ -3,7 +3,26 @@ shorty-form-title-edit = Edit URL
 
 shorty-form-url-path = Path:
 shorty-form-url-path-placeholder = Path
+shorty-form-url-path-generate-placeholder = Leave blank to generate
 shorty-form-url-redirect = Redirect To:
 shorty-form-url-redirect-placeholder = Redirect To
+shorty-form-redirect-type = Redirect Type:
+shorty-form-redirect-type-moved-permanently = 301 Moved Permanently (cacheable)
+shorty-form-redirect-type-found = 302 Found (not cached)
+shorty-form-redirect-type-see-other = 303 See Other (not cached)
+shorty-form-redirect-type-temporary-redirect = 307 Temporary Redirect (not cached)
+shorty-form-redirect-type-permanent-redirect = 308 Permanent Redirect (cacheable)
+shorty-form-expires-at = Expires At (UTC):
+shorty-form-max-clicks = Max Clicks:
+shorty-form-max-clicks-placeholder = Unlimited
+shorty-form-description = Description:
+shorty-form-description-placeholder = What the link is for, used by search
+shorty-form-tags = Tags:
+shorty-form-tags-placeholder = Comma separated, e.g. q3-conference, newsletter
 
-shorty-form-submit-button = Save
\ No newline at end of file
+shorty-form-submit-button = Save
+
+shorty-form-alias-title-add = Add Alias
+shorty-form-alias-url-path = Path:
+shorty-form-alias-url-path-placeholder = Path
+shorty-form-alias-submit = Add
\ No newline at end of file
@@ -3,16 +3,115 @@ shorty-route-title = Shorty
 shorty-route-head-id = ID
 shorty-route-head-path = Path
 shorty-route-head-redirect-url = Redirect URL
+shorty-route-head-redirect-type = Type
 shorty-route-head-created-at = Created At
 shorty-route-head-created-by = Created By
+shorty-route-head-clicks = Clicks
+shorty-route-head-recent-clicks = Clicks (7 Days)
 shorty-route-head-action = Action
 
 shorty-route-action-edit = Edit Url
 shorty-route-action-delete = Delete Url
 shorty-route-action-add = Add Url
+shorty-route-action-stats = View Stats
+shorty-route-action-aliases = Aliases
+shorty-route-action-tags = Tags
+shorty-route-action-trash = Trash
+
+shorty-route-search-placeholder = Search path or redirect URL
+shorty-route-page-previous = Previous
+shorty-route-page-next = Next
+shorty-route-page-summary = Page { $page } of { $pages } ({ $total } URLs)
+shorty-route-empty = No URLs found
+shorty-route-tag-filter = Tagged '{ $tag }'
+shorty-route-tag-filter-clear = Show all
 
 shorty-route-flash-success-edit-url = Successfully edited URL
-shorty-route-flash-success-add-url = Successfully added URL
-shorty-route-flash-success-deleted-url = Successfully deleted URL
+shorty-route-flash-success-add-url = Successfully added URL: { $url_path }
+shorty-route-flash-success-deleted-url = Successfully moved URL to the trash
+shorty-route-flash-success-restored-url = Successfully restored URL
+shorty-route-flash-success-rollback-url = Successfully rolled back URL
+shorty-route-flash-error-rollback-url-path-taken = Could not roll back, another link already uses that path
+shorty-route-flash-error-rollback-url-redirect-taken = Could not roll back, another link already redirects there
+shorty-route-flash-success-add-alias = Successfully added alias: { $url_path }
+shorty-route-flash-success-removed-alias = Successfully removed alias
+shorty-route-flash-success-trashed-tag =
+    Moved { $count ->
+        [one] 1 link
+        *[other] { $count } links
+    } tagged '{ $tag }' to the trash
+shorty-route-flash-success-removed-tag =
+    Removed the tag '{ $tag }' from { $count ->
+        [one] 1 link
+        *[other] { $count } links
+    }
+
+shorty-route-confirm-message = Are you sure you want to delete '{ $id }'?
+
+shorty-trash-title = Trash
+shorty-trash-head-id = ID
+shorty-trash-head-path = Path
+shorty-trash-head-redirect-url = Redirect URL
+shorty-trash-head-created-by = Created By
+shorty-trash-head-deleted-at = Deleted At
+shorty-trash-head-purged-at = Purged At
+shorty-trash-head-action = Action
+shorty-trash-action-restore = Restore Url
+shorty-trash-never = Never
+shorty-trash-empty = The trash is empty
+
+shorty-revision-title = History
+shorty-revision-head-created-at = Changed At
+shorty-revision-head-changed-by = Changed By
+shorty-revision-head-path = Path
+shorty-revision-head-redirect-url = Redirect URL
+shorty-revision-head-action = Action
+shorty-revision-current = Current
+shorty-revision-unknown = Unknown
+shorty-revision-action-rollback = Roll Back
+shorty-revision-confirm-message = Roll back to '{ $path }' redirecting to '{ $url_redirect }'?
+
+shorty-tag-title = Tags
+shorty-tag-head-tag = Tag
+shorty-tag-head-link-count = Links
+shorty-tag-head-action = Action
+shorty-tag-action-list = Show Links
+shorty-tag-action-trash = Move Links to Trash
+shorty-tag-action-remove = Remove Tag
+shorty-tag-empty = No tags yet
+shorty-tag-trash-confirm-message = Move every link tagged '{ $tag }' that you can edit to the trash?
+shorty-tag-remove-confirm-message = Take the tag '{ $tag }' off every link that you can edit?
+
+shorty-alias-title = Aliases: { $path }
+shorty-alias-head-path = Path
+shorty-alias-head-created-at = Added At
+shorty-alias-head-action = Action
+shorty-alias-action-remove = Remove Alias
+shorty-alias-empty = No aliases
+shorty-alias-confirm-message = Are you sure you want to remove '{ $path }'?
+
+shorty-stats-title = Stats: { $path }
+
+shorty-stats-head-summary = Summary
+shorty-stats-head-redirect-url = Redirect URL
+shorty-stats-head-total-clicks = Total Clicks
+shorty-stats-head-period-clicks = Clicks (30 Days)
+shorty-stats-head-daily = Daily Clicks (30 Days)
+shorty-stats-head-hourly = Hourly Clicks (48 Hours)
+shorty-stats-head-top-referers = Top Referers (30 Days)
+shorty-stats-head-top-user-agents = Top User Agents (30 Days)
+shorty-stats-head-browser-families = Browsers (30 Days)
+shorty-stats-head-device-classes = Devices (30 Days)
+shorty-stats-head-period = Period
+shorty-stats-head-clicks = Clicks
+shorty-stats-head-referer = Referer
+shorty-stats-head-user-agent = User Agent
+shorty-stats-head-browser = Browser
+shorty-stats-head-device = Device
 
-shorty-route-confirm-message = Are you sure you want to delete '{ $id }'?
\ No newline at end of file
+shorty-stats-direct = Direct / None
+shorty-stats-unknown = Unknown
+shorty-stats-device-desktop = Desktop
+shorty-stats-device-mobile = Mobile
+shorty-stats-device-tablet = Tablet
+shorty-stats-device-bot = Bot
@@ -7,6 +7,8 @@ top-visitor = You're a visitor, click here to login
 top-navigation-home = Home
 top-navigation-user = User
 top-navigation-url = URL Redirect
+top-navigation-api-docs = API
 top-navigation-stack = Stack
+top-navigation-audit = Audit
 
 top-date-time = { DATETIME($date) }
\ No newline at end of file
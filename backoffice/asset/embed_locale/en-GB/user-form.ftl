@@ -13,6 +13,16 @@ user-form-password-current = Current Password:
 user-form-password-current-placeholder = Current
 user-form-role = Role:
 
+user-form-api-token-title-add = New API Token
+user-form-api-token-name = Name:
+user-form-api-token-name-placeholder = Deploy script
+user-form-api-token-scope = Scope:
+user-form-api-token-scope-read = Read only
+user-form-api-token-scope-read-write = Read and write
+user-form-api-token-scope-root = Root
+user-form-api-token-expires-at = Expires At (UTC):
+user-form-api-token-submit = Create
+
 user-route-submit-add = Add
 user-route-submit-edit = Edit
 user-route-submit-password = Submit
\ No newline at end of file
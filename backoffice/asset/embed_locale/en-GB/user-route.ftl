@@ -7,11 +7,77 @@ user-route-list-action-edit = Edit User
 user-route-list-action-password = Edit Password
 user-route-list-action-sign-out = Sign Out User
 user-route-list-action-add-user = Add User
+user-route-list-action-change-password = Change My Password
 
 user-route-flash-edit-success = Successfully edited user id: { $user_id }
 user-route-flash-password-success = Successfully edited password for user id: { $user_id }
 user-route-flash-add-success = Successfully created user: { $username }
 user-route-flash-sign-out-error = Failed to sign out user id: { $user_id }
 user-route-flash-sign-out-success = Successfully signed out user id: { $user_id }
+user-route-flash-change-password-success = Your password has been changed
+user-route-change-password-required = Please choose a new password before continuing
+user-route-change-password-current-wrong = The current password is wrong
 
-user-route-logout-confirm-message = Are you sure you want to log out '{ $username }' ?
\ No newline at end of file
+user-route-logout-confirm-message = Are you sure you want to log out '{ $username }' ?
+
+user-route-list-action-api-tokens = My API Tokens
+
+user-route-api-token-title = My API Tokens
+user-route-api-token-head-name = Name
+user-route-api-token-head-scope = Scope
+user-route-api-token-head-created-at = Created At
+user-route-api-token-head-last-used-at = Last Used
+user-route-api-token-head-expires-at = Expires At
+user-route-api-token-head-action = Action
+user-route-api-token-never = Never
+user-route-api-token-action-revoke = Revoke
+user-route-api-token-new-token-notice = Copy your new token now, it will not be shown again.
+user-route-api-token-revoke-confirm-message = Are you sure you want to revoke '{ $name }' ?
+user-route-api-token-flash-revoke-success = Successfully revoked API token
+user-route-list-action-login-failures = Failed Logins
+
+user-route-login-failure-title = Failed Logins
+user-route-login-failure-head-username = Username
+user-route-login-failure-head-client-ip = IP
+user-route-login-failure-head-failed-at = Failed At
+user-route-login-failure-head-throttled = Locked Out
+user-route-login-failure-yes = Yes
+user-route-login-failure-no = No
+
+user-route-list-action-two-factor = My Two-Factor Login
+user-route-list-action-reset-two-factor = Reset Two-Factor
+user-route-flash-reset-two-factor-success = Two-factor was reset for user id: { $user_id }
+user-route-flash-reset-two-factor-none = User id: { $user_id } does not have two-factor set up
+
+user-route-two-factor-title = Two-Factor Login
+user-route-two-factor-disabled-notice = Two-factor is off. With it on, a code from an authenticator app is asked for after your password.
+user-route-two-factor-setup-button = Set Up
+user-route-two-factor-pending-notice = Add this account to your authenticator app with the link or the secret, then confirm with the code it shows.
+user-route-two-factor-open-in-app = Open in authenticator app
+user-route-two-factor-secret = Secret
+user-route-two-factor-code = Code
+user-route-two-factor-confirm-button = Confirm
+user-route-two-factor-restart-button = Start Over
+user-route-two-factor-recovery-codes-notice = Save these recovery codes now, they will not be shown again. Each one can be used once in place of a code.
+user-route-two-factor-regenerate-button = New Recovery Codes
+user-route-two-factor-disable-button = Turn Off
+user-route-two-factor-enabled-notice = Two-factor is on, with { $count } recovery codes left.
+user-route-two-factor-reset-confirm-message = Are you sure you want to reset two-factor for '{ $username }' ?
+user-route-two-factor-flash-invalid-code = Invalid code
+user-route-two-factor-flash-enabled = Two-factor is now on
+user-route-two-factor-flash-disabled = Two-factor is now off
+
+user-route-list-action-sessions = My Sessions
+
+user-route-session-title = My Sessions
+user-route-session-head-created-at = Signed In
+user-route-session-head-last-seen-at = Last Seen
+user-route-session-head-expire-after = Expires At
+user-route-session-head-client-ip = IP
+user-route-session-head-user-agent = Browser
+user-route-session-head-action = Action
+user-route-session-unknown = Unknown
+user-route-session-current = This session
+user-route-session-action-revoke = Sign Out
+user-route-session-revoke-confirm-message = Are you sure you want to sign out this session?
+user-route-session-flash-revoke-success = Successfully signed out the session
\ No newline at end of file
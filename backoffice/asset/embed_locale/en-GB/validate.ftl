@@ -39,5 +39,13 @@ validate-time-max = Must be before { $max }
 validate-username-reserved = Username is reserved
 
 validate-must-be-kebab-case = Must be kebab case
+validate-must-be-number = Must be a whole number
+validate-invalid-date-time = Invalid date and time
+validate-invalid-redirect-type = Invalid redirect type
+validate-invalid-api-token-scope = Invalid scope
+validate-api-token-scope-not-allowed = Scope is not allowed
+validate-tags-must-be-kebab-case = Tags must be kebab case
+validate-tags-max = Must be at most { $max } tags
+validate-tags-max-length = Each tag must be at most { $max } characters
 
 validate-flash = Please check the form above for errors.
\ No newline at end of file
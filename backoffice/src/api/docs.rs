@@ -0,0 +1,37 @@
+use crate::api::API_ROUTE;
+use crate::api::openapi::OPENAPI_PATH;
+use crate::common::html::context_html::ContextHtmlBuilder;
+use crate::user::role::user_role_check::must_be_user;
+use maud::{Markup, PreEscaped, html};
+use poem::{Route, get, handler};
+use shared::context::Dep;
+use shared::locale::LocaleExt;
+
+pub const API_DOCS_ROUTE: &str = "/api-docs";
+
+#[handler]
+async fn api_docs(Dep(context_html_builder): Dep<ContextHtmlBuilder>) -> Markup {
+    let l = &context_html_builder.locale;
+    let title = l.text_with_default("api-docs-title", "API Documentation");
+    let download = l.text_with_default("api-docs-download", "Download the OpenAPI document");
+    let spec_url = API_ROUTE.to_owned() + OPENAPI_PATH;
+
+    context_html_builder
+        .attach_title(&title)
+        .set_current_tag("id-tag-api-docs")
+        .attach_content(html! {
+            h1 { (title) }
+            p .text-right {
+                a href=(spec_url) download="openapi.json" { (download) }
+            }
+            div #api-docs data-spec-url=(spec_url) { }
+            script type="module" {
+                (PreEscaped("import {renderApiDocs} from 'assets/api_docs.js'; renderApiDocs(document.getElementById('api-docs'));"))
+            }
+        })
+        .build()
+}
+
+pub fn api_docs_route() -> Route {
+    Route::new().at("/", must_be_user(get(api_docs)))
+}
@@ -0,0 +1,131 @@
+pub mod docs;
+pub mod openapi;
+
+use crate::api::openapi::{OPENAPI_PATH, openapi_json};
+use crate::shorty::route::shorty_api::{SHORTY_API_ROUTE, shorty_api_route};
+use crate::shorty::route::tag_api::{TAG_API_ROUTE, tag_api_route};
+use crate::user::route::login_api::{LOGIN_API_ROUTE, login_api_route};
+use crate::user::route::user_api::{USER_API_ROUTE, user_api_route};
+use error_stack::Report;
+use poem::http::{HeaderMap, Method, StatusCode, header};
+use poem::web::Json;
+use poem::{Endpoint, IntoResponse, Request, Response, Route, get};
+use serde::Serialize;
+use shared::error::{ErrorStackUseJson, FromErrorStack};
+use shared::log::log_poem_error;
+use utoipa::ToSchema;
+
+pub const API_ROUTE: &str = "/api/v1";
+
+/// Body of every API error response.
+#[derive(Serialize, ToSchema)]
+pub struct ApiErrorModel {
+    pub msg: String,
+}
+
+/// Body of a `422` response; `errors` holds the messages for each field.
+#[derive(Serialize, ToSchema)]
+pub struct ApiValidationErrorModel<T> {
+    pub msg: String,
+    pub errors: T,
+}
+
+pub fn api_route() -> Route {
+    Route::new()
+        .at(OPENAPI_PATH, get(openapi_json))
+        .nest(LOGIN_API_ROUTE, login_api_route())
+        .nest(SHORTY_API_ROUTE, shorty_api_route())
+        .nest(TAG_API_ROUTE, tag_api_route())
+        .nest(USER_API_ROUTE, user_api_route())
+}
+
+/// A cross-site form can only post `application/x-www-form-urlencoded`, `multipart/form-data`
+/// or `text/plain`, and cannot set headers, so requiring JSON or a bearer token on unsafe
+/// methods keeps the login cookie from being used by another site.
+fn is_cross_site_proof(method: &Method, headers: &HeaderMap) -> bool {
+    method.is_safe()
+        || headers
+            .get(header::AUTHORIZATION)
+            .is_some_and(|value| value.as_bytes().starts_with(b"Bearer "))
+        || headers
+            .get(header::CONTENT_TYPE)
+            .is_some_and(|value| value.as_bytes().starts_with(b"application/json"))
+}
+
+/// The API sits outside the form CSRF middleware, this takes its place.
+pub async fn require_json_around<EP: Endpoint>(next: EP, req: Request) -> poem::Result<EP::Output> {
+    if !is_cross_site_proof(req.method(), req.headers()) {
+        return Err(poem::Error::from_string(
+            "Send the request as application/json or with an API token",
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ));
+    }
+    next.call(req).await
+}
+
+pub fn api_error<T>(err: Report<T>) -> poem::Error
+where
+    T: Send + Sync + 'static,
+{
+    poem::Error::from_error_stack(err.attach_opaque(ErrorStackUseJson))
+}
+
+pub fn api_validation_error<T: Serialize + Send>(errors: T) -> Response {
+    let mut resp = Json(ApiValidationErrorModel {
+        msg: "Validation failed".to_string(),
+        errors,
+    })
+    .into_response();
+    resp.set_status(StatusCode::UNPROCESSABLE_ENTITY);
+    resp
+}
+
+pub async fn api_catch_all_error(err: poem::Error) -> Response {
+    log_poem_error(&err).await;
+    let status = err.status();
+    let msg = err.to_string();
+    let resp = err.into_response();
+    let is_json = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(b"application/json"));
+    if is_json {
+        return resp;
+    }
+    let mut resp = Json(ApiErrorModel { msg }).into_response();
+    resp.set_status(status);
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::http::HeaderValue;
+
+    #[test]
+    fn test_is_cross_site_proof() {
+        let mut headers = HeaderMap::new();
+        assert!(is_cross_site_proof(&Method::GET, &headers));
+        assert!(!is_cross_site_proof(&Method::POST, &headers));
+        assert!(!is_cross_site_proof(&Method::DELETE, &headers));
+
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        assert!(!is_cross_site_proof(&Method::POST, &headers));
+
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=utf-8"),
+        );
+        assert!(is_cross_site_proof(&Method::POST, &headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer rsk_test"),
+        );
+        assert!(is_cross_site_proof(&Method::DELETE, &headers));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::api::API_ROUTE;
+use crate::shorty::route::shorty_api::{SHORTY_API_ROUTE, ShortyApiDoc};
+use crate::shorty::route::tag_api::{TAG_API_ROUTE, TagApiDoc};
+use crate::user::LOGIN_TOKEN_COOKIE_NAME;
+use crate::user::route::login_api::{LOGIN_API_ROUTE, LoginApiDoc};
+use crate::user::route::user_api::{USER_API_ROUTE, UserApiDoc};
+use poem::handler;
+use poem::web::Json;
+use utoipa::Modify;
+use utoipa::OpenApi;
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+
+pub const OPENAPI_PATH: &str = "/openapi.json";
+
+pub const BEARER_TOKEN_SCHEME: &str = "bearer_token";
+pub const LOGIN_COOKIE_SCHEME: &str = "login_cookie";
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            BEARER_TOKEN_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "Personal API token, created under Users → My API Tokens",
+                    ))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            LOGIN_COOKIE_SCHEME,
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                LOGIN_TOKEN_COOKIE_NAME,
+                "Set by signing in through the session endpoint",
+            ))),
+        );
+        openapi.security = Some(vec![
+            SecurityRequirement::new(BEARER_TOKEN_SCHEME, Vec::<String>::new()),
+            SecurityRequirement::new(LOGIN_COOKIE_SCHEME, Vec::<String>::new()),
+        ]);
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Rusty Shorty Backoffice API",
+        description = "Manage short links and backoffice users.",
+        license(name = "MIT", identifier = "MIT")
+    ),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "session", description = "Sign in and out with a username and password"),
+        (name = "links", description = "Short links"),
+        (name = "tags", description = "Tags on short links, with bulk actions"),
+        (name = "users", description = "Backoffice users; changes need a root user"),
+    )
+)]
+pub struct ApiDoc;
+
+pub fn api_doc() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+        .nest(
+            API_ROUTE.to_owned() + LOGIN_API_ROUTE,
+            LoginApiDoc::openapi(),
+        )
+        .nest(
+            API_ROUTE.to_owned() + SHORTY_API_ROUTE,
+            ShortyApiDoc::openapi(),
+        )
+        .nest(API_ROUTE.to_owned() + TAG_API_ROUTE, TagApiDoc::openapi())
+        .nest(API_ROUTE.to_owned() + USER_API_ROUTE, UserApiDoc::openapi())
+}
+
+#[handler]
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(api_doc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_doc_paths_are_nested_under_api_route() {
+        let doc = api_doc();
+        for path in [
+            "/api/v1/session/",
+            "/api/v1/links/",
+            "/api/v1/links/{url_id}",
+            "/api/v1/tags/{tag}/trash",
+            "/api/v1/users/",
+            "/api/v1/users/{user_id}/password",
+        ] {
+            assert!(doc.paths.paths.contains_key(path), "missing {path}");
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::audit::model::audit_model::{AuditAction, AuditLogFilter};
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Everything is text, as the filter form sends empty values for "any".
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuditLogQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// `YYYY-MM-DD`, in UTC.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// `YYYY-MM-DD`, in UTC and including the whole day.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+fn parse_date(value: &Option<String>) -> Option<NaiveDate> {
+    value
+        .as_deref()
+        .and_then(|value| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok())
+}
+
+impl AuditLogQuery {
+    pub fn filter(&self) -> AuditLogFilter {
+        AuditLogFilter {
+            actor_user_id: self
+                .user
+                .as_deref()
+                .and_then(|user| user.trim().parse().ok()),
+            action: self
+                .action
+                .as_deref()
+                .and_then(|action| AuditAction::try_from(action.trim()).ok()),
+            from: parse_date(&self.from)
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc()),
+            to: parse_date(&self.to)
+                .and_then(|date| date.checked_add_days(Days::new(1)))
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc()),
+        }
+    }
+
+    pub fn with_page(&self, page: i64) -> Self {
+        Self {
+            page: Some(page),
+            ..self.clone()
+        }
+    }
+
+    pub fn as_query_string(&self) -> String {
+        serde_qs::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_filter_ignores_empty_values_and_includes_the_to_day() {
+        let query: AuditLogQuery =
+            serde_qs::from_str("user=&action=link.edit&from=2025-03-01&to=2025-03-31").unwrap();
+        assert_eq!(
+            query.filter(),
+            AuditLogFilter {
+                actor_user_id: None,
+                action: Some(AuditAction::LinkEdit),
+                from: Some(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()),
+                to: Some(Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap()),
+            }
+        );
+
+        let query: AuditLogQuery = serde_qs::from_str("user=2&action=nope&to=soon").unwrap();
+        assert_eq!(
+            query.filter(),
+            AuditLogFilter {
+                actor_user_id: Some(2),
+                ..Default::default()
+            }
+        );
+    }
+}
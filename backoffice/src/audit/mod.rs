@@ -0,0 +1,5 @@
+pub mod form;
+pub mod model;
+pub mod repository;
+pub mod route;
+pub mod service;
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AuditAction {
+    LinkAdd,
+    LinkEdit,
+    LinkDelete,
+    LinkRestore,
+    LinkPurge,
+    LinkRollback,
+    LinkAliasAdd,
+    LinkAliasRemove,
+    TagRemove,
+    UserAdd,
+    UserEdit,
+    UserPassword,
+    UserSignOut,
+    UserResetTwoFactor,
+    TwoFactorEnable,
+    TwoFactorDisable,
+    TwoFactorRecoveryCodes,
+    SessionRevoke,
+    ApiTokenAdd,
+    ApiTokenRevoke,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 20] = [
+        AuditAction::LinkAdd,
+        AuditAction::LinkEdit,
+        AuditAction::LinkDelete,
+        AuditAction::LinkRestore,
+        AuditAction::LinkPurge,
+        AuditAction::LinkRollback,
+        AuditAction::LinkAliasAdd,
+        AuditAction::LinkAliasRemove,
+        AuditAction::TagRemove,
+        AuditAction::UserAdd,
+        AuditAction::UserEdit,
+        AuditAction::UserPassword,
+        AuditAction::UserSignOut,
+        AuditAction::UserResetTwoFactor,
+        AuditAction::TwoFactorEnable,
+        AuditAction::TwoFactorDisable,
+        AuditAction::TwoFactorRecoveryCodes,
+        AuditAction::SessionRevoke,
+        AuditAction::ApiTokenAdd,
+        AuditAction::ApiTokenRevoke,
+    ];
+
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            Self::LinkAdd => "link.add",
+            Self::LinkEdit => "link.edit",
+            Self::LinkDelete => "link.delete",
+            Self::LinkRestore => "link.restore",
+            Self::LinkPurge => "link.purge",
+            Self::LinkRollback => "link.rollback",
+            Self::LinkAliasAdd => "link.alias_add",
+            Self::LinkAliasRemove => "link.alias_remove",
+            Self::TagRemove => "tag.remove",
+            Self::UserAdd => "user.add",
+            Self::UserEdit => "user.edit",
+            Self::UserPassword => "user.password",
+            Self::UserSignOut => "user.sign_out",
+            Self::UserResetTwoFactor => "user.reset_two_factor",
+            Self::TwoFactorEnable => "two_factor.enable",
+            Self::TwoFactorDisable => "two_factor.disable",
+            Self::TwoFactorRecoveryCodes => "two_factor.recovery_codes",
+            Self::SessionRevoke => "session.revoke",
+            Self::ApiTokenAdd => "api_token.add",
+            Self::ApiTokenRevoke => "api_token.revoke",
+        }
+    }
+
+    pub fn target_type(&self) -> &'static str {
+        match self {
+            Self::LinkAdd
+            | Self::LinkEdit
+            | Self::LinkDelete
+            | Self::LinkRestore
+            | Self::LinkPurge
+            | Self::LinkRollback
+            | Self::LinkAliasAdd
+            | Self::LinkAliasRemove => "link",
+            Self::TagRemove => "tag",
+            Self::UserAdd
+            | Self::UserEdit
+            | Self::UserPassword
+            | Self::UserSignOut
+            | Self::UserResetTwoFactor
+            | Self::TwoFactorEnable
+            | Self::TwoFactorDisable
+            | Self::TwoFactorRecoveryCodes => "user",
+            Self::SessionRevoke => "session",
+            Self::ApiTokenAdd | Self::ApiTokenRevoke => "api_token",
+        }
+    }
+}
+
+impl TryFrom<&str> for AuditAction {
+    type Error = ();
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_key() == s)
+            .ok_or(())
+    }
+}
+
+impl TryFrom<String> for AuditAction {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::try_from(s.as_str()).map_err(|_| format!("unknown action '{}'", s))
+    }
+}
+
+impl From<AuditAction> for String {
+    fn from(action: AuditAction) -> Self {
+        action.as_key().to_string()
+    }
+}
+
+/// Who made the change, empty for the command line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditActor {
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub client_ip: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct AuditLogModel {
+    pub actor_user_id: Option<i64>,
+    pub actor_username: Option<String>,
+    /// Kept as text, so entries from a newer release still show.
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<i64>,
+    pub diff: String,
+    pub client_ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct AuditLogPageModel {
+    pub items: Arc<[AuditLogModel]>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+#[derive(Debug)]
+pub struct AuditActorModel {
+    pub user_id: i64,
+    pub username: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AuditLogFilter {
+    pub actor_user_id: Option<i64>,
+    pub action: Option<AuditAction>,
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive.
+    pub to: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,2 @@
+insert into audit_log (actor_user_id, actor_username, action, target_type, target_id, diff, client_ip, created_at)
+values (:actor_user_id, :actor_username, :action, :target_type, :target_id, :diff, :client_ip, :created_at)
\ No newline at end of file
@@ -0,0 +1,6 @@
+select count(*) as total
+from audit_log
+where (:actor_user_id is null or actor_user_id = :actor_user_id)
+  and (:action is null or action = :action)
+  and (:from is null or created_at >= :from)
+  and (:to is null or created_at < :to)
\ No newline at end of file
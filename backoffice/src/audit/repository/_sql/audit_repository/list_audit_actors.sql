@@ -0,0 +1,5 @@
+select actor_user_id, max(actor_username) as actor_username
+from audit_log
+where actor_user_id is not null
+group by actor_user_id
+order by actor_username
\ No newline at end of file
@@ -0,0 +1,15 @@
+select actor_user_id,
+       actor_username,
+       action,
+       target_type,
+       target_id,
+       diff,
+       client_ip,
+       created_at
+from audit_log
+where (:actor_user_id is null or actor_user_id = :actor_user_id)
+  and (:action is null or action = :action)
+  and (:from is null or created_at >= :from)
+  and (:to is null or created_at < :to)
+order by id desc
+limit :limit offset :offset
\ No newline at end of file
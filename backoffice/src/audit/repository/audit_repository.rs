@@ -0,0 +1,210 @@
+use crate::audit::model::audit_model::{
+    AuditAction, AuditActor, AuditActorModel, AuditLogFilter, AuditLogModel,
+};
+use chrono::{DateTime, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rusqlite::{Connection, named_params};
+use shared::context::{Context, ContextError, FromContext};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum AuditRepositoryError {
+    #[error("Query Error")]
+    QueryError,
+    #[error("Row Value Error")]
+    RowValueError,
+    #[error("Borrow Conn Error")]
+    BorrowConnError,
+}
+
+#[mry::mry]
+pub struct AuditRepository {
+    sqlite_client: Option<SqliteClient>,
+}
+
+impl AuditRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self {
+            sqlite_client: Some(sqlite_client),
+            mry: Default::default(),
+        }
+    }
+
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<AuditRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<AuditRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .read(AuditRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<AuditRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<AuditRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(AuditRepositoryError::BorrowConnError, f)
+            .await
+    }
+}
+
+#[mry::mry]
+impl AuditRepository {
+    pub async fn add_audit_log(
+        &self,
+        actor: AuditActor,
+        action: AuditAction,
+        target_id: Option<i64>,
+        diff: String,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), Report<AuditRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/audit_repository/add_audit_log.sql"),
+                named_params! {
+                    ":actor_user_id": actor.user_id,
+                    ":actor_username": actor.username,
+                    ":action": action.as_key(),
+                    ":target_type": action.target_type(),
+                    ":target_id": target_id,
+                    ":diff": diff,
+                    ":client_ip": actor.client_ip,
+                    ":created_at": created_at,
+                },
+            )
+            .change_context(AuditRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn list_audit_log_page(
+        &self,
+        filter: AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Arc<[AuditLogModel]>, Report<AuditRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/audit_repository/list_audit_log_page.sql"
+                ))
+                .change_context(AuditRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":actor_user_id": filter.actor_user_id,
+                        ":action": filter.action.map(|action| action.as_key()),
+                        ":from": filter.from,
+                        ":to": filter.to,
+                        ":limit": limit,
+                        ":offset": offset,
+                    },
+                    |row| {
+                        Ok(AuditLogModel {
+                            actor_user_id: row.get("actor_user_id")?,
+                            actor_username: row.get("actor_username")?,
+                            action: row.get("action")?,
+                            target_type: row.get("target_type")?,
+                            target_id: row.get("target_id")?,
+                            diff: row.get("diff")?,
+                            client_ip: row.get("client_ip")?,
+                            created_at: row.get("created_at")?,
+                        })
+                    },
+                )
+                .change_context(AuditRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(AuditRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    pub async fn count_audit_log(
+        &self,
+        filter: AuditLogFilter,
+    ) -> Result<i64, Report<AuditRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/audit_repository/count_audit_log.sql"))
+                .change_context(AuditRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let total = stmt
+                .query_one(
+                    named_params! {
+                        ":actor_user_id": filter.actor_user_id,
+                        ":action": filter.action.map(|action| action.as_key()),
+                        ":from": filter.from,
+                        ":to": filter.to,
+                    },
+                    |row| row.get("total"),
+                )
+                .change_context(AuditRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(total)
+        })
+        .await
+    }
+
+    pub async fn list_audit_actors(
+        &self,
+    ) -> Result<Arc<[AuditActorModel]>, Report<AuditRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare(include_str!("_sql/audit_repository/list_audit_actors.sql"))
+                .change_context(AuditRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let rows_iter = stmt
+                .query_map(named_params! {}, |row| {
+                    Ok(AuditActorModel {
+                        user_id: row.get("actor_user_id")?,
+                        username: row.get("actor_username")?,
+                    })
+                })
+                .change_context(AuditRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = rows_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(AuditRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl AuditRepository {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            sqlite_client: None
+        })
+    }
+}
+
+impl FromContext for AuditRepository {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
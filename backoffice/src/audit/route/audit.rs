@@ -0,0 +1,151 @@
+use crate::audit::form::audit_log_query::AuditLogQuery;
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::route::locale::audit_locale::{
+    AuditLocale, audit_action_label, audit_route_page_summary,
+};
+use crate::audit::service::audit_service::AuditService;
+use crate::common::html::context_html::ContextHtmlBuilder;
+use maud::{Markup, html};
+use poem::{Error, Route, get, handler};
+use shared::context::Dep;
+use shared::error::FromErrorStack;
+use shared::query_string::query::QueryQs;
+
+pub const AUDIT_ROUTE: &str = "/audit";
+
+const AUDIT_LIST_ID: &str = "audit-list";
+
+fn audit_href(query: &AuditLogQuery) -> String {
+    let query_string = query.as_query_string();
+    if query_string.is_empty() {
+        format!("{}/", AUDIT_ROUTE)
+    } else {
+        format!("{}/?{}", AUDIT_ROUTE, query_string)
+    }
+}
+
+fn audit_link(query: &AuditLogQuery, label: &str) -> Markup {
+    let href = audit_href(query);
+    let target = format!("#{}", AUDIT_LIST_ID);
+    html! {
+        a href=(href) hx-get=(href) hx-target=(target) hx-select=(target) hx-swap="outerHTML" hx-push-url="true" { (label) }
+    }
+}
+
+fn pretty_diff(diff: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(diff)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| diff.to_string())
+}
+
+#[handler]
+async fn list_audit_log(
+    Dep(audit_service): Dep<AuditService>,
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    QueryQs(query): QueryQs<AuditLogQuery>,
+) -> poem::Result<Markup> {
+    let filter = query.filter();
+    let audit_log = audit_service
+        .list_audit_log_page(filter.clone(), query.page)
+        .await
+        .map_err(Error::from_error_stack)?;
+    let actors = audit_service.list_audit_actors().await;
+    let pages = ((audit_log.total + audit_log.per_page - 1) / audit_log.per_page).max(1);
+
+    let l = &context_html_builder.locale;
+    let lc = AuditLocale::new(l);
+    let list_target = format!("#{}", AUDIT_LIST_ID);
+
+    Ok(context_html_builder
+        .attach_title(&lc.title)
+        .set_current_tag("id-tag-audit")
+        .attach_content(html! {
+            h1 { (lc.title) }
+            form .mt-3 method="get" action=(audit_href(&AuditLogQuery::default()))
+                hx-get=(audit_href(&AuditLogQuery::default())) hx-target=(list_target)
+                hx-select=(list_target) hx-swap="outerHTML" hx-push-url="true" {
+                label .label for="user" { (lc.filter_user) } " "
+                select .form-item name="user" #user {
+                    option value="" { (lc.filter_any) }
+                    @for actor in actors.iter() {
+                        option value=(actor.user_id) selected[filter.actor_user_id == Some(actor.user_id)] { (actor.username) }
+                    }
+                }
+                " "
+                label .label for="action" { (lc.filter_action) } " "
+                select .form-item name="action" #action {
+                    option value="" { (lc.filter_any) }
+                    @for action in AuditAction::ALL {
+                        option value=(action.as_key()) selected[filter.action == Some(action)] { (audit_action_label(l, action.as_key())) }
+                    }
+                }
+                " "
+                label .label for="from" { (lc.filter_from) } " "
+                input .form-item type="date" name="from" #from value=(query.from.clone().unwrap_or_default()) {}
+                " "
+                label .label for="to" { (lc.filter_to) } " "
+                input .form-item type="date" name="to" #to value=(query.to.clone().unwrap_or_default()) {}
+                " "
+                input .btn .btn-sky-blue type="submit" value=(lc.filter_submit) {}
+            }
+            div #(AUDIT_LIST_ID) {
+                table .table-full .mt-3 {
+                    thead {
+                        tr {
+                            th { (lc.head_created_at) }
+                            th { (lc.head_actor) }
+                            th { (lc.head_action) }
+                            th { (lc.head_target) }
+                            th { (lc.head_diff) }
+                            th { (lc.head_client_ip) }
+                        }
+                    }
+                    tbody {
+                        @for entry in audit_log.items.iter() {
+                            tr {
+                                td .js-date-local { (entry.created_at.to_rfc3339()) }
+                                td {
+                                    @match (entry.actor_user_id, entry.actor_username.as_deref()) {
+                                        (Some(user_id), Some(username)) => {
+                                            (audit_link(&AuditLogQuery { user: Some(user_id.to_string()), ..Default::default() }, username))
+                                        }
+                                        _ => { (lc.command_line) }
+                                    }
+                                }
+                                td { (audit_action_label(l, &entry.action)) }
+                                td {
+                                    (entry.target_type)
+                                    @if let Some(target_id) = entry.target_id {
+                                        " #" (target_id)
+                                    }
+                                }
+                                td { pre { (pretty_diff(&entry.diff)) } }
+                                td { (entry.client_ip.as_deref().unwrap_or(&lc.unknown)) }
+                            }
+                        }
+                        @if audit_log.items.is_empty() {
+                            tr {
+                                td colspan="6" { (lc.empty) }
+                            }
+                        }
+                    }
+                }
+                div .text-right .mt-3 {
+                    @if audit_log.page > 1 {
+                        (audit_link(&query.with_page(audit_log.page - 1), &lc.page_previous))
+                        " "
+                    }
+                    span { (audit_route_page_summary(l, audit_log.page, pages, audit_log.total)) }
+                    @if audit_log.page < pages {
+                        " "
+                        (audit_link(&query.with_page(audit_log.page + 1), &lc.page_next))
+                    }
+                }
+            }
+        })
+        .build())
+}
+
+pub fn audit_route() -> Route {
+    Route::new().at("/", get(list_audit_log))
+}
@@ -0,0 +1,64 @@
+use poem::i18n::{I18NArgs, Locale};
+use shared::locale::LocaleExt;
+
+pub struct AuditLocale {
+    pub title: String,
+    pub filter_user: String,
+    pub filter_action: String,
+    pub filter_from: String,
+    pub filter_to: String,
+    pub filter_any: String,
+    pub filter_submit: String,
+    pub head_created_at: String,
+    pub head_actor: String,
+    pub head_action: String,
+    pub head_target: String,
+    pub head_diff: String,
+    pub head_client_ip: String,
+    pub command_line: String,
+    pub unknown: String,
+    pub empty: String,
+    pub page_previous: String,
+    pub page_next: String,
+}
+
+impl AuditLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("audit-route-title", "Audit Log"),
+            filter_user: l.text_with_default("audit-route-filter-user", "User"),
+            filter_action: l.text_with_default("audit-route-filter-action", "Action"),
+            filter_from: l.text_with_default("audit-route-filter-from", "From"),
+            filter_to: l.text_with_default("audit-route-filter-to", "To"),
+            filter_any: l.text_with_default("audit-route-filter-any", "Any"),
+            filter_submit: l.text_with_default("audit-route-filter-submit", "Filter"),
+            head_created_at: l.text_with_default("audit-route-head-created-at", "When"),
+            head_actor: l.text_with_default("audit-route-head-actor", "User"),
+            head_action: l.text_with_default("audit-route-head-action", "Action"),
+            head_target: l.text_with_default("audit-route-head-target", "Target"),
+            head_diff: l.text_with_default("audit-route-head-diff", "Changes"),
+            head_client_ip: l.text_with_default("audit-route-head-client-ip", "IP"),
+            command_line: l.text_with_default("audit-route-command-line", "Command line"),
+            unknown: l.text_with_default("audit-route-unknown", "Unknown"),
+            empty: l.text_with_default("audit-route-empty", "No entries found"),
+            page_previous: l.text_with_default("audit-route-page-previous", "Previous"),
+            page_next: l.text_with_default("audit-route-page-next", "Next"),
+        }
+    }
+}
+
+pub fn audit_route_page_summary(l: &Locale, page: i64, pages: i64, total: i64) -> String {
+    l.text_with_default_args(
+        "audit-route-page-summary",
+        format!("Page {page} of {pages} ({total} entries)").as_str(),
+        I18NArgs::from((("page", page), ("pages", pages), ("total", total))),
+    )
+}
+
+/// Falls back to the stored key, for actions this release does not know about.
+pub fn audit_action_label(l: &Locale, action: &str) -> String {
+    l.text_with_default(
+        format!("audit-action-{}", action.replace(['.', '_'], "-")).as_str(),
+        action,
+    )
+}
@@ -0,0 +1,207 @@
+use crate::audit::model::audit_model::{
+    AuditAction, AuditActor, AuditActorModel, AuditLogFilter, AuditLogPageModel,
+};
+use crate::audit::repository::audit_repository::AuditRepository;
+use crate::user::pointer::user_pointer::UserPointer;
+use chrono::{SubsecRound, Utc};
+use error_stack::{Report, ResultExt};
+use log::error;
+use poem::FromRequest;
+use poem::web::RealIp;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use shared::context::{Context, ContextError, FromContext};
+use std::sync::Arc;
+
+pub const PER_PAGE: i64 = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditServiceError {
+    #[error("Database error")]
+    DbError,
+}
+
+pub struct AuditService {
+    audit_repository: AuditRepository,
+    actor: AuditActor,
+}
+
+impl AuditService {
+    pub fn new(audit_repository: AuditRepository, actor: AuditActor) -> Self {
+        Self {
+            audit_repository,
+            actor,
+        }
+    }
+
+    /// The change has already been made by the time this runs, so a failure to write the
+    /// entry is logged rather than handed back to the caller.
+    pub async fn record<B: Serialize, A: Serialize>(
+        &self,
+        action: AuditAction,
+        target_id: Option<i64>,
+        before: Option<&B>,
+        after: Option<&A>,
+    ) {
+        let diff = diff(to_value(before), to_value(after));
+        if let Err(err) = self
+            .audit_repository
+            .add_audit_log(
+                self.actor.clone(),
+                action,
+                target_id,
+                diff.to_string(),
+                Utc::now().trunc_subsecs(0),
+            )
+            .await
+        {
+            error!("Failed to write audit log: {:?}", err);
+        }
+    }
+
+    pub async fn list_audit_log_page(
+        &self,
+        filter: AuditLogFilter,
+        page: Option<i64>,
+    ) -> Result<AuditLogPageModel, Report<AuditServiceError>> {
+        let page = page.unwrap_or(1).max(1);
+        let total = self
+            .audit_repository
+            .count_audit_log(filter.clone())
+            .await
+            .change_context(AuditServiceError::DbError)?;
+        let items = self
+            .audit_repository
+            .list_audit_log_page(filter, PER_PAGE, (page - 1) * PER_PAGE)
+            .await
+            .change_context(AuditServiceError::DbError)?;
+
+        Ok(AuditLogPageModel {
+            items,
+            page,
+            per_page: PER_PAGE,
+            total,
+        })
+    }
+
+    pub async fn list_audit_actors(&self) -> Arc<[AuditActorModel]> {
+        self.audit_repository
+            .list_audit_actors()
+            .await
+            .unwrap_or_default()
+    }
+}
+
+fn to_value<T: Serialize>(value: Option<&T>) -> Map<String, Value> {
+    match value.map(serde_json::to_value) {
+        Some(Ok(Value::Object(map))) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Only the fields that changed, as `{"field": {"before": .., "after": ..}}`.
+fn diff(before: Map<String, Value>, after: Map<String, Value>) -> Value {
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let changes: Map<String, Value> = keys
+        .into_iter()
+        .filter_map(|key| {
+            let old = before.get(key).unwrap_or(&Value::Null);
+            let new = after.get(key).unwrap_or(&Value::Null);
+            (old != new).then(|| (key.clone(), json!({"before": old, "after": new})))
+        })
+        .collect();
+    Value::Object(changes)
+}
+
+impl FromContext for AuditService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        // Without a request the change came from the command line.
+        let actor = match ctx.req_result() {
+            Ok(req) => {
+                let user_pointer: UserPointer = ctx.inject().await?;
+                let RealIp(client_ip) = RealIp::from_request_without_body(req)
+                    .await
+                    .change_context(ContextError::RequestError)?;
+                AuditActor {
+                    user_id: Some(user_pointer.id),
+                    username: Some(user_pointer.username.clone()),
+                    client_ip: client_ip.map(|client_ip| client_ip.to_canonical().to_string()),
+                }
+            }
+            Err(_) => AuditActor::default(),
+        };
+        Ok(Self::new(ctx.inject().await?, actor))
+    }
+}
+
+#[cfg(test)]
+impl AuditService {
+    /// Accepts any number of entries.
+    pub fn new_mock() -> Self {
+        let mut audit_repository = AuditRepository::new_mock();
+        audit_repository
+            .mock_add_audit_log(mry::Any, mry::Any, mry::Any, mry::Any, mry::Any)
+            .returns_with(|_, _, _, _, _| Ok(()));
+        Self::new(audit_repository, AuditActor::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mry::Any;
+
+    #[test]
+    fn test_diff_keeps_only_changed_fields() {
+        let before = to_value(Some(&json!({"path": "sale", "clicks": 5, "note": "a"})));
+        let after = to_value(Some(&json!({"path": "sale", "clicks": 6, "extra": true})));
+        assert_eq!(
+            diff(before, after),
+            json!({
+                "clicks": {"before": 5, "after": 6},
+                "extra": {"before": null, "after": true},
+                "note": {"before": "a", "after": null},
+            })
+        );
+    }
+
+    #[test]
+    fn test_diff_of_nothing_is_empty() {
+        assert_eq!(
+            diff(to_value::<Value>(None), to_value::<Value>(None)),
+            json!({})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_writes_actor_and_diff() {
+        let actor = AuditActor {
+            user_id: Some(1),
+            username: Some("admin".to_string()),
+            client_ip: Some("127.0.0.1".to_string()),
+        };
+        let mut audit_repository = AuditRepository::new_mock();
+        audit_repository
+            .mock_add_audit_log(
+                actor.clone(),
+                AuditAction::LinkAdd,
+                Some(3),
+                r#"{"path":{"after":"sale","before":null}}"#.to_string(),
+                Any,
+            )
+            .returns_once(Ok(()));
+
+        let audit_service = AuditService::new(audit_repository, actor);
+        audit_service
+            .record(
+                AuditAction::LinkAdd,
+                Some(3),
+                None::<&Value>,
+                Some(&json!({"path": "sale"})),
+            )
+            .await;
+    }
+}
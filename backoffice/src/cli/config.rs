@@ -0,0 +1,17 @@
+use crate::cli::CliError;
+use error_stack::{Report, ResultExt};
+use shared::config::Config;
+
+/// Prints the merged config, so a typo in `rusty_shorty.toml` shows up before a deploy.
+pub async fn check() -> Result<(), Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?
+        .upgrade()
+        .ok_or_else(|| Report::new(CliError::ConfigError).attach("Config not found"))?;
+    let config =
+        serde_json::to_string_pretty(config.as_ref()).change_context(CliError::ConfigError)?;
+
+    println!("{}", config);
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::cli::CliError;
+use error_stack::{Report, ResultExt};
+use shared::db::SqliteClient;
+use shared::db::migration::latest_version;
+
+pub async fn migrate() -> Result<(), Report<CliError>> {
+    SqliteClient::init()
+        .await
+        .change_context(CliError::DatabaseError)?;
+
+    println!("Database is at schema version {}", latest_version());
+    Ok(())
+}
+
+pub async fn backup(path: &str) -> Result<(), Report<CliError>> {
+    let sqlite_client = SqliteClient::init()
+        .await
+        .change_context(CliError::DatabaseError)?;
+    sqlite_client
+        .backup(path.to_string())
+        .await
+        .change_context(CliError::DatabaseError)
+        .attach_with(|| format!("Backup path: {}", path))?;
+
+    println!("Backed up the database to {}", path);
+    Ok(())
+}
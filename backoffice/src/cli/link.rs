@@ -0,0 +1,135 @@
+use crate::cli::user::{find_user_id, first_root_user_id};
+use crate::cli::{CliError, inject, invalid_input, locale};
+use crate::shorty::form::add_edit_url_json::AddEditUrlJson;
+use crate::shorty::model::shorty_model::{ListUrlRedirectFilter, ListUrlRedirectSort};
+use crate::shorty::service::add_url_service::AddUrlService;
+use crate::shorty::service::delete_url_service::DeleteUrlService;
+use crate::shorty::service::list_url_service::ListUrlService;
+use crate::shorty::service::trash_url_service::TrashUrlService;
+use chrono::{SubsecRound, Utc};
+use error_stack::{Report, ResultExt};
+
+#[derive(Default)]
+pub struct NewLink {
+    pub url_redirect: String,
+    pub url_path: Option<String>,
+    pub redirect_type: Option<u16>,
+    pub expires_at: Option<String>,
+    pub max_clicks: Option<i64>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// Defaults to the first root user.
+    pub owner: Option<String>,
+}
+
+pub async fn add(new_link: NewLink) -> Result<(), Report<CliError>> {
+    let user_id = match &new_link.owner {
+        Some(owner) => find_user_id(owner).await?,
+        None => first_root_user_id().await?,
+    };
+    let add_url_service: AddUrlService = inject().await?;
+
+    let form = AddEditUrlJson {
+        url_path: new_link.url_path.unwrap_or_default(),
+        url_redirect: new_link.url_redirect,
+        redirect_type: new_link.redirect_type,
+        expires_at: new_link.expires_at,
+        max_clicks: new_link.max_clicks,
+        description: new_link.description,
+        tags: Some(new_link.tags),
+    }
+    .as_form();
+    let validated = match form.as_validated(false).await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Err(invalid_input(&error.as_message(&locale().await?))),
+    };
+    let added = add_url_service
+        .add_url_submit(&validated, user_id)
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!(
+        "Added link {}: /{} -> {}",
+        added.id,
+        added.url_path,
+        validated.url_redirect.as_str()
+    );
+    Ok(())
+}
+
+pub async fn list(
+    search: Option<String>,
+    tag: Option<String>,
+    page: Option<i64>,
+    per_page: Option<i64>,
+) -> Result<(), Report<CliError>> {
+    let list_url_service: ListUrlService = inject().await?;
+    let page = list_url_service
+        .list_urls_page(
+            ListUrlRedirectFilter {
+                search,
+                created_by_user_id: None,
+                tag,
+            },
+            ListUrlRedirectSort::default(),
+            page,
+            per_page,
+        )
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!("ID\tPATH\tDESTINATION\tCLICKS\tOWNER\tTAGS");
+    for url in page.items.iter() {
+        println!(
+            "{}\t/{}\t{}\t{}\t{}\t{}",
+            url.id,
+            url.url_path,
+            url.url_redirect,
+            url.hit_count,
+            url.username,
+            url.tags.join(",")
+        );
+    }
+    let pages = ((page.total + page.per_page - 1) / page.per_page).max(1);
+    println!("Page {} of {}, {} in total", page.page, pages, page.total);
+    Ok(())
+}
+
+pub async fn delete(id: i64) -> Result<(), Report<CliError>> {
+    let delete_url_service: DeleteUrlService = inject().await?;
+    delete_url_service
+        .fetch_user_id_from_url_id(id)
+        .await
+        .change_context(CliError::NotFound)
+        .attach_with(|| format!("No link with id {}", id))?;
+    delete_url_service
+        .delete_url(id)
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!("Moved link {} to the trash", id);
+    Ok(())
+}
+
+pub async fn restore(id: i64) -> Result<(), Report<CliError>> {
+    let trash_url_service: TrashUrlService = inject().await?;
+    trash_url_service
+        .restore_url(id)
+        .await
+        .change_context(CliError::NotFound)
+        .attach_with(|| format!("No link with id {} in the trash", id))?;
+
+    println!("Restored link {}", id);
+    Ok(())
+}
+
+pub async fn purge_trash() -> Result<(), Report<CliError>> {
+    let trash_url_service: TrashUrlService = inject().await?;
+    let purged = trash_url_service
+        .purge_expired(Utc::now().trunc_subsecs(0))
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!("Purged {} links from the trash", purged);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+pub mod config;
+pub mod db;
+pub mod link;
+pub mod user;
+
+use crate::common::locale::build_locale_resources;
+use error_stack::{Report, ResultExt};
+use poem::http::header::ACCEPT_LANGUAGE;
+use poem::i18n::Locale;
+use poem::{FromRequest, Request};
+use serde::Serialize;
+use serde_json::Value;
+use shared::context::{FromContext, fetch_context};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("Config error")]
+    ConfigError,
+    #[error("Database error")]
+    DatabaseError,
+    #[error("Invalid input")]
+    InvalidInput,
+    #[error("Not found")]
+    NotFound,
+    #[error("Command failed")]
+    ServiceError,
+    #[error("IO error")]
+    IoError,
+    #[error("Locale error")]
+    LocaleError,
+}
+
+async fn inject<T: FromContext>() -> Result<T, Report<CliError>> {
+    fetch_context()
+        .await
+        .change_context(CliError::DatabaseError)
+}
+
+/// The validation messages need a `Locale`, which poem only hands out per request.
+async fn locale() -> Result<Locale, Report<CliError>> {
+    let locale_resources = build_locale_resources().change_context(CliError::LocaleError)?;
+    let req = Request::builder()
+        .header(ACCEPT_LANGUAGE, "en-GB")
+        .extension(locale_resources)
+        .finish();
+    Locale::from_request_without_body(&req)
+        .await
+        .map_err(|err| Report::new(CliError::LocaleError).attach(err.to_string()))
+}
+
+/// Turns a form's validation message into a report with one line per problem.
+fn invalid_input<M: Serialize>(message: &M) -> Report<CliError> {
+    let mut report = Report::new(CliError::InvalidInput);
+    if let Ok(Value::Object(fields)) = serde_json::to_value(message) {
+        for (field, errors) in fields {
+            for error in errors.as_array().into_iter().flatten() {
+                if let Some(error) = error.as_str() {
+                    report = report.attach(format!("{}: {}", field, error));
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Uses the given password, or asks for it twice on the terminal without echoing.
+fn password_and_confirm(password: Option<String>) -> Result<(String, String), Report<CliError>> {
+    if let Some(password) = password {
+        return Ok((password.clone(), password));
+    }
+    let password = rpassword::prompt_password("Password: ").change_context(CliError::IoError)?;
+    let password_confirm =
+        rpassword::prompt_password("Confirm password: ").change_context(CliError::IoError)?;
+    Ok((password, password_confirm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::form::add_user::AddUserMessage;
+
+    #[test]
+    fn test_invalid_input_lists_each_message() {
+        let message = AddUserMessage {
+            username: vec!["Username is taken".to_string()].into(),
+            password: vec!["Too short".to_string(), "Too simple".to_string()].into(),
+            password_confirm: vec![].into(),
+        };
+
+        let report = invalid_input(&message);
+        let lines: Vec<&String> = report
+            .frames()
+            .filter_map(|frame| frame.downcast_ref::<String>())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.contains(&&"password: Too short".to_string()));
+    }
+}
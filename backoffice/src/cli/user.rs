@@ -0,0 +1,164 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::cli::{CliError, inject, invalid_input, locale, password_and_confirm};
+use crate::user::form::add_user::AddUserForm;
+use crate::user::form::edit_password_manager::EditPasswordManagerForm;
+use crate::user::form::edit_user::EditUserForm;
+use crate::user::repository::two_factor_repository::TwoFactorRepository;
+use crate::user::role::Role;
+use crate::user::service::user_manager_service::add_user_service::AddUserService;
+use crate::user::service::user_manager_service::edit_password_service::EditPasswordService;
+use crate::user::service::user_manager_service::edit_service::EditUserService;
+use crate::user::service::user_manager_service::list_service::ListUserService;
+use error_stack::{Report, ResultExt};
+
+pub(crate) async fn find_user_id(username: &str) -> Result<i64, Report<CliError>> {
+    let list_user_service: ListUserService = inject().await?;
+    list_user_service
+        .list_users()
+        .await
+        .iter()
+        .find(|user| user.username == username)
+        .map(|user| user.id)
+        .ok_or_else(|| {
+            Report::new(CliError::NotFound).attach(format!("No user named '{}'", username))
+        })
+}
+
+pub(crate) async fn first_root_user_id() -> Result<i64, Report<CliError>> {
+    let list_user_service: ListUserService = inject().await?;
+    list_user_service
+        .list_users()
+        .await
+        .iter()
+        .find(|user| user.role == Role::Root)
+        .map(|user| user.id)
+        .ok_or_else(|| Report::new(CliError::NotFound).attach("No root user"))
+}
+
+fn parse_role(role: &str) -> Result<Role, Report<CliError>> {
+    Role::try_from(role).map_err(|_| {
+        Report::new(CliError::InvalidInput)
+            .attach(format!("Unknown role '{}', expected root or user", role))
+    })
+}
+
+pub async fn add(
+    username: &str,
+    role: &str,
+    password: Option<String>,
+) -> Result<(), Report<CliError>> {
+    let role = parse_role(role)?;
+    let (password, password_confirm) = password_and_confirm(password)?;
+    let add_user_service: AddUserService = inject().await?;
+
+    let form = AddUserForm {
+        username: username.to_string(),
+        password,
+        password_confirm,
+        role,
+        ..Default::default()
+    };
+    let validated = match form.as_validated(&add_user_service).await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Err(invalid_input(&error.as_message(&locale().await?))),
+    };
+    add_user_service
+        .add_user_submit(&validated)
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!("Added user '{}'", validated.username.as_str());
+    Ok(())
+}
+
+pub async fn reset_password(
+    username: &str,
+    password: Option<String>,
+) -> Result<(), Report<CliError>> {
+    let user_id = find_user_id(username).await?;
+    let (password, password_confirm) = password_and_confirm(password)?;
+    let edit_password_service: EditPasswordService = inject().await?;
+
+    let form = EditPasswordManagerForm {
+        password,
+        password_confirm,
+        ..Default::default()
+    };
+    let validated = match form.as_validated().await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Err(invalid_input(&error.as_message(&locale().await?))),
+    };
+    edit_password_service
+        .edit_password_submit(user_id, &validated)
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    // Whoever had the old password is signed out as well.
+    let edit_user_service: EditUserService = inject().await?;
+    edit_user_service
+        .sign_out_user(user_id)
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!(
+        "Password reset for '{}', existing sessions were signed out",
+        username
+    );
+    Ok(())
+}
+
+pub async fn set_role(username: &str, role: &str) -> Result<(), Report<CliError>> {
+    let user_id = find_user_id(username).await?;
+    let role = parse_role(role)?;
+    let edit_user_service: EditUserService = inject().await?;
+
+    let form = EditUserForm {
+        username: username.to_string(),
+        role,
+        ..Default::default()
+    };
+    let validated = match form.as_validated(&edit_user_service, username).await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Err(invalid_input(&error.as_message(&locale().await?))),
+    };
+    edit_user_service
+        .edit_user_submit(user_id, &validated)
+        .await
+        .change_context(CliError::ServiceError)?;
+
+    println!("'{}' is now {}", username, String::from(&validated.role));
+    Ok(())
+}
+
+/// For someone who lost both their authenticator app and their recovery codes.
+pub async fn reset_two_factor(username: &str) -> Result<(), Report<CliError>> {
+    let user_id = find_user_id(username).await?;
+    let two_factor_repository: TwoFactorRepository = inject().await?;
+    let reset = two_factor_repository
+        .delete_totp(user_id)
+        .await
+        .change_context(CliError::DatabaseError)?;
+    if reset {
+        // Goes through the repository, so the key is not needed just to remove a secret.
+        let audit_service: AuditService = inject().await?;
+        audit_service
+            .record(
+                AuditAction::UserResetTwoFactor,
+                Some(user_id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+    }
+
+    if reset {
+        println!(
+            "Two-factor reset for '{}', they sign in with just their password now",
+            username
+        );
+    } else {
+        println!("'{}' does not have two-factor set up", username);
+    }
+    Ok(())
+}
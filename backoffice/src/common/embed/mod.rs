@@ -3,7 +3,7 @@ use rust_embed::Embed;
 use shared::embed::EmbedAsString;
 use std::collections::HashMap;
 
-pub const EMBED_PATH: &'static str = "/assets/";
+pub const EMBED_PATH: &str = "/assets/";
 
 #[derive(Embed)]
 #[folder = "$CARGO_MANIFEST_DIR/asset/embed/"]
@@ -2,8 +2,7 @@ use crate::common::html::HtmlBuilder;
 use crate::common::html::locale::top::TopBuildLocale;
 use crate::user::pointer::user_pointer::UserPointer;
 use crate::user::role::Role;
-use crate::user::route::login::LOGIN_ROUTE;
-use crate::user::route::user::USER_ROUTE;
+use crate::user::{LOGIN_ROUTE, USER_ROUTE};
 use error_stack::Report;
 use maud::{Markup, PreEscaped, html};
 use poem::i18n::Locale;
@@ -45,6 +44,13 @@ impl NavigationItem {
                 locale: "top-navigation-user".to_string(),
                 role: Role::User,
             },
+            Self {
+                name: "API".to_string(),
+                url: "/api-docs".to_string(),
+                tag: "id-tag-api-docs".to_string(),
+                locale: "top-navigation-api-docs".to_string(),
+                role: Role::User,
+            },
             Self {
                 name: "Stack".to_string(),
                 url: "/stack".to_string(),
@@ -52,6 +58,13 @@ impl NavigationItem {
                 locale: "top-navigation-stack".to_string(),
                 role: Role::Root,
             },
+            Self {
+                name: "Audit".to_string(),
+                url: "/audit".to_string(),
+                tag: "id-tag-audit".to_string(),
+                locale: "top-navigation-audit".to_string(),
+                role: Role::Root,
+            },
         ]
         .into()
     }
@@ -98,53 +111,38 @@ impl ContextHtmlBuilder {
     }
 
     pub fn attach_title(&self, title: &str) -> &Self {
-        match self.data.try_write() {
-            Ok(mut data) => {
-                data.title = Some(title.to_string());
-            }
-            Err(_) => {}
+        if let Ok(mut data) = self.data.try_write() {
+            data.title = Some(title.to_string());
         }
         self
     }
 
     pub fn attach_content(&self, content: Markup) -> &Self {
-        match self.data.try_write() {
-            Ok(mut data) => {
-                data.content = Some(content);
-            }
-            Err(_) => {}
+        if let Ok(mut data) = self.data.try_write() {
+            data.content = Some(content);
         }
         self
     }
 
     #[allow(dead_code)]
     pub fn attach_head(&self, head: Markup) -> &Self {
-        match self.data.try_write() {
-            Ok(mut data) => {
-                data.head = Some(head);
-            }
-            Err(_) => {}
+        if let Ok(mut data) = self.data.try_write() {
+            data.head = Some(head);
         }
         self
     }
 
     #[allow(dead_code)]
     pub fn attach_footer(&self, footer: Markup) -> &Self {
-        match self.data.try_write() {
-            Ok(mut data) => {
-                data.footer = Some(footer);
-            }
-            Err(_) => {}
+        if let Ok(mut data) = self.data.try_write() {
+            data.footer = Some(footer);
         }
         self
     }
 
     pub fn attach_flash(&self, flash: Flash) -> &Self {
-        match self.data.try_write() {
-            Ok(mut data) => {
-                data.flash = Some(flash);
-            }
-            Err(_) => {}
+        if let Ok(mut data) = self.data.try_write() {
+            data.flash = Some(flash);
         }
         self
     }
@@ -158,11 +156,8 @@ impl ContextHtmlBuilder {
     }
 
     pub fn set_current_tag(&self, tag: &str) -> &Self {
-        match self.data.try_write() {
-            Ok(mut data) => {
-                data.current_tag = tag.to_string();
-            }
-            Err(_) => {}
+        if let Ok(mut data) = self.data.try_write() {
+            data.current_tag = tag.to_string();
         }
         self
     }
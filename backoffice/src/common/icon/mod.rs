@@ -34,3 +34,35 @@ pub fn no_symbol_icon() -> Markup {
 pub fn document_magnifying_glass_icon() -> Markup {
     get_icon("icon/document_magnifying_glass.svg")
 }
+
+pub fn chart_bar_icon() -> Markup {
+    get_icon("icon/chart_bar.svg")
+}
+
+pub fn command_line_icon() -> Markup {
+    get_icon("icon/command_line.svg")
+}
+
+pub fn shield_check_icon() -> Markup {
+    get_icon("icon/shield_check.svg")
+}
+
+pub fn shield_exclamation_icon() -> Markup {
+    get_icon("icon/shield_exclamation.svg")
+}
+
+pub fn computer_desktop_icon() -> Markup {
+    get_icon("icon/computer_desktop.svg")
+}
+
+pub fn arrow_uturn_left_icon() -> Markup {
+    get_icon("icon/arrow_uturn_left.svg")
+}
+
+pub fn link_icon() -> Markup {
+    get_icon("icon/link.svg")
+}
+
+pub fn tag_icon() -> Markup {
+    get_icon("icon/tag.svg")
+}
@@ -1,41 +1,57 @@
+pub(crate) mod api;
+pub(crate) mod audit;
+pub mod cli;
 pub(crate) mod common;
 pub(crate) mod home;
 pub(crate) mod shorty;
 pub(crate) mod stack;
 pub(crate) mod user;
 
+use crate::api::docs::{API_DOCS_ROUTE, api_docs_route};
+use crate::api::{API_ROUTE, api_catch_all_error, api_route, require_json_around};
+use crate::audit::route::audit::{AUDIT_ROUTE, audit_route};
 use crate::common::cache::init_request_cache;
 use crate::common::embed::{AssetFilesEndPoint, EMBED_PATH};
 use crate::common::locale::build_locale_resources;
 use crate::home::home_route;
 use crate::shorty::route::shorty::{SHORTY_ROUTE, shorty_route};
+use crate::shorty::service::trash_url_service::purge_trash_periodically;
 use crate::stack::route::stack::{STACK_ROUTE, stack_route};
+use crate::user::AllowApiToken;
 use crate::user::role::user_role_check::must_be_root;
 use crate::user::role::visitor_only::visitor_redirect;
 use crate::user::route::login::login_route;
-use crate::user::route::user::{USER_ROUTE, user_route};
+use crate::user::route::user::user_route;
+use crate::user::{LOGIN_ROUTE, USER_ROUTE};
 use error_stack::{Report, ResultExt};
 use poem::listener::TcpListener;
 use poem::middleware::{CatchPanic, CookieJarManager, Csrf};
 use poem::session::{CookieConfig, CookieSession};
-use poem::{EndpointExt, IntoResponse, Server};
+use poem::{EndpointExt, IntoResponse, Route, Server};
 use shared::config::Config;
 use shared::csrf::{CSRF_PATH, route_csrf};
+use shared::db::SqliteClient;
 use shared::embed::enforce_min_js_on_prod;
 use shared::error::boot_error::MainError;
 use shared::htmx::htmx_request_around;
 use shared::log::log_poem_error;
-use user::route::login::LOGIN_ROUTE;
+use shared::shutdown::shutdown_signal;
 
 pub mod export {
+    pub use shared::config::Config;
     pub use shared::error::boot_error::MainError;
     pub use shared::log::init_log;
+    pub use shared::shutdown::trigger_shutdown;
 }
 
 pub async fn boot() -> Result<(), Report<MainError>> {
     let config = Config::fetch()
         .await
         .change_context(MainError::ConfigError)?;
+    SqliteClient::init()
+        .await
+        .change_context(MainError::DatabaseError)?;
+    tokio::spawn(purge_trash_periodically());
 
     let route = home_route();
 
@@ -44,20 +60,39 @@ pub async fn boot() -> Result<(), Report<MainError>> {
         .nest(USER_ROUTE, visitor_redirect(user_route()))
         .nest(SHORTY_ROUTE, visitor_redirect(shorty_route()))
         .nest(CSRF_PATH, route_csrf())
+        .nest(API_DOCS_ROUTE, visitor_redirect(api_docs_route()))
         .nest(STACK_ROUTE, visitor_redirect(must_be_root(stack_route())))
+        .nest(AUDIT_ROUTE, visitor_redirect(must_be_root(audit_route())))
         .nest(
             EMBED_PATH,
             enforce_min_js_on_prod(AssetFilesEndPoint::new()),
         );
 
+    let locale_resources = build_locale_resources().change_context(MainError::LocaleError)?;
+
     let route = route
         .around(htmx_request_around)
         .around(init_request_cache)
-        .data(build_locale_resources().change_context(MainError::LocaleError)?)
+        .data(locale_resources.clone())
         .with(CookieJarManager::new())
         .with(CookieSession::new(CookieConfig::new()))
         .with(Csrf::new())
-        .catch_all_error(catch_all_error)
+        .catch_all_error(catch_all_error);
+
+    // The API is kept outside of the form CSRF middleware; unsafe methods have to be sent as
+    // JSON or with an API token, which a cross-site form cannot do without a CORS preflight.
+    let api = api_route()
+        .around(require_json_around)
+        .around(init_request_cache)
+        .data(locale_resources)
+        .data(AllowApiToken)
+        .with(CookieJarManager::new())
+        .with(CookieSession::new(CookieConfig::new()))
+        .catch_all_error(api_catch_all_error);
+
+    let route = Route::new()
+        .nest(API_ROUTE, api)
+        .nest("/", route)
         .with(CatchPanic::new());
 
     match config.upgrade() {
@@ -66,10 +101,16 @@ pub async fn boot() -> Result<(), Report<MainError>> {
                 "Backoffice Listening on http://{}",
                 config.poem_backoffice.parse_address()
             );
-            Server::new(TcpListener::bind(&config.poem_backoffice.parse_address()))
-                .run(route)
+            let result = Server::new(TcpListener::bind(&config.poem_backoffice.parse_address()))
+                .run_with_graceful_shutdown(
+                    route,
+                    shutdown_signal(),
+                    Some(config.poem_backoffice.shutdown_timeout()),
+                )
                 .await
-                .change_context(MainError::IoError)
+                .change_context(MainError::IoError);
+            println!("Backoffice stopped");
+            result
         }
         None => Err(Report::new(MainError::ConfigError)),
     }
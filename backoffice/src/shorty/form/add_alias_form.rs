@@ -0,0 +1,106 @@
+use crate::common::html::validate::ValidateErrorMessageExt;
+use crate::shorty::form::locale::AliasFormLocale;
+use crate::shorty::rule::url_path::UrlPathRulesExt;
+use cjtoolkit_structured_validator::types::name::name_alias::{Field, FieldError};
+use maud::{Markup, html};
+use poem::i18n::Locale;
+use serde::{Deserialize, Serialize};
+use shared::locale::LocaleExtForResult;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default)]
+pub struct AddAliasForm {
+    pub url_path: String,
+    pub csrf_token: String,
+}
+
+impl AddAliasForm {
+    pub async fn as_validated(&self) -> AddAliasResult {
+        AddAliasResult(
+            async {
+                let url_path = Field::parse_url_path(Some(self.url_path.trim()), true);
+                match url_path {
+                    Ok(url_path) => Ok(AddAliasValidated { url_path }),
+                    Err(_) => Err(AddAliasError { url_path }),
+                }
+            }
+            .await,
+        )
+    }
+
+    pub fn as_form_markup(
+        &self,
+        locale: &Locale,
+        errors: Option<AddAliasMessage>,
+        token: Option<Markup>,
+    ) -> Markup {
+        let errors = errors.unwrap_or_default();
+        let token = token.unwrap_or_default();
+        let alias_form_locale = AliasFormLocale::new(locale);
+
+        html! {
+            h2 .mt-3 { (alias_form_locale.title_add) }
+            form hx-boost="true" hx-target="#main-content" .form method="post" {
+                (token)
+                div .form-group {
+                    label .label for="url-path" { (alias_form_locale.url_path) } br;
+                    input .form-item .w-full type="text" name="url_path" #url-path value=(self.url_path)
+                    placeholder=(alias_form_locale.url_path_placeholder) {}
+                    (errors.url_path.into_error_html())
+                }
+                div .form-group {
+                    input .btn .btn-sky-blue type="submit" value=(alias_form_locale.submit) {}
+                }
+            }
+        }
+    }
+}
+
+pub struct AddAliasValidated {
+    pub url_path: Field,
+}
+
+#[derive(Debug)]
+pub struct AddAliasError {
+    pub url_path: Result<Field, FieldError>,
+}
+
+impl AddAliasError {
+    pub fn as_message(&self, locale: &Locale) -> AddAliasMessage {
+        AddAliasMessage {
+            url_path: self.url_path.as_translated_message(locale),
+        }
+    }
+}
+
+pub struct AddAliasResult(pub Result<AddAliasValidated, AddAliasError>);
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct AddAliasMessage {
+    pub url_path: Arc<[String]>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_as_validated() {
+        let form = AddAliasForm {
+            url_path: " spring-sale ".to_string(),
+            ..Default::default()
+        };
+        let validated = form.as_validated().await.0.unwrap();
+        assert_eq!(validated.url_path.as_str(), "spring-sale");
+
+        let form = AddAliasForm::default();
+        assert!(form.as_validated().await.0.is_err());
+
+        let form = AddAliasForm {
+            url_path: "Spring_Sale".to_string(),
+            ..Default::default()
+        };
+        assert!(form.as_validated().await.0.is_err());
+    }
+}
@@ -0,0 +1,18 @@
+use crate::shorty::form::add_alias_form::AddAliasForm;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+pub struct AddAliasJson {
+    pub url_path: String,
+}
+
+impl AddAliasJson {
+    pub fn as_form(&self) -> AddAliasForm {
+        AddAliasForm {
+            url_path: self.url_path.clone(),
+            csrf_token: String::new(),
+        }
+    }
+}
@@ -1,44 +1,95 @@
 use crate::common::html::context_html::ContextHtmlBuilder;
 use crate::common::html::validate::ValidateErrorMessageExt;
 use crate::shorty::form::locale::ShortyFormLocale;
+use crate::shorty::model::shorty_model::GetUrlRedirectModel;
+use crate::shorty::rule::description::DescriptionRulesExt;
+use crate::shorty::rule::expires_at::ExpiresAtRulesExt;
+use crate::shorty::rule::max_clicks::MaxClicksRulesExt;
+use crate::shorty::rule::redirect_type::{RedirectTypeError, RedirectTypeRulesExt};
+use crate::shorty::rule::tags::{Tags, TagsError};
 use crate::shorty::rule::url_path::UrlPathRulesExt;
 use crate::shorty::rule::url_redirect::UrlRedirectRulesExt;
+use chrono::{DateTime, Utc};
 use cjtoolkit_structured_validator::common::flag_error::FlagCounter;
+use cjtoolkit_structured_validator::types::description::{Description, DescriptionError};
 use cjtoolkit_structured_validator::types::name::name_alias::{Field, FieldError};
+use cjtoolkit_structured_validator::types::numbers::unsigned::{Unsigned, UnsignedError};
+use cjtoolkit_structured_validator::types::times_chrono::naive_date_time::{
+    NaiveDateTimeError, NaiveDateTimeValue,
+};
 use cjtoolkit_structured_validator::types::url::{Url, UrlError};
 use maud::{Markup, html};
 use poem::i18n::Locale;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use shared::locale::LocaleExtForResult;
+use shared::redirect::RedirectType;
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 #[derive(Deserialize, Default)]
 pub struct AddEditUrlForm {
+    #[serde(default)]
     pub url_path: String,
     pub url_redirect: String,
+    #[serde(default)]
+    pub redirect_type: String,
+    #[serde(default)]
+    pub expires_at: String,
+    #[serde(default)]
+    pub max_clicks: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: String,
     pub csrf_token: String,
 }
 
 impl AddEditUrlForm {
-    pub async fn as_validated(&self) -> AddEditUrlResult {
+    pub async fn as_validated(&self, is_edit: bool) -> AddEditUrlResult {
         AddEditUrlResult(
             async {
                 let mut flag = FlagCounter::new();
 
-                let url_path = flag.check(Field::parse_url_path(Some(&self.url_path.trim())));
+                let url_path = self.url_path.trim();
+                let url_path = flag.check(Field::parse_url_path(
+                    Some(url_path).filter(|url_path| !url_path.is_empty()),
+                    is_edit,
+                ));
                 let url_redirect =
-                    flag.check(Url::parse_url_redirect(Some(&self.url_redirect.trim())));
+                    flag.check(Url::parse_url_redirect(Some(self.url_redirect.trim())));
+                let redirect_type = flag.check(RedirectType::parse_redirect_type(Some(
+                    self.redirect_type.trim(),
+                )));
+                let expires_at = flag.check(NaiveDateTimeValue::parse_expires_at(Some(
+                    self.expires_at.trim(),
+                )));
+                let max_clicks =
+                    flag.check(Unsigned::parse_max_clicks(Some(self.max_clicks.trim())));
+                let description = flag.check(Description::parse_url_description(Some(
+                    self.description.trim(),
+                )));
+                let tags = flag.check(Tags::parse_tags(Some(&self.tags)));
 
                 if flag.is_flagged() {
                     return Err(AddEditUrlError {
                         url_path,
                         url_redirect,
+                        redirect_type,
+                        expires_at,
+                        max_clicks,
+                        description,
+                        tags,
                     });
                 }
 
                 Ok(AddEditUrlValidated {
                     url_path: url_path.expect("Url path is not empty"),
                     url_redirect: url_redirect.expect("Url redirect is not empty"),
+                    redirect_type: redirect_type.expect("Redirect type is valid"),
+                    expires_at: expires_at.expect("Expires at is valid"),
+                    max_clicks: max_clicks.expect("Max clicks is valid"),
+                    description: description.expect("Description is valid"),
+                    tags: tags.expect("Tags are valid"),
                 })
             }
             .await,
@@ -51,11 +102,15 @@ impl AddEditUrlForm {
         errors: Option<AddEditUrlMessage>,
         token: Option<Markup>,
         is_edit: bool,
+        history: Option<Markup>,
     ) -> Markup {
         let errors = errors.unwrap_or_default();
         let token = token.unwrap_or_default();
+        let history = history.unwrap_or_default();
 
         let user_form_locale = ShortyFormLocale::new(&context_html_builder.locale);
+        let current_redirect_type =
+            RedirectType::parse_redirect_type(Some(&self.redirect_type)).unwrap_or_default();
         let title = if is_edit {
             &user_form_locale.title_edit
         } else {
@@ -69,7 +124,7 @@ impl AddEditUrlForm {
                 div .form-group {
                     label .label for="url-path" { (&user_form_locale.url_path) } br;
                     input .form-item .w-full type="text" name="url_path" #url-path value=(self.url_path)
-                    placeholder=(&user_form_locale.url_path_placeholder) {}
+                    placeholder=(if is_edit { &user_form_locale.url_path_placeholder } else { &user_form_locale.url_path_generate_placeholder }) {}
                     (errors.url_path.into_error_html())
                 }
                 div .form-group {
@@ -78,10 +133,45 @@ impl AddEditUrlForm {
                     placeholder=(&user_form_locale.url_redirect_placeholder) {}
                     (errors.url_redirect.into_error_html())
                 }
+                div .form-group {
+                    label .label for="redirect-type" { (&user_form_locale.redirect_type) } br;
+                    select .form-item .w-full name="redirect_type" #redirect-type {
+                        @for redirect_type in RedirectType::ALL {
+                            option value=(redirect_type.code()) selected[redirect_type == current_redirect_type] {
+                                (user_form_locale.redirect_type_label(redirect_type))
+                            }
+                        }
+                    }
+                    (errors.redirect_type.into_error_html())
+                }
+                div .form-group {
+                    label .label for="expires-at" { (&user_form_locale.expires_at) } br;
+                    input .form-item .w-full type="datetime-local" name="expires_at" #expires-at value=(self.expires_at) {}
+                    (errors.expires_at.into_error_html())
+                }
+                div .form-group {
+                    label .label for="max-clicks" { (&user_form_locale.max_clicks) } br;
+                    input .form-item .w-full type="number" min="1" name="max_clicks" #max-clicks value=(self.max_clicks)
+                    placeholder=(&user_form_locale.max_clicks_placeholder) {}
+                    (errors.max_clicks.into_error_html())
+                }
+                div .form-group {
+                    label .label for="description" { (&user_form_locale.description) } br;
+                    textarea .form-item .w-full name="description" #description rows="3"
+                    placeholder=(&user_form_locale.description_placeholder) { (self.description) }
+                    (errors.description.into_error_html())
+                }
+                div .form-group {
+                    label .label for="tags" { (&user_form_locale.tags) } br;
+                    input .form-item .w-full type="text" name="tags" #tags value=(self.tags)
+                    placeholder=(&user_form_locale.tags_placeholder) {}
+                    (errors.tags.into_error_html())
+                }
                 div .form-group {
                     input .btn .btn-sky-blue type="submit" value=(&user_form_locale.submit_button) {}
                 }
             }
+            (history)
         }).build()
     }
 }
@@ -89,12 +179,55 @@ impl AddEditUrlForm {
 pub struct AddEditUrlValidated {
     pub url_path: Field,
     pub url_redirect: Url,
+    pub redirect_type: RedirectType,
+    pub expires_at: NaiveDateTimeValue,
+    pub max_clicks: Unsigned,
+    pub description: Description,
+    pub tags: Tags,
+}
+
+impl AddEditUrlValidated {
+    pub fn url_path(&self) -> Option<&str> {
+        Some(self.url_path.as_str()).filter(|url_path| !url_path.is_empty())
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+            .as_naive_date_time()
+            .map(|expires_at| expires_at.and_utc())
+    }
+
+    pub fn max_clicks(&self) -> Option<i64> {
+        self.max_clicks
+            .clone()
+            .into_option()
+            .map(|max_clicks| max_clicks.as_usize() as i64)
+    }
+
+    /// The link as it is stored, for the audit log.
+    pub fn as_url_redirect_model(&self, url_path: &str) -> GetUrlRedirectModel {
+        GetUrlRedirectModel {
+            url_path: url_path.to_string(),
+            url_redirect: self.url_redirect.as_str().to_string(),
+            redirect_type: self.redirect_type,
+            expires_at: self.expires_at(),
+            max_clicks: self.max_clicks(),
+            description: Some(self.description.as_str().to_string())
+                .filter(|description| !description.is_empty()),
+            tags: self.tags.as_slice().to_vec(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct AddEditUrlError {
     pub url_path: Result<Field, FieldError>,
     pub url_redirect: Result<Url, UrlError>,
+    pub redirect_type: Result<RedirectType, RedirectTypeError>,
+    pub expires_at: Result<NaiveDateTimeValue, NaiveDateTimeError>,
+    pub max_clicks: Result<Unsigned, UnsignedError>,
+    pub description: Result<Description, DescriptionError>,
+    pub tags: Result<Tags, TagsError>,
 }
 
 impl AddEditUrlError {
@@ -102,14 +235,24 @@ impl AddEditUrlError {
         AddEditUrlMessage {
             url_path: self.url_path.as_translated_message(locale),
             url_redirect: self.url_redirect.as_translated_message(locale),
+            redirect_type: self.redirect_type.as_translated_message(locale),
+            expires_at: self.expires_at.as_translated_message(locale),
+            max_clicks: self.max_clicks.as_translated_message(locale),
+            description: self.description.as_translated_message(locale),
+            tags: self.tags.as_translated_message(locale),
         }
     }
 }
 
 pub struct AddEditUrlResult(pub Result<AddEditUrlValidated, AddEditUrlError>);
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, ToSchema)]
 pub struct AddEditUrlMessage {
     pub url_path: Arc<[String]>,
     pub url_redirect: Arc<[String]>,
+    pub redirect_type: Arc<[String]>,
+    pub expires_at: Arc<[String]>,
+    pub max_clicks: Arc<[String]>,
+    pub description: Arc<[String]>,
+    pub tags: Arc<[String]>,
 }
@@ -0,0 +1,41 @@
+use crate::shorty::form::add_edit_url_form::AddEditUrlForm;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+pub struct AddEditUrlJson {
+    /// Leave blank when adding to generate a short code.
+    pub url_path: String,
+    pub url_redirect: String,
+    #[schema(example = 303)]
+    pub redirect_type: Option<u16>,
+    #[schema(example = "2030-01-01T10:00")]
+    pub expires_at: Option<String>,
+    pub max_clicks: Option<i64>,
+    /// Free text matched by search alongside the path and destination.
+    pub description: Option<String>,
+    #[schema(example = json!(["q3-conference", "newsletter"]))]
+    pub tags: Option<Vec<String>>,
+}
+
+impl AddEditUrlJson {
+    pub fn as_form(&self) -> AddEditUrlForm {
+        AddEditUrlForm {
+            url_path: self.url_path.clone(),
+            url_redirect: self.url_redirect.clone(),
+            redirect_type: self
+                .redirect_type
+                .map(|redirect_type| redirect_type.to_string())
+                .unwrap_or_default(),
+            expires_at: self.expires_at.clone().unwrap_or_default(),
+            max_clicks: self
+                .max_clicks
+                .map(|max_clicks| max_clicks.to_string())
+                .unwrap_or_default(),
+            description: self.description.clone().unwrap_or_default(),
+            tags: self.tags.clone().unwrap_or_default().join(","),
+            csrf_token: String::new(),
+        }
+    }
+}
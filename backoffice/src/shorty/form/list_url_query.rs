@@ -0,0 +1,118 @@
+use crate::shorty::model::shorty_model::{
+    ListUrlRedirectFilter, ListUrlRedirectSort, ListUrlRedirectSortColumn, SortOrder,
+};
+use serde::{Deserialize, Serialize, Serializer};
+use utoipa::IntoParams;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[serde(default)]
+#[into_params(parameter_in = Query)]
+pub struct ListUrlQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<i64>,
+    /// Matches part of the path or the destination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// Only links created by this user id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<i64>,
+    /// Only links with this tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_sort_column"
+    )]
+    pub sort: Option<ListUrlRedirectSortColumn>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_sort_order"
+    )]
+    pub order: Option<SortOrder>,
+}
+
+// serde_qs writes unit variants as `sort[clicks]`, which it cannot read back.
+fn serialize_sort_column<S: Serializer>(
+    column: &Option<ListUrlRedirectSortColumn>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    column.map(|column| column.as_key()).serialize(serializer)
+}
+
+fn serialize_sort_order<S: Serializer>(
+    order: &Option<SortOrder>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    order.map(|order| order.as_key()).serialize(serializer)
+}
+
+impl ListUrlQuery {
+    pub fn filter(&self) -> ListUrlRedirectFilter {
+        ListUrlRedirectFilter {
+            search: self.search.clone(),
+            created_by_user_id: self.created_by,
+            tag: self.tag.clone(),
+        }
+    }
+
+    pub fn sort(&self) -> ListUrlRedirectSort {
+        ListUrlRedirectSort {
+            column: self.sort.unwrap_or_default(),
+            order: self.order.unwrap_or_default(),
+        }
+    }
+
+    pub fn with_page(&self, page: i64) -> Self {
+        Self {
+            page: Some(page),
+            ..self.clone()
+        }
+    }
+
+    /// Sorting by the current column flips the order, any other column starts ascending.
+    pub fn with_sort(&self, column: ListUrlRedirectSortColumn) -> Self {
+        let current = self.sort();
+        let order = if current.column == column {
+            current.order.toggled()
+        } else {
+            SortOrder::Asc
+        };
+        Self {
+            page: None,
+            sort: Some(column),
+            order: Some(order),
+            ..self.clone()
+        }
+    }
+
+    pub fn as_query_string(&self) -> String {
+        serde_qs::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_sort_toggles_current_column_and_resets_page() {
+        let query = ListUrlQuery {
+            page: Some(3),
+            search: Some("sale".to_string()),
+            sort: Some(ListUrlRedirectSortColumn::Clicks),
+            ..Default::default()
+        };
+
+        let same = query.with_sort(ListUrlRedirectSortColumn::Clicks);
+        assert_eq!(same.page, None);
+        assert_eq!(same.order, Some(SortOrder::Desc));
+        assert_eq!(same.as_query_string(), "search=sale&sort=clicks&order=desc");
+        let parsed: ListUrlQuery = serde_qs::from_str(&same.as_query_string()).unwrap();
+        assert_eq!(parsed.sort(), same.sort());
+
+        let other = query.with_sort(ListUrlRedirectSortColumn::Path);
+        assert_eq!(other.order, Some(SortOrder::Asc));
+    }
+}
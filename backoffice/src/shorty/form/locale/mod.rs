@@ -1,13 +1,28 @@
 use poem::i18n::Locale;
 use shared::locale::LocaleExt;
+use shared::redirect::RedirectType;
 
 pub struct ShortyFormLocale {
     pub title_edit: String,
     pub title_add: String,
     pub url_path: String,
     pub url_path_placeholder: String,
+    pub url_path_generate_placeholder: String,
     pub url_redirect: String,
     pub url_redirect_placeholder: String,
+    pub redirect_type: String,
+    pub redirect_type_moved_permanently: String,
+    pub redirect_type_found: String,
+    pub redirect_type_see_other: String,
+    pub redirect_type_temporary_redirect: String,
+    pub redirect_type_permanent_redirect: String,
+    pub expires_at: String,
+    pub max_clicks: String,
+    pub max_clicks_placeholder: String,
+    pub description: String,
+    pub description_placeholder: String,
+    pub tags: String,
+    pub tags_placeholder: String,
     pub submit_button: String,
 }
 
@@ -18,10 +33,78 @@ impl ShortyFormLocale {
             title_add: l.text_with_default("shorty-form-title-add", "Add Url"),
             url_path: l.text_with_default("shorty-form-url-path", "Path:"),
             url_path_placeholder: l.text_with_default("shorty-form-url-path-placeholder", "Path"),
+            url_path_generate_placeholder: l.text_with_default(
+                "shorty-form-url-path-generate-placeholder",
+                "Leave blank to generate",
+            ),
             url_redirect: l.text_with_default("shorty-form-url-redirect", "Redirect To:"),
             url_redirect_placeholder: l
                 .text_with_default("shorty-form-url-redirect-placeholder", "Redirect To"),
+            redirect_type: l.text_with_default("shorty-form-redirect-type", "Redirect Type:"),
+            redirect_type_moved_permanently: l.text_with_default(
+                "shorty-form-redirect-type-moved-permanently",
+                "301 Moved Permanently (cacheable)",
+            ),
+            redirect_type_found: l
+                .text_with_default("shorty-form-redirect-type-found", "302 Found (not cached)"),
+            redirect_type_see_other: l.text_with_default(
+                "shorty-form-redirect-type-see-other",
+                "303 See Other (not cached)",
+            ),
+            redirect_type_temporary_redirect: l.text_with_default(
+                "shorty-form-redirect-type-temporary-redirect",
+                "307 Temporary Redirect (not cached)",
+            ),
+            redirect_type_permanent_redirect: l.text_with_default(
+                "shorty-form-redirect-type-permanent-redirect",
+                "308 Permanent Redirect (cacheable)",
+            ),
+            expires_at: l.text_with_default("shorty-form-expires-at", "Expires At (UTC):"),
+            max_clicks: l.text_with_default("shorty-form-max-clicks", "Max Clicks:"),
+            max_clicks_placeholder: l
+                .text_with_default("shorty-form-max-clicks-placeholder", "Unlimited"),
+            description: l.text_with_default("shorty-form-description", "Description:"),
+            description_placeholder: l.text_with_default(
+                "shorty-form-description-placeholder",
+                "What the link is for, used by search",
+            ),
+            tags: l.text_with_default("shorty-form-tags", "Tags:"),
+            tags_placeholder: l.text_with_default(
+                "shorty-form-tags-placeholder",
+                "Comma separated, e.g. q3-conference, newsletter",
+            ),
             submit_button: l.text_with_default("shorty-form-submit-button", "Save"),
         }
     }
 }
+
+impl ShortyFormLocale {
+    pub fn redirect_type_label(&self, redirect_type: RedirectType) -> &str {
+        match redirect_type {
+            RedirectType::MovedPermanently => &self.redirect_type_moved_permanently,
+            RedirectType::Found => &self.redirect_type_found,
+            RedirectType::SeeOther => &self.redirect_type_see_other,
+            RedirectType::TemporaryRedirect => &self.redirect_type_temporary_redirect,
+            RedirectType::PermanentRedirect => &self.redirect_type_permanent_redirect,
+        }
+    }
+}
+
+pub struct AliasFormLocale {
+    pub title_add: String,
+    pub url_path: String,
+    pub url_path_placeholder: String,
+    pub submit: String,
+}
+
+impl AliasFormLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title_add: l.text_with_default("shorty-form-alias-title-add", "Add Alias"),
+            url_path: l.text_with_default("shorty-form-alias-url-path", "Path:"),
+            url_path_placeholder: l
+                .text_with_default("shorty-form-alias-url-path-placeholder", "Path"),
+            submit: l.text_with_default("shorty-form-alias-submit", "Add"),
+        }
+    }
+}
@@ -1,2 +1,6 @@
+pub mod add_alias_form;
+pub mod add_alias_json;
 pub mod add_edit_url_form;
+pub mod add_edit_url_json;
+pub mod list_url_query;
 pub mod locale;
@@ -1 +1,2 @@
 pub mod shorty_model;
+pub mod shorty_stats_model;
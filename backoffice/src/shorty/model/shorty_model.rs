@@ -1,22 +1,156 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::redirect::RedirectType;
+use std::sync::Arc;
+use utoipa::ToSchema;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ListUrlRedirectModel {
     pub id: i64,
     pub url_path: String,
     pub url_redirect: String,
+    #[schema(value_type = u16, example = 303)]
+    pub redirect_type: RedirectType,
     pub created_at: DateTime<Utc>,
     pub created_by_user_id: i64,
     pub username: String,
+    pub hit_count: i64,
+    pub recent_hit_count: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_clicks: Option<i64>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListUrlRedirectPageModel {
+    pub items: Arc<[ListUrlRedirectModel]>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ListUrlRedirectFilter {
+    /// An FTS5 query against the path, destination and description.
+    pub search: Option<String>,
+    pub created_by_user_id: Option<i64>,
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListUrlRedirectSortColumn {
+    #[default]
+    Id,
+    Path,
+    CreatedAt,
+    Creator,
+    Clicks,
+}
+
+impl ListUrlRedirectSortColumn {
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Path => "path",
+            Self::CreatedAt => "created_at",
+            Self::Creator => "creator",
+            Self::Clicks => "clicks",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            Self::Asc => "asc",
+            Self::Desc => "desc",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ListUrlRedirectSort {
+    pub column: ListUrlRedirectSortColumn,
+    pub order: SortOrder,
+}
+
+/// Also what a link is added or edited with.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct GetUrlRedirectModel {
     pub url_path: String,
     pub url_redirect: String,
+    pub redirect_type: RedirectType,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_clicks: Option<i64>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Default)]
 pub struct GetUserIdByUrlIdModel {
     pub created_by_user_id: i64,
 }
+
+#[derive(Debug)]
+pub struct TrashedUrlRedirectModel {
+    pub id: i64,
+    pub url_path: String,
+    pub url_redirect: String,
+    pub created_by_user_id: i64,
+    pub username: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UrlRedirectAliasModel {
+    pub id: i64,
+    pub url_path: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Only links outside the trash are counted.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UrlTagCountModel {
+    pub tag: String,
+    pub link_count: i64,
+}
+
+/// How many links a bulk action on a tag changed.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UrlTagBulkModel {
+    pub tag: String,
+    pub link_count: i64,
+}
+
+/// `username` is empty when the user who made the change has since been removed.
+#[derive(Debug, Clone, Default)]
+pub struct UrlRedirectRevisionModel {
+    pub id: i64,
+    pub url_path: String,
+    pub url_redirect: String,
+    pub username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AddUrlRedirectModel {
+    pub id: i64,
+    pub url_path: String,
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitBucketModel {
+    pub bucket: String,
+    pub hits: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitLabelModel {
+    pub label: Option<String>,
+    pub hits: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeviceClass {
+    Desktop,
+    Mobile,
+    Tablet,
+    Bot,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceClassHitModel {
+    pub device_class: DeviceClass,
+    pub hits: i64,
+}
+
+#[derive(Debug)]
+pub struct UrlStatsModel {
+    pub url_path: String,
+    pub url_redirect: String,
+    pub hit_count: i64,
+    pub period_hit_count: i64,
+    pub daily: Arc<[HitBucketModel]>,
+    pub hourly: Arc<[HitBucketModel]>,
+    pub top_referers: Arc<[HitLabelModel]>,
+    pub top_user_agents: Arc<[HitLabelModel]>,
+    pub browser_families: Arc<[HitLabelModel]>,
+    pub device_classes: Arc<[DeviceClassHitModel]>,
+}
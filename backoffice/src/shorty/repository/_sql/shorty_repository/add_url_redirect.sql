@@ -1,2 +1,3 @@
-insert into url_redirect (url_path, url_redirect, created_at, created_by_user_id)
-values (:url_path, :url_redirect, datetime(), :user_id);
\ No newline at end of file
+insert into url_redirect (url_path, url_redirect, redirect_type, created_at, created_by_user_id, expires_at,
+                          max_clicks, description)
+values (:url_path, :url_redirect, :redirect_type, datetime(), :user_id, :expires_at, :max_clicks, nullif(:description, ''));
\ No newline at end of file
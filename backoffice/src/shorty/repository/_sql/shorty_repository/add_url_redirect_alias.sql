@@ -0,0 +1,2 @@
+insert into url_redirect_alias (url_redirect_id, url_path, created_at)
+values (:url_redirect_id, :url_path, datetime())
\ No newline at end of file
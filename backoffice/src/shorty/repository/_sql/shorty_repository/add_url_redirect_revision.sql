@@ -0,0 +1,9 @@
+insert into url_redirect_revision (url_redirect_id, url_path, url_redirect, changed_by_user_id, created_at)
+select ur.id, ur.url_path, ur.url_redirect, :changed_by_user_id, :created_at
+from url_redirect ur
+where ur.id = :url_redirect_id
+  and (ur.url_path, ur.url_redirect) is not (select urr.url_path, urr.url_redirect
+                                             from url_redirect_revision urr
+                                             where urr.url_redirect_id = ur.id
+                                             order by urr.id desc
+                                             limit 1)
\ No newline at end of file
@@ -0,0 +1,2 @@
+insert into url_redirect_tag (url_redirect_id, tag)
+values (:url_redirect_id, :tag)
\ No newline at end of file
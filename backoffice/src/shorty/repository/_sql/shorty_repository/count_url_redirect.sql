@@ -0,0 +1,6 @@
+select count(*) as total
+from url_redirect as ur
+where ur.deleted_at is null
+  and (:search is null or ur.id in (select rowid from url_redirect_search where url_redirect_search match :search))
+  and (:created_by_user_id is null or ur.created_by_user_id = :created_by_user_id)
+  and (:tag is null or ur.id in (select url_redirect_id from url_redirect_tag where tag = :tag))
\ No newline at end of file
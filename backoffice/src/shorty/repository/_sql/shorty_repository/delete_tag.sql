@@ -0,0 +1,5 @@
+delete
+from url_redirect_tag
+where tag = :tag
+  and (:created_by_user_id is null or
+       url_redirect_id in (select id from url_redirect where created_by_user_id = :created_by_user_id))
\ No newline at end of file
@@ -1,3 +1,4 @@
-delete
-from url_redirect
-where id = :id
\ No newline at end of file
+update url_redirect
+set deleted_at=:deleted_at
+where id = :id
+  and deleted_at is null
\ No newline at end of file
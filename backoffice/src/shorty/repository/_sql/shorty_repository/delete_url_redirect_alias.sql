@@ -0,0 +1,5 @@
+delete
+from url_redirect_alias
+where id = :id
+  and url_redirect_id = :url_redirect_id
+returning url_path
\ No newline at end of file
@@ -0,0 +1,4 @@
+delete
+from url_redirect_alias
+where url_redirect_id = :url_redirect_id
+  and url_path = :url_path
\ No newline at end of file
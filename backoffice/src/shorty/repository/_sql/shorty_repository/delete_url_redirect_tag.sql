@@ -0,0 +1,3 @@
+delete
+from url_redirect_tag
+where url_redirect_id = :url_redirect_id
\ No newline at end of file
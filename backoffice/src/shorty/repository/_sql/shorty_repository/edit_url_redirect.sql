@@ -1,4 +1,9 @@
 update url_redirect
 set url_path=:url_path,
-    url_redirect=:url_redirect
-where id = :id
\ No newline at end of file
+    url_redirect=:url_redirect,
+    redirect_type=:redirect_type,
+    expires_at=:expires_at,
+    max_clicks=:max_clicks,
+    description=nullif(:description, '')
+where id = :id
+  and deleted_at is null
\ No newline at end of file
@@ -0,0 +1,4 @@
+select url_path
+from url_redirect
+where id = :id
+  and deleted_at is null
\ No newline at end of file
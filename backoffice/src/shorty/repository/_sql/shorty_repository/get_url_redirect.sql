@@ -1,3 +1,12 @@
-select url_path, url_redirect
+select url_path,
+       url_redirect,
+       redirect_type,
+       expires_at,
+       max_clicks,
+       description,
+       (select group_concat(urt.tag, ',')
+        from url_redirect_tag as urt
+        where urt.url_redirect_id = url_redirect.id) as tags
 from url_redirect
-where id = :id
\ No newline at end of file
+where id = :id
+  and deleted_at is null
\ No newline at end of file
@@ -0,0 +1,22 @@
+select ur.id,
+       ur.url_path,
+       ur.url_redirect,
+       ur.redirect_type,
+       ur.created_at,
+       bu.username,
+       ur.created_by_user_id,
+       ur.hit_count,
+       (select count(*)
+        from url_redirect_hit as urh
+        where urh.url_redirect_id = ur.id
+          and urh.hit_at >= datetime('now', '-7 days')) as recent_hit_count,
+       ur.expires_at,
+       ur.max_clicks,
+       ur.description,
+       (select group_concat(urt.tag, ',')
+        from url_redirect_tag as urt
+        where urt.url_redirect_id = ur.id) as tags
+from url_redirect as ur
+         inner join backoffice_users bu on bu.id = ur.created_by_user_id
+where ur.id = :id
+  and ur.deleted_at is null
\ No newline at end of file
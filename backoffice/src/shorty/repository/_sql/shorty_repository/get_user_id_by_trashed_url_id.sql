@@ -0,0 +1,4 @@
+select created_by_user_id
+from url_redirect
+where id = :id
+  and deleted_at is not null
\ No newline at end of file
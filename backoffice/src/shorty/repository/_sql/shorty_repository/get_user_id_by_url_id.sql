@@ -1,3 +1,4 @@
 select created_by_user_id
 from url_redirect
-where id = :id
\ No newline at end of file
+where id = :id
+  and deleted_at is null
\ No newline at end of file
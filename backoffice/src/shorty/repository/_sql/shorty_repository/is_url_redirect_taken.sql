@@ -0,0 +1,4 @@
+select exists(select 1
+              from url_redirect
+              where url_redirect = :url_redirect
+                and id is not :except_id) as taken
\ No newline at end of file
@@ -0,0 +1,10 @@
+select ur.id,
+       ur.url_path,
+       ur.url_redirect,
+       ur.created_by_user_id,
+       bu.username,
+       ur.deleted_at
+from url_redirect as ur
+         inner join backoffice_users bu on bu.id = ur.created_by_user_id
+where ur.deleted_at is not null
+order by ur.deleted_at desc, ur.id desc
\ No newline at end of file
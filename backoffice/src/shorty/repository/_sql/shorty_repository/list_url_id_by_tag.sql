@@ -0,0 +1,6 @@
+select ur.id
+from url_redirect as ur
+where ur.deleted_at is null
+  and ur.id in (select url_redirect_id from url_redirect_tag where tag = :tag)
+  and (:created_by_user_id is null or ur.created_by_user_id = :created_by_user_id)
+order by ur.id
\ No newline at end of file
@@ -0,0 +1,4 @@
+select id, url_path, created_at
+from url_redirect_alias
+where url_redirect_id = :url_redirect_id
+order by url_path
\ No newline at end of file
@@ -0,0 +1,44 @@
+select ur.id,
+       ur.url_path,
+       ur.url_redirect,
+       ur.redirect_type,
+       ur.created_at,
+       bu.username,
+       ur.created_by_user_id,
+       ur.hit_count,
+       (select count(*)
+        from url_redirect_hit as urh
+        where urh.url_redirect_id = ur.id
+          and urh.hit_at >= datetime('now', '-7 days')) as recent_hit_count,
+       ur.expires_at,
+       ur.max_clicks,
+       ur.description,
+       (select group_concat(urt.tag, ',')
+        from url_redirect_tag as urt
+        where urt.url_redirect_id = ur.id) as tags
+from url_redirect as ur
+         inner join backoffice_users bu on bu.id = ur.created_by_user_id
+where ur.deleted_at is null
+  and (:search is null or ur.id in (select rowid from url_redirect_search where url_redirect_search match :search))
+  and (:created_by_user_id is null or ur.created_by_user_id = :created_by_user_id)
+  and (:tag is null or ur.id in (select url_redirect_id from url_redirect_tag where tag = :tag))
+order by case when :sort_order = 'asc' then
+                  case :sort_column
+                      when 'path' then ur.url_path
+                      when 'created_at' then ur.created_at
+                      when 'creator' then bu.username
+                      when 'clicks' then ur.hit_count
+                      else ur.id
+                      end
+             end asc,
+         case when :sort_order = 'desc' then
+                  case :sort_column
+                      when 'path' then ur.url_path
+                      when 'created_at' then ur.created_at
+                      when 'creator' then bu.username
+                      when 'clicks' then ur.hit_count
+                      else ur.id
+                      end
+             end desc,
+         ur.id asc
+limit :limit offset :offset
\ No newline at end of file
@@ -0,0 +1,9 @@
+select urr.id,
+       urr.url_path,
+       urr.url_redirect,
+       bu.username,
+       urr.created_at
+from url_redirect_revision as urr
+         left join backoffice_users bu on bu.id = urr.changed_by_user_id
+where urr.url_redirect_id = :url_redirect_id
+order by urr.id desc
\ No newline at end of file
@@ -0,0 +1,6 @@
+select urt.tag, count(*) as link_count
+from url_redirect_tag as urt
+         inner join url_redirect as ur on ur.id = urt.url_redirect_id
+where ur.deleted_at is null
+group by urt.tag
+order by urt.tag
\ No newline at end of file
@@ -0,0 +1,4 @@
+delete
+from url_redirect
+where deleted_at < :deleted_before
+returning id
\ No newline at end of file
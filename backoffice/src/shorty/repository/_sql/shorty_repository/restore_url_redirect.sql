@@ -0,0 +1,5 @@
+update url_redirect
+set deleted_at=null
+where id = :id
+  and deleted_at is not null
+returning url_path
\ No newline at end of file
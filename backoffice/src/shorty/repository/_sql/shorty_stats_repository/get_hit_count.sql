@@ -0,0 +1,3 @@
+select hit_count
+from url_redirect
+where id = :id
\ No newline at end of file
@@ -0,0 +1,6 @@
+select strftime('%Y-%m-%d %H:00', hit_at) as bucket, count(*) as hits
+from url_redirect_hit
+where url_redirect_id = :id
+  and hit_at >= :since
+group by bucket
+order by bucket asc
\ No newline at end of file
@@ -0,0 +1,7 @@
+select referer as label, count(*) as hits
+from url_redirect_hit
+where url_redirect_id = :id
+  and hit_at >= :since
+group by referer
+order by hits desc
+limit :limit
\ No newline at end of file
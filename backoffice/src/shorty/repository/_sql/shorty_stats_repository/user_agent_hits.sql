@@ -0,0 +1,6 @@
+select user_agent as label, count(*) as hits
+from url_redirect_hit
+where url_redirect_id = :id
+  and hit_at >= :since
+group by user_agent
+order by hits desc
\ No newline at end of file
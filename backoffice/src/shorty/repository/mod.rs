@@ -1 +1,2 @@
 pub mod shorty_repository;
+pub mod shorty_stats_repository;
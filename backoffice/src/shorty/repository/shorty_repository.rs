@@ -1,12 +1,15 @@
 use crate::shorty::model::shorty_model::{
-    GetUrlRedirectModel, GetUserIdByUrlIdModel, ListUrlRedirectModel,
+    GetUrlRedirectModel, GetUserIdByUrlIdModel, ListUrlRedirectFilter, ListUrlRedirectModel,
+    ListUrlRedirectSort, TrashedUrlRedirectModel, UrlRedirectAliasModel, UrlRedirectRevisionModel,
+    UrlTagCountModel,
 };
+use chrono::{DateTime, Utc};
 use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
-use rusqlite::{Connection, OptionalExtension, named_params};
+use rusqlite::{Connection, OptionalExtension, Row, named_params};
 use shared::context::{Context, ContextError, FromContext};
-use shared::db::{BorrowConnectionExt, SqliteClient};
-use std::sync::{Arc, MutexGuard};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -17,6 +20,147 @@ pub enum ShortyRepositoryError {
     RowValueError,
     #[error("Borrow Conn error")]
     BorrowConnError,
+    #[error("Url path is taken")]
+    UrlPathTaken,
+    #[error("Url redirect is taken")]
+    UrlRedirectTaken,
+}
+
+/// Paths are unique across links and aliases, the triggers guard the paths shared between them.
+fn is_url_path_conflict(err: &rusqlite::Error) -> bool {
+    matches!(
+        err,
+        rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error {
+                extended_code: rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE,
+                ..
+            },
+            Some(message),
+        ) if message.contains("url_redirect.url_path") || message.contains("url_redirect_alias.url_path")
+    ) || matches!(
+        err,
+        rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error {
+                extended_code: rusqlite::ffi::SQLITE_CONSTRAINT_TRIGGER,
+                ..
+            },
+            Some(message),
+        ) if message == "url_path is taken"
+    )
+}
+
+fn url_path_error(err: rusqlite::Error) -> Report<ShortyRepositoryError> {
+    if is_url_path_conflict(&err) {
+        Report::new(err)
+            .change_context(ShortyRepositoryError::UrlPathTaken)
+            .attach(StatusCode::CONFLICT)
+    } else {
+        Report::new(err)
+            .change_context(ShortyRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+/// Checked in the same transaction as the write, so two links cannot race to the same destination.
+fn check_url_redirect_in(
+    conn: &Connection,
+    url_redirect: &str,
+    except_id: Option<i64>,
+) -> Result<(), Report<ShortyRepositoryError>> {
+    let taken: bool = conn
+        .query_one(
+            include_str!("_sql/shorty_repository/is_url_redirect_taken.sql"),
+            named_params! {
+                ":url_redirect": url_redirect,
+                ":except_id": except_id,
+            },
+            |row| row.get("taken"),
+        )
+        .change_context(ShortyRepositoryError::RowValueError)
+        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    if taken {
+        return Err(
+            Report::new(ShortyRepositoryError::UrlRedirectTaken).attach(StatusCode::CONFLICT)
+        );
+    }
+    Ok(())
+}
+
+/// Skipped when the path and destination are the same as the latest revision.
+fn add_revision_in(
+    conn: &Connection,
+    url_redirect_id: i64,
+    changed_by_user_id: i64,
+    created_at: DateTime<Utc>,
+) -> Result<(), Report<ShortyRepositoryError>> {
+    conn.execute(
+        include_str!("_sql/shorty_repository/add_url_redirect_revision.sql"),
+        named_params! {
+            ":url_redirect_id": url_redirect_id,
+            ":changed_by_user_id": changed_by_user_id,
+            ":created_at": created_at,
+        },
+    )
+    .change_context(ShortyRepositoryError::QueryError)
+    .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(())
+}
+
+fn list_url_redirect_from_row(row: &Row) -> rusqlite::Result<ListUrlRedirectModel> {
+    Ok(ListUrlRedirectModel {
+        id: row.get("id")?,
+        url_path: row.get("url_path")?,
+        url_redirect: row.get("url_redirect")?,
+        redirect_type: row.get("redirect_type")?,
+        created_at: row.get("created_at")?,
+        created_by_user_id: row.get("created_by_user_id")?,
+        username: row.get("username")?,
+        hit_count: row.get("hit_count")?,
+        recent_hit_count: row.get("recent_hit_count")?,
+        expires_at: row.get("expires_at")?,
+        max_clicks: row.get("max_clicks")?,
+        description: row.get("description")?,
+        tags: tags_from_column(row.get("tags")?),
+    })
+}
+
+/// Tags come back joined by commas, which they cannot contain.
+fn tags_from_column(tags: Option<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .unwrap_or_default()
+        .split(',')
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+    tags.sort();
+    tags
+}
+
+fn set_tags_in(
+    conn: &Connection,
+    url_redirect_id: i64,
+    tags: &[String],
+) -> Result<(), Report<ShortyRepositoryError>> {
+    conn.execute(
+        include_str!("_sql/shorty_repository/delete_url_redirect_tag.sql"),
+        named_params! {
+            ":url_redirect_id": url_redirect_id,
+        },
+    )
+    .change_context(ShortyRepositoryError::QueryError)
+    .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    for tag in tags {
+        conn.execute(
+            include_str!("_sql/shorty_repository/add_url_redirect_tag.sql"),
+            named_params! {
+                ":url_redirect_id": url_redirect_id,
+                ":tag": tag,
+            },
+        )
+        .change_context(ShortyRepositoryError::QueryError)
+        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+    Ok(())
 }
 
 #[mry::mry]
@@ -32,165 +176,720 @@ impl ShortyRepository {
         }
     }
 
-    fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<ShortyRepositoryError>> {
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<ShortyRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<ShortyRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
         self.sqlite_client
-            .borrow_conn()
-            .change_context(ShortyRepositoryError::BorrowConnError)
+            .read(ShortyRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<ShortyRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<ShortyRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(ShortyRepositoryError::BorrowConnError, f)
+            .await
     }
 }
 
 #[mry::mry]
 impl ShortyRepository {
-    pub fn add_url_redirect(
+    pub async fn add_url_redirect(
         &self,
-        url_path: &str,
-        url_redirect: &str,
+        url: GetUrlRedirectModel,
+        unique_url_redirect: bool,
         user_id: i64,
-    ) -> Result<(), Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/shorty_repository/add_url_redirect.sql"),
-            named_params! {
-                ":url_path": url_path,
-                ":url_redirect": url_redirect,
-                ":user_id": user_id,
-            },
-        )
-        .change_context(ShortyRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+        now: DateTime<Utc>,
+    ) -> Result<i64, Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            if unique_url_redirect {
+                check_url_redirect_in(&tx, &url.url_redirect, None)?;
+            }
+            tx.execute(
+                include_str!("_sql/shorty_repository/add_url_redirect.sql"),
+                named_params! {
+                    ":url_path": url.url_path,
+                    ":url_redirect": url.url_redirect,
+                    ":redirect_type": url.redirect_type,
+                    ":user_id": user_id,
+                    ":expires_at": url.expires_at,
+                    ":max_clicks": url.max_clicks,
+                    ":description": url.description.unwrap_or_default(),
+                },
+            )
+            .map_err(url_path_error)?;
+            let id = tx.last_insert_rowid();
+            set_tags_in(&tx, id, &url.tags)?;
+            add_revision_in(&tx, id, user_id, now)?;
+            tx.commit()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(())
+            Ok(id)
+        })
+        .await
     }
 
-    pub fn delete_url_redirect(&self, id: i64) -> Result<(), Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
+    /// Moves the link to the trash, the path stays taken until it is purged.
+    pub async fn delete_url_redirect(
+        &self,
+        id: i64,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<(), Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/shorty_repository/delete_url_redirect.sql"),
+                named_params! {
+                    ":id": id,
+                    ":deleted_at": deleted_at,
+                },
+            )
+            .change_context(ShortyRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
 
-        conn.execute(
-            include_str!("_sql/shorty_repository/delete_url_redirect.sql"),
-            named_params! {
-                ":id": id,
-            },
-        )
-        .change_context(ShortyRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    /// In one transaction, returns the ids that were moved, skipping any already in the trash.
+    pub async fn delete_url_redirect_by_ids(
+        &self,
+        ids: Arc<[i64]>,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<Vec<i64>, Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let mut deleted = Vec::with_capacity(ids.len());
+            {
+                let mut stmt = tx
+                    .prepare_cached(include_str!(
+                        "_sql/shorty_repository/delete_url_redirect.sql"
+                    ))
+                    .change_context(ShortyRepositoryError::QueryError)
+                    .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                for id in ids.iter() {
+                    let changed = stmt
+                        .execute(named_params! {
+                            ":id": id,
+                            ":deleted_at": deleted_at,
+                        })
+                        .change_context(ShortyRepositoryError::QueryError)
+                        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                    if changed > 0 {
+                        deleted.push(*id);
+                    }
+                }
+            }
+            tx.commit()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(())
+            Ok(deleted)
+        })
+        .await
     }
 
-    pub fn edit_url_redirect(
+    /// The path of the restored link, none when it was not in the trash.
+    pub async fn restore_url_redirect(
         &self,
         id: i64,
-        url_path: &str,
-        url_redirect: &str,
-    ) -> Result<(), Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
+    ) -> Result<Option<String>, Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/restore_url_redirect.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        conn.execute(
-            include_str!("_sql/shorty_repository/edit_url_redirect.sql"),
-            named_params! {
-                ":id": id,
-                ":url_path": url_path,
-                ":url_redirect": url_redirect,
-            },
-        )
-        .change_context(ShortyRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let url_path = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| row.get("url_path"),
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(())
+            Ok(url_path)
+        })
+        .await
     }
 
-    pub fn get_url_redirect(
+    /// The ids of the purged links.
+    pub async fn purge_url_redirect(
         &self,
-        id: i64,
-    ) -> Result<Option<GetUrlRedirectModel>, Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<i64>, Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/purge_url_redirect.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/shorty_repository/get_url_redirect.sql"))
-            .map_err(|_| Report::new(ShortyRepositoryError::QueryError))
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let purged = stmt
+                .query_map(
+                    named_params! {
+                        ":deleted_before": deleted_before,
+                    },
+                    |row| row.get("id"),
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?
+                .collect::<Result<Vec<i64>, _>>()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let item = stmt
-            .query_one(
-                named_params! {
-                    ":id": id,
-                },
-                |row| {
-                    Ok(GetUrlRedirectModel {
+            Ok(purged)
+        })
+        .await
+    }
+
+    pub async fn list_trashed_url_redirect(
+        &self,
+    ) -> Result<Arc<[TrashedUrlRedirectModel]>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/list_trashed_url_redirect.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(named_params! {}, |row| {
+                    Ok(TrashedUrlRedirectModel {
+                        id: row.get("id")?,
                         url_path: row.get("url_path")?,
                         url_redirect: row.get("url_redirect")?,
+                        created_by_user_id: row.get("created_by_user_id")?,
+                        username: row.get("username")?,
+                        deleted_at: row.get("deleted_at")?,
                     })
+                })
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    pub async fn edit_url_redirect(
+        &self,
+        id: i64,
+        url: GetUrlRedirectModel,
+        unique_url_redirect: bool,
+        changed_by_user_id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(), Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            if unique_url_redirect {
+                check_url_redirect_in(&tx, &url.url_redirect, Some(id))?;
+            }
+            let old_url_path: Option<String> = tx
+                .query_one(
+                    include_str!("_sql/shorty_repository/get_url_path_by_url_id.sql"),
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| row.get("url_path"),
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let renamed_from = old_url_path.filter(|old_url_path| *old_url_path != url.url_path);
+            if renamed_from.is_some() {
+                // Going back to one of its own aliases turns that alias into the path.
+                tx.execute(
+                    include_str!("_sql/shorty_repository/delete_url_redirect_alias_by_path.sql"),
+                    named_params! {
+                        ":url_redirect_id": id,
+                        ":url_path": url.url_path,
+                    },
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            }
+            tx.execute(
+                include_str!("_sql/shorty_repository/edit_url_redirect.sql"),
+                named_params! {
+                    ":id": id,
+                    ":url_path": url.url_path,
+                    ":url_redirect": url.url_redirect,
+                    ":redirect_type": url.redirect_type,
+                    ":expires_at": url.expires_at,
+                    ":max_clicks": url.max_clicks,
+                    ":description": url.description.unwrap_or_default(),
                 },
             )
-            .optional()
-            .change_context(ShortyRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            .map_err(url_path_error)?;
+            if let Some(renamed_from) = renamed_from {
+                // Printed links keep working under the old path.
+                tx.execute(
+                    include_str!("_sql/shorty_repository/add_url_redirect_alias.sql"),
+                    named_params! {
+                        ":url_redirect_id": id,
+                        ":url_path": renamed_from,
+                    },
+                )
+                .map_err(url_path_error)?;
+            }
+            set_tags_in(&tx, id, &url.tags)?;
+            add_revision_in(&tx, id, changed_by_user_id, now)?;
+            tx.commit()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(item)
+            Ok(())
+        })
+        .await
     }
 
-    pub fn get_user_id_by_url_id(
+    pub async fn list_url_redirect_alias(
         &self,
-        id: i64,
-    ) -> Result<Option<GetUserIdByUrlIdModel>, Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        url_redirect_id: i64,
+    ) -> Result<Arc<[UrlRedirectAliasModel]>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/list_url_redirect_alias.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!(
-                "_sql/shorty_repository/get_user_id_by_url_id.sql"
-            ))
-            .change_context(ShortyRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":url_redirect_id": url_redirect_id,
+                    },
+                    |row| {
+                        Ok(UrlRedirectAliasModel {
+                            id: row.get("id")?,
+                            url_path: row.get("url_path")?,
+                            created_at: row.get("created_at")?,
+                        })
+                    },
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
 
-        let item = stmt
-            .query_one(
+    pub async fn add_url_redirect_alias(
+        &self,
+        url_redirect_id: i64,
+        url_path: &str,
+    ) -> Result<i64, Report<ShortyRepositoryError>> {
+        let url_path = url_path.to_owned();
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/shorty_repository/add_url_redirect_alias.sql"),
                 named_params! {
-                    ":id": id,
-                },
-                |row| {
-                    Ok(GetUserIdByUrlIdModel {
-                        created_by_user_id: row.get("created_by_user_id")?,
-                    })
+                    ":url_redirect_id": url_redirect_id,
+                    ":url_path": url_path,
                 },
             )
-            .optional()
-            .change_context(ShortyRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            .map_err(url_path_error)?;
 
-        Ok(item)
+            Ok(conn.last_insert_rowid())
+        })
+        .await
     }
 
-    pub fn list_url_redirect(
+    /// Returns the removed path, `None` when the alias does not belong to the link.
+    pub async fn delete_url_redirect_alias(
         &self,
-    ) -> Result<Arc<[ListUrlRedirectModel]>, Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        url_redirect_id: i64,
+        id: i64,
+    ) -> Result<Option<String>, Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/delete_url_redirect_alias.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/shorty_repository/list_url_redirect.sql"))
-            .map_err(|_| Report::new(ShortyRepositoryError::QueryError))
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let url_path = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                        ":url_redirect_id": url_redirect_id,
+                    },
+                    |row| row.get("url_path"),
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let items_iter = stmt
-            .query_map(named_params! {}, |row| {
-                Ok(ListUrlRedirectModel {
-                    id: row.get("id")?,
-                    url_path: row.get("url_path")?,
-                    url_redirect: row.get("url_redirect")?,
-                    created_at: row.get("created_at")?,
-                    created_by_user_id: row.get("created_by_user_id")?,
-                    username: row.get("username")?,
+            Ok(url_path)
+        })
+        .await
+    }
+
+    /// Counts trashed links too, as they can be restored.
+    pub async fn list_url_redirect_tag_count(
+        &self,
+    ) -> Result<Arc<[UrlTagCountModel]>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/list_url_redirect_tag_count.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(named_params! {}, |row| {
+                    Ok(UrlTagCountModel {
+                        tag: row.get("tag")?,
+                        link_count: row.get("link_count")?,
+                    })
                 })
-            })
-            .change_context(ShortyRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let items = items_iter
-            .collect::<Result<Vec<_>, _>>()
-            .change_context(ShortyRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    /// Links outside the trash, only the ones created by the user when one is given.
+    pub async fn list_url_id_by_tag(
+        &self,
+        tag: &str,
+        created_by_user_id: Option<i64>,
+    ) -> Result<Arc<[i64]>, Report<ShortyRepositoryError>> {
+        let tag = tag.to_owned();
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/list_url_id_by_tag.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":tag": tag,
+                        ":created_by_user_id": created_by_user_id,
+                    },
+                    |row| row.get("id"),
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    /// Takes the tag off links, including the ones in the trash, and returns how many.
+    pub async fn delete_tag(
+        &self,
+        tag: &str,
+        created_by_user_id: Option<i64>,
+    ) -> Result<usize, Report<ShortyRepositoryError>> {
+        let tag = tag.to_owned();
+        self.write(move |conn| {
+            let deleted = conn
+                .execute(
+                    include_str!("_sql/shorty_repository/delete_tag.sql"),
+                    named_params! {
+                        ":tag": tag,
+                        ":created_by_user_id": created_by_user_id,
+                    },
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(deleted)
+        })
+        .await
+    }
+
+    pub async fn list_url_redirect_revision(
+        &self,
+        url_redirect_id: i64,
+    ) -> Result<Arc<[UrlRedirectRevisionModel]>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/list_url_redirect_revision.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":url_redirect_id": url_redirect_id,
+                    },
+                    |row| {
+                        Ok(UrlRedirectRevisionModel {
+                            id: row.get("id")?,
+                            url_path: row.get("url_path")?,
+                            url_redirect: row.get("url_redirect")?,
+                            username: row.get("username")?,
+                            created_at: row.get("created_at")?,
+                        })
+                    },
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(items.into())
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    pub async fn get_url_redirect(
+        &self,
+        id: i64,
+    ) -> Result<Option<GetUrlRedirectModel>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/shorty_repository/get_url_redirect.sql"))
+                .map_err(|_| Report::new(ShortyRepositoryError::QueryError))
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let item = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| {
+                        Ok(GetUrlRedirectModel {
+                            url_path: row.get("url_path")?,
+                            url_redirect: row.get("url_redirect")?,
+                            redirect_type: row.get("redirect_type")?,
+                            expires_at: row.get("expires_at")?,
+                            max_clicks: row.get("max_clicks")?,
+                            description: row.get("description")?,
+                            tags: tags_from_column(row.get("tags")?),
+                        })
+                    },
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(item)
+        })
+        .await
+    }
+
+    pub async fn get_user_id_by_url_id(
+        &self,
+        id: i64,
+    ) -> Result<Option<GetUserIdByUrlIdModel>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/get_user_id_by_url_id.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let item = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| {
+                        Ok(GetUserIdByUrlIdModel {
+                            created_by_user_id: row.get("created_by_user_id")?,
+                        })
+                    },
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(item)
+        })
+        .await
+    }
+
+    pub async fn get_user_id_by_trashed_url_id(
+        &self,
+        id: i64,
+    ) -> Result<Option<GetUserIdByUrlIdModel>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/get_user_id_by_trashed_url_id.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let item = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| {
+                        Ok(GetUserIdByUrlIdModel {
+                            created_by_user_id: row.get("created_by_user_id")?,
+                        })
+                    },
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(item)
+        })
+        .await
+    }
+
+    pub async fn list_url_redirect_page(
+        &self,
+        filter: ListUrlRedirectFilter,
+        sort: ListUrlRedirectSort,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Arc<[ListUrlRedirectModel]>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/list_url_redirect_page.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":search": filter.search,
+                        ":created_by_user_id": filter.created_by_user_id,
+                        ":tag": filter.tag,
+                        ":sort_column": sort.column.as_key(),
+                        ":sort_order": sort.order.as_key(),
+                        ":limit": limit,
+                        ":offset": offset,
+                    },
+                    list_url_redirect_from_row,
+                )
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    pub async fn count_url_redirect(
+        &self,
+        filter: ListUrlRedirectFilter,
+    ) -> Result<i64, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/count_url_redirect.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let total = stmt
+                .query_one(
+                    named_params! {
+                        ":search": filter.search,
+                        ":created_by_user_id": filter.created_by_user_id,
+                        ":tag": filter.tag,
+                    },
+                    |row| row.get("total"),
+                )
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(total)
+        })
+        .await
+    }
+
+    pub async fn get_url_redirect_detail(
+        &self,
+        id: i64,
+    ) -> Result<Option<ListUrlRedirectModel>, Report<ShortyRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_repository/get_url_redirect_detail.sql"
+                ))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let item = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                    },
+                    list_url_redirect_from_row,
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(item)
+        })
+        .await
     }
 }
 
@@ -0,0 +1,234 @@
+use crate::shorty::model::shorty_stats_model::{HitBucketModel, HitLabelModel};
+use chrono::{DateTime, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use shared::context::{Context, ContextError, FromContext};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum ShortyStatsRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Row Value error")]
+    RowValueError,
+    #[error("Borrow Conn error")]
+    BorrowConnError,
+}
+
+#[mry::mry]
+pub struct ShortyStatsRepository {
+    sqlite_client: Option<SqliteClient>,
+}
+
+impl ShortyStatsRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self {
+            sqlite_client: Some(sqlite_client),
+            mry: Default::default(),
+        }
+    }
+
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<ShortyStatsRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<ShortyStatsRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .read(ShortyStatsRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn query_buckets(
+        &self,
+        sql: &'static str,
+        id: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Arc<[HitBucketModel]>, Report<ShortyStatsRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(sql)
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":id": id,
+                        ":since": since,
+                    },
+                    |row| {
+                        Ok(HitBucketModel {
+                            bucket: row.get("bucket")?,
+                            hits: row.get("hits")?,
+                        })
+                    },
+                )
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyStatsRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+}
+
+#[mry::mry]
+impl ShortyStatsRepository {
+    pub async fn get_hit_count(
+        &self,
+        id: i64,
+    ) -> Result<Option<i64>, Report<ShortyStatsRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_stats_repository/get_hit_count.sql"
+                ))
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let item = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| row.get("hit_count"),
+                )
+                .optional()
+                .change_context(ShortyStatsRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(item)
+        })
+        .await
+    }
+
+    pub async fn daily_hits(
+        &self,
+        id: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Arc<[HitBucketModel]>, Report<ShortyStatsRepositoryError>> {
+        self.query_buckets(
+            include_str!("_sql/shorty_stats_repository/daily_hits.sql"),
+            id,
+            since,
+        )
+        .await
+    }
+
+    pub async fn hourly_hits(
+        &self,
+        id: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Arc<[HitBucketModel]>, Report<ShortyStatsRepositoryError>> {
+        self.query_buckets(
+            include_str!("_sql/shorty_stats_repository/hourly_hits.sql"),
+            id,
+            since,
+        )
+        .await
+    }
+
+    pub async fn top_referers(
+        &self,
+        id: i64,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Arc<[HitLabelModel]>, Report<ShortyStatsRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_stats_repository/top_referers.sql"
+                ))
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":id": id,
+                        ":since": since,
+                        ":limit": limit,
+                    },
+                    |row| {
+                        Ok(HitLabelModel {
+                            label: row.get("label")?,
+                            hits: row.get("hits")?,
+                        })
+                    },
+                )
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyStatsRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    pub async fn user_agent_hits(
+        &self,
+        id: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Arc<[HitLabelModel]>, Report<ShortyStatsRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/shorty_stats_repository/user_agent_hits.sql"
+                ))
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":id": id,
+                        ":since": since,
+                    },
+                    |row| {
+                        Ok(HitLabelModel {
+                            label: row.get("label")?,
+                            hits: row.get("hits")?,
+                        })
+                    },
+                )
+                .change_context(ShortyStatsRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ShortyStatsRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl ShortyStatsRepository {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            sqlite_client: None
+        })
+    }
+}
+
+impl FromContext for ShortyStatsRepository {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
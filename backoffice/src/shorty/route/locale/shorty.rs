@@ -6,12 +6,24 @@ pub struct ShortyRouteLocale {
     pub head_id: String,
     pub head_path: String,
     pub head_redirect_url: String,
+    pub head_redirect_type: String,
     pub head_created_at: String,
     pub head_created_by: String,
+    pub head_clicks: String,
+    pub head_recent_clicks: String,
     pub head_action: String,
     pub action_edit: String,
     pub action_delete: String,
     pub action_add: String,
+    pub action_stats: String,
+    pub action_aliases: String,
+    pub action_tags: String,
+    pub action_trash: String,
+    pub search_placeholder: String,
+    pub page_previous: String,
+    pub page_next: String,
+    pub empty: String,
+    pub tag_filter_clear: String,
 }
 
 impl ShortyRouteLocale {
@@ -22,16 +34,40 @@ impl ShortyRouteLocale {
             head_path: l.text_with_default("shorty-route-head-path", "Path"),
             head_redirect_url: l
                 .text_with_default("shorty-route-head-redirect-url", "Redirect URL"),
+            head_redirect_type: l.text_with_default("shorty-route-head-redirect-type", "Type"),
             head_created_at: l.text_with_default("shorty-route-head-created-at", "Created At"),
             head_created_by: l.text_with_default("shorty-route-head-created-by", "Created By"),
+            head_clicks: l.text_with_default("shorty-route-head-clicks", "Clicks"),
+            head_recent_clicks: l
+                .text_with_default("shorty-route-head-recent-clicks", "Clicks (7 Days)"),
             head_action: l.text_with_default("shorty-route-head-action", "Action"),
             action_edit: l.text_with_default("shorty-route-action-edit", "Edit Url"),
             action_delete: l.text_with_default("shorty-route-action-delete", "Delete Url"),
             action_add: l.text_with_default("shorty-route-action-add", "Add Url"),
+            action_stats: l.text_with_default("shorty-route-action-stats", "View Stats"),
+            action_aliases: l.text_with_default("shorty-route-action-aliases", "Aliases"),
+            action_tags: l.text_with_default("shorty-route-action-tags", "Tags"),
+            action_trash: l.text_with_default("shorty-route-action-trash", "Trash"),
+            search_placeholder: l.text_with_default(
+                "shorty-route-search-placeholder",
+                "Search path or redirect URL",
+            ),
+            page_previous: l.text_with_default("shorty-route-page-previous", "Previous"),
+            page_next: l.text_with_default("shorty-route-page-next", "Next"),
+            empty: l.text_with_default("shorty-route-empty", "No URLs found"),
+            tag_filter_clear: l.text_with_default("shorty-route-tag-filter-clear", "Show all"),
         }
     }
 }
 
+pub fn shorty_route_tag_filter(l: &Locale, tag: &str) -> String {
+    l.text_with_default_args(
+        "shorty-route-tag-filter",
+        format!("Tagged '{tag}'").as_str(),
+        I18NArgs::from((("tag", tag),)),
+    )
+}
+
 pub fn short_route_confirm_message(l: &Locale, id: i64) -> String {
     l.text_with_default_args(
         "shorty-route-confirm-message",
@@ -39,3 +75,227 @@ pub fn short_route_confirm_message(l: &Locale, id: i64) -> String {
         I18NArgs::from((("id", id),)),
     )
 }
+
+pub fn shorty_route_page_summary(l: &Locale, page: i64, pages: i64, total: i64) -> String {
+    l.text_with_default_args(
+        "shorty-route-page-summary",
+        format!("Page {page} of {pages} ({total} URLs)").as_str(),
+        I18NArgs::from((("page", page), ("pages", pages), ("total", total))),
+    )
+}
+
+pub struct ShortyTrashLocale {
+    pub title: String,
+    pub head_id: String,
+    pub head_path: String,
+    pub head_redirect_url: String,
+    pub head_created_by: String,
+    pub head_deleted_at: String,
+    pub head_purged_at: String,
+    pub head_action: String,
+    pub action_restore: String,
+    pub never: String,
+    pub empty: String,
+}
+
+impl ShortyTrashLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("shorty-trash-title", "Trash"),
+            head_id: l.text_with_default("shorty-trash-head-id", "ID"),
+            head_path: l.text_with_default("shorty-trash-head-path", "Path"),
+            head_redirect_url: l
+                .text_with_default("shorty-trash-head-redirect-url", "Redirect URL"),
+            head_created_by: l.text_with_default("shorty-trash-head-created-by", "Created By"),
+            head_deleted_at: l.text_with_default("shorty-trash-head-deleted-at", "Deleted At"),
+            head_purged_at: l.text_with_default("shorty-trash-head-purged-at", "Purged At"),
+            head_action: l.text_with_default("shorty-trash-head-action", "Action"),
+            action_restore: l.text_with_default("shorty-trash-action-restore", "Restore Url"),
+            never: l.text_with_default("shorty-trash-never", "Never"),
+            empty: l.text_with_default("shorty-trash-empty", "The trash is empty"),
+        }
+    }
+}
+
+pub struct ShortyRevisionLocale {
+    pub title: String,
+    pub head_created_at: String,
+    pub head_changed_by: String,
+    pub head_path: String,
+    pub head_redirect_url: String,
+    pub head_action: String,
+    pub current: String,
+    pub unknown: String,
+    pub action_rollback: String,
+}
+
+impl ShortyRevisionLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("shorty-revision-title", "History"),
+            head_created_at: l.text_with_default("shorty-revision-head-created-at", "Changed At"),
+            head_changed_by: l.text_with_default("shorty-revision-head-changed-by", "Changed By"),
+            head_path: l.text_with_default("shorty-revision-head-path", "Path"),
+            head_redirect_url: l
+                .text_with_default("shorty-revision-head-redirect-url", "Redirect URL"),
+            head_action: l.text_with_default("shorty-revision-head-action", "Action"),
+            current: l.text_with_default("shorty-revision-current", "Current"),
+            unknown: l.text_with_default("shorty-revision-unknown", "Unknown"),
+            action_rollback: l.text_with_default("shorty-revision-action-rollback", "Roll Back"),
+        }
+    }
+}
+
+pub fn shorty_revision_confirm_message(l: &Locale, path: &str, url_redirect: &str) -> String {
+    l.text_with_default_args(
+        "shorty-revision-confirm-message",
+        format!("Roll back to '{path}' redirecting to '{url_redirect}'?").as_str(),
+        I18NArgs::from((("path", path), ("url_redirect", url_redirect))),
+    )
+}
+
+pub struct ShortyTagLocale {
+    pub title: String,
+    pub head_tag: String,
+    pub head_link_count: String,
+    pub head_action: String,
+    pub action_list: String,
+    pub action_trash: String,
+    pub action_remove: String,
+    pub empty: String,
+}
+
+impl ShortyTagLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("shorty-tag-title", "Tags"),
+            head_tag: l.text_with_default("shorty-tag-head-tag", "Tag"),
+            head_link_count: l.text_with_default("shorty-tag-head-link-count", "Links"),
+            head_action: l.text_with_default("shorty-tag-head-action", "Action"),
+            action_list: l.text_with_default("shorty-tag-action-list", "Show Links"),
+            action_trash: l.text_with_default("shorty-tag-action-trash", "Move Links to Trash"),
+            action_remove: l.text_with_default("shorty-tag-action-remove", "Remove Tag"),
+            empty: l.text_with_default("shorty-tag-empty", "No tags yet"),
+        }
+    }
+}
+
+pub fn shorty_tag_trash_confirm_message(l: &Locale, tag: &str) -> String {
+    l.text_with_default_args(
+        "shorty-tag-trash-confirm-message",
+        format!("Move every link tagged '{tag}' that you can edit to the trash?").as_str(),
+        I18NArgs::from((("tag", tag),)),
+    )
+}
+
+pub fn shorty_tag_remove_confirm_message(l: &Locale, tag: &str) -> String {
+    l.text_with_default_args(
+        "shorty-tag-remove-confirm-message",
+        format!("Take the tag '{tag}' off every link that you can edit?").as_str(),
+        I18NArgs::from((("tag", tag),)),
+    )
+}
+
+pub struct ShortyAliasLocale {
+    pub title: String,
+    pub head_path: String,
+    pub head_created_at: String,
+    pub head_action: String,
+    pub action_remove: String,
+    pub empty: String,
+}
+
+impl ShortyAliasLocale {
+    pub fn new(l: &Locale, path: &str) -> Self {
+        Self {
+            title: l.text_with_default_args(
+                "shorty-alias-title",
+                format!("Aliases: {path}").as_str(),
+                I18NArgs::from((("path", path),)),
+            ),
+            head_path: l.text_with_default("shorty-alias-head-path", "Path"),
+            head_created_at: l.text_with_default("shorty-alias-head-created-at", "Added At"),
+            head_action: l.text_with_default("shorty-alias-head-action", "Action"),
+            action_remove: l.text_with_default("shorty-alias-action-remove", "Remove Alias"),
+            empty: l.text_with_default("shorty-alias-empty", "No aliases"),
+        }
+    }
+}
+
+pub fn shorty_alias_confirm_message(l: &Locale, path: &str) -> String {
+    l.text_with_default_args(
+        "shorty-alias-confirm-message",
+        format!("Are you sure you want to remove '{path}'?").as_str(),
+        I18NArgs::from((("path", path),)),
+    )
+}
+
+pub struct ShortyStatsLocale {
+    pub title: String,
+    pub head_summary: String,
+    pub head_redirect_url: String,
+    pub head_total_clicks: String,
+    pub head_period_clicks: String,
+    pub head_daily: String,
+    pub head_hourly: String,
+    pub head_top_referers: String,
+    pub head_top_user_agents: String,
+    pub head_browser_families: String,
+    pub head_device_classes: String,
+    pub head_period: String,
+    pub head_clicks: String,
+    pub head_referer: String,
+    pub head_user_agent: String,
+    pub head_browser: String,
+    pub head_device: String,
+    pub direct: String,
+    pub unknown: String,
+    pub device_desktop: String,
+    pub device_mobile: String,
+    pub device_tablet: String,
+    pub device_bot: String,
+}
+
+impl ShortyStatsLocale {
+    pub fn new(l: &Locale, path: &str) -> Self {
+        Self {
+            title: l.text_with_default_args(
+                "shorty-stats-title",
+                format!("Stats: {path}").as_str(),
+                I18NArgs::from((("path", path),)),
+            ),
+            head_summary: l.text_with_default("shorty-stats-head-summary", "Summary"),
+            head_redirect_url: l
+                .text_with_default("shorty-stats-head-redirect-url", "Redirect URL"),
+            head_total_clicks: l
+                .text_with_default("shorty-stats-head-total-clicks", "Total Clicks"),
+            head_period_clicks: l
+                .text_with_default("shorty-stats-head-period-clicks", "Clicks (30 Days)"),
+            head_daily: l.text_with_default("shorty-stats-head-daily", "Daily Clicks (30 Days)"),
+            head_hourly: l
+                .text_with_default("shorty-stats-head-hourly", "Hourly Clicks (48 Hours)"),
+            head_top_referers: l
+                .text_with_default("shorty-stats-head-top-referers", "Top Referers (30 Days)"),
+            head_top_user_agents: l.text_with_default(
+                "shorty-stats-head-top-user-agents",
+                "Top User Agents (30 Days)",
+            ),
+            head_browser_families: l
+                .text_with_default("shorty-stats-head-browser-families", "Browsers (30 Days)"),
+            head_device_classes: l
+                .text_with_default("shorty-stats-head-device-classes", "Devices (30 Days)"),
+            head_period: l.text_with_default("shorty-stats-head-period", "Period"),
+            head_clicks: l.text_with_default("shorty-stats-head-clicks", "Clicks"),
+            head_referer: l.text_with_default("shorty-stats-head-referer", "Referer"),
+            head_user_agent: l.text_with_default("shorty-stats-head-user-agent", "User Agent"),
+            head_browser: l.text_with_default("shorty-stats-head-browser", "Browser"),
+            head_device: l.text_with_default("shorty-stats-head-device", "Device"),
+            direct: l.text_with_default("shorty-stats-direct", "Direct / None"),
+            unknown: l.text_with_default("shorty-stats-unknown", "Unknown"),
+            device_desktop: l.text_with_default("shorty-stats-device-desktop", "Desktop"),
+            device_mobile: l.text_with_default("shorty-stats-device-mobile", "Mobile"),
+            device_tablet: l.text_with_default("shorty-stats-device-tablet", "Tablet"),
+            device_bot: l.text_with_default("shorty-stats-device-bot", "Bot"),
+        }
+    }
+}
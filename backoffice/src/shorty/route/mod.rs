@@ -1,2 +1,4 @@
 pub mod locale;
 pub mod shorty;
+pub mod shorty_api;
+pub mod tag_api;
@@ -1,89 +1,456 @@
 use crate::common::html::context_html::ContextHtmlBuilder;
-use crate::common::icon::{pencil_square_icon, plus_icon, trash_icon};
+use crate::common::icon::{
+    arrow_uturn_left_icon, chart_bar_icon, link_icon, no_symbol_icon, pencil_square_icon,
+    plus_icon, tag_icon, trash_icon,
+};
+use crate::shorty::form::add_alias_form::{AddAliasForm, AddAliasMessage};
 use crate::shorty::form::add_edit_url_form::AddEditUrlForm;
-use crate::shorty::route::locale::shorty::{ShortyRouteLocale, short_route_confirm_message};
+use crate::shorty::form::list_url_query::ListUrlQuery;
+use crate::shorty::model::shorty_model::{
+    ListUrlRedirectSortColumn, SortOrder, UrlRedirectRevisionModel,
+};
+use crate::shorty::model::shorty_stats_model::DeviceClass;
+use crate::shorty::route::locale::shorty::{
+    ShortyAliasLocale, ShortyRevisionLocale, ShortyRouteLocale, ShortyStatsLocale, ShortyTagLocale,
+    ShortyTrashLocale, short_route_confirm_message, shorty_alias_confirm_message,
+    shorty_revision_confirm_message, shorty_route_page_summary, shorty_route_tag_filter,
+    shorty_tag_remove_confirm_message, shorty_tag_trash_confirm_message,
+};
+use crate::shorty::rule::expires_at::EXPIRES_AT_FORMAT;
 use crate::shorty::service::add_url_service::AddUrlService;
+use crate::shorty::service::alias_url_service::AliasUrlService;
 use crate::shorty::service::delete_url_service::DeleteUrlService;
-use crate::shorty::service::edit_url_service::EditUrlService;
-use crate::shorty::service::list_url_service::ListUrlService;
+use crate::shorty::service::edit_url_service::{EditUrlService, EditUrlServiceError};
+use crate::shorty::service::list_url_service::{ListUrlService, search_words};
+use crate::shorty::service::shorty_stats_service::ShortyStatsService;
+use crate::shorty::service::tag_url_service::TagUrlService;
+use crate::shorty::service::trash_url_service::TrashUrlService;
 use crate::user::pointer::user_pointer::UserPointer;
 use crate::user::role::Role;
 use crate::user::role::user_role_check::must_be_user;
+use chrono::Utc;
 use maud::{Markup, html};
 use poem::http::StatusCode;
-use poem::i18n::Locale;
+use poem::i18n::{I18NArgs, Locale};
 use poem::session::Session;
 use poem::web::{CsrfToken, CsrfVerifier, Path, Redirect};
-use poem::{Error, IntoResponse, Response, Route, get, handler};
+use poem::{Error, IntoResponse, Response, Route, delete, get, handler, post};
 use shared::context::Dep;
-use shared::csrf::{CsrfTokenHtml, CsrfVerifierError};
+use shared::csrf::{CsrfTokenHtml, CsrfVerifierError, csrf_header_check};
 use shared::error::{ExtraResultExt, FromErrorStack};
-use shared::flag::{Flag, flag_add, flag_edit};
 use shared::flag::path_edit::PathEdit;
+use shared::flag::{Flag, flag_add, flag_edit};
 use shared::flash::{Flash, FlashMessage};
 use shared::htmx::HtmxHeader;
 use shared::locale::LocaleExt;
 use shared::query_string::form::FormQs;
+use shared::query_string::query::QueryQs;
 
 pub const SHORTY_ROUTE: &str = "/shorty";
 
+const SHORTY_LIST_ID: &str = "shorty-list";
+
+fn list_urls_href(query: &ListUrlQuery) -> String {
+    let query_string = query.as_query_string();
+    if query_string.is_empty() {
+        format!("{}/", SHORTY_ROUTE)
+    } else {
+        format!("{}/?{}", SHORTY_ROUTE, query_string)
+    }
+}
+
+fn list_urls_link(query: &ListUrlQuery, label: Markup) -> Markup {
+    let href = list_urls_href(query);
+    let target = format!("#{}", SHORTY_LIST_ID);
+    html! {
+        a href=(href) hx-get=(href) hx-target=(target) hx-select=(target) hx-swap="outerHTML" hx-push-url="true" { (label) }
+    }
+}
+
+fn tag_filter_query(tag: Option<&str>) -> ListUrlQuery {
+    ListUrlQuery {
+        tag: tag.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+/// Wraps every word that starts with one of the search words in `mark`.
+fn highlight_words(text: &str, words: &[String]) -> Markup {
+    let mut parts: Vec<&str> = vec![];
+    let mut start = 0;
+    let mut in_word = false;
+    for (index, c) in text.char_indices() {
+        if c.is_alphanumeric() != in_word && index != start {
+            parts.push(&text[start..index]);
+            start = index;
+        }
+        in_word = c.is_alphanumeric();
+    }
+    parts.push(&text[start..]);
+
+    html! {
+        @for part in parts {
+            @if words.iter().any(|word| part.to_lowercase().starts_with(word.as_str())) {
+                mark { (part) }
+            } @else {
+                (part)
+            }
+        }
+    }
+}
+
+fn sort_head(label: &str, query: &ListUrlQuery, column: ListUrlRedirectSortColumn) -> Markup {
+    let sort = query.sort();
+    let indicator = match sort.order {
+        _ if sort.column != column => "",
+        SortOrder::Asc => " ▲",
+        SortOrder::Desc => " ▼",
+    };
+    html! {
+        th { (list_urls_link(&query.with_sort(column), html! { (label) (indicator) })) }
+    }
+}
+
 #[handler]
 async fn list_urls(
     Dep(list_url_service): Dep<ListUrlService>,
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
     Dep(user_id_context): Dep<UserPointer>,
-) -> Markup {
-    let list_urls = list_url_service.list_urls();
+    QueryQs(query): QueryQs<ListUrlQuery>,
+    csrf_token: &CsrfToken,
+) -> poem::Result<Markup> {
+    let list_urls = list_url_service
+        .list_urls_page(query.filter(), query.sort(), query.page, query.per_page)
+        .await
+        .map_err(Error::from_error_stack)?;
+    let search_words = search_words(query.search.as_deref().unwrap_or_default());
+    let pages = ((list_urls.total + list_urls.per_page - 1) / list_urls.per_page).max(1);
     let edit_icon = pencil_square_icon();
     let delete_icon = trash_icon();
     let add_icon = plus_icon();
+    let stats_icon = chart_bar_icon();
+    let alias_icon = link_icon();
 
     let lc = ShortyRouteLocale::new(&context_html_builder.locale);
+    let list_target = format!("#{}", SHORTY_LIST_ID);
 
-    context_html_builder
+    Ok(context_html_builder
         .attach_title(&lc.title)
         .set_current_tag("id-tag-shorty")
         .attach_content(html! {
             h1 { (lc.title) }
-            table .table-full {
-                thead {
-                    tr {
-                        th { (lc.head_id) }
-                        th { (lc.head_path) }
-                        th { (lc.head_redirect_url) }
-                        th { (lc.head_created_at) }
-                        th { (lc.head_created_by) }
-                        th .action { (lc.head_action) }
+            form .mt-3 method="get" action=(list_urls_href(&ListUrlQuery::default()))
+                hx-get=(list_urls_href(&ListUrlQuery::default())) hx-trigger="input delay:300ms, submit"
+                hx-target=(list_target) hx-select=(list_target) hx-swap="outerHTML" hx-push-url="true" {
+                input .form-item type="search" name="search" value=(query.search.clone().unwrap_or_default())
+                    placeholder=(lc.search_placeholder) {}
+                @if let Some(sort) = query.sort {
+                    input type="hidden" name="sort" value=(sort.as_key()) {}
+                }
+                @if let Some(order) = query.order {
+                    input type="hidden" name="order" value=(order.as_key()) {}
+                }
+                @if let Some(per_page) = query.per_page {
+                    input type="hidden" name="per_page" value=(per_page) {}
+                }
+                @if let Some(created_by) = query.created_by {
+                    input type="hidden" name="created_by" value=(created_by) {}
+                }
+                @if let Some(tag) = &query.tag {
+                    input type="hidden" name="tag" value=(tag) {}
+                }
+            }
+            div #(SHORTY_LIST_ID) {
+                @if let Some(tag) = &query.tag {
+                    p .mt-3 {
+                        (shorty_route_tag_filter(&context_html_builder.locale, tag))
+                        " "
+                        (list_urls_link(&ListUrlQuery { tag: None, page: None, ..query.clone() }, html! { (lc.tag_filter_clear) }))
                     }
                 }
-                tbody {
-                    @for url in list_urls.iter() {
+                table .table-full .mt-3 {
+                    thead {
                         tr {
-                            td { (url.id) }
-                            td { (url.url_path) }
-                            td { (url.url_redirect) }
-                            td .js-date-local { (url.created_at.to_rfc3339()) }
-                            td { (url.username) }
-                            td .action {
-                                @if user_id_context.role == Role::Root || user_id_context.id == url.created_by_user_id {
-                                    a .icon href=( format!("{}/edit/{}", SHORTY_ROUTE, url.id)) title=(lc.action_edit)
-                                        hx-get=( format!("{}/edit/{}", SHORTY_ROUTE, url.id)) hx-target="#main-content" hx-push-url="true" { (edit_icon) }
-                                    " "
-                                    a .icon hx-confirm=(short_route_confirm_message(&context_html_builder.locale ,url.id))
-                                        href=( format!("{}/delete/{}", SHORTY_ROUTE, url.id)) title=(lc.action_delete)
-                                        hx-delete=( format!("{}/delete/{}", SHORTY_ROUTE, url.id)) hx-target="#main-content" { (delete_icon) }
+                            (sort_head(&lc.head_id, &query, ListUrlRedirectSortColumn::Id))
+                            (sort_head(&lc.head_path, &query, ListUrlRedirectSortColumn::Path))
+                            th { (lc.head_redirect_url) }
+                            th { (lc.head_redirect_type) }
+                            (sort_head(&lc.head_created_at, &query, ListUrlRedirectSortColumn::CreatedAt))
+                            (sort_head(&lc.head_created_by, &query, ListUrlRedirectSortColumn::Creator))
+                            (sort_head(&lc.head_clicks, &query, ListUrlRedirectSortColumn::Clicks))
+                            th { (lc.head_recent_clicks) }
+                            th .action { (lc.head_action) }
+                        }
+                    }
+                    tbody {
+                        @for url in list_urls.items.iter() {
+                            tr {
+                                td { (url.id) }
+                                td {
+                                    (highlight_words(&url.url_path, &search_words))
+                                    @if let Some(description) = &url.description {
+                                        br;
+                                        small { (highlight_words(description, &search_words)) }
+                                    }
+                                    @if !url.tags.is_empty() {
+                                        br;
+                                        small {
+                                            @for tag in url.tags.iter() {
+                                                (list_urls_link(&tag_filter_query(Some(tag)), html! { "#" (tag) }))
+                                                " "
+                                            }
+                                        }
+                                    }
+                                }
+                                td { (highlight_words(&url.url_redirect, &search_words)) }
+                                td { (url.redirect_type.code()) }
+                                td .js-date-local { (url.created_at.to_rfc3339()) }
+                                td { (url.username) }
+                                td { (url.hit_count) }
+                                td { (url.recent_hit_count) }
+                                td .action {
+                                    @if user_id_context.role == Role::Root || user_id_context.id == url.created_by_user_id {
+                                        a .icon href=( format!("{}/stats/{}", SHORTY_ROUTE, url.id)) title=(lc.action_stats)
+                                            hx-get=( format!("{}/stats/{}", SHORTY_ROUTE, url.id)) hx-target="#main-content" hx-push-url="true" { (stats_icon) }
+                                        " "
+                                        a .icon href=( format!("{}/aliases/{}", SHORTY_ROUTE, url.id)) title=(lc.action_aliases)
+                                            hx-get=( format!("{}/aliases/{}", SHORTY_ROUTE, url.id)) hx-target="#main-content" hx-push-url="true" { (alias_icon) }
+                                        " "
+                                        a .icon href=( format!("{}/edit/{}", SHORTY_ROUTE, url.id)) title=(lc.action_edit)
+                                            hx-get=( format!("{}/edit/{}", SHORTY_ROUTE, url.id)) hx-target="#main-content" hx-push-url="true" { (edit_icon) }
+                                        " "
+                                        button .icon type="button" hx-confirm=(short_route_confirm_message(&context_html_builder.locale ,url.id))
+                                            title=(lc.action_delete) hx-delete=( format!("{}/delete/{}", SHORTY_ROUTE, url.id))
+                                            hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (delete_icon) }
+                                    }
                                 }
                             }
                         }
+                        @if list_urls.items.is_empty() {
+                            tr {
+                                td colspan="9" { (lc.empty) }
+                            }
+                        }
+                    }
+                }
+                div .text-right .mt-3 {
+                    @if list_urls.page > 1 {
+                        (list_urls_link(&query.with_page(list_urls.page - 1), html! { (lc.page_previous) }))
+                        " "
+                    }
+                    span { (shorty_route_page_summary(&context_html_builder.locale, list_urls.page, pages, list_urls.total)) }
+                    @if list_urls.page < pages {
+                        " "
+                        (list_urls_link(&query.with_page(list_urls.page + 1), html! { (lc.page_next) }))
                     }
                 }
             }
             div .text-right .mt-3 {
+                a .inline-block href=( format!("{}/tags", SHORTY_ROUTE)) title=(lc.action_tags)
+                    hx-get=( format!("{}/tags", SHORTY_ROUTE)) hx-target="#main-content" hx-push-url="true" { (tag_icon()) }
+                " "
+                a .inline-block href=( format!("{}/trash", SHORTY_ROUTE)) title=(lc.action_trash)
+                    hx-get=( format!("{}/trash", SHORTY_ROUTE)) hx-target="#main-content" hx-push-url="true" { (delete_icon) }
+                " "
                 a .inline-block href=( format!("{}/add", SHORTY_ROUTE)) title=(lc.action_add)
                     hx-get=( format!("{}/add", SHORTY_ROUTE)) hx-target="#main-content" hx-push-url="true" { (add_icon) }
             }
         })
-        .build()
+        .build())
+}
+
+fn hit_bar(hits: i64, max_hits: i64) -> Markup {
+    let width = if max_hits > 0 {
+        hits * 100 / max_hits
+    } else {
+        0
+    };
+    html! {
+        div style=(format!("width: {width}%; min-width: 1px; height: 0.75rem; background-color: #0284c7;")) {}
+    }
+}
+
+fn hit_table(head_label: &str, head_clicks: &str, rows: &[(String, i64)]) -> Markup {
+    let max_hits = rows.iter().map(|(_, hits)| *hits).max().unwrap_or_default();
+    html! {
+        table .table-full {
+            thead {
+                tr {
+                    th { (head_label) }
+                    th .w-full {}
+                    th .text-right { (head_clicks) }
+                }
+            }
+            tbody {
+                @for (label, hits) in rows {
+                    tr {
+                        td { (label) }
+                        td { (hit_bar(*hits, max_hits)) }
+                        td .text-right { (hits) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[handler]
+async fn url_stats(
+    Dep(shorty_stats_service): Dep<ShortyStatsService>,
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+) -> poem::Result<Markup> {
+    let subject_id = shorty_stats_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    let stats = shorty_stats_service
+        .fetch_stats(url_id, Utc::now())
+        .await
+        .map_err(Error::from_error_stack)?;
+
+    let lc = ShortyStatsLocale::new(&context_html_builder.locale, &stats.url_path);
+
+    let daily: Vec<(String, i64)> = stats
+        .daily
+        .iter()
+        .rev()
+        .map(|bucket| (bucket.bucket.clone(), bucket.hits))
+        .collect();
+    let hourly: Vec<(String, i64)> = stats
+        .hourly
+        .iter()
+        .rev()
+        .map(|bucket| (bucket.bucket.clone(), bucket.hits))
+        .collect();
+    let top_referers: Vec<(String, i64)> = stats
+        .top_referers
+        .iter()
+        .map(|item| (item.label.clone().unwrap_or(lc.direct.clone()), item.hits))
+        .collect();
+    let top_user_agents: Vec<(String, i64)> = stats
+        .top_user_agents
+        .iter()
+        .map(|item| (item.label.clone().unwrap_or(lc.unknown.clone()), item.hits))
+        .collect();
+    let browser_families: Vec<(String, i64)> = stats
+        .browser_families
+        .iter()
+        .map(|item| (item.label.clone().unwrap_or(lc.unknown.clone()), item.hits))
+        .collect();
+    let device_classes: Vec<(String, i64)> = stats
+        .device_classes
+        .iter()
+        .map(|item| {
+            let label = match item.device_class {
+                DeviceClass::Desktop => lc.device_desktop.clone(),
+                DeviceClass::Mobile => lc.device_mobile.clone(),
+                DeviceClass::Tablet => lc.device_tablet.clone(),
+                DeviceClass::Bot => lc.device_bot.clone(),
+                DeviceClass::Unknown => lc.unknown.clone(),
+            };
+            (label, item.hits)
+        })
+        .collect();
+
+    Ok(context_html_builder
+        .attach_title(&lc.title)
+        .set_current_tag("id-tag-shorty")
+        .attach_content(html! {
+            h1 { (lc.title) }
+            h2 { (lc.head_summary) }
+            table .table-full {
+                tbody {
+                    tr {
+                        th { (lc.head_redirect_url) }
+                        td { (stats.url_redirect) }
+                    }
+                    tr {
+                        th { (lc.head_total_clicks) }
+                        td { (stats.hit_count) }
+                    }
+                    tr {
+                        th { (lc.head_period_clicks) }
+                        td { (stats.period_hit_count) }
+                    }
+                }
+            }
+            h2 .mt-3 { (lc.head_daily) }
+            (hit_table(&lc.head_period, &lc.head_clicks, &daily))
+            h2 .mt-3 { (lc.head_hourly) }
+            (hit_table(&lc.head_period, &lc.head_clicks, &hourly))
+            h2 .mt-3 { (lc.head_top_referers) }
+            (hit_table(&lc.head_referer, &lc.head_clicks, &top_referers))
+            h2 .mt-3 { (lc.head_top_user_agents) }
+            (hit_table(&lc.head_user_agent, &lc.head_clicks, &top_user_agents))
+            h2 .mt-3 { (lc.head_browser_families) }
+            (hit_table(&lc.head_browser, &lc.head_clicks, &browser_families))
+            h2 .mt-3 { (lc.head_device_classes) }
+            (hit_table(&lc.head_device, &lc.head_clicks, &device_classes))
+        })
+        .build())
+}
+
+/// Shows what changed against the revision before it.
+fn revision_change(older: Option<&str>, newer: &str) -> Markup {
+    html! {
+        @match older {
+            Some(older) if older != newer => {
+                del { (older) } br;
+                ins { (newer) }
+            }
+            _ => { (newer) }
+        }
+    }
+}
+
+fn revision_history_html(
+    l: &Locale,
+    url_id: i64,
+    revisions: &[UrlRedirectRevisionModel],
+    csrf_token: &CsrfToken,
+) -> Markup {
+    let lc = ShortyRevisionLocale::new(l);
+    let rollback_icon = arrow_uturn_left_icon();
+
+    html! {
+        h2 .mt-3 { (lc.title) }
+        table .table-full {
+            thead {
+                tr {
+                    th { (lc.head_created_at) }
+                    th { (lc.head_changed_by) }
+                    th { (lc.head_path) }
+                    th { (lc.head_redirect_url) }
+                    th .action { (lc.head_action) }
+                }
+            }
+            tbody {
+                @for (index, revision) in revisions.iter().enumerate() {
+                    @let older = revisions.get(index + 1);
+                    tr {
+                        td .js-date-local { (revision.created_at.to_rfc3339()) }
+                        td { (revision.username.as_deref().unwrap_or(&lc.unknown)) }
+                        td { (revision_change(older.map(|older| older.url_path.as_str()), &revision.url_path)) }
+                        td { (revision_change(older.map(|older| older.url_redirect.as_str()), &revision.url_redirect)) }
+                        td .action {
+                            @if index == 0 {
+                                (lc.current)
+                            } @else {
+                                button .icon type="button" hx-confirm=(shorty_revision_confirm_message(l, &revision.url_path, &revision.url_redirect))
+                                    title=(lc.action_rollback) hx-post=( format!("{}/rollback/{}/{}", SHORTY_ROUTE, url_id, revision.id))
+                                    hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (rollback_icon) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 enum PostResponse {
@@ -110,9 +477,11 @@ async fn url_get(
     flag: Flag,
 ) -> poem::Result<Markup> {
     let mut url_form = AddEditUrlForm::default();
+    let mut history = None;
     if flag.is_edit() {
         let subject_id = edit_url_service
             .fetch_user_id_from_url_id(url_id)
+            .await
             .map_err(Error::from_error_stack)?;
         if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id
         {
@@ -120,9 +489,31 @@ async fn url_get(
         }
         let subject_url = edit_url_service
             .get_url_redirect(url_id)
+            .await
             .map_err(Error::from_error_stack)?;
         url_form.url_path = subject_url.url_path;
         url_form.url_redirect = subject_url.url_redirect;
+        url_form.redirect_type = subject_url.redirect_type.code().to_string();
+        url_form.expires_at = subject_url
+            .expires_at
+            .map(|expires_at| expires_at.format(EXPIRES_AT_FORMAT).to_string())
+            .unwrap_or_default();
+        url_form.max_clicks = subject_url
+            .max_clicks
+            .map(|max_clicks| max_clicks.to_string())
+            .unwrap_or_default();
+        url_form.description = subject_url.description.unwrap_or_default();
+        url_form.tags = subject_url.tags.join(", ");
+        let revisions = edit_url_service
+            .list_revisions(url_id)
+            .await
+            .map_err(Error::from_error_stack)?;
+        history = Some(revision_history_html(
+            &context_html_builder.locale,
+            url_id,
+            &revisions,
+            csrf_token,
+        ));
     }
 
     Ok(url_form
@@ -131,11 +522,13 @@ async fn url_get(
             None,
             Some(csrf_token.as_html()),
             flag.is_edit(),
+            history,
         )
         .await)
 }
 
 #[handler]
+#[allow(clippy::too_many_arguments)]
 async fn url_post(
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
     Dep(edit_url_service): Dep<EditUrlService>,
@@ -152,6 +545,7 @@ async fn url_post(
     if flag.is_edit() {
         let subject_id = edit_url_service
             .fetch_user_id_from_url_id(url_id)
+            .await
             .map_err(Error::from_error_stack)?;
         if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id
         {
@@ -161,13 +555,14 @@ async fn url_post(
     csrf_verifier
         .verify(edit_url_form.csrf_token.as_str())
         .map_err(Error::from_error_stack)?;
-    let validated_result = edit_url_form.as_validated().await.0;
+    let validated_result = edit_url_form.as_validated(flag.is_edit()).await.0;
     match validated_result {
         Ok(validated) => {
             let l = &context_html_builder.locale;
             if flag.is_edit() {
                 edit_url_service
-                    .edit_url_submit(&validated, url_id)
+                    .edit_url_submit(&validated, url_id, user_id_context.id)
+                    .await
                     .log_it()
                     .map_err(Error::from_error_stack)?;
                 session.flash(Flash::Success {
@@ -177,14 +572,16 @@ async fn url_post(
                     ),
                 });
             } else if flag.is_add() {
-                add_url_service
+                let added = add_url_service
                     .add_url_submit(&validated, user_id_context.id)
+                    .await
                     .log_it()
                     .map_err(Error::from_error_stack)?;
                 session.flash(Flash::Success {
-                    msg: l.text_with_default(
+                    msg: l.text_with_default_args(
                         "shorty-route-flash-success-add-url",
-                        "Successfully added URL",
+                        format!("Successfully added URL: {}", added.url_path).as_str(),
+                        I18NArgs::from((("url_path", added.url_path.as_str()),)),
                     ),
                 });
             }
@@ -196,6 +593,20 @@ async fn url_post(
         }
         Err(error) => {
             let errors = error.as_message(&context_html_builder.locale);
+            let history = if flag.is_edit() {
+                let revisions = edit_url_service
+                    .list_revisions(url_id)
+                    .await
+                    .map_err(Error::from_error_stack)?;
+                Some(revision_history_html(
+                    &context_html_builder.locale,
+                    url_id,
+                    &revisions,
+                    csrf_token,
+                ))
+            } else {
+                None
+            };
             context_html_builder.attach_form_flash_error();
             Ok(PostResponse::Validation(
                 edit_url_form
@@ -204,6 +615,7 @@ async fn url_post(
                         Some(errors),
                         Some(csrf_token.as_html()),
                         flag.is_edit(),
+                        history,
                     )
                     .await,
             )
@@ -223,18 +635,20 @@ async fn delete_url(
 ) -> poem::Result<Response> {
     let subject_id = delete_url_service
         .fetch_user_id_from_url_id(url_id)
+        .await
         .map_err(Error::from_error_stack)?;
     if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
         return Err(Error::from_status(StatusCode::FORBIDDEN));
     }
     delete_url_service
         .delete_url(url_id)
+        .await
         .log_it()
         .map_err(Error::from_error_stack)?;
     session.flash(Flash::Success {
         msg: l.text_with_default(
             "shorty-route-flash-success-deleted-url",
-            "Successfully deleted URL",
+            "Successfully moved URL to the trash",
         ),
     });
     Ok(htmx_header.do_location(
@@ -243,6 +657,458 @@ async fn delete_url(
     ))
 }
 
+#[handler]
+async fn rollback_url(
+    Dep(edit_url_service): Dep<EditUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path((url_id, revision_id)): Path<(i64, i64)>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let subject_id = edit_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    let result = edit_url_service
+        .rollback_url(url_id, revision_id, user_id_context.id)
+        .await;
+    match result.as_ref().map_err(|err| err.current_context()) {
+        Err(EditUrlServiceError::UrlPathTaken) => session.flash(Flash::Error {
+            msg: l.text_with_default(
+                "shorty-route-flash-error-rollback-url-path-taken",
+                "Could not roll back, another link already uses that path",
+            ),
+        }),
+        Err(EditUrlServiceError::UrlRedirectTaken) => session.flash(Flash::Error {
+            msg: l.text_with_default(
+                "shorty-route-flash-error-rollback-url-redirect-taken",
+                "Could not roll back, another link already redirects there",
+            ),
+        }),
+        _ => {
+            result.log_it().map_err(Error::from_error_stack)?;
+            session.flash(Flash::Success {
+                msg: l.text_with_default(
+                    "shorty-route-flash-success-rollback-url",
+                    "Successfully rolled back URL",
+                ),
+            });
+        }
+    }
+    Ok(htmx_header.do_location(
+        Redirect::see_other(format!("{}/edit/{}", SHORTY_ROUTE, url_id)),
+        "#main-content",
+    ))
+}
+
+async fn aliases_html(
+    alias_url_service: &AliasUrlService,
+    edit_url_service: &EditUrlService,
+    context_html_builder: ContextHtmlBuilder,
+    url_id: i64,
+    alias_form: &AddAliasForm,
+    errors: Option<AddAliasMessage>,
+    csrf_token: &CsrfToken,
+) -> poem::Result<Markup> {
+    let subject_url = edit_url_service
+        .get_url_redirect(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    let aliases = alias_url_service
+        .list_aliases(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    let remove_icon = trash_icon();
+
+    let l = &context_html_builder.locale;
+    let lc = ShortyAliasLocale::new(l, &subject_url.url_path);
+    let form = alias_form.as_form_markup(l, errors, Some(csrf_token.as_html()));
+
+    Ok(context_html_builder
+        .attach_title(&lc.title)
+        .set_current_tag("id-tag-shorty")
+        .attach_content(html! {
+            h1 { (lc.title) }
+            table .table-full .mt-3 {
+                thead {
+                    tr {
+                        th { (lc.head_path) }
+                        th { (lc.head_created_at) }
+                        th .action { (lc.head_action) }
+                    }
+                }
+                tbody {
+                    @for alias in aliases.iter() {
+                        tr {
+                            td { (alias.url_path) }
+                            td .js-date-local { (alias.created_at.to_rfc3339()) }
+                            td .action {
+                                button .icon type="button" hx-confirm=(shorty_alias_confirm_message(l, &alias.url_path))
+                                    title=(lc.action_remove) hx-delete=( format!("{}/aliases/{}/remove/{}", SHORTY_ROUTE, url_id, alias.id))
+                                    hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (remove_icon) }
+                            }
+                        }
+                    }
+                    @if aliases.is_empty() {
+                        tr {
+                            td colspan="3" { (lc.empty) }
+                        }
+                    }
+                }
+            }
+            (form)
+        })
+        .build())
+}
+
+#[handler]
+async fn aliases_get(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(alias_url_service): Dep<AliasUrlService>,
+    Dep(edit_url_service): Dep<EditUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+    csrf_token: &CsrfToken,
+) -> poem::Result<Markup> {
+    let subject_id = alias_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    aliases_html(
+        &alias_url_service,
+        &edit_url_service,
+        context_html_builder,
+        url_id,
+        &AddAliasForm::default(),
+        None,
+        csrf_token,
+    )
+    .await
+}
+
+#[handler]
+#[allow(clippy::too_many_arguments)]
+async fn aliases_post(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(alias_url_service): Dep<AliasUrlService>,
+    Dep(edit_url_service): Dep<EditUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+    FormQs(alias_form): FormQs<AddAliasForm>,
+    csrf_token: &CsrfToken,
+    csrf_verifier: &CsrfVerifier,
+    session: &Session,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let subject_id = alias_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    csrf_verifier
+        .verify(alias_form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    match alias_form.as_validated().await.0 {
+        Ok(validated) => {
+            alias_url_service
+                .add_alias(&validated, url_id)
+                .await
+                .log_it()
+                .map_err(Error::from_error_stack)?;
+            let url_path = validated.url_path.as_str();
+            session.flash(Flash::Success {
+                msg: context_html_builder.locale.text_with_default_args(
+                    "shorty-route-flash-success-add-alias",
+                    format!("Successfully added alias: {}", url_path).as_str(),
+                    I18NArgs::from((("url_path", url_path),)),
+                ),
+            });
+            Ok(htmx_header.do_location(
+                Redirect::see_other(format!("{}/aliases/{}", SHORTY_ROUTE, url_id)),
+                "#main-content",
+            ))
+        }
+        Err(error) => {
+            let errors = error.as_message(&context_html_builder.locale);
+            context_html_builder.attach_form_flash_error();
+            Ok(PostResponse::Validation(
+                aliases_html(
+                    &alias_url_service,
+                    &edit_url_service,
+                    context_html_builder,
+                    url_id,
+                    &alias_form,
+                    Some(errors),
+                    csrf_token,
+                )
+                .await?,
+            )
+            .into_response())
+        }
+    }
+}
+
+#[handler]
+async fn remove_alias(
+    Dep(alias_url_service): Dep<AliasUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path((url_id, alias_id)): Path<(i64, i64)>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let subject_id = alias_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    alias_url_service
+        .remove_alias(url_id, alias_id)
+        .await
+        .log_it()
+        .map_err(Error::from_error_stack)?;
+    session.flash(Flash::Success {
+        msg: l.text_with_default(
+            "shorty-route-flash-success-removed-alias",
+            "Successfully removed alias",
+        ),
+    });
+    Ok(htmx_header.do_location(
+        Redirect::see_other(format!("{}/aliases/{}", SHORTY_ROUTE, url_id)),
+        "#main-content",
+    ))
+}
+
+#[handler]
+async fn list_tags(
+    Dep(tag_url_service): Dep<TagUrlService>,
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    csrf_token: &CsrfToken,
+) -> poem::Result<Markup> {
+    let tags = tag_url_service
+        .list_tag_counts()
+        .await
+        .map_err(Error::from_error_stack)?;
+    let list_icon = tag_icon();
+    let trash_links_icon = trash_icon();
+    let remove_icon = no_symbol_icon();
+
+    let l = &context_html_builder.locale;
+    let lc = ShortyTagLocale::new(l);
+
+    Ok(context_html_builder
+        .attach_title(&lc.title)
+        .set_current_tag("id-tag-shorty")
+        .attach_content(html! {
+            h1 { (lc.title) }
+            table .table-full .mt-3 {
+                thead {
+                    tr {
+                        th { (lc.head_tag) }
+                        th { (lc.head_link_count) }
+                        th .action { (lc.head_action) }
+                    }
+                }
+                tbody {
+                    @for tag in tags.iter() {
+                        tr {
+                            td { (tag.tag) }
+                            td { (tag.link_count) }
+                            td .action {
+                                @let href = list_urls_href(&tag_filter_query(Some(&tag.tag)));
+                                a .icon href=(href) title=(lc.action_list)
+                                    hx-get=(href) hx-target="#main-content" hx-push-url="true" { (list_icon) }
+                                " "
+                                button .icon type="button" hx-confirm=(shorty_tag_trash_confirm_message(l, &tag.tag))
+                                    title=(lc.action_trash) hx-post=( format!("{}/tags/trash/{}", SHORTY_ROUTE, tag.tag))
+                                    hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (trash_links_icon) }
+                                " "
+                                button .icon type="button" hx-confirm=(shorty_tag_remove_confirm_message(l, &tag.tag))
+                                    title=(lc.action_remove) hx-delete=( format!("{}/tags/remove/{}", SHORTY_ROUTE, tag.tag))
+                                    hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (remove_icon) }
+                            }
+                        }
+                    }
+                    @if tags.is_empty() {
+                        tr {
+                            td colspan="3" { (lc.empty) }
+                        }
+                    }
+                }
+            }
+        })
+        .build())
+}
+
+/// Root users act on every link with the tag, other users on their own links only.
+fn tag_owner_scope(user_id_context: &UserPointer) -> Option<i64> {
+    if user_id_context.role == Role::Root {
+        None
+    } else {
+        Some(user_id_context.id)
+    }
+}
+
+#[handler]
+async fn trash_tag(
+    Dep(tag_url_service): Dep<TagUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(tag): Path<String>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let count = tag_url_service
+        .trash_tag(&tag, tag_owner_scope(&user_id_context))
+        .await
+        .log_it()
+        .map_err(Error::from_error_stack)?;
+    session.flash(Flash::Success {
+        msg: l.text_with_default_args(
+            "shorty-route-flash-success-trashed-tag",
+            format!("Moved {} links tagged '{}' to the trash", count, tag).as_str(),
+            I18NArgs::from((("count", count), ("tag", tag.as_str()))),
+        ),
+    });
+    Ok(htmx_header.do_location(
+        Redirect::see_other(SHORTY_ROUTE.to_owned() + "/tags"),
+        "#main-content",
+    ))
+}
+
+#[handler]
+async fn remove_tag(
+    Dep(tag_url_service): Dep<TagUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(tag): Path<String>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let count = tag_url_service
+        .remove_tag(&tag, tag_owner_scope(&user_id_context))
+        .await
+        .log_it()
+        .map_err(Error::from_error_stack)?;
+    session.flash(Flash::Success {
+        msg: l.text_with_default_args(
+            "shorty-route-flash-success-removed-tag",
+            format!("Removed the tag '{}' from {} links", tag, count).as_str(),
+            I18NArgs::from((("tag", tag.as_str()), ("count", count))),
+        ),
+    });
+    Ok(htmx_header.do_location(
+        Redirect::see_other(SHORTY_ROUTE.to_owned() + "/tags"),
+        "#main-content",
+    ))
+}
+
+#[handler]
+async fn list_trash(
+    Dep(trash_url_service): Dep<TrashUrlService>,
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(user_id_context): Dep<UserPointer>,
+    csrf_token: &CsrfToken,
+) -> Markup {
+    let trash = trash_url_service.list_trash().await;
+    let restore_icon = arrow_uturn_left_icon();
+
+    let lc = ShortyTrashLocale::new(&context_html_builder.locale);
+
+    context_html_builder
+        .attach_title(&lc.title)
+        .set_current_tag("id-tag-shorty")
+        .attach_content(html! {
+            h1 { (lc.title) }
+            table .table-full .mt-3 {
+                thead {
+                    tr {
+                        th { (lc.head_id) }
+                        th { (lc.head_path) }
+                        th { (lc.head_redirect_url) }
+                        th { (lc.head_created_by) }
+                        th { (lc.head_deleted_at) }
+                        th { (lc.head_purged_at) }
+                        th .action { (lc.head_action) }
+                    }
+                }
+                tbody {
+                    @for url in trash.iter() {
+                        tr {
+                            td { (url.id) }
+                            td { (url.url_path) }
+                            td { (url.url_redirect) }
+                            td { (url.username) }
+                            td .js-date-local { (url.deleted_at.to_rfc3339()) }
+                            @if let Some(purged_at) = trash_url_service.purged_at(url.deleted_at) {
+                                td .js-date-local { (purged_at.to_rfc3339()) }
+                            } @else {
+                                td { (lc.never) }
+                            }
+                            td .action {
+                                @if user_id_context.role == Role::Root || user_id_context.id == url.created_by_user_id {
+                                    button .icon type="button" title=(lc.action_restore)
+                                        hx-post=( format!("{}/restore/{}", SHORTY_ROUTE, url.id)) hx-headers=(csrf_token.as_hx_headers())
+                                        hx-target="#main-content" { (restore_icon) }
+                                }
+                            }
+                        }
+                    }
+                    @if trash.is_empty() {
+                        tr {
+                            td colspan="7" { (lc.empty) }
+                        }
+                    }
+                }
+            }
+        })
+        .build()
+}
+
+#[handler]
+async fn restore_url(
+    Dep(trash_url_service): Dep<TrashUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let subject_id = trash_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    if user_id_context.role < Role::Root && user_id_context.id != subject_id.created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    trash_url_service
+        .restore_url(url_id)
+        .await
+        .log_it()
+        .map_err(Error::from_error_stack)?;
+    session.flash(Flash::Success {
+        msg: l.text_with_default(
+            "shorty-route-flash-success-restored-url",
+            "Successfully restored URL",
+        ),
+    });
+    Ok(htmx_header.do_location(
+        Redirect::see_other(SHORTY_ROUTE.to_owned() + "/trash"),
+        "#main-content",
+    ))
+}
+
 pub fn shorty_route() -> Route {
     Route::new()
         .at("/", must_be_user(get(list_urls)))
@@ -252,7 +1118,34 @@ pub fn shorty_route() -> Route {
         )
         .at(
             "/delete/:url_id",
-            must_be_user(get(delete_url).delete(delete_url)),
+            must_be_user(delete(csrf_header_check(delete_url))),
+        )
+        .at(
+            "/rollback/:url_id/:revision_id",
+            must_be_user(post(csrf_header_check(rollback_url))),
+        )
+        .at(
+            "/aliases/:url_id",
+            must_be_user(get(aliases_get).post(aliases_post)),
+        )
+        .at(
+            "/aliases/:url_id/remove/:alias_id",
+            must_be_user(delete(csrf_header_check(remove_alias))),
+        )
+        .at("/tags", must_be_user(get(list_tags)))
+        .at(
+            "/tags/trash/:tag",
+            must_be_user(post(csrf_header_check(trash_tag))),
+        )
+        .at(
+            "/tags/remove/:tag",
+            must_be_user(delete(csrf_header_check(remove_tag))),
+        )
+        .at("/trash", must_be_user(get(list_trash)))
+        .at(
+            "/restore/:url_id",
+            must_be_user(post(csrf_header_check(restore_url))),
         )
         .at("/add", must_be_user(flag_add(get(url_get).post(url_post))))
+        .at("/stats/:url_id", must_be_user(get(url_stats)))
 }
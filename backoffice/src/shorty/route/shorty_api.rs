@@ -0,0 +1,367 @@
+use crate::api::{ApiErrorModel, ApiValidationErrorModel, api_error, api_validation_error};
+use crate::shorty::form::add_alias_form::AddAliasMessage;
+use crate::shorty::form::add_alias_json::AddAliasJson;
+use crate::shorty::form::add_edit_url_form::AddEditUrlMessage;
+use crate::shorty::form::add_edit_url_json::AddEditUrlJson;
+use crate::shorty::form::list_url_query::ListUrlQuery;
+use crate::shorty::model::shorty_model::{
+    ListUrlRedirectModel, ListUrlRedirectPageModel, UrlRedirectAliasModel,
+};
+use crate::shorty::service::add_url_service::AddUrlService;
+use crate::shorty::service::alias_url_service::AliasUrlService;
+use crate::shorty::service::delete_url_service::DeleteUrlService;
+use crate::shorty::service::edit_url_service::EditUrlService;
+use crate::shorty::service::list_url_service::ListUrlService;
+use crate::shorty::service::trash_url_service::TrashUrlService;
+use crate::user::pointer::user_pointer::UserPointer;
+use crate::user::role::Role;
+use crate::user::role::user_role_check::must_be_user;
+use poem::http::StatusCode;
+use poem::i18n::Locale;
+use poem::web::{Json, Path};
+use poem::{Error, IntoResponse, Response, Route, delete, get, handler, post};
+use shared::context::Dep;
+use shared::error::ExtraResultExt;
+use shared::query_string::query::QueryQs;
+use utoipa::OpenApi;
+
+pub const SHORTY_API_ROUTE: &str = "/links";
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_urls,
+    add_url,
+    get_url,
+    edit_url,
+    delete_url,
+    restore_url,
+    list_aliases,
+    add_alias,
+    remove_alias
+))]
+pub struct ShortyApiDoc;
+
+fn check_owner(user_id_context: &UserPointer, created_by_user_id: i64) -> poem::Result<()> {
+    if user_id_context.role < Role::Root && user_id_context.id != created_by_user_id {
+        return Err(Error::from_status(StatusCode::FORBIDDEN));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "links",
+    params(ListUrlQuery),
+    responses(
+        (status = 200, description = "A page of links", body = ListUrlRedirectPageModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn list_urls(
+    Dep(list_url_service): Dep<ListUrlService>,
+    QueryQs(query): QueryQs<ListUrlQuery>,
+) -> poem::Result<Json<ListUrlRedirectPageModel>> {
+    let page = list_url_service
+        .list_urls_page(query.filter(), query.sort(), query.page, query.per_page)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(page))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{url_id}",
+    tag = "links",
+    params(("url_id" = i64, Path)),
+    responses(
+        (status = 200, description = "The link", body = ListUrlRedirectModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 404, description = "No such link", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn get_url(
+    Dep(list_url_service): Dep<ListUrlService>,
+    Path(url_id): Path<i64>,
+) -> poem::Result<Json<ListUrlRedirectModel>> {
+    let url = list_url_service
+        .fetch_url(url_id)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(url))
+}
+
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "links",
+    request_body = AddEditUrlJson,
+    responses(
+        (status = 201, description = "The created link", body = ListUrlRedirectModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+        (status = 409, description = "Path is already taken", body = ApiErrorModel),
+        (status = 422, description = "Validation failed", body = ApiValidationErrorModel<AddEditUrlMessage>),
+    )
+)]
+#[handler]
+async fn add_url(
+    Dep(add_url_service): Dep<AddUrlService>,
+    Dep(list_url_service): Dep<ListUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Json(body): Json<AddEditUrlJson>,
+    l: Locale,
+) -> poem::Result<Response> {
+    let validated = match body.as_form().as_validated(false).await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Ok(api_validation_error(error.as_message(&l))),
+    };
+    let added = add_url_service
+        .add_url_submit(&validated, user_id_context.id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    let url = list_url_service
+        .fetch_url(added.id)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(url).with_status(StatusCode::CREATED).into_response())
+}
+
+#[utoipa::path(
+    put,
+    path = "/{url_id}",
+    tag = "links",
+    params(("url_id" = i64, Path)),
+    request_body = AddEditUrlJson,
+    responses(
+        (status = 200, description = "The updated link", body = ListUrlRedirectModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Not the owner, or token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such link", body = ApiErrorModel),
+        (status = 422, description = "Validation failed", body = ApiValidationErrorModel<AddEditUrlMessage>),
+    )
+)]
+#[handler]
+async fn edit_url(
+    Dep(edit_url_service): Dep<EditUrlService>,
+    Dep(list_url_service): Dep<ListUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+    Json(body): Json<AddEditUrlJson>,
+    l: Locale,
+) -> poem::Result<Response> {
+    let subject_id = edit_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(api_error)?;
+    check_owner(&user_id_context, subject_id.created_by_user_id)?;
+    let validated = match body.as_form().as_validated(true).await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Ok(api_validation_error(error.as_message(&l))),
+    };
+    edit_url_service
+        .edit_url_submit(&validated, url_id, user_id_context.id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    let url = list_url_service
+        .fetch_url(url_id)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(url).into_response())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{url_id}",
+    tag = "links",
+    params(("url_id" = i64, Path)),
+    responses(
+        (status = 204, description = "Link moved to the trash"),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Not the owner, or token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such link", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn delete_url(
+    Dep(delete_url_service): Dep<DeleteUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+) -> poem::Result<StatusCode> {
+    let subject_id = delete_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(api_error)?;
+    check_owner(&user_id_context, subject_id.created_by_user_id)?;
+    delete_url_service
+        .delete_url(url_id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/{url_id}/restore",
+    tag = "links",
+    params(("url_id" = i64, Path)),
+    responses(
+        (status = 200, description = "The restored link", body = ListUrlRedirectModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Not the owner, or token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such link in the trash", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn restore_url(
+    Dep(trash_url_service): Dep<TrashUrlService>,
+    Dep(list_url_service): Dep<ListUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+) -> poem::Result<Json<ListUrlRedirectModel>> {
+    let subject_id = trash_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(api_error)?;
+    check_owner(&user_id_context, subject_id.created_by_user_id)?;
+    trash_url_service
+        .restore_url(url_id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    let url = list_url_service
+        .fetch_url(url_id)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(url))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{url_id}/aliases",
+    tag = "links",
+    params(("url_id" = i64, Path)),
+    responses(
+        (status = 200, description = "The link's aliases", body = [UrlRedirectAliasModel]),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Not the owner", body = ApiErrorModel),
+        (status = 404, description = "No such link", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn list_aliases(
+    Dep(alias_url_service): Dep<AliasUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+) -> poem::Result<Json<Vec<UrlRedirectAliasModel>>> {
+    let subject_id = alias_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(api_error)?;
+    check_owner(&user_id_context, subject_id.created_by_user_id)?;
+    let aliases = alias_url_service
+        .list_aliases(url_id)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(aliases.to_vec()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{url_id}/aliases",
+    tag = "links",
+    params(("url_id" = i64, Path)),
+    request_body = AddAliasJson,
+    responses(
+        (status = 201, description = "The link's aliases", body = [UrlRedirectAliasModel]),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Not the owner, or token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such link", body = ApiErrorModel),
+        (status = 409, description = "Path is already taken", body = ApiErrorModel),
+        (status = 422, description = "Validation failed", body = ApiValidationErrorModel<AddAliasMessage>),
+    )
+)]
+#[handler]
+async fn add_alias(
+    Dep(alias_url_service): Dep<AliasUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(url_id): Path<i64>,
+    Json(body): Json<AddAliasJson>,
+    l: Locale,
+) -> poem::Result<Response> {
+    let subject_id = alias_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(api_error)?;
+    check_owner(&user_id_context, subject_id.created_by_user_id)?;
+    let validated = match body.as_form().as_validated().await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Ok(api_validation_error(error.as_message(&l))),
+    };
+    alias_url_service
+        .add_alias(&validated, url_id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    let aliases = alias_url_service
+        .list_aliases(url_id)
+        .await
+        .map_err(api_error)?;
+    Ok(Json(aliases.to_vec())
+        .with_status(StatusCode::CREATED)
+        .into_response())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{url_id}/aliases/{alias_id}",
+    tag = "links",
+    params(("url_id" = i64, Path), ("alias_id" = i64, Path)),
+    responses(
+        (status = 204, description = "Alias removed"),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Not the owner, or token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such link or alias", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn remove_alias(
+    Dep(alias_url_service): Dep<AliasUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path((url_id, alias_id)): Path<(i64, i64)>,
+) -> poem::Result<StatusCode> {
+    let subject_id = alias_url_service
+        .fetch_user_id_from_url_id(url_id)
+        .await
+        .map_err(api_error)?;
+    check_owner(&user_id_context, subject_id.created_by_user_id)?;
+    alias_url_service
+        .remove_alias(url_id, alias_id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn shorty_api_route() -> Route {
+    Route::new()
+        .at("/", must_be_user(get(list_urls).post(add_url)))
+        .at(
+            "/:url_id",
+            must_be_user(get(get_url).put(edit_url).delete(delete_url)),
+        )
+        .at("/:url_id/restore", must_be_user(post(restore_url)))
+        .at(
+            "/:url_id/aliases",
+            must_be_user(get(list_aliases).post(add_alias)),
+        )
+        .at(
+            "/:url_id/aliases/:alias_id",
+            must_be_user(delete(remove_alias)),
+        )
+}
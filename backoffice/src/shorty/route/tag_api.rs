@@ -0,0 +1,108 @@
+use crate::api::{ApiErrorModel, api_error};
+use crate::shorty::model::shorty_model::{UrlTagBulkModel, UrlTagCountModel};
+use crate::shorty::service::tag_url_service::TagUrlService;
+use crate::user::pointer::user_pointer::UserPointer;
+use crate::user::role::Role;
+use crate::user::role::user_role_check::must_be_user;
+use poem::web::{Json, Path};
+use poem::{Route, delete, get, handler, post};
+use shared::context::Dep;
+use shared::error::ExtraResultExt;
+use utoipa::OpenApi;
+
+pub const TAG_API_ROUTE: &str = "/tags";
+
+#[derive(OpenApi)]
+#[openapi(paths(list_tags, trash_tag, remove_tag))]
+pub struct TagApiDoc;
+
+/// Root users act on every link with the tag, other users on their own links only.
+fn owner_scope(user_id_context: &UserPointer) -> Option<i64> {
+    if user_id_context.role == Role::Root {
+        None
+    } else {
+        Some(user_id_context.id)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "tags",
+    responses(
+        (status = 200, description = "Every tag with how many links outside the trash have it", body = [UrlTagCountModel]),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn list_tags(
+    Dep(tag_url_service): Dep<TagUrlService>,
+) -> poem::Result<Json<Vec<UrlTagCountModel>>> {
+    let tags = tag_url_service.list_tag_counts().await.map_err(api_error)?;
+    Ok(Json(tags.to_vec()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{tag}/trash",
+    tag = "tags",
+    params(("tag" = String, Path)),
+    responses(
+        (status = 200, description = "How many links were moved to the trash", body = UrlTagBulkModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+        (status = 415, description = "Signed in with the session but not sent as application/json", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn trash_tag(
+    Dep(tag_url_service): Dep<TagUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(tag): Path<String>,
+) -> poem::Result<Json<UrlTagBulkModel>> {
+    let link_count = tag_url_service
+        .trash_tag(&tag, owner_scope(&user_id_context))
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(Json(UrlTagBulkModel {
+        tag,
+        link_count: link_count as i64,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{tag}",
+    tag = "tags",
+    params(("tag" = String, Path)),
+    responses(
+        (status = 200, description = "How many links the tag was taken off", body = UrlTagBulkModel),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+        (status = 415, description = "Signed in with the session but not sent as application/json", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn remove_tag(
+    Dep(tag_url_service): Dep<TagUrlService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(tag): Path<String>,
+) -> poem::Result<Json<UrlTagBulkModel>> {
+    let link_count = tag_url_service
+        .remove_tag(&tag, owner_scope(&user_id_context))
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(Json(UrlTagBulkModel {
+        tag,
+        link_count: link_count as i64,
+    }))
+}
+
+pub fn tag_api_route() -> Route {
+    Route::new()
+        .at("/", must_be_user(get(list_tags)))
+        .at("/:tag/trash", must_be_user(post(trash_tag)))
+        .at("/:tag", must_be_user(delete(remove_tag)))
+}
@@ -0,0 +1,40 @@
+use cjtoolkit_structured_validator::types::description::{
+    Description, DescriptionError, DescriptionRules,
+};
+
+fn description_rule() -> DescriptionRules {
+    DescriptionRules {
+        is_mandatory: false,
+        min_length: None,
+        max_length: Some(500),
+    }
+}
+
+pub trait DescriptionRulesExt {
+    fn parse_url_description(description: Option<&str>) -> Result<Description, DescriptionError>;
+}
+
+impl DescriptionRulesExt for Description {
+    fn parse_url_description(description: Option<&str>) -> Result<Description, DescriptionError> {
+        Self::parse_custom(
+            description.filter(|description| !description.is_empty()),
+            description_rule(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_description() {
+        let result = Description::parse_url_description(Some("Spring campaign"));
+        assert_eq!(result.unwrap().as_str(), "Spring campaign");
+
+        let result = Description::parse_url_description(Some(""));
+        assert!(result.unwrap().into_option().is_none());
+
+        assert!(Description::parse_url_description(Some(&"a".repeat(501))).is_err());
+    }
+}
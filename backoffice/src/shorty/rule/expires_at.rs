@@ -0,0 +1,74 @@
+use chrono::NaiveDateTime;
+use cjtoolkit_structured_validator::common::locale::{
+    LocaleData, LocaleMessage, ValidateErrorCollector,
+};
+use cjtoolkit_structured_validator::common::validation_check::ValidationCheck;
+use cjtoolkit_structured_validator::types::times_chrono::naive_date_time::{
+    NaiveDateTimeError, NaiveDateTimeRules, NaiveDateTimeValue,
+};
+use std::sync::Arc;
+
+pub const EXPIRES_AT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+fn expires_at_rule() -> NaiveDateTimeRules {
+    NaiveDateTimeRules {
+        is_mandatory: false,
+        min: None,
+        max: None,
+    }
+}
+
+struct InvalidDateTimeLocale;
+
+impl LocaleMessage for InvalidDateTimeLocale {
+    fn get_locale_data(&self) -> Arc<LocaleData> {
+        LocaleData::new("validate-invalid-date-time")
+    }
+}
+
+fn parse_date_time(expires_at: &str) -> Result<NaiveDateTime, NaiveDateTimeError> {
+    NaiveDateTime::parse_from_str(expires_at, EXPIRES_AT_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(expires_at, "%Y-%m-%dT%H:%M:%S"))
+        .map_err(|_| {
+            let mut messages = ValidateErrorCollector::new();
+            messages.push((
+                "Invalid date and time".to_string(),
+                Box::new(InvalidDateTimeLocale),
+            ));
+            NaiveDateTimeError::validate_new(messages.into())
+        })
+}
+
+pub trait ExpiresAtRulesExt {
+    fn parse_expires_at(expires_at: Option<&str>)
+    -> Result<NaiveDateTimeValue, NaiveDateTimeError>;
+}
+
+impl ExpiresAtRulesExt for NaiveDateTimeValue {
+    fn parse_expires_at(
+        expires_at: Option<&str>,
+    ) -> Result<NaiveDateTimeValue, NaiveDateTimeError> {
+        let expires_at = match expires_at.filter(|expires_at| !expires_at.is_empty()) {
+            Some(expires_at) => Some(parse_date_time(expires_at)?),
+            None => None,
+        };
+        Self::parse_custom(expires_at, expires_at_rule())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expires_at() {
+        let result = NaiveDateTimeValue::parse_expires_at(Some("2025-03-10T12:30"));
+        assert!(result.unwrap().as_naive_date_time().is_some());
+
+        let result = NaiveDateTimeValue::parse_expires_at(Some(""));
+        assert!(result.unwrap().as_naive_date_time().is_none());
+
+        let result = NaiveDateTimeValue::parse_expires_at(Some("next tuesday"));
+        assert!(result.is_err());
+    }
+}
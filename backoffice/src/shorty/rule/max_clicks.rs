@@ -0,0 +1,66 @@
+use cjtoolkit_structured_validator::common::locale::{
+    LocaleData, LocaleMessage, ValidateErrorCollector,
+};
+use cjtoolkit_structured_validator::common::validation_check::ValidationCheck;
+use cjtoolkit_structured_validator::types::numbers::unsigned::{
+    Unsigned, UnsignedError, UnsignedRules,
+};
+use std::sync::Arc;
+
+fn max_clicks_rule() -> UnsignedRules {
+    UnsignedRules {
+        is_mandatory: false,
+        min: Some(1),
+        max: None,
+    }
+}
+
+struct MustBeNumberLocale;
+
+impl LocaleMessage for MustBeNumberLocale {
+    fn get_locale_data(&self) -> Arc<LocaleData> {
+        LocaleData::new("validate-must-be-number")
+    }
+}
+
+fn parse_number(max_clicks: &str) -> Result<usize, UnsignedError> {
+    max_clicks.parse::<usize>().map_err(|_| {
+        let mut messages = ValidateErrorCollector::new();
+        messages.push((
+            "Must be a whole number".to_string(),
+            Box::new(MustBeNumberLocale),
+        ));
+        UnsignedError::validate_new(messages.into())
+    })
+}
+
+pub trait MaxClicksRulesExt {
+    fn parse_max_clicks(max_clicks: Option<&str>) -> Result<Unsigned, UnsignedError>;
+}
+
+impl MaxClicksRulesExt for Unsigned {
+    fn parse_max_clicks(max_clicks: Option<&str>) -> Result<Unsigned, UnsignedError> {
+        let max_clicks = match max_clicks.filter(|max_clicks| !max_clicks.is_empty()) {
+            Some(max_clicks) => Some(parse_number(max_clicks)?),
+            None => None,
+        };
+        Self::parse_custom(max_clicks, max_clicks_rule())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_max_clicks() {
+        let result = Unsigned::parse_max_clicks(Some("10"));
+        assert_eq!(result.unwrap().into_option().unwrap().as_usize(), 10);
+
+        let result = Unsigned::parse_max_clicks(None);
+        assert!(result.unwrap().into_option().is_none());
+
+        assert!(Unsigned::parse_max_clicks(Some("0")).is_err());
+        assert!(Unsigned::parse_max_clicks(Some("ten")).is_err());
+    }
+}
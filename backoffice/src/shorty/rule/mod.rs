@@ -1,2 +1,7 @@
+pub mod description;
+pub mod expires_at;
+pub mod max_clicks;
+pub mod redirect_type;
+pub mod tags;
 pub mod url_path;
 pub mod url_redirect;
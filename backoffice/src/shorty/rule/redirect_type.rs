@@ -0,0 +1,72 @@
+use cjtoolkit_structured_validator::common::locale::{
+    LocaleData, LocaleMessage, ValidateErrorCollector, ValidateErrorStore,
+};
+use cjtoolkit_structured_validator::common::validation_check::ValidationCheck;
+use shared::redirect::RedirectType;
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RedirectTypeError(pub ValidateErrorStore);
+
+impl ValidationCheck for RedirectTypeError {
+    fn validate_new(messages: ValidateErrorStore) -> Self {
+        Self(messages)
+    }
+}
+
+impl From<&RedirectTypeError> for ValidateErrorStore {
+    fn from(error: &RedirectTypeError) -> Self {
+        error.0.clone()
+    }
+}
+
+struct InvalidRedirectTypeLocale;
+
+impl LocaleMessage for InvalidRedirectTypeLocale {
+    fn get_locale_data(&self) -> Arc<LocaleData> {
+        LocaleData::new("validate-invalid-redirect-type")
+    }
+}
+
+pub trait RedirectTypeRulesExt {
+    fn parse_redirect_type(redirect_type: Option<&str>) -> Result<RedirectType, RedirectTypeError>;
+}
+
+impl RedirectTypeRulesExt for RedirectType {
+    fn parse_redirect_type(redirect_type: Option<&str>) -> Result<RedirectType, RedirectTypeError> {
+        let redirect_type = match redirect_type.filter(|redirect_type| !redirect_type.is_empty()) {
+            Some(redirect_type) => redirect_type,
+            None => return Ok(RedirectType::default()),
+        };
+        match redirect_type.parse::<u16>().ok().and_then(Self::from_code) {
+            Some(redirect_type) => Ok(redirect_type),
+            None => {
+                let mut messages = ValidateErrorCollector::new();
+                messages.push((
+                    "Invalid redirect type".to_string(),
+                    Box::new(InvalidRedirectTypeLocale),
+                ));
+                Err(RedirectTypeError::validate_new(messages.into()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_redirect_type() {
+        assert_eq!(
+            RedirectType::parse_redirect_type(Some("308")),
+            Ok(RedirectType::PermanentRedirect)
+        );
+        assert_eq!(
+            RedirectType::parse_redirect_type(None),
+            Ok(RedirectType::SeeOther)
+        );
+        assert!(RedirectType::parse_redirect_type(Some("200")).is_err());
+        assert!(RedirectType::parse_redirect_type(Some("abc")).is_err());
+    }
+}
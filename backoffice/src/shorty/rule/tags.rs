@@ -0,0 +1,114 @@
+use cjtoolkit_structured_validator::common::locale::{
+    LocaleData, LocaleMessage, LocaleValue, ValidateErrorCollector, ValidateErrorStore,
+};
+use cjtoolkit_structured_validator::common::validation_check::ValidationCheck;
+use regex::Regex;
+use std::sync::{Arc, OnceLock};
+
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 32;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TagsError(pub ValidateErrorStore);
+
+impl ValidationCheck for TagsError {
+    fn validate_new(messages: ValidateErrorStore) -> Self {
+        Self(messages)
+    }
+}
+
+impl From<&TagsError> for ValidateErrorStore {
+    fn from(error: &TagsError) -> Self {
+        error.0.clone()
+    }
+}
+
+enum TagsLocale {
+    MustBeKebabCase,
+    MaxTags(usize),
+    MaxTagLength(usize),
+}
+
+impl LocaleMessage for TagsLocale {
+    fn get_locale_data(&self) -> Arc<LocaleData> {
+        match self {
+            Self::MustBeKebabCase => LocaleData::new("validate-tags-must-be-kebab-case"),
+            Self::MaxTags(max) => LocaleData::new_with_vec(
+                "validate-tags-max",
+                vec![("max".to_string(), LocaleValue::from(*max))],
+            ),
+            Self::MaxTagLength(max) => LocaleData::new_with_vec(
+                "validate-tags-max-length",
+                vec![("max".to_string(), LocaleValue::from(*max))],
+            ),
+        }
+    }
+}
+
+static TAG_REGEX_CACHE: OnceLock<Regex> = OnceLock::new();
+
+/// Comma separated, lower cased, sorted and without duplicates.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Tags(Vec<String>);
+
+impl Tags {
+    pub fn parse_tags(tags: Option<&str>) -> Result<Tags, TagsError> {
+        let mut tags: Vec<String> = tags
+            .unwrap_or_default()
+            .split(',')
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        let regex = TAG_REGEX_CACHE
+            .get_or_init(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").expect("Invalid regex"));
+        let mut messages = ValidateErrorCollector::new();
+        if tags.iter().any(|tag| !regex.is_match(tag)) {
+            messages.push((
+                "Tags must be kebab case".to_string(),
+                Box::new(TagsLocale::MustBeKebabCase),
+            ));
+        }
+        if tags.iter().any(|tag| tag.chars().count() > MAX_TAG_LENGTH) {
+            messages.push((
+                format!("Each tag must be at most {} characters", MAX_TAG_LENGTH),
+                Box::new(TagsLocale::MaxTagLength(MAX_TAG_LENGTH)),
+            ));
+        }
+        if tags.len() > MAX_TAGS {
+            messages.push((
+                format!("Must be at most {} tags", MAX_TAGS),
+                Box::new(TagsLocale::MaxTags(MAX_TAGS)),
+            ));
+        }
+        TagsError::validate_check(messages)?;
+        Ok(Tags(tags))
+    }
+
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        let tags = Tags::parse_tags(Some(" Q3-Conference, newsletter,,q3-conference ")).unwrap();
+        assert_eq!(tags.as_slice(), ["newsletter", "q3-conference"]);
+
+        assert!(Tags::parse_tags(None).unwrap().as_slice().is_empty());
+        assert!(Tags::parse_tags(Some("q3 conference")).is_err());
+        assert!(Tags::parse_tags(Some(&"a".repeat(MAX_TAG_LENGTH + 1))).is_err());
+
+        let too_many = (0..=MAX_TAGS)
+            .map(|index| format!("tag-{}", index))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(Tags::parse_tags(Some(&too_many)).is_err());
+    }
+}
@@ -6,9 +6,9 @@ use cjtoolkit_structured_validator::types::name::name_alias::{Field, FieldError,
 use regex::Regex;
 use std::sync::{Arc, OnceLock};
 
-fn url_path_rule() -> FieldRules {
+fn url_path_rule(is_mandatory: bool) -> FieldRules {
     FieldRules {
-        is_mandatory: true,
+        is_mandatory,
         min_length: Some(1),
         max_length: Some(100),
     }
@@ -40,13 +40,15 @@ fn must_be_kebab_case(url_path: &str) -> Result<(), FieldError> {
 }
 
 pub trait UrlPathRulesExt {
-    fn parse_url_path(url_path: Option<&str>) -> Result<Field, FieldError>;
+    fn parse_url_path(url_path: Option<&str>, is_mandatory: bool) -> Result<Field, FieldError>;
 }
 
 impl UrlPathRulesExt for Field {
-    fn parse_url_path(url_path: Option<&str>) -> Result<Field, FieldError> {
-        let url_path = Field::parse_custom(url_path, url_path_rule());
-        if let Ok(url_path_ref) = url_path.as_ref() {
+    fn parse_url_path(url_path: Option<&str>, is_mandatory: bool) -> Result<Field, FieldError> {
+        let url_path = Field::parse_custom(url_path, url_path_rule(is_mandatory));
+        if let Ok(url_path_ref) = url_path.as_ref()
+            && !url_path_ref.as_str().is_empty()
+        {
             must_be_kebab_case(url_path_ref.as_str())?;
         }
         url_path
@@ -67,4 +69,12 @@ mod tests {
         let result = must_be_kebab_case(url_path);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_url_path_blank() {
+        assert!(Field::parse_url_path(None, true).is_err());
+
+        let url_path = Field::parse_url_path(None, false).unwrap();
+        assert!(url_path.into_option().is_none());
+    }
 }
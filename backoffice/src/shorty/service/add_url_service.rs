@@ -1,39 +1,136 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
 use crate::shorty::form::add_edit_url_form::AddEditUrlValidated;
-use crate::shorty::repository::shorty_repository::ShortyRepository;
-use error_stack::{Report, ResultExt};
+use crate::shorty::model::shorty_model::{AddUrlRedirectModel, GetUrlRedirectModel};
+use crate::shorty::repository::shorty_repository::{ShortyRepository, ShortyRepositoryError};
+use crate::shorty::service::short_code_service::ShortCodeService;
+use chrono::{SubsecRound, Utc};
+use error_stack::Report;
+use poem::http::StatusCode;
+use shared::config::ConfigPointer;
 use shared::context::{Context, ContextError, FromContext};
+use shared::redirect::invalidation::RedirectInvalidator;
+
+const SHORT_CODE_MAX_ATTEMPTS: usize = 5;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AddUrlServiceError {
     #[error("Database error")]
     DbError,
+    #[error("Could not generate a free short code")]
+    ShortCodeExhausted,
+    #[error("Another link already redirects there")]
+    UrlRedirectTaken,
+}
+
+fn add_error(err: Report<ShortyRepositoryError>) -> Report<AddUrlServiceError> {
+    match err.current_context() {
+        ShortyRepositoryError::UrlRedirectTaken => {
+            err.change_context(AddUrlServiceError::UrlRedirectTaken)
+        }
+        _ => err.change_context(AddUrlServiceError::DbError),
+    }
 }
 
 pub struct AddUrlService {
     shorty_repository: ShortyRepository,
+    short_code_service: ShortCodeService,
+    redirect_invalidator: RedirectInvalidator,
+    audit_service: AuditService,
+    unique_url_redirect: bool,
 }
 
 impl AddUrlService {
-    pub fn new(shorty_repository: ShortyRepository) -> Self {
-        Self { shorty_repository }
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        short_code_service: ShortCodeService,
+        redirect_invalidator: RedirectInvalidator,
+        audit_service: AuditService,
+        unique_url_redirect: bool,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            short_code_service,
+            redirect_invalidator,
+            audit_service,
+            unique_url_redirect,
+        }
     }
 
-    pub fn add_url_submit(
+    async fn add_url_redirect(
         &self,
         form: &AddEditUrlValidated,
+        url_path: &str,
         user_id: i64,
-    ) -> Result<(), Report<AddUrlServiceError>> {
-        self.shorty_repository
-            .add_url_redirect(form.url_path.as_str(), form.url_redirect.as_str(), user_id)
-            .change_context(AddUrlServiceError::DbError)?;
+    ) -> Result<i64, Report<ShortyRepositoryError>> {
+        let url = form.as_url_redirect_model(url_path);
+        let id = self
+            .shorty_repository
+            .add_url_redirect(
+                url.clone(),
+                self.unique_url_redirect,
+                user_id,
+                Utc::now().trunc_subsecs(0),
+            )
+            .await?;
+        // The path may be cached as unknown.
+        self.redirect_invalidator.invalidate_path(url_path);
+        self.audit_service
+            .record(
+                AuditAction::LinkAdd,
+                Some(id),
+                None::<&GetUrlRedirectModel>,
+                Some(&url),
+            )
+            .await;
+        Ok(id)
+    }
 
-        Ok(())
+    pub async fn add_url_submit(
+        &self,
+        form: &AddEditUrlValidated,
+        user_id: i64,
+    ) -> Result<AddUrlRedirectModel, Report<AddUrlServiceError>> {
+        if let Some(url_path) = form.url_path() {
+            let id = self
+                .add_url_redirect(form, url_path, user_id)
+                .await
+                .map_err(add_error)?;
+            return Ok(AddUrlRedirectModel {
+                id,
+                url_path: url_path.to_string(),
+            });
+        }
+
+        for _ in 0..SHORT_CODE_MAX_ATTEMPTS {
+            let url_path = self.short_code_service.generate();
+            match self.add_url_redirect(form, &url_path, user_id).await {
+                Ok(id) => return Ok(AddUrlRedirectModel { id, url_path }),
+                Err(err)
+                    if matches!(err.current_context(), ShortyRepositoryError::UrlPathTaken) =>
+                {
+                    continue;
+                }
+                Err(err) => return Err(add_error(err)),
+            }
+        }
+
+        Err(Report::new(AddUrlServiceError::ShortCodeExhausted)
+            .attach(format!("Attempts: {}", SHORT_CODE_MAX_ATTEMPTS))
+            .attach(StatusCode::SERVICE_UNAVAILABLE))
     }
 }
 
 impl FromContext for AddUrlService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?))
+        let config: ConfigPointer = ctx.inject().await?;
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+            config.shorty.unique_url_redirect,
+        ))
     }
 }
 
@@ -41,43 +138,184 @@ impl FromContext for AddUrlService {
 mod tests {
     use super::*;
     use crate::shorty::form::add_edit_url_form::AddEditUrlForm;
-    use crate::shorty::repository::shorty_repository::ShortyRepositoryError;
+    use shared::redirect::RedirectType;
+    use std::sync::Mutex;
+
+    fn url_redirect_model(url_path: &str) -> GetUrlRedirectModel {
+        GetUrlRedirectModel {
+            url_path: url_path.to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            redirect_type: RedirectType::SeeOther,
+            ..Default::default()
+        }
+    }
 
     #[tokio::test]
     async fn test_add_url_submit_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
-            .mock_add_url_redirect("hello", "http://hello.com", 1)
-            .returns_once(Ok(()));
+            .mock_add_url_redirect(url_redirect_model("hello"), false, 1, mry::Any)
+            .returns_once(Ok(1));
 
-        let add_url_service = AddUrlService::new(shorty_repository);
+        let add_url_service = AddUrlService::new(
+            shorty_repository,
+            ShortCodeService::new_mock(),
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
 
-        let mut add_edit_url_form = AddEditUrlForm::default();
-        add_edit_url_form.url_path = "hello".to_string();
-        add_edit_url_form.url_redirect = "http://hello.com".to_string();
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
 
-        let validated = add_edit_url_form.as_validated().await.0.unwrap();
+        let validated = add_edit_url_form.as_validated(false).await.0.unwrap();
 
-        let result = add_url_service.add_url_submit(&validated, 1);
-        assert!(result.is_ok());
+        let result = add_url_service.add_url_submit(&validated, 1).await;
+        assert_eq!(
+            result.unwrap(),
+            AddUrlRedirectModel {
+                id: 1,
+                url_path: "hello".to_string()
+            }
+        );
     }
 
     #[tokio::test]
     async fn test_add_url_submit_db_error() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
-            .mock_add_url_redirect("hello", "http://hello.com", 1)
+            .mock_add_url_redirect(url_redirect_model("hello"), false, 1, mry::Any)
             .returns_once(Err(Report::new(ShortyRepositoryError::QueryError)));
 
-        let add_url_service = AddUrlService::new(shorty_repository);
+        let add_url_service = AddUrlService::new(
+            shorty_repository,
+            ShortCodeService::new_mock(),
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
 
-        let mut add_edit_url_form = AddEditUrlForm::default();
-        add_edit_url_form.url_path = "hello".to_string();
-        add_edit_url_form.url_redirect = "http://hello.com".to_string();
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
 
-        let validated = add_edit_url_form.as_validated().await.0.unwrap();
+        let validated = add_edit_url_form.as_validated(false).await.0.unwrap();
 
-        let result = add_url_service.add_url_submit(&validated, 1);
+        let result = add_url_service.add_url_submit(&validated, 1).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_add_url_submit_url_redirect_taken() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_add_url_redirect(url_redirect_model("hello"), true, 1, mry::Any)
+            .returns_once(Err(
+                Report::new(ShortyRepositoryError::UrlRedirectTaken).attach(StatusCode::CONFLICT)
+            ));
+
+        let add_url_service = AddUrlService::new(
+            shorty_repository,
+            ShortCodeService::new_mock(),
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            true,
+        );
+
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
+
+        let validated = add_edit_url_form.as_validated(false).await.0.unwrap();
+
+        let error = add_url_service
+            .add_url_submit(&validated, 1)
+            .await
+            .unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::CONFLICT);
+        assert!(matches!(
+            error.current_context(),
+            AddUrlServiceError::UrlRedirectTaken
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_add_url_submit_generates_path_and_retries_on_conflict() {
+        let codes = Mutex::new(vec!["free", "taken"]);
+        let mut short_code_service = ShortCodeService::new_mock();
+        short_code_service
+            .mock_generate()
+            .returns_with(move || codes.lock().unwrap().pop().unwrap().to_string());
+
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_add_url_redirect(url_redirect_model("taken"), false, 1, mry::Any)
+            .returns_once(Err(Report::new(ShortyRepositoryError::UrlPathTaken)));
+        shorty_repository
+            .mock_add_url_redirect(url_redirect_model("free"), false, 1, mry::Any)
+            .returns_once(Ok(1));
+
+        let add_url_service = AddUrlService::new(
+            shorty_repository,
+            short_code_service,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+
+        let add_edit_url_form = AddEditUrlForm {
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
+
+        let validated = add_edit_url_form.as_validated(false).await.0.unwrap();
+
+        let result = add_url_service.add_url_submit(&validated, 1).await;
+        assert_eq!(result.unwrap().url_path, "free");
+    }
+
+    #[tokio::test]
+    async fn test_add_url_submit_generation_exhausted() {
+        let mut short_code_service = ShortCodeService::new_mock();
+        short_code_service
+            .mock_generate()
+            .returns("taken".to_string());
+
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_add_url_redirect(url_redirect_model("taken"), false, 1, mry::Any)
+            .returns_with(|_, _, _, _| Err(Report::new(ShortyRepositoryError::UrlPathTaken)));
+
+        let add_url_service = AddUrlService::new(
+            shorty_repository,
+            short_code_service,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+
+        let add_edit_url_form = AddEditUrlForm {
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
+
+        let validated = add_edit_url_form.as_validated(false).await.0.unwrap();
+
+        let error = add_url_service
+            .add_url_submit(&validated, 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.current_context(),
+            AddUrlServiceError::ShortCodeExhausted
+        ));
+    }
 }
@@ -0,0 +1,204 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::shorty::form::add_alias_form::AddAliasValidated;
+use crate::shorty::model::shorty_model::{GetUserIdByUrlIdModel, UrlRedirectAliasModel};
+use crate::shorty::repository::shorty_repository::ShortyRepository;
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use serde_json::json;
+use shared::context::{Context, ContextError, FromContext};
+use shared::redirect::invalidation::RedirectInvalidator;
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AliasUrlServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Not found")]
+    NotFound,
+}
+
+pub struct AliasUrlService {
+    shorty_repository: ShortyRepository,
+    redirect_invalidator: RedirectInvalidator,
+    audit_service: AuditService,
+}
+
+impl AliasUrlService {
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        redirect_invalidator: RedirectInvalidator,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            redirect_invalidator,
+            audit_service,
+        }
+    }
+
+    pub async fn list_aliases(
+        &self,
+        id: i64,
+    ) -> Result<Arc<[UrlRedirectAliasModel]>, Report<AliasUrlServiceError>> {
+        self.shorty_repository
+            .list_url_redirect_alias(id)
+            .await
+            .change_context(AliasUrlServiceError::DbError)
+    }
+
+    /// A path already used by a link or an alias is returned as a conflict.
+    pub async fn add_alias(
+        &self,
+        form: &AddAliasValidated,
+        id: i64,
+    ) -> Result<i64, Report<AliasUrlServiceError>> {
+        let url_path = form.url_path.as_str();
+        let alias_id = self
+            .shorty_repository
+            .add_url_redirect_alias(id, url_path)
+            .await
+            .change_context(AliasUrlServiceError::DbError)?;
+        // The path may be cached as unknown.
+        self.redirect_invalidator.invalidate_path(url_path);
+        self.audit_service
+            .record(
+                AuditAction::LinkAliasAdd,
+                Some(id),
+                None::<&()>,
+                Some(&json!({ "url_path": url_path })),
+            )
+            .await;
+        Ok(alias_id)
+    }
+
+    pub async fn remove_alias(
+        &self,
+        id: i64,
+        alias_id: i64,
+    ) -> Result<(), Report<AliasUrlServiceError>> {
+        let url_path = self
+            .shorty_repository
+            .delete_url_redirect_alias(id, alias_id)
+            .await
+            .change_context(AliasUrlServiceError::DbError)?
+            .ok_or_else(|| {
+                Report::new(AliasUrlServiceError::NotFound).attach(StatusCode::NOT_FOUND)
+            })?;
+        self.redirect_invalidator.invalidate_path(&url_path);
+        self.audit_service
+            .record(
+                AuditAction::LinkAliasRemove,
+                Some(id),
+                Some(&json!({ "url_path": url_path })),
+                None::<&()>,
+            )
+            .await;
+        Ok(())
+    }
+
+    pub async fn fetch_user_id_from_url_id(
+        &self,
+        id: i64,
+    ) -> Result<GetUserIdByUrlIdModel, Report<AliasUrlServiceError>> {
+        self.shorty_repository
+            .get_user_id_by_url_id(id)
+            .await
+            .change_context(AliasUrlServiceError::DbError)?
+            .ok_or_else(|| {
+                Report::new(AliasUrlServiceError::NotFound).attach(StatusCode::NOT_FOUND)
+            })
+    }
+}
+
+impl FromContext for AliasUrlService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shorty::repository::shorty_repository::ShortyRepositoryError;
+    use crate::shorty::rule::url_path::UrlPathRulesExt;
+    use cjtoolkit_structured_validator::types::name::name_alias::Field;
+    use shared::redirect::invalidation::RedirectInvalidation;
+    use tokio::sync::broadcast::channel;
+
+    fn validated(url_path: &str) -> AddAliasValidated {
+        AddAliasValidated {
+            url_path: Field::parse_url_path(Some(url_path), true).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_alias_invalidates_path() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_add_url_redirect_alias(1, "sale")
+            .returns_once(Ok(3));
+
+        let redirect_invalidator = RedirectInvalidator::new(channel(1).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        let alias_url_service = AliasUrlService::new(
+            shorty_repository,
+            redirect_invalidator,
+            AuditService::new_mock(),
+        );
+        assert_eq!(
+            alias_url_service
+                .add_alias(&validated("sale"), 1)
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RedirectInvalidation::Path("sale".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_alias_path_taken() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_add_url_redirect_alias(1, "sale")
+            .returns_once(Err(
+                Report::new(ShortyRepositoryError::UrlPathTaken).attach(StatusCode::CONFLICT)
+            ));
+
+        let alias_url_service = AliasUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+        );
+        let error = alias_url_service
+            .add_alias(&validated("sale"), 1)
+            .await
+            .unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_remove_alias_of_another_link() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_delete_url_redirect_alias(1, 3)
+            .returns_once(Ok(None));
+
+        let alias_url_service = AliasUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+        );
+        let error = alias_url_service.remove_alias(1, 3).await.unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+}
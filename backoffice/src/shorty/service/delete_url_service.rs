@@ -1,8 +1,12 @@
-use crate::shorty::model::shorty_model::GetUserIdByUrlIdModel;
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::shorty::model::shorty_model::{GetUrlRedirectModel, GetUserIdByUrlIdModel};
 use crate::shorty::repository::shorty_repository::ShortyRepository;
+use chrono::{SubsecRound, Utc};
 use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
 use shared::context::{Context, ContextError, FromContext};
+use shared::redirect::invalidation::RedirectInvalidator;
 
 #[derive(Debug, thiserror::Error)]
 pub enum DeleteUrlServiceError {
@@ -12,27 +16,55 @@ pub enum DeleteUrlServiceError {
 
 pub struct DeleteUrlService {
     shorty_repository: ShortyRepository,
+    redirect_invalidator: RedirectInvalidator,
+    audit_service: AuditService,
 }
 
 impl DeleteUrlService {
-    pub fn new(shorty_repository: ShortyRepository) -> Self {
-        Self { shorty_repository }
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        redirect_invalidator: RedirectInvalidator,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            redirect_invalidator,
+            audit_service,
+        }
     }
 
-    pub fn delete_url(&self, id: i64) -> Result<(), Report<DeleteUrlServiceError>> {
+    /// Moves the link to the trash, its path stays taken until it is purged.
+    pub async fn delete_url(&self, id: i64) -> Result<(), Report<DeleteUrlServiceError>> {
+        let now = Utc::now().trunc_subsecs(0);
+        let before = self
+            .shorty_repository
+            .get_url_redirect(id)
+            .await
+            .change_context(DeleteUrlServiceError::DbError)?;
         self.shorty_repository
-            .delete_url_redirect(id)
+            .delete_url_redirect(id, now)
+            .await
             .change_context(DeleteUrlServiceError::DbError)?;
+        self.redirect_invalidator.invalidate_id(id);
+        self.audit_service
+            .record(
+                AuditAction::LinkDelete,
+                Some(id),
+                before.as_ref(),
+                None::<&GetUrlRedirectModel>,
+            )
+            .await;
 
         Ok(())
     }
 
-    pub fn fetch_user_id_from_url_id(
+    pub async fn fetch_user_id_from_url_id(
         &self,
         id: i64,
     ) -> Result<GetUserIdByUrlIdModel, Report<DeleteUrlServiceError>> {
         self.shorty_repository
             .get_user_id_by_url_id(id)
+            .await
             .change_context(DeleteUrlServiceError::DbError)?
             .ok_or_else(|| {
                 Report::new(DeleteUrlServiceError::DbError).attach(StatusCode::NOT_FOUND)
@@ -42,7 +74,11 @@ impl DeleteUrlService {
 
 impl FromContext for DeleteUrlService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?))
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+        ))
     }
 }
 
@@ -50,33 +86,53 @@ impl FromContext for DeleteUrlService {
 mod tests {
     use super::*;
     use crate::shorty::repository::shorty_repository::ShortyRepositoryError;
+    use mry::Any;
+    use shared::redirect::invalidation::RedirectInvalidation;
+    use tokio::sync::broadcast::channel;
 
-    #[test]
-    fn test_delete_url_success() {
+    #[tokio::test]
+    async fn test_delete_url_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
-            .mock_delete_url_redirect(1)
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_delete_url_redirect(1, Any)
             .returns_once(Ok(()));
 
-        let delete_url_service = DeleteUrlService::new(shorty_repository);
-        let result = delete_url_service.delete_url(1);
+        let redirect_invalidator = RedirectInvalidator::new(channel(1).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        let delete_url_service = DeleteUrlService::new(
+            shorty_repository,
+            redirect_invalidator,
+            AuditService::new_mock(),
+        );
+        let result = delete_url_service.delete_url(1).await;
         assert!(result.is_ok());
+        assert_eq!(receiver.try_recv().unwrap(), RedirectInvalidation::Id(1));
     }
 
-    #[test]
-    fn test_delete_url_db_error() {
+    #[tokio::test]
+    async fn test_delete_url_db_error() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
-            .mock_delete_url_redirect(1)
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_delete_url_redirect(1, Any)
             .returns_once(Err(Report::new(ShortyRepositoryError::QueryError)));
 
-        let delete_url_service = DeleteUrlService::new(shorty_repository);
-        let result = delete_url_service.delete_url(1);
+        let delete_url_service = DeleteUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+        );
+        let result = delete_url_service.delete_url(1).await;
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_fetch_user_id_from_url_id_success() {
+    #[tokio::test]
+    async fn test_fetch_user_id_from_url_id_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_user_id_by_url_id(1)
@@ -84,35 +140,50 @@ mod tests {
                 created_by_user_id: 1,
             })));
 
-        let delete_url_service = DeleteUrlService::new(shorty_repository);
-        let user_id = delete_url_service.fetch_user_id_from_url_id(1).unwrap();
+        let delete_url_service = DeleteUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+        );
+        let user_id = delete_url_service
+            .fetch_user_id_from_url_id(1)
+            .await
+            .unwrap();
         assert_eq!(user_id.created_by_user_id, 1);
     }
 
-    #[test]
-    fn test_fetch_user_id_from_url_id_not_found() {
+    #[tokio::test]
+    async fn test_fetch_user_id_from_url_id_not_found() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_user_id_by_url_id(1)
             .returns_once(Ok(None));
 
-        let delete_url_service = DeleteUrlService::new(shorty_repository);
-        let user_id = delete_url_service.fetch_user_id_from_url_id(1);
+        let delete_url_service = DeleteUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+        );
+        let user_id = delete_url_service.fetch_user_id_from_url_id(1).await;
         assert!(user_id.is_err());
         let error = user_id.as_ref().err().unwrap();
         let http_code = error.downcast_ref::<StatusCode>().unwrap();
         assert_eq!(http_code, &StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_fetch_user_id_from_url_id_db_error() {
+    #[tokio::test]
+    async fn test_fetch_user_id_from_url_id_db_error() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_user_id_by_url_id(1)
             .returns_once(Err(Report::new(ShortyRepositoryError::QueryError)));
 
-        let delete_url_service = DeleteUrlService::new(shorty_repository);
-        let user_id = delete_url_service.fetch_user_id_from_url_id(1);
+        let delete_url_service = DeleteUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+        );
+        let user_id = delete_url_service.fetch_user_id_from_url_id(1).await;
         assert!(user_id.is_err());
     }
 }
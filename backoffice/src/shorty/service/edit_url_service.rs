@@ -1,53 +1,176 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
 use crate::shorty::form::add_edit_url_form::AddEditUrlValidated;
-use crate::shorty::model::shorty_model::{GetUrlRedirectModel, GetUserIdByUrlIdModel};
-use crate::shorty::repository::shorty_repository::ShortyRepository;
+use crate::shorty::model::shorty_model::{
+    GetUrlRedirectModel, GetUserIdByUrlIdModel, UrlRedirectRevisionModel,
+};
+use crate::shorty::repository::shorty_repository::{ShortyRepository, ShortyRepositoryError};
+use chrono::{SubsecRound, Utc};
 use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
+use shared::config::ConfigPointer;
 use shared::context::{Context, ContextError, FromContext};
+use shared::redirect::invalidation::RedirectInvalidator;
+use std::sync::Arc;
 
 #[derive(Debug, thiserror::Error)]
 pub enum EditUrlServiceError {
     #[error("Database error")]
     DbError,
+    #[error("Another link already redirects there")]
+    UrlRedirectTaken,
+    #[error("Another link already uses the path")]
+    UrlPathTaken,
+}
+
+fn edit_error(err: Report<ShortyRepositoryError>) -> Report<EditUrlServiceError> {
+    match err.current_context() {
+        ShortyRepositoryError::UrlPathTaken => {
+            err.change_context(EditUrlServiceError::UrlPathTaken)
+        }
+        ShortyRepositoryError::UrlRedirectTaken => {
+            err.change_context(EditUrlServiceError::UrlRedirectTaken)
+        }
+        _ => err.change_context(EditUrlServiceError::DbError),
+    }
 }
 
 pub struct EditUrlService {
     shorty_repository: ShortyRepository,
+    redirect_invalidator: RedirectInvalidator,
+    audit_service: AuditService,
+    unique_url_redirect: bool,
 }
 
 impl EditUrlService {
-    pub fn new(shorty_repository: ShortyRepository) -> Self {
-        Self { shorty_repository }
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        redirect_invalidator: RedirectInvalidator,
+        audit_service: AuditService,
+        unique_url_redirect: bool,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            redirect_invalidator,
+            audit_service,
+            unique_url_redirect,
+        }
     }
 
-    pub fn get_url_redirect(
+    pub async fn get_url_redirect(
         &self,
         id: i64,
     ) -> Result<GetUrlRedirectModel, Report<EditUrlServiceError>> {
         self.shorty_repository
             .get_url_redirect(id)
+            .await
             .change_context(EditUrlServiceError::DbError)?
             .ok_or_else(|| Report::new(EditUrlServiceError::DbError).attach(StatusCode::NOT_FOUND))
     }
 
-    pub fn edit_url_submit(
+    pub async fn edit_url_submit(
         &self,
         form: &AddEditUrlValidated,
         id: i64,
+        user_id: i64,
     ) -> Result<(), Report<EditUrlServiceError>> {
-        self.shorty_repository
-            .edit_url_redirect(id, form.url_path.as_str(), form.url_redirect.as_str())
+        let before = self
+            .shorty_repository
+            .get_url_redirect(id)
+            .await
             .change_context(EditUrlServiceError::DbError)?;
+        let after = form.as_url_redirect_model(form.url_path.as_str());
+        self.shorty_repository
+            .edit_url_redirect(
+                id,
+                after.clone(),
+                self.unique_url_redirect,
+                user_id,
+                Utc::now().trunc_subsecs(0),
+            )
+            .await
+            .map_err(edit_error)?;
+        // Drops the entry under the old path, and any cached miss for the new one.
+        self.redirect_invalidator.invalidate_id(id);
+        self.redirect_invalidator
+            .invalidate_path(form.url_path.as_str());
+        self.audit_service
+            .record(
+                AuditAction::LinkEdit,
+                Some(id),
+                before.as_ref(),
+                Some(&after),
+            )
+            .await;
 
         Ok(())
     }
 
-    pub fn fetch_user_id_from_url_id(
+    /// Newest first, the first revision is the link as it is now.
+    pub async fn list_revisions(
+        &self,
+        id: i64,
+    ) -> Result<Arc<[UrlRedirectRevisionModel]>, Report<EditUrlServiceError>> {
+        self.shorty_repository
+            .list_url_redirect_revision(id)
+            .await
+            .change_context(EditUrlServiceError::DbError)
+    }
+
+    /// Puts back the path and destination of an earlier revision, the other fields are kept.
+    pub async fn rollback_url(
+        &self,
+        id: i64,
+        revision_id: i64,
+        user_id: i64,
+    ) -> Result<(), Report<EditUrlServiceError>> {
+        let before = self.get_url_redirect(id).await?;
+        let revision = self
+            .list_revisions(id)
+            .await?
+            .iter()
+            .find(|revision| revision.id == revision_id)
+            .cloned()
+            .ok_or_else(|| {
+                Report::new(EditUrlServiceError::DbError).attach(StatusCode::NOT_FOUND)
+            })?;
+        let after = GetUrlRedirectModel {
+            url_path: revision.url_path,
+            url_redirect: revision.url_redirect,
+            ..before.clone()
+        };
+        self.shorty_repository
+            .edit_url_redirect(
+                id,
+                after.clone(),
+                self.unique_url_redirect,
+                user_id,
+                Utc::now().trunc_subsecs(0),
+            )
+            .await
+            .map_err(edit_error)?;
+        self.redirect_invalidator.invalidate_id(id);
+        self.redirect_invalidator
+            .invalidate_path(after.url_path.as_str());
+        self.audit_service
+            .record(
+                AuditAction::LinkRollback,
+                Some(id),
+                Some(&before),
+                Some(&after),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn fetch_user_id_from_url_id(
         &self,
         id: i64,
     ) -> Result<GetUserIdByUrlIdModel, Report<EditUrlServiceError>> {
         self.shorty_repository
             .get_user_id_by_url_id(id)
+            .await
             .change_context(EditUrlServiceError::DbError)?
             .ok_or_else(|| Report::new(EditUrlServiceError::DbError).attach(StatusCode::NOT_FOUND))
     }
@@ -55,7 +178,13 @@ impl EditUrlService {
 
 impl FromContext for EditUrlService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?))
+        let config: ConfigPointer = ctx.inject().await?;
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+            config.shorty.unique_url_redirect,
+        ))
     }
 }
 
@@ -63,33 +192,48 @@ impl FromContext for EditUrlService {
 mod tests {
     use super::*;
     use crate::shorty::form::add_edit_url_form::AddEditUrlForm;
-    use crate::shorty::repository::shorty_repository::ShortyRepositoryError;
+    use chrono::DateTime;
+    use mry::Any;
+    use shared::redirect::RedirectType;
+    use shared::redirect::invalidation::RedirectInvalidation;
+    use tokio::sync::broadcast::channel;
 
-    #[test]
-    fn test_get_url_redirect_success() {
+    #[tokio::test]
+    async fn test_get_url_redirect_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_url_redirect(1)
             .returns_once(Ok(Some(GetUrlRedirectModel {
                 url_path: "hello".to_string(),
                 url_redirect: "hi".to_string(),
+                ..Default::default()
             })));
 
-        let edit_url_service = EditUrlService::new(shorty_repository);
-        let url_redirect = edit_url_service.get_url_redirect(1).unwrap();
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+        let url_redirect = edit_url_service.get_url_redirect(1).await.unwrap();
         assert_eq!(url_redirect.url_path, "hello");
         assert_eq!(url_redirect.url_redirect, "hi");
     }
 
-    #[test]
-    fn test_get_url_redirect_not_found() {
+    #[tokio::test]
+    async fn test_get_url_redirect_not_found() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_url_redirect(1)
             .returns_once(Ok(None));
 
-        let edit_url_service = EditUrlService::new(shorty_repository);
-        let url_redirect = edit_url_service.get_url_redirect(1);
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+        let url_redirect = edit_url_service.get_url_redirect(1).await;
         assert!(url_redirect.is_err());
         let error = url_redirect.as_ref().err().unwrap();
         let http_code = error.downcast_ref::<StatusCode>().unwrap();
@@ -100,18 +244,89 @@ mod tests {
     async fn test_edit_url_submit_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
-            .mock_edit_url_redirect(1, "hello", "http://hello.com")
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_edit_url_redirect(
+                1,
+                GetUrlRedirectModel {
+                    url_path: "hello".to_string(),
+                    url_redirect: "http://hello.com".to_string(),
+                    ..Default::default()
+                },
+                false,
+                1,
+                Any,
+            )
             .returns_once(Ok(()));
 
-        let edit_url_service = EditUrlService::new(shorty_repository);
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
 
-        let mut add_edit_url_form = AddEditUrlForm::default();
-        add_edit_url_form.url_path = "hello".to_string();
-        add_edit_url_form.url_redirect = "http://hello.com".to_string();
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
 
-        let validate = add_edit_url_form.as_validated().await.0.unwrap();
+        let validate = add_edit_url_form.as_validated(true).await.0.unwrap();
 
-        let result = edit_url_service.edit_url_submit(&validate, 1);
+        let result = edit_url_service.edit_url_submit(&validate, 1, 1).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_edit_url_submit_with_limits() {
+        let expires_at = DateTime::parse_from_rfc3339("2025-03-10T12:30:00Z")
+            .unwrap()
+            .to_utc();
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_edit_url_redirect(
+                1,
+                GetUrlRedirectModel {
+                    url_path: "hello".to_string(),
+                    url_redirect: "http://hello.com".to_string(),
+                    redirect_type: RedirectType::PermanentRedirect,
+                    expires_at: Some(expires_at),
+                    max_clicks: Some(50),
+                    description: Some("Spring campaign".to_string()),
+                    tags: vec!["newsletter".to_string(), "q3-conference".to_string()],
+                },
+                false,
+                1,
+                Any,
+            )
+            .returns_once(Ok(()));
+
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            redirect_type: "308".to_string(),
+            expires_at: "2025-03-10T12:30".to_string(),
+            max_clicks: "50".to_string(),
+            description: " Spring campaign ".to_string(),
+            tags: "q3-conference, Newsletter".to_string(),
+            ..Default::default()
+        };
+
+        let validate = add_edit_url_form.as_validated(true).await.0.unwrap();
+
+        let result = edit_url_service.edit_url_submit(&validate, 1, 1).await;
         assert!(result.is_ok());
     }
 
@@ -119,23 +334,197 @@ mod tests {
     async fn test_edit_url_submit_db_error() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
-            .mock_edit_url_redirect(1, "hello", "http://hello.com")
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_edit_url_redirect(
+                1,
+                GetUrlRedirectModel {
+                    url_path: "hello".to_string(),
+                    url_redirect: "http://hello.com".to_string(),
+                    ..Default::default()
+                },
+                false,
+                1,
+                Any,
+            )
             .returns_once(Err(Report::new(ShortyRepositoryError::QueryError)));
 
-        let edit_url_service = EditUrlService::new(shorty_repository);
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
 
-        let mut add_edit_url_form = AddEditUrlForm::default();
-        add_edit_url_form.url_path = "hello".to_string();
-        add_edit_url_form.url_redirect = "http://hello.com".to_string();
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
 
-        let validate = add_edit_url_form.as_validated().await.0.unwrap();
+        let validate = add_edit_url_form.as_validated(true).await.0.unwrap();
 
-        let result = edit_url_service.edit_url_submit(&validate, 1);
+        let result = edit_url_service.edit_url_submit(&validate, 1, 1).await;
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_fetch_user_id_from_url_id_success() {
+    #[tokio::test]
+    async fn test_edit_url_submit_url_redirect_taken() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_edit_url_redirect(1, Any, true, 1, Any)
+            .returns_once(Err(
+                Report::new(ShortyRepositoryError::UrlRedirectTaken).attach(StatusCode::CONFLICT)
+            ));
+
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            true,
+        );
+
+        let add_edit_url_form = AddEditUrlForm {
+            url_path: "hello".to_string(),
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
+
+        let validate = add_edit_url_form.as_validated(true).await.0.unwrap();
+
+        let error = edit_url_service
+            .edit_url_submit(&validate, 1, 1)
+            .await
+            .unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::CONFLICT);
+        assert!(matches!(
+            error.current_context(),
+            EditUrlServiceError::UrlRedirectTaken
+        ));
+    }
+
+    fn revisions() -> Arc<[UrlRedirectRevisionModel]> {
+        Arc::new([
+            UrlRedirectRevisionModel {
+                id: 2,
+                url_path: "sale".to_string(),
+                url_redirect: "http://example.com/new".to_string(),
+                ..Default::default()
+            },
+            UrlRedirectRevisionModel {
+                id: 1,
+                url_path: "promo".to_string(),
+                url_redirect: "http://example.com/old".to_string(),
+                ..Default::default()
+            },
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_rollback_url_keeps_other_fields() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel {
+                url_path: "sale".to_string(),
+                url_redirect: "http://example.com/new".to_string(),
+                redirect_type: RedirectType::PermanentRedirect,
+                max_clicks: Some(50),
+                description: Some("Spring campaign".to_string()),
+                tags: vec!["q3-conference".to_string()],
+                ..Default::default()
+            })));
+        shorty_repository
+            .mock_list_url_redirect_revision(1)
+            .returns_once(Ok(revisions()));
+        shorty_repository
+            .mock_edit_url_redirect(
+                1,
+                GetUrlRedirectModel {
+                    url_path: "promo".to_string(),
+                    url_redirect: "http://example.com/old".to_string(),
+                    redirect_type: RedirectType::PermanentRedirect,
+                    max_clicks: Some(50),
+                    description: Some("Spring campaign".to_string()),
+                    tags: vec!["q3-conference".to_string()],
+                    ..Default::default()
+                },
+                false,
+                3,
+                Any,
+            )
+            .returns_once(Ok(()));
+
+        let redirect_invalidator = RedirectInvalidator::new(channel(2).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            redirect_invalidator,
+            AuditService::new_mock(),
+            false,
+        );
+        assert!(edit_url_service.rollback_url(1, 1, 3).await.is_ok());
+        assert_eq!(receiver.try_recv().unwrap(), RedirectInvalidation::Id(1));
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RedirectInvalidation::Path("promo".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_url_path_taken() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_list_url_redirect_revision(1)
+            .returns_once(Ok(revisions()));
+        shorty_repository
+            .mock_edit_url_redirect(1, Any, false, 3, Any)
+            .returns_once(Err(Report::new(ShortyRepositoryError::UrlPathTaken)));
+
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+        let error = edit_url_service.rollback_url(1, 1, 3).await.unwrap_err();
+        assert!(matches!(
+            error.current_context(),
+            EditUrlServiceError::UrlPathTaken
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_url_unknown_revision() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_list_url_redirect_revision(1)
+            .returns_once(Ok(revisions()));
+
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+        let error = edit_url_service.rollback_url(1, 7, 3).await.unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_user_id_from_url_id_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_user_id_by_url_id(1)
@@ -143,23 +532,44 @@ mod tests {
                 created_by_user_id: 1,
             })));
 
-        let edit_url_service = EditUrlService::new(shorty_repository);
-        let user_id = edit_url_service.fetch_user_id_from_url_id(1).unwrap();
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+        let user_id = edit_url_service.fetch_user_id_from_url_id(1).await.unwrap();
         assert_eq!(user_id.created_by_user_id, 1);
     }
 
-    #[test]
-    fn test_fetch_user_id_from_url_id_not_found() {
+    #[tokio::test]
+    async fn test_fetch_user_id_from_url_id_not_found() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_get_user_id_by_url_id(1)
             .returns_once(Ok(None));
 
-        let edit_url_service = EditUrlService::new(shorty_repository);
-        let user_id = edit_url_service.fetch_user_id_from_url_id(1);
+        let edit_url_service = EditUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            false,
+        );
+        let user_id = edit_url_service.fetch_user_id_from_url_id(1).await;
         assert!(user_id.is_err());
         let error = user_id.as_ref().err().unwrap();
         let http_code = error.downcast_ref::<StatusCode>().unwrap();
         assert_eq!(http_code, &StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn test_edit_url_requires_path() {
+        let add_edit_url_form = AddEditUrlForm {
+            url_redirect: "http://hello.com".to_string(),
+            ..Default::default()
+        };
+
+        let validated = add_edit_url_form.as_validated(true).await.0;
+        assert!(validated.is_err());
+    }
 }
@@ -1,8 +1,23 @@
-use crate::shorty::model::shorty_model::ListUrlRedirectModel;
+use crate::shorty::model::shorty_model::{
+    ListUrlRedirectFilter, ListUrlRedirectModel, ListUrlRedirectPageModel, ListUrlRedirectSort,
+};
 use crate::shorty::repository::shorty_repository::ShortyRepository;
-use error_stack::Report;
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
 use shared::context::{Context, ContextError, FromContext};
-use std::sync::Arc;
+
+pub const PER_PAGE_DEFAULT: i64 = 20;
+pub const PER_PAGE_MAX: i64 = 100;
+/// Keeps the offset of the last page inside an `i64`.
+pub const PAGE_MAX: i64 = i64::MAX / PER_PAGE_MAX;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ListUrlServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Not found")]
+    NotFound,
+}
 
 pub struct ListUrlService {
     shorty_repository: ShortyRepository,
@@ -13,15 +28,211 @@ impl ListUrlService {
         Self { shorty_repository }
     }
 
-    pub fn list_urls(&self) -> Arc<[ListUrlRedirectModel]> {
+    pub async fn list_urls_page(
+        &self,
+        filter: ListUrlRedirectFilter,
+        sort: ListUrlRedirectSort,
+        page: Option<i64>,
+        per_page: Option<i64>,
+    ) -> Result<ListUrlRedirectPageModel, Report<ListUrlServiceError>> {
+        let page = page.unwrap_or(1).clamp(1, PAGE_MAX);
+        let per_page = per_page.unwrap_or(PER_PAGE_DEFAULT).clamp(1, PER_PAGE_MAX);
+        let filter = ListUrlRedirectFilter {
+            search: filter.search.as_deref().and_then(fts_query),
+            tag: filter
+                .tag
+                .map(|tag| tag.trim().to_lowercase())
+                .filter(|tag| !tag.is_empty()),
+            ..filter
+        };
+
+        let total = self
+            .shorty_repository
+            .count_url_redirect(filter.clone())
+            .await
+            .change_context(ListUrlServiceError::DbError)?;
+        let items = self
+            .shorty_repository
+            .list_url_redirect_page(filter, sort, per_page, (page - 1) * per_page)
+            .await
+            .change_context(ListUrlServiceError::DbError)?;
+
+        Ok(ListUrlRedirectPageModel {
+            items,
+            page,
+            per_page,
+            total,
+        })
+    }
+
+    pub async fn fetch_url(
+        &self,
+        id: i64,
+    ) -> Result<ListUrlRedirectModel, Report<ListUrlServiceError>> {
         self.shorty_repository
-            .list_url_redirect()
-            .unwrap_or_default()
+            .get_url_redirect_detail(id)
+            .await
+            .change_context(ListUrlServiceError::DbError)?
+            .ok_or_else(|| Report::new(ListUrlServiceError::NotFound).attach(StatusCode::NOT_FOUND))
     }
 }
 
+/// Splits search text into lowercase words the same way the FTS5 `unicode61` tokenizer does.
+pub fn search_words(search: &str) -> Vec<String> {
+    search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Every word has to match, either in full or as the start of a longer word.
+fn fts_query(search: &str) -> Option<String> {
+    let words = search_words(search);
+    if words.is_empty() {
+        return None;
+    }
+    Some(
+        words
+            .iter()
+            .map(|word| format!("\"{}\"*", word))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
 impl FromContext for ListUrlService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
         Ok(Self::new(ctx.inject().await?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shorty::model::shorty_model::{ListUrlRedirectSortColumn, SortOrder};
+    use crate::shorty::repository::shorty_repository::ShortyRepositoryError;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_list_urls_page_clamps_and_builds_fts_query() {
+        let filter = ListUrlRedirectFilter {
+            search: Some(" Spring-SALE OR \"50%\" ".to_string()),
+            created_by_user_id: Some(2),
+            tag: Some(" Q3-Conference ".to_string()),
+        };
+        let expected_filter = ListUrlRedirectFilter {
+            search: Some("\"spring\"* \"sale\"* \"or\"* \"50\"*".to_string()),
+            created_by_user_id: Some(2),
+            tag: Some("q3-conference".to_string()),
+        };
+        let sort = ListUrlRedirectSort {
+            column: ListUrlRedirectSortColumn::Clicks,
+            order: SortOrder::Desc,
+        };
+
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_count_url_redirect(expected_filter.clone())
+            .returns_once(Ok(0));
+        shorty_repository
+            .mock_list_url_redirect_page(expected_filter, sort, PER_PAGE_MAX, 0)
+            .returns_once(Ok(Arc::new([])));
+
+        let list_url_service = ListUrlService::new(shorty_repository);
+        let page = list_url_service
+            .list_urls_page(filter, sort, Some(0), Some(1000))
+            .await
+            .unwrap();
+        assert_eq!(page.page, 1);
+        assert_eq!(page.per_page, PER_PAGE_MAX);
+        assert_eq!(page.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_urls_page_caps_page() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_count_url_redirect(ListUrlRedirectFilter::default())
+            .returns_once(Ok(0));
+        shorty_repository
+            .mock_list_url_redirect_page(
+                ListUrlRedirectFilter::default(),
+                ListUrlRedirectSort::default(),
+                PER_PAGE_MAX,
+                (PAGE_MAX - 1) * PER_PAGE_MAX,
+            )
+            .returns_once(Ok(Arc::new([])));
+
+        let list_url_service = ListUrlService::new(shorty_repository);
+        let page = list_url_service
+            .list_urls_page(
+                ListUrlRedirectFilter::default(),
+                ListUrlRedirectSort::default(),
+                Some(i64::MAX),
+                Some(PER_PAGE_MAX),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.page, PAGE_MAX);
+    }
+
+    #[tokio::test]
+    async fn test_list_urls_page_db_error() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_count_url_redirect(ListUrlRedirectFilter::default())
+            .returns_once(Err(Report::new(ShortyRepositoryError::QueryError)));
+
+        let list_url_service = ListUrlService::new(shorty_repository);
+        let result = list_url_service
+            .list_urls_page(
+                ListUrlRedirectFilter::default(),
+                ListUrlRedirectSort::default(),
+                None,
+                None,
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_not_found() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect_detail(1)
+            .returns_once(Ok(None));
+
+        let list_url_service = ListUrlService::new(shorty_repository);
+        let error = list_url_service.fetch_url(1).await.unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_list_urls_page_ignores_search_without_words() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_count_url_redirect(ListUrlRedirectFilter::default())
+            .returns_once(Ok(0));
+        shorty_repository
+            .mock_list_url_redirect_page(
+                ListUrlRedirectFilter::default(),
+                ListUrlRedirectSort::default(),
+                PER_PAGE_DEFAULT,
+                0,
+            )
+            .returns_once(Ok(Arc::new([])));
+
+        let list_url_service = ListUrlService::new(shorty_repository);
+        let filter = ListUrlRedirectFilter {
+            search: Some(" -- ".to_string()),
+            created_by_user_id: None,
+            tag: Some(" ".to_string()),
+        };
+        let result = list_url_service
+            .list_urls_page(filter, ListUrlRedirectSort::default(), None, None)
+            .await;
+        assert!(result.is_ok());
+    }
+}
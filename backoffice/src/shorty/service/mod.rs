@@ -1,4 +1,9 @@
 pub mod add_url_service;
+pub mod alias_url_service;
 pub mod delete_url_service;
 pub mod edit_url_service;
 pub mod list_url_service;
+pub mod short_code_service;
+pub mod shorty_stats_service;
+pub mod tag_url_service;
+pub mod trash_url_service;
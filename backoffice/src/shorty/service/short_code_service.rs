@@ -0,0 +1,91 @@
+use error_stack::Report;
+use rand::Rng;
+use shared::config::ConfigPointer;
+use shared::context::{Context, ContextError, FromContext};
+use std::sync::Arc;
+
+/// Generated paths have to pass the same kebab case rule as typed ones.
+fn is_valid_alphabet(alphabet: &str) -> bool {
+    alphabet
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+#[mry::mry]
+pub struct ShortCodeService {
+    alphabet: Arc<[char]>,
+    length: usize,
+}
+
+impl ShortCodeService {
+    pub fn new(alphabet: &str, length: usize) -> Self {
+        let mut chars: Vec<char> = alphabet.chars().collect();
+        chars.sort_unstable();
+        chars.dedup();
+        Self {
+            alphabet: chars.into(),
+            length: length.max(1),
+            mry: Default::default(),
+        }
+    }
+}
+
+#[mry::mry]
+impl ShortCodeService {
+    pub fn generate(&self) -> String {
+        let mut rng = rand::rng();
+        (0..self.length)
+            .map(|_| self.alphabet[rng.random_range(0..self.alphabet.len())])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl ShortCodeService {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            alphabet: Arc::new([]),
+            length: 0,
+        })
+    }
+}
+
+impl FromContext for ShortCodeService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let config: ConfigPointer = ctx.inject().await?;
+        let shorty = &config.shorty;
+        if shorty.short_code_alphabet.is_empty() {
+            return Err(
+                Report::new(ContextError::ConfigError).attach("Short code alphabet is empty")
+            );
+        }
+        if !is_valid_alphabet(&shorty.short_code_alphabet) {
+            return Err(Report::new(ContextError::ConfigError)
+                .attach("Short code alphabet may only use a-z and 0-9"));
+        }
+        Ok(Self::new(
+            &shorty.short_code_alphabet,
+            shorty.short_code_length,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_uses_alphabet_and_length() {
+        let short_code_service = ShortCodeService::new("abcabc", 12);
+        let code = short_code_service.generate();
+        assert_eq!(code.chars().count(), 12);
+        assert!(code.chars().all(|c| "abc".contains(c)));
+    }
+
+    #[test]
+    fn test_is_valid_alphabet() {
+        assert!(is_valid_alphabet("23456789abcdefghjkmnpqrstuvwxyz"));
+        assert!(!is_valid_alphabet("abcABC"));
+        assert!(!is_valid_alphabet("abc-"));
+    }
+}
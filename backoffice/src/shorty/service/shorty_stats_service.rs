@@ -0,0 +1,445 @@
+use crate::shorty::model::shorty_model::GetUserIdByUrlIdModel;
+use crate::shorty::model::shorty_stats_model::{
+    DeviceClass, DeviceClassHitModel, HitBucketModel, HitLabelModel, UrlStatsModel,
+};
+use crate::shorty::repository::shorty_repository::ShortyRepository;
+use crate::shorty::repository::shorty_stats_repository::ShortyStatsRepository;
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use shared::context::{Context, ContextError, FromContext};
+use shared::error::ExtraResultExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+
+pub const STATS_DAYS: i64 = 30;
+pub const STATS_HOURS: i64 = 48;
+const STATS_TOP_LIMIT: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum ShortyStatsServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Not found")]
+    NotFound,
+}
+
+pub struct ShortyStatsService {
+    shorty_repository: ShortyRepository,
+    shorty_stats_repository: ShortyStatsRepository,
+}
+
+impl ShortyStatsService {
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        shorty_stats_repository: ShortyStatsRepository,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            shorty_stats_repository,
+        }
+    }
+
+    pub async fn fetch_user_id_from_url_id(
+        &self,
+        id: i64,
+    ) -> Result<GetUserIdByUrlIdModel, Report<ShortyStatsServiceError>> {
+        self.shorty_repository
+            .get_user_id_by_url_id(id)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)?
+            .ok_or_else(|| {
+                Report::new(ShortyStatsServiceError::NotFound).attach(StatusCode::NOT_FOUND)
+            })
+    }
+
+    pub async fn fetch_stats(
+        &self,
+        id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<UrlStatsModel, Report<ShortyStatsServiceError>> {
+        let url = self
+            .shorty_repository
+            .get_url_redirect(id)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)
+            .log_it()?
+            .ok_or_else(|| {
+                Report::new(ShortyStatsServiceError::NotFound).attach(StatusCode::NOT_FOUND)
+            })?;
+
+        let day_start =
+            now.duration_trunc(TimeDelta::days(1)).unwrap_or(now) - TimeDelta::days(STATS_DAYS - 1);
+        let hour_start = now.duration_trunc(TimeDelta::hours(1)).unwrap_or(now)
+            - TimeDelta::hours(STATS_HOURS - 1);
+
+        let hit_count = self
+            .shorty_stats_repository
+            .get_hit_count(id)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)
+            .log_it()?
+            .unwrap_or_default();
+        let daily = self
+            .shorty_stats_repository
+            .daily_hits(id, day_start)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)
+            .log_it()?;
+        let hourly = self
+            .shorty_stats_repository
+            .hourly_hits(id, hour_start)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)
+            .log_it()?;
+        let top_referers = self
+            .shorty_stats_repository
+            .top_referers(id, day_start, STATS_TOP_LIMIT as i64)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)
+            .log_it()?;
+        let user_agents = self
+            .shorty_stats_repository
+            .user_agent_hits(id, day_start)
+            .await
+            .change_context(ShortyStatsServiceError::DbError)
+            .log_it()?;
+
+        let daily = fill_buckets(
+            &daily,
+            day_start,
+            STATS_DAYS,
+            TimeDelta::days(1),
+            "%Y-%m-%d",
+        );
+        let hourly = fill_buckets(
+            &hourly,
+            hour_start,
+            STATS_HOURS,
+            TimeDelta::hours(1),
+            "%Y-%m-%d %H:00",
+        );
+
+        Ok(UrlStatsModel {
+            url_path: url.url_path,
+            url_redirect: url.url_redirect,
+            hit_count,
+            period_hit_count: daily.iter().map(|bucket| bucket.hits).sum(),
+            daily,
+            hourly,
+            top_referers,
+            top_user_agents: user_agents.iter().take(STATS_TOP_LIMIT).cloned().collect(),
+            browser_families: count_browser_families(&user_agents),
+            device_classes: count_device_classes(&user_agents),
+        })
+    }
+}
+
+fn fill_buckets(
+    rows: &[HitBucketModel],
+    start: DateTime<Utc>,
+    steps: i64,
+    step: TimeDelta,
+    format: &str,
+) -> Arc<[HitBucketModel]> {
+    let hits: HashMap<&str, i64> = rows
+        .iter()
+        .map(|row| (row.bucket.as_str(), row.hits))
+        .collect();
+    (0..steps)
+        .map(|i| {
+            let bucket = (start + step * i as i32).format(format).to_string();
+            HitBucketModel {
+                hits: hits.get(bucket.as_str()).copied().unwrap_or_default(),
+                bucket,
+            }
+        })
+        .collect()
+}
+
+fn is_bot(user_agent: &str) -> bool {
+    let user_agent = user_agent.to_lowercase();
+    [
+        "bot",
+        "crawl",
+        "spider",
+        "slurp",
+        "curl/",
+        "wget/",
+        "python-",
+        "go-http-client",
+    ]
+    .iter()
+    .any(|needle| user_agent.contains(needle))
+}
+
+fn browser_family(user_agent: &str) -> Option<&'static str> {
+    if is_bot(user_agent) {
+        return Some("Bot");
+    }
+    [
+        ("Edg", "Edge"),
+        ("OPR/", "Opera"),
+        ("Opera", "Opera"),
+        ("SamsungBrowser/", "Samsung Internet"),
+        ("Firefox/", "Firefox"),
+        ("FxiOS/", "Firefox"),
+        ("CriOS/", "Chrome"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+    ]
+    .iter()
+    .find(|(needle, _)| user_agent.contains(needle))
+    .map(|(_, family)| *family)
+}
+
+fn device_class(user_agent: &str) -> DeviceClass {
+    if user_agent.is_empty() {
+        DeviceClass::Unknown
+    } else if is_bot(user_agent) {
+        DeviceClass::Bot
+    } else if user_agent.contains("iPad")
+        || user_agent.contains("Tablet")
+        || (user_agent.contains("Android") && !user_agent.contains("Mobile"))
+    {
+        DeviceClass::Tablet
+    } else if user_agent.contains("Mobi")
+        || user_agent.contains("iPhone")
+        || user_agent.contains("Android")
+    {
+        DeviceClass::Mobile
+    } else if ["Windows", "Macintosh", "X11", "Linux", "CrOS"]
+        .iter()
+        .any(|needle| user_agent.contains(needle))
+    {
+        DeviceClass::Desktop
+    } else {
+        DeviceClass::Unknown
+    }
+}
+
+fn count_browser_families(user_agents: &[HitLabelModel]) -> Arc<[HitLabelModel]> {
+    let mut counts: HashMap<Option<&'static str>, i64> = HashMap::new();
+    for user_agent in user_agents {
+        let family = user_agent.label.as_deref().and_then(browser_family);
+        *counts.entry(family).or_default() += user_agent.hits;
+    }
+    let mut families: Vec<HitLabelModel> = counts
+        .into_iter()
+        .map(|(family, hits)| HitLabelModel {
+            label: family.map(str::to_string),
+            hits,
+        })
+        .collect();
+    families.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.label.cmp(&b.label)));
+    families.into()
+}
+
+fn count_device_classes(user_agents: &[HitLabelModel]) -> Arc<[DeviceClassHitModel]> {
+    let mut counts: HashMap<DeviceClass, i64> = HashMap::new();
+    for user_agent in user_agents {
+        let device_class = device_class(user_agent.label.as_deref().unwrap_or_default());
+        *counts.entry(device_class).or_default() += user_agent.hits;
+    }
+    let mut device_classes: Vec<DeviceClassHitModel> = counts
+        .into_iter()
+        .map(|(device_class, hits)| DeviceClassHitModel { device_class, hits })
+        .collect();
+    device_classes.sort_by(|a, b| {
+        b.hits
+            .cmp(&a.hits)
+            .then_with(|| a.device_class.cmp(&b.device_class))
+    });
+    device_classes.into()
+}
+
+impl FromContext for ShortyStatsService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shorty::model::shorty_model::GetUrlRedirectModel;
+    use crate::shorty::repository::shorty_stats_repository::ShortyStatsRepositoryError;
+    use chrono::TimeZone;
+
+    const FIREFOX_DESKTOP: &str =
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:131.0) Gecko/20100101 Firefox/131.0";
+    const CHROME_ANDROID: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Mobile Safari/537.36";
+    const SAFARI_IPAD: &str = "Mozilla/5.0 (iPad; CPU OS 17_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.6 Mobile/15E148 Safari/604.1";
+    const EDGE_DESKTOP: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36 Edg/129.0.0.0";
+    const GOOGLE_BOT: &str =
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 10, 15, 42, 0).unwrap()
+    }
+
+    #[test]
+    fn test_fill_buckets_zero_fills_missing() {
+        let rows = [HitBucketModel {
+            bucket: "2025-03-09".to_string(),
+            hits: 4,
+        }];
+        let start = Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap();
+        let buckets = fill_buckets(&rows, start, 3, TimeDelta::days(1), "%Y-%m-%d");
+        assert_eq!(
+            buckets.as_ref(),
+            &[
+                HitBucketModel {
+                    bucket: "2025-03-08".to_string(),
+                    hits: 0
+                },
+                HitBucketModel {
+                    bucket: "2025-03-09".to_string(),
+                    hits: 4
+                },
+                HitBucketModel {
+                    bucket: "2025-03-10".to_string(),
+                    hits: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_browser_family() {
+        assert_eq!(browser_family(FIREFOX_DESKTOP), Some("Firefox"));
+        assert_eq!(browser_family(CHROME_ANDROID), Some("Chrome"));
+        assert_eq!(browser_family(SAFARI_IPAD), Some("Safari"));
+        assert_eq!(browser_family(EDGE_DESKTOP), Some("Edge"));
+        assert_eq!(browser_family(GOOGLE_BOT), Some("Bot"));
+        assert_eq!(browser_family("something"), None);
+    }
+
+    #[test]
+    fn test_device_class() {
+        assert_eq!(device_class(FIREFOX_DESKTOP), DeviceClass::Desktop);
+        assert_eq!(device_class(CHROME_ANDROID), DeviceClass::Mobile);
+        assert_eq!(device_class(SAFARI_IPAD), DeviceClass::Tablet);
+        assert_eq!(device_class(GOOGLE_BOT), DeviceClass::Bot);
+        assert_eq!(device_class(""), DeviceClass::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stats_success() {
+        let day_start = Utc.with_ymd_and_hms(2025, 2, 9, 0, 0, 0).unwrap();
+        let hour_start = Utc.with_ymd_and_hms(2025, 3, 8, 16, 0, 0).unwrap();
+
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel {
+                url_path: "hello".to_string(),
+                url_redirect: "http://hello.com".to_string(),
+                ..Default::default()
+            })));
+
+        let mut shorty_stats_repository = ShortyStatsRepository::new_mock();
+        shorty_stats_repository
+            .mock_get_hit_count(1)
+            .returns_once(Ok(Some(12)));
+        shorty_stats_repository
+            .mock_daily_hits(1, day_start)
+            .returns_once(Ok(Arc::new([
+                HitBucketModel {
+                    bucket: "2025-02-09".to_string(),
+                    hits: 2,
+                },
+                HitBucketModel {
+                    bucket: "2025-03-10".to_string(),
+                    hits: 3,
+                },
+            ])));
+        shorty_stats_repository
+            .mock_hourly_hits(1, hour_start)
+            .returns_once(Ok(Arc::new([HitBucketModel {
+                bucket: "2025-03-10 15:00".to_string(),
+                hits: 3,
+            }])));
+        shorty_stats_repository
+            .mock_top_referers(1, day_start, STATS_TOP_LIMIT as i64)
+            .returns_once(Ok(Arc::new([HitLabelModel {
+                label: None,
+                hits: 5,
+            }])));
+        shorty_stats_repository
+            .mock_user_agent_hits(1, day_start)
+            .returns_once(Ok(Arc::new([
+                HitLabelModel {
+                    label: Some(FIREFOX_DESKTOP.to_string()),
+                    hits: 3,
+                },
+                HitLabelModel {
+                    label: Some(EDGE_DESKTOP.to_string()),
+                    hits: 1,
+                },
+                HitLabelModel {
+                    label: None,
+                    hits: 1,
+                },
+            ])));
+
+        let service = ShortyStatsService::new(shorty_repository, shorty_stats_repository);
+        let stats = service.fetch_stats(1, now()).await.unwrap();
+
+        assert_eq!(stats.url_path, "hello");
+        assert_eq!(stats.hit_count, 12);
+        assert_eq!(stats.period_hit_count, 5);
+        assert_eq!(stats.daily.len(), STATS_DAYS as usize);
+        assert_eq!(stats.daily.first().unwrap().hits, 2);
+        assert_eq!(stats.daily.last().unwrap().hits, 3);
+        assert_eq!(stats.hourly.len(), STATS_HOURS as usize);
+        assert_eq!(stats.hourly.last().unwrap().hits, 3);
+        assert_eq!(stats.top_user_agents.len(), 3);
+        assert_eq!(
+            stats.browser_families.first().unwrap(),
+            &HitLabelModel {
+                label: Some("Firefox".to_string()),
+                hits: 3
+            }
+        );
+        assert_eq!(
+            stats.device_classes.first().unwrap(),
+            &DeviceClassHitModel {
+                device_class: DeviceClass::Desktop,
+                hits: 4
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stats_not_found() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(None));
+
+        let service = ShortyStatsService::new(shorty_repository, ShortyStatsRepository::new_mock());
+        let result = service.fetch_stats(1, now()).await;
+        assert!(result.is_err());
+        let error = result.err().unwrap();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stats_db_error() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_get_url_redirect(1)
+            .returns_once(Ok(Some(GetUrlRedirectModel::default())));
+
+        let mut shorty_stats_repository = ShortyStatsRepository::new_mock();
+        shorty_stats_repository
+            .mock_get_hit_count(1)
+            .returns_once(Err(Report::new(ShortyStatsRepositoryError::QueryError)));
+
+        let service = ShortyStatsService::new(shorty_repository, shorty_stats_repository);
+        assert!(service.fetch_stats(1, now()).await.is_err());
+    }
+}
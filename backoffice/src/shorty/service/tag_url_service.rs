@@ -0,0 +1,182 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::shorty::model::shorty_model::{GetUrlRedirectModel, UrlTagCountModel};
+use crate::shorty::repository::shorty_repository::ShortyRepository;
+use chrono::{SubsecRound, Utc};
+use error_stack::{Report, ResultExt};
+use serde_json::json;
+use shared::context::{Context, ContextError, FromContext};
+use shared::redirect::invalidation::RedirectInvalidator;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TagUrlServiceError {
+    #[error("Database error")]
+    DbError,
+}
+
+/// Bulk actions take `created_by_user_id` to stay within one user's links, none covers every link.
+pub struct TagUrlService {
+    shorty_repository: ShortyRepository,
+    redirect_invalidator: RedirectInvalidator,
+    audit_service: AuditService,
+}
+
+impl TagUrlService {
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        redirect_invalidator: RedirectInvalidator,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            redirect_invalidator,
+            audit_service,
+        }
+    }
+
+    pub async fn list_tag_counts(
+        &self,
+    ) -> Result<Arc<[UrlTagCountModel]>, Report<TagUrlServiceError>> {
+        self.shorty_repository
+            .list_url_redirect_tag_count()
+            .await
+            .change_context(TagUrlServiceError::DbError)
+    }
+
+    /// The links are moved in one go, but each is audited and can be restored like any other.
+    pub async fn trash_tag(
+        &self,
+        tag: &str,
+        created_by_user_id: Option<i64>,
+    ) -> Result<usize, Report<TagUrlServiceError>> {
+        let ids = self
+            .shorty_repository
+            .list_url_id_by_tag(tag, created_by_user_id)
+            .await
+            .change_context(TagUrlServiceError::DbError)?;
+        let mut befores = HashMap::with_capacity(ids.len());
+        for id in ids.iter() {
+            let before = self
+                .shorty_repository
+                .get_url_redirect(*id)
+                .await
+                .change_context(TagUrlServiceError::DbError)?;
+            befores.insert(*id, before);
+        }
+        let deleted = self
+            .shorty_repository
+            .delete_url_redirect_by_ids(ids, Utc::now().trunc_subsecs(0))
+            .await
+            .change_context(TagUrlServiceError::DbError)?;
+        for id in deleted.iter() {
+            self.redirect_invalidator.invalidate_id(*id);
+            self.audit_service
+                .record(
+                    AuditAction::LinkDelete,
+                    Some(*id),
+                    befores.get(id).and_then(Option::as_ref),
+                    None::<&GetUrlRedirectModel>,
+                )
+                .await;
+        }
+        Ok(deleted.len())
+    }
+
+    pub async fn remove_tag(
+        &self,
+        tag: &str,
+        created_by_user_id: Option<i64>,
+    ) -> Result<usize, Report<TagUrlServiceError>> {
+        let removed = self
+            .shorty_repository
+            .delete_tag(tag, created_by_user_id)
+            .await
+            .change_context(TagUrlServiceError::DbError)?;
+        if removed > 0 {
+            self.audit_service
+                .record(
+                    AuditAction::TagRemove,
+                    None,
+                    Some(&json!({ "tag": tag, "link_count": removed })),
+                    None::<&()>,
+                )
+                .await;
+        }
+        Ok(removed)
+    }
+}
+
+impl FromContext for TagUrlService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::model::audit_model::AuditActor;
+    use crate::audit::repository::audit_repository::AuditRepository;
+    use mry::Any;
+    use shared::redirect::invalidation::RedirectInvalidation;
+    use tokio::sync::broadcast::channel;
+
+    #[tokio::test]
+    async fn test_trash_tag_moves_each_link() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_list_url_id_by_tag("q3-conference", Some(2))
+            .returns_once(Ok(Arc::from([4, 7])));
+        shorty_repository
+            .mock_get_url_redirect(Any)
+            .returns_with(|_| Ok(Some(GetUrlRedirectModel::default())));
+        shorty_repository
+            .mock_delete_url_redirect_by_ids(Arc::from([4, 7]), Any)
+            .returns_once(Ok(vec![4, 7]));
+
+        let redirect_invalidator = RedirectInvalidator::new(channel(2).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        let tag_url_service = TagUrlService::new(
+            shorty_repository,
+            redirect_invalidator,
+            AuditService::new_mock(),
+        );
+        assert_eq!(
+            tag_url_service
+                .trash_tag("q3-conference", Some(2))
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(receiver.try_recv().unwrap(), RedirectInvalidation::Id(4));
+        assert_eq!(receiver.try_recv().unwrap(), RedirectInvalidation::Id(7));
+    }
+
+    #[tokio::test]
+    async fn test_remove_tag_unused_is_not_audited() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_delete_tag("q3-conference", None)
+            .returns_once(Ok(0));
+
+        // Any audit entry would panic, as the repository has nothing mocked.
+        let tag_url_service = TagUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new(AuditRepository::new_mock(), AuditActor::default()),
+        );
+        assert_eq!(
+            tag_url_service
+                .remove_tag("q3-conference", None)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
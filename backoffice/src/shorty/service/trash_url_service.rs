@@ -0,0 +1,291 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::shorty::model::shorty_model::{GetUserIdByUrlIdModel, TrashedUrlRedirectModel};
+use crate::shorty::repository::shorty_repository::ShortyRepository;
+use chrono::{DateTime, SubsecRound, TimeDelta, Utc};
+use error_stack::{Report, ResultExt};
+use log::error;
+use poem::http::StatusCode;
+use shared::config::ConfigPointer;
+use shared::context::{Context, ContextError, FromContext, fetch_context};
+use shared::redirect::invalidation::RedirectInvalidator;
+use shared::shutdown::shutdown_signal;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum TrashUrlServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Not found")]
+    NotFound,
+}
+
+pub struct TrashUrlService {
+    shorty_repository: ShortyRepository,
+    redirect_invalidator: RedirectInvalidator,
+    audit_service: AuditService,
+    trash_retention: Option<TimeDelta>,
+}
+
+impl TrashUrlService {
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        redirect_invalidator: RedirectInvalidator,
+        audit_service: AuditService,
+        trash_retention: Option<TimeDelta>,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            redirect_invalidator,
+            audit_service,
+            trash_retention,
+        }
+    }
+
+    pub async fn list_trash(&self) -> Arc<[TrashedUrlRedirectModel]> {
+        self.shorty_repository
+            .list_trashed_url_redirect()
+            .await
+            .unwrap_or_default()
+    }
+
+    pub fn purged_at(&self, deleted_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.trash_retention
+            .map(|trash_retention| deleted_at + trash_retention)
+    }
+
+    /// Nothing to purge when the retention is off. Returns how many links were purged.
+    pub async fn purge_expired(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<usize, Report<TrashUrlServiceError>> {
+        let Some(trash_retention) = self.trash_retention else {
+            return Ok(0);
+        };
+        let purged = self
+            .shorty_repository
+            .purge_url_redirect(now - trash_retention)
+            .await
+            .change_context(TrashUrlServiceError::DbError)?;
+        for id in purged.iter() {
+            self.audit_service
+                .record(AuditAction::LinkPurge, Some(*id), None::<&()>, None::<&()>)
+                .await;
+        }
+        Ok(purged.len())
+    }
+
+    pub async fn restore_url(&self, id: i64) -> Result<(), Report<TrashUrlServiceError>> {
+        let url_path = self
+            .shorty_repository
+            .restore_url_redirect(id)
+            .await
+            .change_context(TrashUrlServiceError::DbError)?
+            .ok_or_else(|| {
+                Report::new(TrashUrlServiceError::NotFound).attach(StatusCode::NOT_FOUND)
+            })?;
+        // The path and the aliases are cached as unknown while the link was in the trash.
+        let aliases = self
+            .shorty_repository
+            .list_url_redirect_alias(id)
+            .await
+            .change_context(TrashUrlServiceError::DbError)?;
+        self.redirect_invalidator.invalidate_path(&url_path);
+        for alias in aliases.iter() {
+            self.redirect_invalidator.invalidate_path(&alias.url_path);
+        }
+        self.audit_service
+            .record(AuditAction::LinkRestore, Some(id), None::<&()>, None::<&()>)
+            .await;
+        Ok(())
+    }
+
+    pub async fn fetch_user_id_from_url_id(
+        &self,
+        id: i64,
+    ) -> Result<GetUserIdByUrlIdModel, Report<TrashUrlServiceError>> {
+        self.shorty_repository
+            .get_user_id_by_trashed_url_id(id)
+            .await
+            .change_context(TrashUrlServiceError::DbError)?
+            .ok_or_else(|| {
+                Report::new(TrashUrlServiceError::NotFound).attach(StatusCode::NOT_FOUND)
+            })
+    }
+}
+
+impl FromContext for TrashUrlService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let config: ConfigPointer = ctx.inject().await?;
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+            config.shorty.trash_retention(),
+        ))
+    }
+}
+
+/// Purges the trash every hour until the backoffice shuts down.
+pub async fn purge_trash_periodically() {
+    let mut interval = interval(PURGE_INTERVAL);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = &mut shutdown => return,
+        }
+        let trash_url_service = match fetch_context::<TrashUrlService>().await {
+            Ok(trash_url_service) => trash_url_service,
+            Err(err) => {
+                error!("Failed to purge the trash: {:?}", err);
+                continue;
+            }
+        };
+        if let Err(err) = trash_url_service
+            .purge_expired(Utc::now().trunc_subsecs(0))
+            .await
+        {
+            error!("Failed to purge the trash: {:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::model::audit_model::AuditActor;
+    use crate::audit::repository::audit_repository::AuditRepository;
+    use crate::shorty::model::shorty_model::UrlRedirectAliasModel;
+    use chrono::TimeZone;
+    use mry::Any;
+    use shared::redirect::invalidation::RedirectInvalidation;
+    use tokio::sync::broadcast::channel;
+
+    #[tokio::test]
+    async fn test_purge_expired_uses_retention() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 31, 12, 0, 0).unwrap();
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_purge_url_redirect(Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap())
+            .returns_once(Ok(vec![3, 4]));
+
+        let trash_url_service = TrashUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            Some(TimeDelta::days(30)),
+        );
+        assert_eq!(trash_url_service.purge_expired(now).await.unwrap(), 2);
+
+        // Kept forever, so the repository is not touched.
+        let trash_url_service = TrashUrlService::new(
+            ShortyRepository::new_mock(),
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            None,
+        );
+        assert_eq!(trash_url_service.purge_expired(now).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_audits_each_link() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_purge_url_redirect(Any)
+            .returns_once(Ok(vec![3]));
+        let mut audit_repository = AuditRepository::new_mock();
+        audit_repository
+            .mock_add_audit_log(Any, AuditAction::LinkPurge, Some(3), Any, Any)
+            .returns_once(Ok(()));
+
+        let trash_url_service = TrashUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new(audit_repository, AuditActor::default()),
+            Some(TimeDelta::days(30)),
+        );
+        assert!(trash_url_service.purge_expired(Utc::now()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_restore_url_invalidates_path() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_restore_url_redirect(1)
+            .returns_once(Ok(Some("sale".to_string())));
+        shorty_repository
+            .mock_list_url_redirect_alias(1)
+            .returns_once(Ok(Arc::new([])));
+
+        let redirect_invalidator = RedirectInvalidator::new(channel(1).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        let trash_url_service = TrashUrlService::new(
+            shorty_repository,
+            redirect_invalidator,
+            AuditService::new_mock(),
+            None,
+        );
+        assert!(trash_url_service.restore_url(1).await.is_ok());
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RedirectInvalidation::Path("sale".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_url_invalidates_alias_paths() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_restore_url_redirect(1)
+            .returns_once(Ok(Some("sale".to_string())));
+        shorty_repository
+            .mock_list_url_redirect_alias(1)
+            .returns_once(Ok(Arc::new([UrlRedirectAliasModel {
+                id: 2,
+                url_path: "promo".to_string(),
+                created_at: Utc::now(),
+            }])));
+
+        let redirect_invalidator = RedirectInvalidator::new(channel(2).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        let trash_url_service = TrashUrlService::new(
+            shorty_repository,
+            redirect_invalidator,
+            AuditService::new_mock(),
+            None,
+        );
+        assert!(trash_url_service.restore_url(1).await.is_ok());
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RedirectInvalidation::Path("sale".to_string())
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RedirectInvalidation::Path("promo".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_url_not_in_trash() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_restore_url_redirect(1)
+            .returns_once(Ok(None));
+
+        let trash_url_service = TrashUrlService::new(
+            shorty_repository,
+            RedirectInvalidator::default(),
+            AuditService::new_mock(),
+            None,
+        );
+        let error = trash_url_service.restore_url(1).await.unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+}
@@ -3,10 +3,11 @@ use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
 use rusqlite::{Connection, OptionalExtension, named_params};
 use shared::context::{Context, ContextError, FromContext};
-use shared::db::{BorrowConnectionExt, SqliteClient};
-use std::sync::{Arc, MutexGuard};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
 use thiserror::Error;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum StackRepositoryError {
     #[error("Query Error")]
@@ -30,85 +31,105 @@ impl StackRepository {
         }
     }
 
-    fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<StackRepositoryError>> {
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<StackRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<StackRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
         self.sqlite_client
-            .borrow_conn()
-            .change_context(StackRepositoryError::BorrowConnError)
+            .read(StackRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<StackRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<StackRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(StackRepositoryError::BorrowConnError, f)
+            .await
     }
 }
 
 #[mry::mry]
 impl StackRepository {
-    pub fn clear(&self) -> Result<(), Report<StackRepositoryError>> {
-        let conn = self.borrow_conn()?;
-        conn.execute(
-            include_str!("_sql/stack_repository/clear.sql"),
-            named_params! {},
-        )
-        .change_context(StackRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        Ok(())
+    pub async fn clear(&self) -> Result<(), Report<StackRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/stack_repository/clear.sql"),
+                named_params! {},
+            )
+            .change_context(StackRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(())
+        })
+        .await
     }
 
-    pub fn fetch_error_stack(
+    pub async fn fetch_error_stack(
         &self,
         id: i64,
     ) -> Result<Option<StackModel>, Report<StackRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        let mut stmt = conn
-            .prepare(include_str!("_sql/stack_repository/fetch_error_stack.sql"))
-            .change_context(StackRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare(include_str!("_sql/stack_repository/fetch_error_stack.sql"))
+                .change_context(StackRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let row = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id
+                    },
+                    |row| {
+                        Ok(StackModel {
+                            id: row.get("id")?,
+                            error_name: row.get("error_name")?,
+                            error_summary: row.get("error_summary")?,
+                            error_stack: row.get("error_stack")?,
+                            reported_at: row.get("reported_at")?,
+                        })
+                    },
+                )
+                .optional()
+                .change_context(StackRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(row)
+        })
+        .await
+    }
 
-        let row = stmt
-            .query_one(
-                named_params! {
-                    ":id": id
-                },
-                |row| {
-                    Ok(StackModel {
+    pub async fn list_error_stack(
+        &self,
+    ) -> Result<Arc<[ListStackModel]>, Report<StackRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare(include_str!("_sql/stack_repository/list_error_stack.sql"))
+                .change_context(StackRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let rows_iter = stmt
+                .query_map(named_params! {}, |row| {
+                    Ok(ListStackModel {
                         id: row.get("id")?,
                         error_name: row.get("error_name")?,
                         error_summary: row.get("error_summary")?,
-                        error_stack: row.get("error_stack")?,
                         reported_at: row.get("reported_at")?,
                     })
-                },
-            )
-            .optional()
-            .change_context(StackRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-
-        Ok(row)
-    }
-
-    pub fn list_error_stack(&self) -> Result<Arc<[ListStackModel]>, Report<StackRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        let mut stmt = conn
-            .prepare(include_str!("_sql/stack_repository/list_error_stack.sql"))
-            .change_context(StackRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-
-        let rows_iter = stmt
-            .query_map(named_params! {}, |row| {
-                Ok(ListStackModel {
-                    id: row.get("id")?,
-                    error_name: row.get("error_name")?,
-                    error_summary: row.get("error_summary")?,
-                    reported_at: row.get("reported_at")?,
                 })
-            })
-            .change_context(StackRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                .change_context(StackRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let items = rows_iter
-            .collect::<Result<Vec<_>, _>>()
-            .change_context(StackRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let items = rows_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(StackRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(items.into())
+            Ok(items.into())
+        })
+        .await
     }
 }
 
@@ -16,11 +16,11 @@ use shared::htmx::HtmxHeader;
 pub const STACK_ROUTE: &str = "/stack";
 
 #[handler]
-fn list_error_stack(
+async fn list_error_stack(
     Dep(stack_service): Dep<StackService>,
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
 ) -> Markup {
-    let error_stack_list = stack_service.list_error_stack();
+    let error_stack_list = stack_service.list_error_stack().await;
     let open_icon = document_magnifying_glass_icon();
     let clear_icon = no_symbol_icon();
 
@@ -65,13 +65,14 @@ fn list_error_stack(
 }
 
 #[handler]
-fn fetch_error_stack_detail(
+async fn fetch_error_stack_detail(
     Dep(stack_service): Dep<StackService>,
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
     Path(view_id): Path<i64>,
 ) -> poem::Result<Markup> {
     let item = stack_service
         .fetch_error_stack(view_id)
+        .await
         .map_err(poem::Error::from_error_stack)?;
 
     let lc = StackFetchLocale::new(&context_html_builder.locale, item.error_name.as_str());
@@ -92,13 +93,14 @@ fn fetch_error_stack_detail(
 }
 
 #[handler]
-fn clear(
+async fn clear(
     Dep(stack_service): Dep<StackService>,
     session: &Session,
     htmx_header: HtmxHeader,
 ) -> poem::Result<Response> {
     stack_service
         .clear()
+        .await
         .map_err(poem::Error::from_error_stack)?;
 
     session.flash(Flash::Success {
@@ -24,23 +24,31 @@ impl StackService {
         Self { stack_repository }
     }
 
-    pub fn clear(&self) -> Result<(), Report<StackServiceError>> {
+    pub async fn clear(&self) -> Result<(), Report<StackServiceError>> {
         self.stack_repository
             .clear()
+            .await
             .change_context(StackServiceError::DbError)
             .log_it()
     }
 
-    pub fn fetch_error_stack(&self, id: i64) -> Result<StackModel, Report<StackServiceError>> {
+    pub async fn fetch_error_stack(
+        &self,
+        id: i64,
+    ) -> Result<StackModel, Report<StackServiceError>> {
         self.stack_repository
             .fetch_error_stack(id)
+            .await
             .change_context(StackServiceError::DbError)
             .log_it()?
             .ok_or_else(|| Report::new(StackServiceError::NotFound).attach(StatusCode::NOT_FOUND))
     }
 
-    pub fn list_error_stack(&self) -> Arc<[ListStackModel]> {
-        self.stack_repository.list_error_stack().unwrap_or_default()
+    pub async fn list_error_stack(&self) -> Arc<[ListStackModel]> {
+        self.stack_repository
+            .list_error_stack()
+            .await
+            .unwrap_or_default()
     }
 }
 
@@ -55,30 +63,30 @@ mod tests {
     use super::*;
     use crate::stack::repository::stack_repository::StackRepositoryError;
 
-    #[test]
-    fn test_stack_service_clear_success() {
+    #[tokio::test]
+    async fn test_stack_service_clear_success() {
         let mut stack_repository = StackRepository::new_mock();
         stack_repository.mock_clear().returns_once(Ok(()));
 
         let stack_service = StackService::new(stack_repository);
-        let result = stack_service.clear();
+        let result = stack_service.clear().await;
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_stack_service_clear_failure() {
+    #[tokio::test]
+    async fn test_stack_service_clear_failure() {
         let mut stack_repository = StackRepository::new_mock();
         stack_repository
             .mock_clear()
             .returns_once(Err(Report::new(StackRepositoryError::QueryError)));
 
         let stack_service = StackService::new(stack_repository);
-        let result = stack_service.clear();
+        let result = stack_service.clear().await;
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_stack_service_fetch_error_stack_success() {
+    #[tokio::test]
+    async fn test_stack_service_fetch_error_stack_success() {
         let mut stack_repository = StackRepository::new_mock();
         stack_repository
             .mock_fetch_error_stack(1)
@@ -91,31 +99,31 @@ mod tests {
             })));
 
         let stack_service = StackService::new(stack_repository);
-        let result = stack_service.fetch_error_stack(1);
+        let result = stack_service.fetch_error_stack(1).await;
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_stack_service_fetch_error_stack_error() {
+    #[tokio::test]
+    async fn test_stack_service_fetch_error_stack_error() {
         let mut stack_repository = StackRepository::new_mock();
         stack_repository
             .mock_fetch_error_stack(1)
             .returns_once(Err(Report::new(StackRepositoryError::QueryError)));
 
         let stack_service = StackService::new(stack_repository);
-        let result = stack_service.fetch_error_stack(1);
+        let result = stack_service.fetch_error_stack(1).await;
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_stack_service_fetch_error_stack_not_found() {
+    #[tokio::test]
+    async fn test_stack_service_fetch_error_stack_not_found() {
         let mut stack_repository = StackRepository::new_mock();
         stack_repository
             .mock_fetch_error_stack(1)
             .returns_once(Ok(None));
 
         let stack_service = StackService::new(stack_repository);
-        let result = stack_service.fetch_error_stack(1);
+        let result = stack_service.fetch_error_stack(1).await;
         assert!(result.is_err());
         let result = result.err().unwrap();
         let error_code = result.downcast_ref::<StatusCode>().unwrap();
@@ -0,0 +1,149 @@
+use crate::common::html::validate::ValidateErrorMessageExt;
+use crate::shorty::rule::expires_at::ExpiresAtRulesExt;
+use crate::user::form::locale::ApiTokenFormLocale;
+use crate::user::model::api_token_model::ApiTokenScope;
+use crate::user::role::Role;
+use crate::user::rule::api_token::{
+    ApiTokenNameRulesExt, ApiTokenScopeError, ApiTokenScopeRulesExt,
+};
+use chrono::{DateTime, Utc};
+use cjtoolkit_structured_validator::common::flag_error::FlagCounter;
+use cjtoolkit_structured_validator::types::name::name_alias::{Field, FieldError};
+use cjtoolkit_structured_validator::types::times_chrono::naive_date_time::{
+    NaiveDateTimeError, NaiveDateTimeValue,
+};
+use maud::{Markup, html};
+use poem::i18n::Locale;
+use serde::{Deserialize, Serialize};
+use shared::locale::LocaleExtForResult;
+use std::sync::Arc;
+
+#[derive(Deserialize, Default)]
+pub struct AddApiTokenForm {
+    pub name: String,
+    pub scope: String,
+    #[serde(default)]
+    pub expires_at: String,
+    pub csrf_token: String,
+}
+
+impl AddApiTokenForm {
+    pub async fn as_validated(&self, role: &Role) -> AddApiTokenResult {
+        AddApiTokenResult(
+            async {
+                let mut flag = FlagCounter::new();
+
+                let name = flag.check(Field::parse_api_token_name(Some(self.name.trim())));
+                let scope = flag.check(ApiTokenScope::parse_api_token_scope(
+                    Some(self.scope.trim()),
+                    role,
+                ));
+                let expires_at = flag.check(NaiveDateTimeValue::parse_expires_at(Some(
+                    self.expires_at.trim(),
+                )));
+
+                if flag.is_flagged() {
+                    return Err(AddApiTokenError {
+                        name,
+                        scope,
+                        expires_at,
+                    });
+                }
+
+                Ok(AddApiTokenValidated {
+                    name: name.expect("Name is not empty"),
+                    scope: scope.expect("Scope is valid"),
+                    expires_at: expires_at.expect("Expires at is valid"),
+                })
+            }
+            .await,
+        )
+    }
+
+    pub fn as_form_markup(
+        &self,
+        locale: &Locale,
+        errors: Option<AddApiTokenMessage>,
+        token: Option<Markup>,
+        role: &Role,
+    ) -> Markup {
+        let errors = errors.unwrap_or_default();
+        let token = token.unwrap_or_default();
+        let api_token_form_locale = ApiTokenFormLocale::new(locale);
+        let current_scope = ApiTokenScope::try_from(self.scope.as_str()).unwrap_or_default();
+
+        html! {
+            h2 .mt-3 { (api_token_form_locale.title_add) }
+            form hx-boost="true" hx-target="#main-content" .form method="post" {
+                (token)
+                div .form-group {
+                    label .label for="name" { (api_token_form_locale.name) } br;
+                    input .form-item .w-full type="text" name="name" #name value=(self.name)
+                    placeholder=(api_token_form_locale.name_placeholder) {}
+                    (errors.name.into_error_html())
+                }
+                div .form-group {
+                    label .label for="scope" { (api_token_form_locale.scope) } br;
+                    select .form-item .w-full name="scope" #scope {
+                        @for scope in ApiTokenScope::all_scopes() {
+                            @if scope != ApiTokenScope::Root || *role == Role::Root {
+                                option value=(scope.as_stringed()) selected[scope == current_scope] {
+                                    (api_token_form_locale.scope_label(scope))
+                                }
+                            }
+                        }
+                    }
+                    (errors.scope.into_error_html())
+                }
+                div .form-group {
+                    label .label for="expires-at" { (api_token_form_locale.expires_at) } br;
+                    input .form-item .w-full type="datetime-local" name="expires_at" #expires-at value=(self.expires_at) {}
+                    (errors.expires_at.into_error_html())
+                }
+                div .form-group {
+                    input .btn .btn-sky-blue type="submit" value=(api_token_form_locale.submit) {}
+                }
+            }
+        }
+    }
+}
+
+pub struct AddApiTokenValidated {
+    pub name: Field,
+    pub scope: ApiTokenScope,
+    pub expires_at: NaiveDateTimeValue,
+}
+
+impl AddApiTokenValidated {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+            .as_naive_date_time()
+            .map(|expires_at| expires_at.and_utc())
+    }
+}
+
+#[derive(Debug)]
+pub struct AddApiTokenError {
+    pub name: Result<Field, FieldError>,
+    pub scope: Result<ApiTokenScope, ApiTokenScopeError>,
+    pub expires_at: Result<NaiveDateTimeValue, NaiveDateTimeError>,
+}
+
+impl AddApiTokenError {
+    pub fn as_message(&self, locale: &Locale) -> AddApiTokenMessage {
+        AddApiTokenMessage {
+            name: self.name.as_translated_message(locale),
+            scope: self.scope.as_translated_message(locale),
+            expires_at: self.expires_at.as_translated_message(locale),
+        }
+    }
+}
+
+pub struct AddApiTokenResult(pub Result<AddApiTokenValidated, AddApiTokenError>);
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct AddApiTokenMessage {
+    pub name: Arc<[String]>,
+    pub scope: Arc<[String]>,
+    pub expires_at: Arc<[String]>,
+}
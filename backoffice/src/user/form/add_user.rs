@@ -13,6 +13,7 @@ use poem::i18n::Locale;
 use serde::{Deserialize, Serialize};
 use shared::locale::LocaleExtForResult;
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 #[derive(Deserialize, Default)]
 pub struct AddUserForm {
@@ -30,11 +31,11 @@ impl AddUserForm {
                 let mut flag = FlagCounter::new();
 
                 let username = flag.check(
-                    Username::parse_user_add(Some(&self.username.trim()), service, None).await,
+                    Username::parse_user_add(Some(self.username.trim()), service, None).await,
                 );
                 let (password, password_confirm) = Password::parse_password_add(
-                    Some(&self.password.trim()),
-                    &self.password_confirm.trim(),
+                    Some(self.password.trim()),
+                    self.password_confirm.trim(),
                 );
                 let password = flag.check(password);
                 let password_confirm = flag.check(password_confirm);
@@ -149,7 +150,7 @@ impl AddUserError {
 
 pub struct AddUserResult(pub Result<AddUserValidated, AddUserError>);
 
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Default, ToSchema)]
 pub struct AddUserMessage {
     pub username: Arc<[String]>,
     pub password: Arc<[String]>,
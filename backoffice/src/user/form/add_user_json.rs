@@ -0,0 +1,25 @@
+use crate::user::form::add_user::AddUserForm;
+use crate::user::role::Role;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+pub struct AddUserJson {
+    pub username: String,
+    pub password: String,
+    pub password_confirm: String,
+    pub role: Role,
+}
+
+impl AddUserJson {
+    pub fn as_form(&self) -> AddUserForm {
+        AddUserForm {
+            username: self.username.clone(),
+            password: self.password.clone(),
+            password_confirm: self.password_confirm.clone(),
+            role: self.role.clone(),
+            csrf_token: String::new(),
+        }
+    }
+}
@@ -9,9 +9,12 @@ use poem::i18n::Locale;
 use serde::{Deserialize, Serialize};
 use shared::locale::LocaleExtForResult;
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 #[derive(Deserialize, Default)]
 pub struct EditPasswordManagerForm {
+    #[serde(default)]
+    pub current_password: String,
     pub password: String,
     pub password_confirm: String,
     pub csrf_token: String,
@@ -24,8 +27,8 @@ impl EditPasswordManagerForm {
                 let mut flag = FlagCounter::new();
 
                 let (password, password_confirm) = Password::parse_password_add(
-                    Some(&self.password.trim()),
-                    &self.password_confirm.trim(),
+                    Some(self.password.trim()),
+                    self.password_confirm.trim(),
                 );
                 let password = flag.check(password);
                 let password_confirm = flag.check(password_confirm);
@@ -52,6 +55,7 @@ impl EditPasswordManagerForm {
         errors: Option<EditPasswordManagerMessage>,
         token: Option<Markup>,
         username: Option<String>,
+        ask_current_password: bool,
     ) -> Markup {
         let errors = errors.unwrap_or_default();
         let token = token.unwrap_or_default();
@@ -64,6 +68,14 @@ impl EditPasswordManagerForm {
                 h2 { (username) }
                 form hx-boost="true" hx-target="#main-content" .form method="post" {
                     (token)
+                    @if ask_current_password {
+                        div .form-group {
+                            label .label for="current-password" { (user_form_locale.password_current) }
+                            input .form-item .w-full type="password" name="current_password" #current-password
+                            placeholder=(user_form_locale.password_current_placeholder) {}
+                            (errors.current_password.into_error_html())
+                        }
+                    }
                     div .form-group {
                         label .label for="password" { (user_form_locale.password) }
                         input .form-item .w-full type="password" name="password" #password
@@ -111,6 +123,7 @@ pub struct EditPasswordManagerError {
 impl EditPasswordManagerError {
     pub fn as_message(&self, locale: &Locale) -> EditPasswordManagerMessage {
         EditPasswordManagerMessage {
+            current_password: Arc::new([]),
             password: self.password.as_translated_message(locale),
             password_confirm: self.password_confirm.as_translated_message(locale),
         }
@@ -121,8 +134,11 @@ pub struct EditPasswordManagerResult(
     pub Result<EditPasswordManagerValidated, EditPasswordManagerError>,
 );
 
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Default, ToSchema)]
 pub struct EditPasswordManagerMessage {
+    /// Only the change password page asks for it.
+    #[serde(skip)]
+    pub current_password: Arc<[String]>,
     pub password: Arc<[String]>,
     pub password_confirm: Arc<[String]>,
 }
@@ -0,0 +1,21 @@
+use crate::user::form::edit_password_manager::EditPasswordManagerForm;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+pub struct EditPasswordManagerJson {
+    pub password: String,
+    pub password_confirm: String,
+}
+
+impl EditPasswordManagerJson {
+    pub fn as_form(&self) -> EditPasswordManagerForm {
+        EditPasswordManagerForm {
+            current_password: String::new(),
+            password: self.password.clone(),
+            password_confirm: self.password_confirm.clone(),
+            csrf_token: String::new(),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use poem::i18n::Locale;
 use serde::{Deserialize, Serialize};
 use shared::locale::LocaleExtForResult;
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 #[derive(Deserialize, Default)]
 pub struct EditUserForm {
@@ -32,7 +33,7 @@ impl EditUserForm {
 
                 let username = flag.check(
                     Username::parse_user_add(
-                        Some(&self.username.trim()),
+                        Some(self.username.trim()),
                         service,
                         Some(current_user_name),
                     )
@@ -122,7 +123,7 @@ impl EditUserError {
 
 pub struct EditUserResult(pub Result<EditUserValidated, EditUserError>);
 
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Default, ToSchema)]
 pub struct EditUserMessage {
     pub username: Arc<[String]>,
 }
@@ -0,0 +1,21 @@
+use crate::user::form::edit_user::EditUserForm;
+use crate::user::role::Role;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+pub struct EditUserJson {
+    pub username: String,
+    pub role: Role,
+}
+
+impl EditUserJson {
+    pub fn as_form(&self) -> EditUserForm {
+        EditUserForm {
+            username: self.username.clone(),
+            role: self.role.clone(),
+            csrf_token: String::new(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::user::model::api_token_model::ApiTokenScope;
 use poem::i18n::Locale;
 use shared::locale::LocaleExt;
 
@@ -11,6 +12,8 @@ pub struct UserFormLocale {
     pub password_placeholder: String,
     pub password_confirm: String,
     pub password_confirm_placeholder: String,
+    pub password_current: String,
+    pub password_current_placeholder: String,
     pub role: String,
     pub submit_add: String,
     pub submit_edit: String,
@@ -34,6 +37,10 @@ impl UserFormLocale {
                 .text_with_default("user-form-password-confirm", "Password Confirm:"),
             password_confirm_placeholder: locale
                 .text_with_default("user-form-password-confirm-placeholder", "Password Confirm"),
+            password_current: locale
+                .text_with_default("user-form-password-current", "Current Password:"),
+            password_current_placeholder: locale
+                .text_with_default("user-form-password-current-placeholder", "Current"),
             role: locale.text_with_default("user-form-role", "Role:"),
             submit_add: locale.text_with_default("user-form-submit-add", "Add"),
             submit_edit: locale.text_with_default("user-form-submit-edit", "Edit"),
@@ -41,3 +48,42 @@ impl UserFormLocale {
         }
     }
 }
+
+pub struct ApiTokenFormLocale {
+    pub title_add: String,
+    pub name: String,
+    pub name_placeholder: String,
+    pub scope: String,
+    pub scope_read: String,
+    pub scope_read_write: String,
+    pub scope_root: String,
+    pub expires_at: String,
+    pub submit: String,
+}
+
+impl ApiTokenFormLocale {
+    pub fn new(locale: &Locale) -> Self {
+        Self {
+            title_add: locale.text_with_default("user-form-api-token-title-add", "New API Token"),
+            name: locale.text_with_default("user-form-api-token-name", "Name:"),
+            name_placeholder: locale
+                .text_with_default("user-form-api-token-name-placeholder", "Deploy script"),
+            scope: locale.text_with_default("user-form-api-token-scope", "Scope:"),
+            scope_read: locale.text_with_default("user-form-api-token-scope-read", "Read only"),
+            scope_read_write: locale
+                .text_with_default("user-form-api-token-scope-read-write", "Read and write"),
+            scope_root: locale.text_with_default("user-form-api-token-scope-root", "Root"),
+            expires_at: locale
+                .text_with_default("user-form-api-token-expires-at", "Expires At (UTC):"),
+            submit: locale.text_with_default("user-form-api-token-submit", "Create"),
+        }
+    }
+
+    pub fn scope_label(&self, scope: ApiTokenScope) -> &str {
+        match scope {
+            ApiTokenScope::Read => &self.scope_read,
+            ApiTokenScope::ReadWrite => &self.scope_read_write,
+            ApiTokenScope::Root => &self.scope_root,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::user::form::login::UserLoginForm;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+pub struct LoginJson {
+    pub username: String,
+    pub password: String,
+    /// Required for users with two-factor enabled, a code from their app or a recovery code.
+    pub totp_code: Option<String>,
+}
+
+impl LoginJson {
+    pub fn as_form(&self) -> UserLoginForm {
+        UserLoginForm {
+            username: self.username.clone(),
+            password: self.password.clone(),
+            csrf_token: String::new(),
+        }
+    }
+}
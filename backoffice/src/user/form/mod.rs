@@ -1,5 +1,11 @@
+pub mod add_api_token;
 pub mod add_user;
+pub mod add_user_json;
 pub mod edit_password_manager;
+pub mod edit_password_manager_json;
 pub mod edit_user;
+pub mod edit_user_json;
 pub mod locale;
 pub mod login;
+pub mod login_json;
+pub mod two_factor;
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// A code from the authenticator app or a recovery code, used for the second login step as
+/// well as for managing two-factor.
+#[derive(Deserialize, Clone)]
+pub struct TwoFactorCodeForm {
+    #[serde(default)]
+    pub code: String,
+    pub csrf_token: String,
+}
@@ -0,0 +1,46 @@
+use poem::i18n::{I18NArgs, Locale};
+use shared::locale::LocaleExt;
+
+pub struct ApiTokenLocale {
+    pub title: String,
+    pub head_name: String,
+    pub head_scope: String,
+    pub head_created_at: String,
+    pub head_last_used_at: String,
+    pub head_expires_at: String,
+    pub head_action: String,
+    pub never: String,
+    pub action_revoke: String,
+    pub new_token_notice: String,
+}
+
+impl ApiTokenLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("user-route-api-token-title", "My API Tokens"),
+            head_name: l.text_with_default("user-route-api-token-head-name", "Name"),
+            head_scope: l.text_with_default("user-route-api-token-head-scope", "Scope"),
+            head_created_at: l
+                .text_with_default("user-route-api-token-head-created-at", "Created At"),
+            head_last_used_at: l
+                .text_with_default("user-route-api-token-head-last-used-at", "Last Used"),
+            head_expires_at: l
+                .text_with_default("user-route-api-token-head-expires-at", "Expires At"),
+            head_action: l.text_with_default("user-route-api-token-head-action", "Action"),
+            never: l.text_with_default("user-route-api-token-never", "Never"),
+            action_revoke: l.text_with_default("user-route-api-token-action-revoke", "Revoke"),
+            new_token_notice: l.text_with_default(
+                "user-route-api-token-new-token-notice",
+                "Copy your new token now, it will not be shown again.",
+            ),
+        }
+    }
+}
+
+pub fn api_token_revoke_confirm_message(l: &Locale, name: &str) -> String {
+    l.text_with_default_args(
+        "user-route-api-token-revoke-confirm-message",
+        format!("Are you sure you want to revoke '{name}'?").as_str(),
+        I18NArgs::from((("name", name),)),
+    )
+}
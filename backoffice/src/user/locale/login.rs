@@ -1,4 +1,6 @@
-use poem::i18n::Locale;
+use crate::user::service::user_login_service::retry_after_seconds;
+use chrono::TimeDelta;
+use poem::i18n::{I18NArgs, Locale};
 use shared::locale::LocaleExt;
 
 pub struct LoginLocale {
@@ -33,6 +35,48 @@ impl LoginPostLocale {
     }
 }
 
+pub struct TwoFactorLoginLocale {
+    pub title: String,
+    pub code: String,
+    pub confirm_button: String,
+}
+
+impl TwoFactorLoginLocale {
+    pub fn new(locale: &Locale) -> Self {
+        Self {
+            title: locale.text_with_default("login-two-factor-title", "Two-Factor Login"),
+            code: locale.text_with_default(
+                "login-two-factor-code",
+                "Code from your app, or a recovery code",
+            ),
+            confirm_button: locale.text_with_default("login-two-factor-confirm-button", "Verify"),
+        }
+    }
+}
+
+pub struct TwoFactorLoginPostLocale {
+    pub flash_failed: String,
+    pub flash_expired: String,
+    pub api_required: String,
+}
+
+impl TwoFactorLoginPostLocale {
+    pub fn new(locale: &Locale) -> Self {
+        Self {
+            flash_failed: locale
+                .text_with_default("login-two-factor-post-flash-failed", "Invalid code"),
+            flash_expired: locale.text_with_default(
+                "login-two-factor-post-flash-expired",
+                "The login has expired, please sign in again",
+            ),
+            api_required: locale.text_with_default(
+                "login-two-factor-api-required",
+                "A two-factor code is required",
+            ),
+        }
+    }
+}
+
 pub struct LogoutLocale {
     pub flash_success: String,
 }
@@ -44,3 +88,12 @@ impl LogoutLocale {
         }
     }
 }
+
+pub fn login_throttled_message(l: &Locale, retry_after: TimeDelta) -> String {
+    let seconds = retry_after_seconds(retry_after);
+    l.text_with_default_args(
+        "login-post-flash-throttled",
+        format!("Too many failed attempts, try again in {seconds} seconds").as_str(),
+        I18NArgs::from((("seconds", seconds),)),
+    )
+}
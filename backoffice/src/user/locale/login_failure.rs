@@ -0,0 +1,29 @@
+use poem::i18n::Locale;
+use shared::locale::LocaleExt;
+
+pub struct LoginFailureLocale {
+    pub title: String,
+    pub head_username: String,
+    pub head_client_ip: String,
+    pub head_failed_at: String,
+    pub head_throttled: String,
+    pub yes: String,
+    pub no: String,
+}
+
+impl LoginFailureLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("user-route-login-failure-title", "Failed Logins"),
+            head_username: l
+                .text_with_default("user-route-login-failure-head-username", "Username"),
+            head_client_ip: l.text_with_default("user-route-login-failure-head-client-ip", "IP"),
+            head_failed_at: l
+                .text_with_default("user-route-login-failure-head-failed-at", "Failed At"),
+            head_throttled: l
+                .text_with_default("user-route-login-failure-head-throttled", "Locked Out"),
+            yes: l.text_with_default("user-route-login-failure-yes", "Yes"),
+            no: l.text_with_default("user-route-login-failure-no", "No"),
+        }
+    }
+}
@@ -1,2 +1,6 @@
+pub mod api_token;
 pub mod login;
+pub mod login_failure;
+pub mod session;
+pub mod two_factor;
 pub mod user;
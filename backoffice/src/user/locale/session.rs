@@ -0,0 +1,39 @@
+use poem::i18n::Locale;
+use shared::locale::LocaleExt;
+
+pub struct SessionLocale {
+    pub title: String,
+    pub head_created_at: String,
+    pub head_last_seen_at: String,
+    pub head_expire_after: String,
+    pub head_client_ip: String,
+    pub head_user_agent: String,
+    pub head_action: String,
+    pub unknown: String,
+    pub current: String,
+    pub action_revoke: String,
+    pub revoke_confirm_message: String,
+}
+
+impl SessionLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("user-route-session-title", "My Sessions"),
+            head_created_at: l.text_with_default("user-route-session-head-created-at", "Signed In"),
+            head_last_seen_at: l
+                .text_with_default("user-route-session-head-last-seen-at", "Last Seen"),
+            head_expire_after: l
+                .text_with_default("user-route-session-head-expire-after", "Expires At"),
+            head_client_ip: l.text_with_default("user-route-session-head-client-ip", "IP"),
+            head_user_agent: l.text_with_default("user-route-session-head-user-agent", "Browser"),
+            head_action: l.text_with_default("user-route-session-head-action", "Action"),
+            unknown: l.text_with_default("user-route-session-unknown", "Unknown"),
+            current: l.text_with_default("user-route-session-current", "This session"),
+            action_revoke: l.text_with_default("user-route-session-action-revoke", "Sign Out"),
+            revoke_confirm_message: l.text_with_default(
+                "user-route-session-revoke-confirm-message",
+                "Are you sure you want to sign out this session?",
+            ),
+        }
+    }
+}
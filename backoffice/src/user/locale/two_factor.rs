@@ -0,0 +1,67 @@
+use poem::i18n::{I18NArgs, Locale};
+use shared::locale::LocaleExt;
+
+pub struct TwoFactorLocale {
+    pub title: String,
+    pub disabled_notice: String,
+    pub setup_button: String,
+    pub pending_notice: String,
+    pub open_in_app: String,
+    pub secret: String,
+    pub code: String,
+    pub confirm_button: String,
+    pub restart_button: String,
+    pub recovery_codes_notice: String,
+    pub regenerate_button: String,
+    pub disable_button: String,
+}
+
+impl TwoFactorLocale {
+    pub fn new(l: &Locale) -> Self {
+        Self {
+            title: l.text_with_default("user-route-two-factor-title", "Two-Factor Login"),
+            disabled_notice: l.text_with_default(
+                "user-route-two-factor-disabled-notice",
+                "Two-factor is off. With it on, a code from an authenticator app is asked for after your password.",
+            ),
+            setup_button: l.text_with_default("user-route-two-factor-setup-button", "Set Up"),
+            pending_notice: l.text_with_default(
+                "user-route-two-factor-pending-notice",
+                "Add this account to your authenticator app with the link or the secret, then confirm with the code it shows.",
+            ),
+            open_in_app: l.text_with_default(
+                "user-route-two-factor-open-in-app",
+                "Open in authenticator app",
+            ),
+            secret: l.text_with_default("user-route-two-factor-secret", "Secret"),
+            code: l.text_with_default("user-route-two-factor-code", "Code"),
+            confirm_button: l.text_with_default("user-route-two-factor-confirm-button", "Confirm"),
+            restart_button: l.text_with_default("user-route-two-factor-restart-button", "Start Over"),
+            recovery_codes_notice: l.text_with_default(
+                "user-route-two-factor-recovery-codes-notice",
+                "Save these recovery codes now, they will not be shown again. Each one can be used once in place of a code.",
+            ),
+            regenerate_button: l.text_with_default(
+                "user-route-two-factor-regenerate-button",
+                "New Recovery Codes",
+            ),
+            disable_button: l.text_with_default("user-route-two-factor-disable-button", "Turn Off"),
+        }
+    }
+}
+
+pub fn two_factor_enabled_notice(l: &Locale, recovery_codes_left: i64) -> String {
+    l.text_with_default_args(
+        "user-route-two-factor-enabled-notice",
+        format!("Two-factor is on, with {recovery_codes_left} recovery codes left.").as_str(),
+        I18NArgs::from((("count", recovery_codes_left),)),
+    )
+}
+
+pub fn two_factor_reset_confirm_message(l: &Locale, username: &str) -> String {
+    l.text_with_default_args(
+        "user-route-two-factor-reset-confirm-message",
+        format!("Are you sure you want to reset two-factor for '{username}'?").as_str(),
+        I18NArgs::from((("username", username),)),
+    )
+}
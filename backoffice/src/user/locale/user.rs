@@ -10,6 +10,12 @@ pub struct UserLocale {
     pub user_list_action_password: String,
     pub user_list_action_sign_out: String,
     pub user_list_action_add_user: String,
+    pub user_list_action_api_tokens: String,
+    pub user_list_action_change_password: String,
+    pub user_list_action_login_failures: String,
+    pub user_list_action_two_factor: String,
+    pub user_list_action_reset_two_factor: String,
+    pub user_list_action_sessions: String,
 }
 
 impl UserLocale {
@@ -27,6 +33,22 @@ impl UserLocale {
                 .text_with_default("user-route-list-action-sign-out", "Sign Out User"),
             user_list_action_add_user: l
                 .text_with_default("user-route-list-action-add-user", "Add Users"),
+            user_list_action_api_tokens: l
+                .text_with_default("user-route-list-action-api-tokens", "My API Tokens"),
+            user_list_action_change_password: l.text_with_default(
+                "user-route-list-action-change-password",
+                "Change My Password",
+            ),
+            user_list_action_login_failures: l
+                .text_with_default("user-route-list-action-login-failures", "Failed Logins"),
+            user_list_action_two_factor: l
+                .text_with_default("user-route-list-action-two-factor", "My Two-Factor Login"),
+            user_list_action_reset_two_factor: l.text_with_default(
+                "user-route-list-action-reset-two-factor",
+                "Reset Two-Factor",
+            ),
+            user_list_action_sessions: l
+                .text_with_default("user-route-list-action-sessions", "My Sessions"),
         }
     }
 }
@@ -10,3 +10,11 @@ pub mod rule;
 pub mod service;
 
 pub const LOGIN_TOKEN_COOKIE_NAME: &str = "login_token";
+
+pub const LOGIN_ROUTE: &str = "/user-login";
+pub const USER_ROUTE: &str = "/user";
+pub const CHANGE_PASSWORD_PATH: &str = "/change-password";
+
+/// Request data marking routes that accept `Authorization: Bearer` API tokens.
+#[derive(Clone, Copy)]
+pub struct AllowApiToken;
@@ -0,0 +1,74 @@
+use crate::user::role::Role;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ApiTokenScope {
+    #[default]
+    Read,
+    ReadWrite,
+    Root,
+}
+
+impl TryFrom<&str> for ApiTokenScope {
+    type Error = ();
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "read" => Ok(Self::Read),
+            "read-write" => Ok(Self::ReadWrite),
+            "root" => Ok(Self::Root),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&ApiTokenScope> for String {
+    fn from(s: &ApiTokenScope) -> Self {
+        match s {
+            ApiTokenScope::Read => "read".to_string(),
+            ApiTokenScope::ReadWrite => "read-write".to_string(),
+            ApiTokenScope::Root => "root".to_string(),
+        }
+    }
+}
+
+impl ApiTokenScope {
+    pub fn all_scopes() -> Vec<Self> {
+        vec![Self::Read, Self::ReadWrite, Self::Root]
+    }
+
+    pub fn as_stringed(&self) -> String {
+        String::from(self)
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        *self == Self::Read
+    }
+
+    /// The role a token acts with, never more than its owner currently has.
+    pub fn effective_role(&self, role: Role) -> Role {
+        match self {
+            Self::Root => role,
+            Self::Read | Self::ReadWrite if role == Role::Root => Role::User,
+            Self::Read | Self::ReadWrite => role,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiTokenModel {
+    pub id: i64,
+    pub name: String,
+    pub scope: ApiTokenScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct ApiTokenUserModel {
+    pub token_id: i64,
+    pub id: i64,
+    pub username: String,
+    pub role: Role,
+    pub scope: ApiTokenScope,
+}
@@ -0,0 +1,9 @@
+use chrono::{DateTime, Utc};
+
+pub struct LoginFailureModel {
+    pub username: String,
+    pub client_ip: Option<String>,
+    /// Turned away by the throttle, without checking the password.
+    pub throttled: bool,
+    pub failed_at: DateTime<Utc>,
+}
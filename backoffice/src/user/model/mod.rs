@@ -1,2 +1,6 @@
+pub mod api_token_model;
+pub mod login_failure_model;
+pub mod session_model;
+pub mod two_factor_model;
 pub mod user_manager_model;
 pub mod user_model;
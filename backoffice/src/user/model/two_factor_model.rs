@@ -0,0 +1,14 @@
+pub struct TotpModel {
+    /// Encrypted, see `shared::totp::TotpCipher`.
+    pub secret: Box<[u8]>,
+    /// Off until the user confirms set up with a first code.
+    pub enabled: bool,
+    pub last_used_step: i64,
+}
+
+pub struct LoginChallengeModel {
+    pub user_id: i64,
+    /// As typed at login, for the throttle.
+    pub username: String,
+    pub attempts: i64,
+}
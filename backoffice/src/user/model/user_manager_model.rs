@@ -1,13 +1,15 @@
 use crate::user::role::Role;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ListUser {
     pub id: i64,
     pub username: String,
     pub role: Role,
 }
 
+#[derive(Serialize)]
 pub struct FetchUser {
     pub username: String,
     pub role: Role,
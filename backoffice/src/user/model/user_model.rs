@@ -5,6 +5,9 @@ pub struct UserIdContext {
     pub id: i64,
     pub username: String,
     pub role: Role,
+    pub read_only: bool,
+    /// Set for a bootstrapped user until they choose their own password.
+    pub must_change_password: bool,
 }
 
 pub struct IdPassword {
@@ -28,7 +28,7 @@ impl FromContext for UserPointer {
         let user_pointer = match request_cache.user_pointer.as_ref() {
             None => {
                 let user_service: UserCheckService = ctx.inject().await?;
-                let user_id_context = user_service.get_user_context();
+                let user_id_context = user_service.get_user_context().await;
                 let user_pointer = UserPointer(Arc::new(user_id_context));
                 request_cache.user_pointer = Some(user_pointer.clone());
                 user_pointer
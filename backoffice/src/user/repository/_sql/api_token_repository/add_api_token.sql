@@ -0,0 +1,2 @@
+insert into user_api_tokens (user_id, name, token_hash, scope, created_at, expires_at)
+values (:user_id, :name, :token_hash, :scope, datetime(), :expires_at)
\ No newline at end of file
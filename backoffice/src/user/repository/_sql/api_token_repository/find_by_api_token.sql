@@ -0,0 +1,6 @@
+select t.id as token_id, u.id, u.username, u.role, t.scope
+from backoffice_users as u
+         inner join user_api_tokens t on u.id = t.user_id
+where t.token_hash = :token_hash
+  and (t.expires_at is null or t.expires_at > :now)
+limit 1;
\ No newline at end of file
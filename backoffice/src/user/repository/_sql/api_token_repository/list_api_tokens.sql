@@ -0,0 +1,4 @@
+select id, name, scope, created_at, last_used_at, expires_at
+from user_api_tokens
+where user_id = :user_id
+order by id asc
\ No newline at end of file
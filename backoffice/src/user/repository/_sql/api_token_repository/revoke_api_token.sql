@@ -0,0 +1,4 @@
+delete
+from user_api_tokens
+where id = :id
+  and user_id = :user_id
\ No newline at end of file
@@ -0,0 +1,4 @@
+update user_api_tokens
+set last_used_at = :now
+where id = :id
+  and (last_used_at is null or last_used_at < :stale_before)
\ No newline at end of file
@@ -0,0 +1,2 @@
+insert into login_failure (username, client_ip, throttled, failed_at)
+values (:username, :client_ip, :throttled, :failed_at)
\ No newline at end of file
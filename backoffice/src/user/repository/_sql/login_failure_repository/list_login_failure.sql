@@ -0,0 +1,4 @@
+select username, client_ip, throttled, failed_at
+from login_failure
+order by id desc
+limit :limit
\ No newline at end of file
@@ -0,0 +1,3 @@
+delete
+from login_failure
+where failed_at < :before
\ No newline at end of file
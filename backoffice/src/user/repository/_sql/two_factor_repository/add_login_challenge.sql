@@ -0,0 +1,2 @@
+insert into login_challenge (token_hash, user_id, username, expires_at)
+values (:token_hash, :user_id, :username, :expires_at)
\ No newline at end of file
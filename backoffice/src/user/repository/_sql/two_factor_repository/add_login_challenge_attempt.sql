@@ -0,0 +1,3 @@
+update login_challenge
+set attempts = attempts + 1
+where token_hash = :token_hash
\ No newline at end of file
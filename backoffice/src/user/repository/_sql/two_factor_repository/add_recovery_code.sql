@@ -0,0 +1,2 @@
+insert into user_totp_recovery_code (user_id, code_hash)
+values (:user_id, :code_hash)
\ No newline at end of file
@@ -0,0 +1,4 @@
+select count(*)
+from user_totp_recovery_code
+where user_id = :user_id
+  and used_at is null
\ No newline at end of file
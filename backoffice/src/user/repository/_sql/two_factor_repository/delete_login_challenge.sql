@@ -0,0 +1,3 @@
+delete
+from login_challenge
+where token_hash = :token_hash
\ No newline at end of file
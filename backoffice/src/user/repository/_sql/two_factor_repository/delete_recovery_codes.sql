@@ -0,0 +1,3 @@
+delete
+from user_totp_recovery_code
+where user_id = :user_id
\ No newline at end of file
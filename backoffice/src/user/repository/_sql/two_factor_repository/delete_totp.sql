@@ -0,0 +1,3 @@
+delete
+from user_totp
+where user_id = :user_id
\ No newline at end of file
@@ -0,0 +1,6 @@
+update user_totp
+set enabled        = 1,
+    last_used_step = :step
+where user_id = :user_id
+  and enabled = 0
+  and last_used_step < :step
\ No newline at end of file
@@ -0,0 +1,4 @@
+select user_id, username, attempts
+from login_challenge
+where token_hash = :token_hash
+  and expires_at >= :now
\ No newline at end of file
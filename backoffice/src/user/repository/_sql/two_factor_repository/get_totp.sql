@@ -0,0 +1,3 @@
+select secret, enabled, last_used_step
+from user_totp
+where user_id = :user_id
\ No newline at end of file
@@ -0,0 +1,3 @@
+delete
+from login_challenge
+where expires_at < :now
\ No newline at end of file
@@ -0,0 +1,6 @@
+insert into user_totp (user_id, secret, enabled, last_used_step, created_at)
+values (:user_id, :secret, 0, 0, :created_at)
+on conflict (user_id) do update set secret         = excluded.secret,
+                                    last_used_step = 0,
+                                    created_at     = excluded.created_at
+where enabled = 0
\ No newline at end of file
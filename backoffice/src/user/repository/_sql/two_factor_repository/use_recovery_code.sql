@@ -0,0 +1,5 @@
+update user_totp_recovery_code
+set used_at = :used_at
+where user_id = :user_id
+  and code_hash = :code_hash
+  and used_at is null
\ No newline at end of file
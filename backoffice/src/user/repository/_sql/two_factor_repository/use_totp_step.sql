@@ -0,0 +1,5 @@
+update user_totp
+set last_used_step = :step
+where user_id = :user_id
+  and enabled = 1
+  and last_used_step < :step
\ No newline at end of file
@@ -1,3 +1,4 @@
 update backoffice_users
-set password = :password
+set password             = :password,
+    must_change_password = 0
 where id = :id
\ No newline at end of file
@@ -1,2 +1,2 @@
-insert into user_login_tokens(user_id, token, expire_after)
-values (:user_id, :token, datetime('now', '+30 day'))
\ No newline at end of file
+insert into user_login_tokens(user_id, token, created_at, last_seen_at, expire_after, client_ip, user_agent)
+values (:user_id, :token, :now, :now, :expire_after, :client_ip, :user_agent)
\ No newline at end of file
@@ -1,6 +1,7 @@
-select u.id, u.username, u.role
+select u.id, u.username, u.role, u.must_change_password
 from backoffice_users as u
          inner join user_login_tokens ult on u.id = ult.user_id
 where ult.token = :token
-  and ult.expire_after > datetime('now')
+  and ult.expire_after > :now
+  and (:idle_before is null or ult.last_seen_at > :idle_before)
 limit 1;
\ No newline at end of file
@@ -0,0 +1,6 @@
+select id, created_at, last_seen_at, expire_after, client_ip, user_agent, coalesce(token = :token, 0) as current
+from user_login_tokens
+where user_id = :user_id
+  and expire_after > :now
+  and (:idle_before is null or last_seen_at > :idle_before)
+order by last_seen_at desc, id desc
\ No newline at end of file
@@ -0,0 +1,4 @@
+delete
+from user_login_tokens
+where user_id = :user_id
+  and expire_after <= :now
\ No newline at end of file
@@ -0,0 +1,4 @@
+delete
+from user_login_tokens
+where id = :id
+  and user_id = :user_id
\ No newline at end of file
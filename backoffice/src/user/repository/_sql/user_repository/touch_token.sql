@@ -0,0 +1,4 @@
+update user_login_tokens
+set last_seen_at = :now
+where token = :token
+  and last_seen_at < :stale_before
\ No newline at end of file
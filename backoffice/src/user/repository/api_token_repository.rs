@@ -0,0 +1,229 @@
+use crate::user::model::api_token_model::{ApiTokenModel, ApiTokenScope, ApiTokenUserModel};
+use crate::user::role::Role;
+use chrono::{DateTime, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use shared::context::{Context, ContextError, FromContext};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum ApiTokenRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Row Value error")]
+    RowValueError,
+    #[error("Borrow Conn error")]
+    BorrowConnError,
+}
+
+#[mry::mry]
+pub struct ApiTokenRepository {
+    sqlite_client: Option<SqliteClient>,
+}
+
+impl ApiTokenRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self {
+            sqlite_client: Some(sqlite_client),
+            mry: Default::default(),
+        }
+    }
+
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<ApiTokenRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<ApiTokenRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .read(ApiTokenRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<ApiTokenRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<ApiTokenRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(ApiTokenRepositoryError::BorrowConnError, f)
+            .await
+    }
+}
+
+#[mry::mry]
+impl ApiTokenRepository {
+    pub async fn add_api_token(
+        &self,
+        user_id: i64,
+        name: &str,
+        token_hash: &str,
+        scope: ApiTokenScope,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<i64, Report<ApiTokenRepositoryError>> {
+        let name = name.to_owned();
+        let token_hash = token_hash.to_owned();
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/api_token_repository/add_api_token.sql"),
+                named_params! {
+                    ":user_id": user_id,
+                    ":name": name,
+                    ":token_hash": token_hash,
+                    ":scope": scope.as_stringed(),
+                    ":expires_at": expires_at,
+                },
+            )
+            .change_context(ApiTokenRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    pub async fn list_api_tokens(
+        &self,
+        user_id: i64,
+    ) -> Result<Arc<[ApiTokenModel]>, Report<ApiTokenRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/api_token_repository/list_api_tokens.sql"
+                ))
+                .change_context(ApiTokenRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":user_id": user_id,
+                    },
+                    |row| {
+                        Ok(ApiTokenModel {
+                            id: row.get("id")?,
+                            name: row.get("name")?,
+                            scope: ApiTokenScope::try_from(row.get::<_, String>("scope")?.as_str())
+                                .unwrap_or_default(),
+                            created_at: row.get("created_at")?,
+                            last_used_at: row.get("last_used_at")?,
+                            expires_at: row.get("expires_at")?,
+                        })
+                    },
+                )
+                .change_context(ApiTokenRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ApiTokenRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+
+    pub async fn revoke_api_token(
+        &self,
+        id: i64,
+        user_id: i64,
+    ) -> Result<usize, Report<ApiTokenRepositoryError>> {
+        self.write(move |conn| {
+            let revoked = conn
+                .execute(
+                    include_str!("_sql/api_token_repository/revoke_api_token.sql"),
+                    named_params! {
+                        ":id": id,
+                        ":user_id": user_id,
+                    },
+                )
+                .change_context(ApiTokenRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(revoked)
+        })
+        .await
+    }
+
+    pub async fn find_by_api_token(
+        &self,
+        token_hash: String,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApiTokenUserModel>, Report<ApiTokenRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/api_token_repository/find_by_api_token.sql"
+                ))
+                .change_context(ApiTokenRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let item = stmt
+                .query_one(
+                    named_params! {
+                        ":token_hash": token_hash,
+                        ":now": now,
+                    },
+                    |row| {
+                        Ok(ApiTokenUserModel {
+                            token_id: row.get("token_id")?,
+                            id: row.get("id")?,
+                            username: row.get("username")?,
+                            role: Role::try_from(row.get::<_, String>("role")?.as_str())
+                                .unwrap_or_default(),
+                            scope: ApiTokenScope::try_from(row.get::<_, String>("scope")?.as_str())
+                                .unwrap_or_default(),
+                        })
+                    },
+                )
+                .optional()
+                .change_context(ApiTokenRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(item)
+        })
+        .await
+    }
+
+    pub async fn touch_api_token(
+        &self,
+        id: i64,
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<(), Report<ApiTokenRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/api_token_repository/touch_api_token.sql"),
+                named_params! {
+                    ":id": id,
+                    ":now": now,
+                    ":stale_before": stale_before,
+                },
+            )
+            .change_context(ApiTokenRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl ApiTokenRepository {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            sqlite_client: None
+        })
+    }
+}
+
+impl FromContext for ApiTokenRepository {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
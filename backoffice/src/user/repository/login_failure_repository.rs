@@ -0,0 +1,148 @@
+use crate::user::model::login_failure_model::LoginFailureModel;
+use chrono::{DateTime, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rusqlite::{Connection, named_params};
+use shared::context::{Context, ContextError, FromContext};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum LoginFailureRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Row Value error")]
+    RowValueError,
+    #[error("Borrow Conn error")]
+    BorrowConnError,
+}
+
+#[mry::mry]
+pub struct LoginFailureRepository {
+    sqlite_client: Option<SqliteClient>,
+}
+
+impl LoginFailureRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self {
+            sqlite_client: Some(sqlite_client),
+            mry: Default::default(),
+        }
+    }
+
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<LoginFailureRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<LoginFailureRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .read(LoginFailureRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<LoginFailureRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<LoginFailureRepositoryError>>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(LoginFailureRepositoryError::BorrowConnError, f)
+            .await
+    }
+}
+
+#[mry::mry]
+impl LoginFailureRepository {
+    /// Also drops the failures from before `prune_before`, so the table stays small.
+    pub async fn add_login_failure(
+        &self,
+        username: String,
+        client_ip: Option<String>,
+        throttled: bool,
+        failed_at: DateTime<Utc>,
+        prune_before: DateTime<Utc>,
+    ) -> Result<(), Report<LoginFailureRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/login_failure_repository/add_login_failure.sql"),
+                named_params! {
+                    ":username": username,
+                    ":client_ip": client_ip,
+                    ":throttled": throttled,
+                    ":failed_at": failed_at,
+                },
+            )
+            .change_context(LoginFailureRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            conn.execute(
+                include_str!("_sql/login_failure_repository/prune_login_failure.sql"),
+                named_params! {
+                    ":before": prune_before,
+                },
+            )
+            .change_context(LoginFailureRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn list_login_failures(
+        &self,
+        limit: i64,
+    ) -> Result<Arc<[LoginFailureModel]>, Report<LoginFailureRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/login_failure_repository/list_login_failure.sql"
+                ))
+                .change_context(LoginFailureRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":limit": limit,
+                    },
+                    |row| {
+                        Ok(LoginFailureModel {
+                            username: row.get("username")?,
+                            client_ip: row.get("client_ip")?,
+                            throttled: row.get("throttled")?,
+                            failed_at: row.get("failed_at")?,
+                        })
+                    },
+                )
+                .change_context(LoginFailureRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(LoginFailureRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl LoginFailureRepository {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            sqlite_client: None
+        })
+    }
+}
+
+impl FromContext for LoginFailureRepository {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
@@ -1,2 +1,5 @@
+pub mod api_token_repository;
+pub mod login_failure_repository;
+pub mod two_factor_repository;
 pub mod user_manager_repository;
 pub mod user_repository;
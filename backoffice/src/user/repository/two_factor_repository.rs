@@ -0,0 +1,418 @@
+use crate::user::model::two_factor_model::{LoginChallengeModel, TotpModel};
+use chrono::{DateTime, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rusqlite::{Connection, OptionalExtension, named_params};
+use shared::context::{Context, ContextError, FromContext};
+use shared::db::{RunConnectionExt, SqliteClient};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum TwoFactorRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Row Value error")]
+    RowValueError,
+    #[error("Borrow Conn error")]
+    BorrowConnError,
+}
+
+#[mry::mry]
+pub struct TwoFactorRepository {
+    sqlite_client: Option<SqliteClient>,
+}
+
+impl TwoFactorRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self {
+            sqlite_client: Some(sqlite_client),
+            mry: Default::default(),
+        }
+    }
+
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<TwoFactorRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<TwoFactorRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .read(TwoFactorRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<TwoFactorRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<TwoFactorRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(TwoFactorRepositoryError::BorrowConnError, f)
+            .await
+    }
+}
+
+fn replace_recovery_codes_in(
+    conn: &Connection,
+    user_id: i64,
+    code_hashes: &[String],
+) -> Result<(), Report<TwoFactorRepositoryError>> {
+    conn.execute(
+        include_str!("_sql/two_factor_repository/delete_recovery_codes.sql"),
+        named_params! {
+            ":user_id": user_id,
+        },
+    )
+    .change_context(TwoFactorRepositoryError::QueryError)
+    .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut stmt = conn
+        .prepare_cached(include_str!(
+            "_sql/two_factor_repository/add_recovery_code.sql"
+        ))
+        .change_context(TwoFactorRepositoryError::QueryError)
+        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    for code_hash in code_hashes {
+        stmt.execute(named_params! {
+            ":user_id": user_id,
+            ":code_hash": code_hash,
+        })
+        .change_context(TwoFactorRepositoryError::QueryError)
+        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+    Ok(())
+}
+
+#[mry::mry]
+impl TwoFactorRepository {
+    pub async fn get_totp(
+        &self,
+        user_id: i64,
+    ) -> Result<Option<TotpModel>, Report<TwoFactorRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/two_factor_repository/get_totp.sql"))
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            stmt.query_one(
+                named_params! {
+                    ":user_id": user_id,
+                },
+                |row| {
+                    Ok(TotpModel {
+                        secret: row.get("secret")?,
+                        enabled: row.get("enabled")?,
+                        last_used_step: row.get("last_used_step")?,
+                    })
+                },
+            )
+            .optional()
+            .change_context(TwoFactorRepositoryError::RowValueError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)
+        })
+        .await
+    }
+
+    /// Leaves an enabled secret alone.
+    pub async fn save_pending_totp(
+        &self,
+        user_id: i64,
+        secret: Box<[u8]>,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/two_factor_repository/save_pending_totp.sql"),
+                named_params! {
+                    ":user_id": user_id,
+                    ":secret": secret,
+                    ":created_at": created_at,
+                },
+            )
+            .change_context(TwoFactorRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// False when the secret was not pending, or the step was already used.
+    pub async fn enable_totp(
+        &self,
+        user_id: i64,
+        step: i64,
+        code_hashes: Vec<String>,
+    ) -> Result<bool, Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let enabled = tx
+                .execute(
+                    include_str!("_sql/two_factor_repository/enable_totp.sql"),
+                    named_params! {
+                        ":user_id": user_id,
+                        ":step": step,
+                    },
+                )
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            if enabled == 0 {
+                return Ok(false);
+            }
+            replace_recovery_codes_in(&tx, user_id, &code_hashes)?;
+            tx.commit()
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(true)
+        })
+        .await
+    }
+
+    /// Moves `last_used_step` forward, false when the step is not newer, so a code is only
+    /// ever accepted once even with two requests racing.
+    pub async fn use_totp_step(
+        &self,
+        user_id: i64,
+        step: i64,
+    ) -> Result<bool, Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            let updated = conn
+                .execute(
+                    include_str!("_sql/two_factor_repository/use_totp_step.sql"),
+                    named_params! {
+                        ":user_id": user_id,
+                        ":step": step,
+                    },
+                )
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    /// Also drops the recovery codes.
+    pub async fn delete_totp(
+        &self,
+        user_id: i64,
+    ) -> Result<bool, Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            replace_recovery_codes_in(&tx, user_id, &[])?;
+            let deleted = tx
+                .execute(
+                    include_str!("_sql/two_factor_repository/delete_totp.sql"),
+                    named_params! {
+                        ":user_id": user_id,
+                    },
+                )
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            tx.commit()
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    pub async fn replace_recovery_codes(
+        &self,
+        user_id: i64,
+        code_hashes: Vec<String>,
+    ) -> Result<(), Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            replace_recovery_codes_in(&tx, user_id, &code_hashes)?;
+            tx.commit()
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// False when there is no unused code with that hash.
+    pub async fn use_recovery_code(
+        &self,
+        user_id: i64,
+        code_hash: String,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool, Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            let updated = conn
+                .execute(
+                    include_str!("_sql/two_factor_repository/use_recovery_code.sql"),
+                    named_params! {
+                        ":user_id": user_id,
+                        ":code_hash": code_hash,
+                        ":used_at": used_at,
+                    },
+                )
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    pub async fn count_recovery_codes(
+        &self,
+        user_id: i64,
+    ) -> Result<i64, Report<TwoFactorRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/two_factor_repository/count_recovery_codes.sql"
+                ))
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            stmt.query_one(
+                named_params! {
+                    ":user_id": user_id,
+                },
+                |row| row.get(0),
+            )
+            .change_context(TwoFactorRepositoryError::RowValueError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)
+        })
+        .await
+    }
+
+    /// Also drops the challenges that have expired.
+    pub async fn add_login_challenge(
+        &self,
+        token_hash: String,
+        user_id: i64,
+        username: String,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(), Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/two_factor_repository/prune_login_challenge.sql"),
+                named_params! {
+                    ":now": now,
+                },
+            )
+            .change_context(TwoFactorRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            conn.execute(
+                include_str!("_sql/two_factor_repository/add_login_challenge.sql"),
+                named_params! {
+                    ":token_hash": token_hash,
+                    ":user_id": user_id,
+                    ":username": username,
+                    ":expires_at": expires_at,
+                },
+            )
+            .change_context(TwoFactorRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn find_login_challenge(
+        &self,
+        token_hash: String,
+        now: DateTime<Utc>,
+    ) -> Result<Option<LoginChallengeModel>, Report<TwoFactorRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/two_factor_repository/find_login_challenge.sql"
+                ))
+                .change_context(TwoFactorRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            stmt.query_one(
+                named_params! {
+                    ":token_hash": token_hash,
+                    ":now": now,
+                },
+                |row| {
+                    Ok(LoginChallengeModel {
+                        user_id: row.get("user_id")?,
+                        username: row.get("username")?,
+                        attempts: row.get("attempts")?,
+                    })
+                },
+            )
+            .optional()
+            .change_context(TwoFactorRepositoryError::RowValueError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)
+        })
+        .await
+    }
+
+    pub async fn add_login_challenge_attempt(
+        &self,
+        token_hash: String,
+    ) -> Result<(), Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/two_factor_repository/add_login_challenge_attempt.sql"),
+                named_params! {
+                    ":token_hash": token_hash,
+                },
+            )
+            .change_context(TwoFactorRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn delete_login_challenge(
+        &self,
+        token_hash: String,
+    ) -> Result<(), Report<TwoFactorRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/two_factor_repository/delete_login_challenge.sql"),
+                named_params! {
+                    ":token_hash": token_hash,
+                },
+            )
+            .change_context(TwoFactorRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl TwoFactorRepository {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            sqlite_client: None
+        })
+    }
+}
+
+impl FromContext for TwoFactorRepository {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
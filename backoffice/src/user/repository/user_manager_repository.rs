@@ -4,10 +4,11 @@ use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
 use rusqlite::{Connection, OptionalExtension, named_params};
 use shared::context::{Context, ContextError, FromContext};
-use shared::db::{BorrowConnectionExt, SqliteClient};
-use std::sync::{Arc, MutexGuard};
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
 use thiserror::Error;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum UserManagerRepositoryError {
     #[error("Query error")]
@@ -31,202 +32,228 @@ impl UserManagerRepository {
         }
     }
 
-    fn borrow_conn(
-        &'_ self,
-    ) -> Result<MutexGuard<'_, Connection>, Report<UserManagerRepositoryError>> {
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<UserManagerRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<UserManagerRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
         self.sqlite_client
-            .borrow_conn()
-            .change_context(UserManagerRepositoryError::BorrowConnError)
+            .read(UserManagerRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<UserManagerRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<UserManagerRepositoryError>>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(UserManagerRepositoryError::BorrowConnError, f)
+            .await
     }
 }
 
 #[mry::mry]
 impl UserManagerRepository {
-    pub fn add_user(
+    pub async fn add_user(
         &self,
         username: String,
         password: Box<[u8]>,
         role: &Role,
-    ) -> Result<(), Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/user_manager_repository/add_user.sql"),
-            named_params! {
-                ":username": username,
-                ":password": password,
-                ":role": role.as_stringed(),
-            },
-        )
-        .change_context(UserManagerRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        Ok(())
+    ) -> Result<i64, Report<UserManagerRepositoryError>> {
+        let role = role.to_owned();
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_manager_repository/add_user.sql"),
+                named_params! {
+                    ":username": username,
+                    ":password": password,
+                    ":role": role.as_stringed(),
+                },
+            )
+            .change_context(UserManagerRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
     }
 
-    pub fn edit_password(
+    pub async fn edit_password(
         &self,
         id: i64,
         password: Box<[u8]>,
     ) -> Result<(), Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/user_manager_repository/edit_password.sql"),
-            named_params! {
-                ":id": id,
-                ":password": password,
-            },
-        )
-        .change_context(UserManagerRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        Ok(())
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_manager_repository/edit_password.sql"),
+                named_params! {
+                    ":id": id,
+                    ":password": password,
+                },
+            )
+            .change_context(UserManagerRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(())
+        })
+        .await
     }
 
-    pub fn edit_user(
+    pub async fn edit_user(
         &self,
         id: i64,
         username: String,
         role: &Role,
     ) -> Result<(), Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-        conn.execute(
-            include_str!("_sql/user_manager_repository/edit_user.sql"),
-            named_params! {
-                ":id": id,
-                ":username": username,
-                ":role": role.as_stringed(),
-            },
-        )
-        .change_context(UserManagerRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        Ok(())
+        let role = role.to_owned();
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_manager_repository/edit_user.sql"),
+                named_params! {
+                    ":id": id,
+                    ":username": username,
+                    ":role": role.as_stringed(),
+                },
+            )
+            .change_context(UserManagerRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(())
+        })
+        .await
     }
 
-    pub fn fetch_user(
+    pub async fn fetch_user(
         &self,
         id: i64,
     ) -> Result<Option<FetchUser>, Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/user_manager_repository/fetch_user.sql"))
-            .change_context(UserManagerRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        let row: Option<FetchUser> = stmt
-            .query_one(
-                named_params! {
-                    ":id": id
-                },
-                |row| {
-                    Ok(FetchUser {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/user_manager_repository/fetch_user.sql"))
+                .change_context(UserManagerRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let row: Option<FetchUser> = stmt
+                .query_one(
+                    named_params! {
+                        ":id": id
+                    },
+                    |row| {
+                        Ok(FetchUser {
+                            username: row.get("username")?,
+                            role: Role::try_from(row.get::<_, String>("role")?.as_str())
+                                .unwrap_or_default(),
+                        })
+                    },
+                )
+                .optional()
+                .change_context(UserManagerRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(row)
+        })
+        .await
+    }
+
+    pub async fn list_users(&self) -> Result<Arc<[ListUser]>, Report<UserManagerRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/user_manager_repository/list_users.sql"))
+                .change_context(UserManagerRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let rows = stmt
+                .query_map(named_params! {}, |row| {
+                    Ok(ListUser {
+                        id: row.get("id")?,
                         username: row.get("username")?,
                         role: Role::try_from(row.get::<_, String>("role")?.as_str())
                             .unwrap_or_default(),
                     })
-                },
-            )
-            .optional()
-            .change_context(UserManagerRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        Ok(row)
-    }
-
-    pub fn list_users(&self) -> Result<Arc<[ListUser]>, Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/user_manager_repository/list_users.sql"))
-            .change_context(UserManagerRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        let rows = stmt
-            .query_map(named_params! {}, |row| {
-                Ok(ListUser {
-                    id: row.get("id")?,
-                    username: row.get("username")?,
-                    role: Role::try_from(row.get::<_, String>("role")?.as_str())
-                        .unwrap_or_default(),
                 })
-            })
-            .change_context(UserManagerRepositoryError::RowValueError)?;
+                .change_context(UserManagerRepositoryError::RowValueError)?;
 
-        let users = rows
-            .collect::<Result<Vec<_>, _>>()
-            .change_context(UserManagerRepositoryError::RowValueError)?;
+            let users = rows
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(UserManagerRepositoryError::RowValueError)?;
 
-        Ok(users.into())
+            Ok(users.into())
+        })
+        .await
     }
 
-    pub fn revoke_all_token_by_id(
+    pub async fn revoke_all_token_by_id(
         &self,
         user_id: i64,
     ) -> Result<(), Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/user_manager_repository/revoke_all_token_by_id.sql"),
-            named_params! {
-                ":user_id": user_id,
-            },
-        )
-        .change_context(UserManagerRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-        Ok(())
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_manager_repository/revoke_all_token_by_id.sql"),
+                named_params! {
+                    ":user_id": user_id,
+                },
+            )
+            .change_context(UserManagerRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(())
+        })
+        .await
     }
 
-    pub fn username_taken(
+    pub async fn username_taken(
         &self,
         username: String,
     ) -> Result<bool, Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        let mut stmt = conn
-            .prepare_cached(include_str!(
-                "_sql/user_manager_repository/username_taken.sql"
-            ))
-            .change_context(UserManagerRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/user_manager_repository/username_taken.sql"
+                ))
+                .change_context(UserManagerRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let row: Option<bool> = stmt
-            .query_one(
-                named_params! {
-                    ":username": username
-                },
-                |row| Ok(row.get("taken")?),
-            )
-            .optional()
-            .change_context(UserManagerRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let row: Option<bool> = stmt
+                .query_one(
+                    named_params! {
+                        ":username": username
+                    },
+                    |row| row.get("taken"),
+                )
+                .optional()
+                .change_context(UserManagerRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(row.unwrap_or_default())
+            Ok(row.unwrap_or_default())
+        })
+        .await
     }
 
-    #[allow(dead_code)]
-    pub fn fetch_password(
+    pub async fn fetch_password(
         &self,
         user_id: i64,
     ) -> Result<FetchPassword, Report<UserManagerRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!(
+                    "_sql/user_manager_repository/fetch_password.sql"
+                ))
+                .change_context(UserManagerRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!(
-                "_sql/user_manager_repository/fetch_password.sql"
-            ))
-            .change_context(UserManagerRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let row = stmt
+                .query_one(
+                    named_params! {
+                        ":id": user_id
+                    },
+                    |row| {
+                        Ok(FetchPassword {
+                            password: row.get("password")?,
+                        })
+                    },
+                )
+                .change_context(UserManagerRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let row = stmt
-            .query_one(
-                named_params! {
-                    ":id": user_id
-                },
-                |row| {
-                    Ok(FetchPassword {
-                        password: row.get("password")?,
-                    })
-                },
-            )
-            .change_context(UserManagerRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-
-        Ok(row)
+            Ok(row)
+        })
+        .await
     }
 }
 
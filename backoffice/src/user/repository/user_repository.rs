@@ -1,13 +1,16 @@
+use crate::user::model::session_model::SessionModel;
 use crate::user::model::user_model::{IdPassword, UserIdContext};
 use crate::user::role::Role;
+use chrono::{DateTime, Utc};
 use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
 use rusqlite::{Connection, OptionalExtension, named_params};
 use shared::context::{Context, ContextError, FromContext};
-use shared::db::{BorrowConnectionExt, SqliteClient};
-use std::sync::MutexGuard;
+use shared::db::{RunConnectionExt, SqliteClient};
+use std::sync::Arc;
 use thiserror::Error;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum UserRepositoryError {
     #[error("Query error")]
@@ -33,120 +36,257 @@ impl UserRepository {
         }
     }
 
-    fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<UserRepositoryError>> {
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<UserRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<UserRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
         self.sqlite_client
-            .borrow_conn()
-            .change_context(UserRepositoryError::BorrowConnError)
+            .read(UserRepositoryError::BorrowConnError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<UserRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<UserRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(UserRepositoryError::BorrowConnError, f)
+            .await
     }
 }
 
 #[mry::mry]
 impl UserRepository {
-    pub fn add_token(
+    /// Drops the user's expired sessions on the way.
+    pub async fn add_token(
         &self,
         token: String,
         user_id: i64,
+        client_ip: Option<String>,
+        user_agent: Option<String>,
+        now: DateTime<Utc>,
+        expire_after: DateTime<Utc>,
     ) -> Result<(), Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/user_repository/add_token.sql"),
-            named_params! {
-                ":token": token,
-                ":user_id": user_id,
-            },
-        )
-        .change_context(UserRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-
-        Ok(())
-    }
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_repository/prune_token.sql"),
+                named_params! {
+                    ":user_id": user_id,
+                    ":now": now,
+                },
+            )
+            .change_context(UserRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            conn.execute(
+                include_str!("_sql/user_repository/add_token.sql"),
+                named_params! {
+                    ":token": token,
+                    ":user_id": user_id,
+                    ":client_ip": client_ip,
+                    ":user_agent": user_agent,
+                    ":now": now,
+                    ":expire_after": expire_after,
+                },
+            )
+            .change_context(UserRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    pub fn delete_token(&self, token: String) -> Result<(), Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
+            Ok(())
+        })
+        .await
+    }
 
-        conn.execute(
-            include_str!("_sql/user_repository/delete_token.sql"),
-            named_params! {
-                ":token": token,
-            },
-        )
-        .change_context(UserRepositoryError::QueryError)
-        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+    pub async fn delete_token(&self, token: String) -> Result<(), Report<UserRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_repository/delete_token.sql"),
+                named_params! {
+                    ":token": token,
+                },
+            )
+            .change_context(UserRepositoryError::QueryError)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
-    pub fn find_by_token(
+    /// `idle_before` signs out sessions not seen since then, `None` when there is no idle
+    /// timeout.
+    pub async fn find_by_token(
         &self,
         token: String,
+        now: DateTime<Utc>,
+        idle_before: Option<DateTime<Utc>>,
     ) -> Result<UserIdContext, Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/user_repository/find_by_token.sql"))
+                .change_context(UserRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/user_repository/find_by_token.sql"))
-            .change_context(UserRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let row: Option<UserIdContext> = stmt
+                .query_one(
+                    named_params! {
+                        ":token": token,
+                        ":now": now,
+                        ":idle_before": idle_before,
+                    },
+                    |row| {
+                        Ok(UserIdContext {
+                            id: row.get("id")?,
+                            username: row.get("username")?,
+                            role: Role::try_from(row.get::<_, String>("role")?.as_str())
+                                .unwrap_or_default(),
+                            read_only: false,
+                            must_change_password: row.get("must_change_password")?,
+                        })
+                    },
+                )
+                .optional()
+                .change_context(UserRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let row: Option<UserIdContext> = stmt
-            .query_one(
+            match row {
+                Some(row) => Ok(row),
+                None => {
+                    Err(Report::new(UserRepositoryError::NotFoundError)
+                        .attach(StatusCode::NOT_FOUND))
+                }
+            }
+        })
+        .await
+    }
+
+    pub async fn touch_token(
+        &self,
+        token: String,
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/user_repository/touch_token.sql"),
                 named_params! {
                     ":token": token,
-                },
-                |row| {
-                    Ok(UserIdContext {
-                        id: row.get("id")?,
-                        username: row.get("username")?,
-                        role: Role::try_from(row.get::<_, String>("role")?.as_str())
-                            .unwrap_or_default(),
-                    })
+                    ":now": now,
+                    ":stale_before": stale_before,
                 },
             )
-            .optional()
-            .change_context(UserRepositoryError::RowValueError)
+            .change_context(UserRepositoryError::QueryError)
             .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        match row {
-            Some(row) => Ok(row),
-            None => {
-                Err(Report::new(UserRepositoryError::NotFoundError).attach(StatusCode::NOT_FOUND))
-            }
-        }
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn list_sessions(
+        &self,
+        user_id: i64,
+        token: Option<String>,
+        now: DateTime<Utc>,
+        idle_before: Option<DateTime<Utc>>,
+    ) -> Result<Arc<[SessionModel]>, Report<UserRepositoryError>> {
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/user_repository/list_sessions.sql"))
+                .change_context(UserRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items_iter = stmt
+                .query_map(
+                    named_params! {
+                        ":user_id": user_id,
+                        ":token": token,
+                        ":now": now,
+                        ":idle_before": idle_before,
+                    },
+                    |row| {
+                        Ok(SessionModel {
+                            id: row.get("id")?,
+                            created_at: row.get("created_at")?,
+                            last_seen_at: row.get("last_seen_at")?,
+                            expire_after: row.get("expire_after")?,
+                            client_ip: row.get("client_ip")?,
+                            user_agent: row.get("user_agent")?,
+                            current: row.get("current")?,
+                        })
+                    },
+                )
+                .change_context(UserRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let items = items_iter
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(UserRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(items.into())
+        })
+        .await
     }
 
-    pub fn get_user_password(
+    pub async fn revoke_session(
+        &self,
+        id: i64,
+        user_id: i64,
+    ) -> Result<usize, Report<UserRepositoryError>> {
+        self.write(move |conn| {
+            let revoked = conn
+                .execute(
+                    include_str!("_sql/user_repository/revoke_session.sql"),
+                    named_params! {
+                        ":id": id,
+                        ":user_id": user_id,
+                    },
+                )
+                .change_context(UserRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(revoked)
+        })
+        .await
+    }
+
+    pub async fn get_user_password(
         &self,
         username: String,
     ) -> Result<IdPassword, Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/user_repository/get_user_password.sql"))
+                .change_context(UserRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/user_repository/get_user_password.sql"))
-            .change_context(UserRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let row: Option<IdPassword> = stmt
+                .query_one(
+                    named_params! {
+                        ":username": username,
+                    },
+                    |row| {
+                        Ok(IdPassword {
+                            id: row.get("id")?,
+                            password: row.get("password")?,
+                        })
+                    },
+                )
+                .optional()
+                .change_context(UserRepositoryError::RowValueError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let row: Option<IdPassword> = stmt
-            .query_one(
-                named_params! {
-                    ":username": username,
-                },
-                |row| {
-                    Ok(IdPassword {
-                        id: row.get("id")?,
-                        password: row.get("password")?,
-                    })
-                },
-            )
-            .optional()
-            .change_context(UserRepositoryError::RowValueError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
-
-        match row {
-            Some(row) => Ok(row),
-            None => {
-                Err(Report::new(UserRepositoryError::NotFoundError).attach(StatusCode::NOT_FOUND))
+            match row {
+                Some(row) => Ok(row),
+                None => {
+                    Err(Report::new(UserRepositoryError::NotFoundError)
+                        .attach(StatusCode::NOT_FOUND))
+                }
             }
-        }
+        })
+        .await
     }
 }
 
@@ -1,23 +1,30 @@
+use crate::user::model::user_model::UserIdContext;
+use crate::user::{CHANGE_PASSWORD_PATH, USER_ROUTE};
 use maud::{Markup, html};
+use poem::Request;
 use serde::de::Visitor;
 use serde::{Deserialize, Serialize};
+use utoipa::openapi::RefOr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::{PartialSchema, ToSchema};
 
 pub mod user_role_check;
 pub mod visitor_only;
 
-#[derive(Debug, Clone, PartialEq)]
+/// A user who still has to change their password may only reach the change password page.
+pub(crate) fn is_password_change_pending(user_context: &UserIdContext, req: &Request) -> bool {
+    user_context.must_change_password
+        && req.original_uri().path() != format!("{}{}", USER_ROUTE, CHANGE_PASSWORD_PATH)
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum Role {
     Root,
+    #[default]
     User,
     Visitor,
 }
 
-impl Default for Role {
-    fn default() -> Self {
-        Self::User
-    }
-}
-
 impl Serialize for Role {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -53,6 +60,17 @@ impl<'de> Deserialize<'de> for Role {
     }
 }
 
+impl PartialSchema for Role {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .enum_values(Some(Self::all_roles().iter().map(Self::as_stringed)))
+            .into()
+    }
+}
+
+impl ToSchema for Role {}
+
 impl TryFrom<&str> for Role {
     type Error = ();
     fn try_from(s: &str) -> Result<Self, Self::Error> {
@@ -113,3 +131,51 @@ impl PartialOrd for Role {
         self.level().partial_cmp(&other.level())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::web::{LocalAddr, RemoteAddr};
+    use poem::{Addr, Body, RequestParts};
+
+    /// Built from parts, as the server does, so `original_uri` is set.
+    fn request(path: &str) -> Request {
+        let (parts, _) = poem::http::Request::builder()
+            .uri(path)
+            .body(())
+            .unwrap()
+            .into_parts();
+        Request::from_parts(
+            RequestParts::from((
+                parts,
+                LocalAddr(Addr::default()),
+                RemoteAddr(Addr::default()),
+                poem::http::uri::Scheme::HTTP,
+            )),
+            Body::empty(),
+        )
+    }
+
+    fn user_context(must_change_password: bool) -> UserIdContext {
+        UserIdContext {
+            id: 1,
+            username: "admin".to_string(),
+            role: Role::Root,
+            read_only: false,
+            must_change_password,
+        }
+    }
+
+    #[test]
+    fn test_password_change_pending_blocks_other_pages() {
+        let req = request("/shorty/");
+        assert!(is_password_change_pending(&user_context(true), &req));
+        assert!(!is_password_change_pending(&user_context(false), &req));
+    }
+
+    #[test]
+    fn test_password_change_pending_allows_change_password_page() {
+        let req = request("/user/change-password");
+        assert!(!is_password_change_pending(&user_context(true), &req));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::user::pointer::user_pointer::UserPointer;
-use crate::user::role::Role;
+use crate::user::role::{Role, is_password_change_pending};
 use poem::http::StatusCode;
 use poem::{Endpoint, Error, FromRequest, IntoEndpoint, Request};
 use shared::context::Dep;
@@ -15,6 +15,15 @@ impl<E: Endpoint> Endpoint for UserRoleCheck<E> {
         if user_context.role < self.0 {
             return Err(Error::from_status(StatusCode::UNAUTHORIZED));
         }
+        if user_context.read_only && !req.method().is_safe() {
+            return Err(Error::from_status(StatusCode::FORBIDDEN));
+        }
+        if is_password_change_pending(&user_context, &req) {
+            return Err(Error::from_string(
+                "Password change required",
+                StatusCode::FORBIDDEN,
+            ));
+        }
 
         self.1.call(req).await
     }
@@ -1,6 +1,6 @@
 use crate::user::pointer::user_pointer::UserPointer;
-use crate::user::role::Role;
-use crate::user::route::login::LOGIN_ROUTE;
+use crate::user::role::{Role, is_password_change_pending};
+use crate::user::{CHANGE_PASSWORD_PATH, LOGIN_ROUTE, USER_ROUTE};
 use poem::http::StatusCode;
 use poem::web::Redirect;
 use poem::{Endpoint, Error, FromRequest, IntoEndpoint, IntoResponse, Request};
@@ -42,6 +42,11 @@ impl<E: Endpoint> Endpoint for VisitorRedirect<E> {
                 Redirect::see_other(LOGIN_ROUTE).into_response(),
             ));
         }
+        if is_password_change_pending(&user_context, &req) {
+            return Err(Error::from_response(
+                Redirect::see_other(USER_ROUTE.to_owned() + CHANGE_PASSWORD_PATH).into_response(),
+            ));
+        }
         self.0.call(req).await
     }
 }
@@ -0,0 +1,213 @@
+use crate::common::html::context_html::ContextHtmlBuilder;
+use crate::common::icon::trash_icon;
+use crate::user::USER_ROUTE;
+use crate::user::form::add_api_token::{AddApiTokenForm, AddApiTokenMessage};
+use crate::user::form::locale::ApiTokenFormLocale;
+use crate::user::locale::api_token::{ApiTokenLocale, api_token_revoke_confirm_message};
+use crate::user::pointer::user_pointer::UserPointer;
+use crate::user::role::user_role_check::must_be_user;
+use crate::user::service::api_token_service::ApiTokenService;
+use maud::{Markup, html};
+use poem::http::StatusCode;
+use poem::i18n::Locale;
+use poem::session::Session;
+use poem::web::{CsrfToken, CsrfVerifier, Path, Redirect};
+use poem::{Error, IntoResponse, Response, Route, delete, get, handler};
+use shared::context::Dep;
+use shared::csrf::{CsrfTokenHtml, CsrfVerifierError, csrf_header_check};
+use shared::error::{ExtraResultExt, FromErrorStack};
+use shared::flash::{Flash, FlashMessage};
+use shared::htmx::HtmxHeader;
+use shared::locale::LocaleExt;
+use shared::query_string::form::FormQs;
+
+pub const API_TOKEN_ROUTE: &str = "/api-tokens";
+
+async fn api_token_page(
+    context_html_builder: &ContextHtmlBuilder,
+    api_token_service: &ApiTokenService,
+    user_id_context: &UserPointer,
+    form_markup: Markup,
+    new_token: Option<String>,
+    csrf_token: &CsrfToken,
+) -> Markup {
+    let tokens = api_token_service.list_tokens(user_id_context.id).await;
+    let lc = ApiTokenLocale::new(&context_html_builder.locale);
+    let form_locale = ApiTokenFormLocale::new(&context_html_builder.locale);
+    let revoke_icon = trash_icon();
+    let title = lc.title.as_str();
+    let route = format!("{}{}", USER_ROUTE, API_TOKEN_ROUTE);
+
+    context_html_builder
+        .attach_title(title)
+        .set_current_tag("id-tag-user")
+        .attach_content(html! {
+            h1 { (title) }
+            @if let Some(new_token) = new_token {
+                div .flash-message .flash-message-success {
+                    p { (lc.new_token_notice) }
+                    pre .pre { (new_token) }
+                }
+            }
+            table .table-full {
+                thead {
+                    tr {
+                        th { (lc.head_name) }
+                        th { (lc.head_scope) }
+                        th { (lc.head_created_at) }
+                        th { (lc.head_last_used_at) }
+                        th { (lc.head_expires_at) }
+                        th .action { (lc.head_action) }
+                    }
+                }
+                tbody {
+                    @for token in tokens.iter() {
+                        tr {
+                            td { (token.name) }
+                            td { (form_locale.scope_label(token.scope)) }
+                            td .js-date-local { (token.created_at.to_rfc3339()) }
+                            @if let Some(last_used_at) = token.last_used_at {
+                                td .js-date-local { (last_used_at.to_rfc3339()) }
+                            } @else {
+                                td { (lc.never) }
+                            }
+                            @if let Some(expires_at) = token.expires_at {
+                                td .js-date-local { (expires_at.to_rfc3339()) }
+                            } @else {
+                                td { (lc.never) }
+                            }
+                            td .action {
+                                button .icon type="button" hx-confirm=(api_token_revoke_confirm_message(&context_html_builder.locale, &token.name))
+                                    title=(lc.action_revoke) hx-delete=(format!("{}/revoke/{}", route, token.id))
+                                    hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (revoke_icon) }
+                            }
+                        }
+                    }
+                }
+            }
+            (form_markup)
+        })
+        .build()
+}
+
+#[handler]
+async fn list_api_tokens(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(api_token_service): Dep<ApiTokenService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    csrf_token: &CsrfToken,
+) -> Markup {
+    let form_markup = AddApiTokenForm::default().as_form_markup(
+        &context_html_builder.locale,
+        None,
+        Some(csrf_token.as_html()),
+        &user_id_context.role,
+    );
+    api_token_page(
+        &context_html_builder,
+        &api_token_service,
+        &user_id_context,
+        form_markup,
+        None,
+        csrf_token,
+    )
+    .await
+}
+
+#[handler]
+async fn add_api_token(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(api_token_service): Dep<ApiTokenService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    FormQs(add_api_token_form): FormQs<AddApiTokenForm>,
+    csrf_token: &CsrfToken,
+    csrf_verifier: &CsrfVerifier,
+) -> poem::Result<Response> {
+    csrf_verifier
+        .verify(add_api_token_form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    let validated_result = add_api_token_form
+        .as_validated(&user_id_context.role)
+        .await
+        .0;
+    match validated_result {
+        Ok(validated) => {
+            let token = api_token_service
+                .create_token(user_id_context.id, &validated)
+                .await
+                .log_it()
+                .map_err(Error::from_error_stack)?;
+            let form_markup = AddApiTokenForm::default().as_form_markup(
+                &context_html_builder.locale,
+                None,
+                Some(csrf_token.as_html()),
+                &user_id_context.role,
+            );
+            Ok(api_token_page(
+                &context_html_builder,
+                &api_token_service,
+                &user_id_context,
+                form_markup,
+                Some(token),
+                csrf_token,
+            )
+            .await
+            .into_response())
+        }
+        Err(error) => {
+            let errors: AddApiTokenMessage = error.as_message(&context_html_builder.locale);
+            context_html_builder.attach_form_flash_error();
+            let form_markup = add_api_token_form.as_form_markup(
+                &context_html_builder.locale,
+                Some(errors),
+                Some(csrf_token.as_html()),
+                &user_id_context.role,
+            );
+            Ok(api_token_page(
+                &context_html_builder,
+                &api_token_service,
+                &user_id_context,
+                form_markup,
+                None,
+                csrf_token,
+            )
+            .await
+            .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+            .into_response())
+        }
+    }
+}
+
+#[handler]
+async fn revoke_api_token(
+    Dep(api_token_service): Dep<ApiTokenService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(token_id): Path<i64>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    api_token_service
+        .revoke_token(user_id_context.id, token_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    session.flash(Flash::Success {
+        msg: l.text_with_default(
+            "user-route-api-token-flash-revoke-success",
+            "Successfully revoked API token",
+        ),
+    });
+    Ok(htmx_header.do_location(
+        Redirect::see_other(format!("{}{}", USER_ROUTE, API_TOKEN_ROUTE)),
+        "#main-content",
+    ))
+}
+
+pub fn api_token_route() -> Route {
+    Route::new()
+        .at("/", must_be_user(get(list_api_tokens).post(add_api_token)))
+        .at(
+            "/revoke/:token_id",
+            delete(must_be_user(csrf_header_check(revoke_api_token))),
+        )
+}
@@ -1,18 +1,28 @@
 use crate::common::html::context_html::ContextHtmlBuilder;
+use crate::user::LOGIN_ROUTE;
 use crate::user::LOGIN_TOKEN_COOKIE_NAME;
 use crate::user::form::login::{UserLoginForm, UserLoginFormResult};
-use crate::user::locale::login::{LoginLocale, LoginPostLocale, LogoutLocale};
+use crate::user::form::two_factor::TwoFactorCodeForm;
+use crate::user::locale::login::{
+    LoginLocale, LoginPostLocale, LogoutLocale, TwoFactorLoginLocale, TwoFactorLoginPostLocale,
+    login_throttled_message,
+};
 use crate::user::role::user_role_check::must_be_user;
 use crate::user::role::visitor_only::visitor_only;
-use crate::user::service::user_login_service::UserLoginService;
+use crate::user::service::two_factor_service::{
+    LOGIN_CHALLENGE_COOKIE_NAME, LOGIN_CHALLENGE_MINUTES,
+};
+use crate::user::service::user_login_service::{LoginResult, UserLoginService};
 use chrono::TimeDelta;
 use error_stack::Report;
 use maud::{Markup, html};
 use poem::error::ResponseError;
+use poem::http::HeaderMap;
+use poem::http::header::USER_AGENT;
 use poem::i18n::Locale;
 use poem::session::Session;
 use poem::web::cookie::{Cookie, CookieJar};
-use poem::web::{CsrfToken, CsrfVerifier, Redirect};
+use poem::web::{CsrfToken, CsrfVerifier, RealIp, Redirect};
 use poem::{IntoResponse, Response, Route, get, handler};
 use shared::adapter::unified;
 use shared::context::Dep;
@@ -21,7 +31,35 @@ use shared::csrf::{CsrfError, CsrfTokenHtml, CsrfVerifierError};
 use shared::flash::{Flash, FlashMessage};
 use shared::query_string::form::FormQs;
 
-pub const LOGIN_ROUTE: &str = "/user-login";
+const TWO_FACTOR_PATH: &str = "/two-factor";
+
+pub fn login_token_cookie(token: String, lifetime: TimeDelta) -> Cookie {
+    Cookie::new_with_str(LOGIN_TOKEN_COOKIE_NAME, token)
+        .into_builder()
+        .path("/")
+        .expires_by_delta(lifetime)
+        .secure()
+        .http_only()
+        .build()
+}
+
+/// Kept with the session, so it can be told apart from the others.
+pub fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+fn login_challenge_cookie(challenge: String) -> Cookie {
+    Cookie::new_with_str(LOGIN_CHALLENGE_COOKIE_NAME, challenge)
+        .into_builder()
+        .path(LOGIN_ROUTE)
+        .expires_by_delta(TimeDelta::minutes(LOGIN_CHALLENGE_MINUTES))
+        .secure()
+        .http_only()
+        .build()
+}
 
 #[handler]
 async fn login(
@@ -58,38 +96,57 @@ impl IntoResponse for LoginPostResponse {
 }
 
 #[handler]
+#[allow(clippy::too_many_arguments)]
 async fn login_post(
     Dep(user_login_service): Dep<UserLoginService>,
     FormQs(user_login_form): FormQs<UserLoginForm>,
     session: &Session,
     cookie_jar: &CookieJar,
     csrf_verifier: &CsrfVerifier,
+    RealIp(client_ip): RealIp,
+    headers: &HeaderMap,
     locale: Locale,
 ) -> LoginPostResponse {
     unified(async {
         csrf_verifier
             .verify(user_login_form.csrf_token.as_str())
-            .map_err(|err| LoginPostResponse::CsrfError(err))?;
+            .map_err(LoginPostResponse::CsrfError)?;
         let login_post_locale = LoginPostLocale::new(&locale);
         if let UserLoginFormResult(Ok(user_login_form_validated)) = user_login_form.as_validated() {
-            let token = user_login_service.validate_login(
-                user_login_form_validated.username.as_str().to_string(),
-                user_login_form_validated.password.as_str().to_string(),
-            );
-            if let Some(token) = token {
-                let new_cookie = Cookie::new_with_str(LOGIN_TOKEN_COOKIE_NAME, token)
-                    .into_builder()
-                    .path("/")
-                    .expires_by_delta(TimeDelta::days(30))
-                    .secure()
-                    .http_only()
-                    .build();
-
-                cookie_jar.add(new_cookie);
-                session.flash(Flash::Success {
-                    msg: login_post_locale.flash_success,
-                });
-                return Ok(LoginPostResponse::Redirect(Redirect::see_other("/")));
+            let login_result = user_login_service
+                .validate_login(
+                    user_login_form_validated.username.as_str().to_string(),
+                    user_login_form_validated.password.as_str().to_string(),
+                    client_ip,
+                    user_agent(headers),
+                )
+                .await;
+            match login_result {
+                LoginResult::Success(token) => {
+                    cookie_jar.add(login_token_cookie(
+                        token,
+                        user_login_service.session_lifetime(),
+                    ));
+                    session.flash(Flash::Success {
+                        msg: login_post_locale.flash_success,
+                    });
+                    return Ok(LoginPostResponse::Redirect(Redirect::see_other("/")));
+                }
+                LoginResult::SecondFactor(challenge) => {
+                    cookie_jar.add(login_challenge_cookie(challenge));
+                    return Ok(LoginPostResponse::Redirect(Redirect::see_other(
+                        LOGIN_ROUTE.to_owned() + TWO_FACTOR_PATH,
+                    )));
+                }
+                LoginResult::Throttled(retry_after) => {
+                    session.flash(Flash::Error {
+                        msg: login_throttled_message(&locale, retry_after),
+                    });
+                    return Err(LoginPostResponse::Redirect(Redirect::see_other(
+                        LOGIN_ROUTE.to_owned() + "/",
+                    )));
+                }
+                LoginResult::Failed | LoginResult::ChallengeExpired => {}
             }
         }
 
@@ -103,6 +160,99 @@ async fn login_post(
     .await
 }
 
+#[handler]
+async fn two_factor(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    csrf_token: &CsrfToken,
+    cookie_jar: &CookieJar,
+) -> Response {
+    if cookie_jar.get(LOGIN_CHALLENGE_COOKIE_NAME).is_none() {
+        return Redirect::see_other(LOGIN_ROUTE.to_owned() + "/").into_response();
+    }
+    let two_factor_locale = TwoFactorLoginLocale::new(&context_html_builder.locale);
+    context_html_builder
+        .attach_title(&two_factor_locale.title)
+        .attach_content(html! {
+            h1 .mt-3 { (two_factor_locale.title) }
+            form method="post" .form {
+                (csrf_token.as_html())
+                input .form-item type="text" name="code" placeholder=(two_factor_locale.code)
+                    autocomplete="one-time-code" autofocus {}
+                button .btn .btn-sky-blue .mt-3 type="submit" { (two_factor_locale.confirm_button) }
+            }
+        })
+        .build()
+        .into_response()
+}
+
+#[handler]
+#[allow(clippy::too_many_arguments)]
+async fn two_factor_post(
+    Dep(user_login_service): Dep<UserLoginService>,
+    FormQs(two_factor_form): FormQs<TwoFactorCodeForm>,
+    session: &Session,
+    cookie_jar: &CookieJar,
+    csrf_verifier: &CsrfVerifier,
+    RealIp(client_ip): RealIp,
+    headers: &HeaderMap,
+    locale: Locale,
+) -> LoginPostResponse {
+    unified(async {
+        csrf_verifier
+            .verify(two_factor_form.csrf_token.as_str())
+            .map_err(LoginPostResponse::CsrfError)?;
+        let two_factor_post_locale = TwoFactorLoginPostLocale::new(&locale);
+        let challenge = cookie_jar
+            .get(LOGIN_CHALLENGE_COOKIE_NAME)
+            .map(|cookie| cookie.value_str().to_string())
+            .unwrap_or_default();
+        let login_result = user_login_service
+            .validate_second_factor(
+                challenge,
+                two_factor_form.code,
+                client_ip,
+                user_agent(headers),
+            )
+            .await;
+        let retry = Redirect::see_other(LOGIN_ROUTE.to_owned() + TWO_FACTOR_PATH);
+        match login_result {
+            LoginResult::Success(token) => {
+                cookie_jar.remove(LOGIN_CHALLENGE_COOKIE_NAME);
+                cookie_jar.add(login_token_cookie(
+                    token,
+                    user_login_service.session_lifetime(),
+                ));
+                session.flash(Flash::Success {
+                    msg: LoginPostLocale::new(&locale).flash_success,
+                });
+                Ok(LoginPostResponse::Redirect(Redirect::see_other("/")))
+            }
+            LoginResult::Throttled(retry_after) => {
+                session.flash(Flash::Error {
+                    msg: login_throttled_message(&locale, retry_after),
+                });
+                Err(LoginPostResponse::Redirect(retry))
+            }
+            LoginResult::ChallengeExpired => {
+                cookie_jar.remove(LOGIN_CHALLENGE_COOKIE_NAME);
+                session.flash(Flash::Error {
+                    msg: two_factor_post_locale.flash_expired,
+                });
+                Err(LoginPostResponse::Redirect(Redirect::see_other(
+                    LOGIN_ROUTE.to_owned() + "/",
+                )))
+            }
+            LoginResult::Failed | LoginResult::SecondFactor(_) => {
+                session.flash(Flash::Error {
+                    msg: two_factor_post_locale.flash_failed,
+                });
+                Err(LoginPostResponse::Redirect(retry))
+            }
+        }
+    })
+    .await
+}
+
 #[handler]
 async fn logout(
     Dep(user_login_service): Dep<UserLoginService>,
@@ -110,7 +260,7 @@ async fn logout(
     cookie_jar: &CookieJar,
     locale: Locale,
 ) -> Redirect {
-    user_login_service.logout();
+    user_login_service.logout().await;
     cookie_jar.remove(LOGIN_TOKEN_COOKIE_NAME);
     let logout_locale = LogoutLocale::new(&locale);
     session.flash(Flash::Success {
@@ -122,5 +272,9 @@ async fn logout(
 pub fn login_route() -> Route {
     Route::new()
         .at("/", visitor_only(get(login).post(login_post)))
+        .at(
+            TWO_FACTOR_PATH,
+            visitor_only(get(two_factor).post(two_factor_post)),
+        )
         .at("/logout", must_be_user(get(logout)))
 }
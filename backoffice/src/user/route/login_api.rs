@@ -0,0 +1,117 @@
+use crate::api::ApiErrorModel;
+use crate::user::LOGIN_TOKEN_COOKIE_NAME;
+use crate::user::form::login::UserLoginFormResult;
+use crate::user::form::login_json::LoginJson;
+use crate::user::locale::login::{
+    LoginPostLocale, TwoFactorLoginPostLocale, login_throttled_message,
+};
+use crate::user::role::user_role_check::must_be_user;
+use crate::user::route::login::{login_token_cookie, user_agent};
+use crate::user::service::user_login_service::{
+    LoginResult, UserLoginService, retry_after_seconds,
+};
+use poem::http::{HeaderMap, StatusCode, header};
+use poem::i18n::Locale;
+use poem::web::cookie::CookieJar;
+use poem::web::{Json, RealIp};
+use poem::{Error, IntoResponse, Route, handler, post};
+use shared::context::Dep;
+use utoipa::OpenApi;
+
+pub const LOGIN_API_ROUTE: &str = "/session";
+
+#[derive(OpenApi)]
+#[openapi(paths(login, logout))]
+pub struct LoginApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "session",
+    security(()),
+    request_body = LoginJson,
+    responses(
+        (status = 204, description = "Signed in; the login cookie is set"),
+        (status = 401, description = "Wrong username, password or two-factor code, or the code is missing", body = ApiErrorModel),
+        (status = 429, description = "Too many failed attempts; see the `Retry-After` header", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn login(
+    Dep(user_login_service): Dep<UserLoginService>,
+    Json(body): Json<LoginJson>,
+    cookie_jar: &CookieJar,
+    RealIp(client_ip): RealIp,
+    headers: &HeaderMap,
+    locale: Locale,
+) -> poem::Result<StatusCode> {
+    if let UserLoginFormResult(Ok(validated)) = body.as_form().as_validated() {
+        let mut login_result = user_login_service
+            .validate_login(
+                validated.username.as_str().to_string(),
+                validated.password.as_str().to_string(),
+                client_ip,
+                user_agent(headers),
+            )
+            .await;
+        if let LoginResult::SecondFactor(challenge) = login_result {
+            let Some(totp_code) = body.totp_code else {
+                return Err(Error::from_string(
+                    TwoFactorLoginPostLocale::new(&locale).api_required,
+                    StatusCode::UNAUTHORIZED,
+                ));
+            };
+            login_result = user_login_service
+                .validate_second_factor(challenge, totp_code, client_ip, user_agent(headers))
+                .await;
+        }
+        match login_result {
+            LoginResult::Success(token) => {
+                cookie_jar.add(login_token_cookie(
+                    token,
+                    user_login_service.session_lifetime(),
+                ));
+                return Ok(StatusCode::NO_CONTENT);
+            }
+            LoginResult::Throttled(retry_after) => {
+                return Err(Error::from_response(
+                    Json(ApiErrorModel {
+                        msg: login_throttled_message(&locale, retry_after),
+                    })
+                    .with_status(StatusCode::TOO_MANY_REQUESTS)
+                    .with_header(header::RETRY_AFTER, retry_after_seconds(retry_after))
+                    .into_response(),
+                ));
+            }
+            LoginResult::Failed | LoginResult::SecondFactor(_) | LoginResult::ChallengeExpired => {}
+        }
+    }
+    Err(Error::from_string(
+        LoginPostLocale::new(&locale).flash_failed,
+        StatusCode::UNAUTHORIZED,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/",
+    tag = "session",
+    responses(
+        (status = 204, description = "Signed out; the login cookie is removed"),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn logout(
+    Dep(user_login_service): Dep<UserLoginService>,
+    cookie_jar: &CookieJar,
+) -> StatusCode {
+    user_login_service.logout().await;
+    cookie_jar.remove(LOGIN_TOKEN_COOKIE_NAME);
+    StatusCode::NO_CONTENT
+}
+
+pub fn login_api_route() -> Route {
+    Route::new().at("/", post(login).delete(must_be_user(logout)))
+}
@@ -1,2 +1,7 @@
+pub mod api_token;
 pub mod login;
+pub mod login_api;
+pub mod session;
+pub mod two_factor;
 pub mod user;
+pub mod user_api;
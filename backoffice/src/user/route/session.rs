@@ -0,0 +1,106 @@
+use crate::common::html::context_html::ContextHtmlBuilder;
+use crate::common::icon::trash_icon;
+use crate::user::USER_ROUTE;
+use crate::user::locale::session::SessionLocale;
+use crate::user::pointer::user_pointer::UserPointer;
+use crate::user::role::user_role_check::must_be_user;
+use crate::user::service::session_service::SessionService;
+use maud::{Markup, html};
+use poem::i18n::Locale;
+use poem::session::Session;
+use poem::web::{CsrfToken, Path, Redirect};
+use poem::{Error, Response, Route, delete, get, handler};
+use shared::context::Dep;
+use shared::csrf::{CsrfTokenHtml, csrf_header_check};
+use shared::error::FromErrorStack;
+use shared::flash::{Flash, FlashMessage};
+use shared::htmx::HtmxHeader;
+use shared::locale::LocaleExt;
+
+pub const SESSION_ROUTE: &str = "/sessions";
+
+#[handler]
+async fn list_sessions(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(session_service): Dep<SessionService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    csrf_token: &CsrfToken,
+) -> Markup {
+    let sessions = session_service.list_sessions(user_id_context.id).await;
+    let lc = SessionLocale::new(&context_html_builder.locale);
+    let revoke_icon = trash_icon();
+    let title = lc.title.as_str();
+    let route = format!("{}{}", USER_ROUTE, SESSION_ROUTE);
+
+    context_html_builder
+        .attach_title(title)
+        .set_current_tag("id-tag-user")
+        .attach_content(html! {
+            h1 { (title) }
+            table .table-full {
+                thead {
+                    tr {
+                        th { (lc.head_created_at) }
+                        th { (lc.head_last_seen_at) }
+                        th { (lc.head_expire_after) }
+                        th { (lc.head_client_ip) }
+                        th { (lc.head_user_agent) }
+                        th .action { (lc.head_action) }
+                    }
+                }
+                tbody {
+                    @for session in sessions.iter() {
+                        tr {
+                            td .js-date-local { (session.created_at.to_rfc3339()) }
+                            td .js-date-local { (session.last_seen_at.to_rfc3339()) }
+                            td .js-date-local { (session.expire_after.to_rfc3339()) }
+                            td { (session.client_ip.as_deref().unwrap_or(&lc.unknown)) }
+                            td { (session.user_agent.as_deref().unwrap_or(&lc.unknown)) }
+                            td .action {
+                                @if session.current {
+                                    (lc.current)
+                                } @else {
+                                    button .icon type="button" hx-confirm=(lc.revoke_confirm_message)
+                                        title=(lc.action_revoke) hx-delete=(format!("{}/revoke/{}", route, session.id))
+                                        hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (revoke_icon) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .build()
+}
+
+#[handler]
+async fn revoke_session(
+    Dep(session_service): Dep<SessionService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    Path(session_id): Path<i64>,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    session_service
+        .revoke_session(user_id_context.id, session_id)
+        .await
+        .map_err(Error::from_error_stack)?;
+    session.flash(Flash::Success {
+        msg: l.text_with_default(
+            "user-route-session-flash-revoke-success",
+            "Successfully signed out the session",
+        ),
+    });
+    Ok(htmx_header.do_location(
+        Redirect::see_other(format!("{}{}", USER_ROUTE, SESSION_ROUTE)),
+        "#main-content",
+    ))
+}
+
+pub fn session_route() -> Route {
+    Route::new().at("/", must_be_user(get(list_sessions))).at(
+        "/revoke/:session_id",
+        delete(must_be_user(csrf_header_check(revoke_session))),
+    )
+}
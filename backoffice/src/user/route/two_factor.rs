@@ -0,0 +1,268 @@
+use crate::common::html::context_html::ContextHtmlBuilder;
+use crate::user::USER_ROUTE;
+use crate::user::form::two_factor::TwoFactorCodeForm;
+use crate::user::locale::two_factor::{TwoFactorLocale, two_factor_enabled_notice};
+use crate::user::pointer::user_pointer::UserPointer;
+use crate::user::role::user_role_check::must_be_user;
+use crate::user::service::two_factor_service::{
+    TwoFactorService, TwoFactorServiceError, TwoFactorStatus,
+};
+use chrono::Utc;
+use error_stack::Report;
+use maud::{Markup, html};
+use poem::i18n::Locale;
+use poem::session::Session;
+use poem::web::{CsrfToken, CsrfVerifier, Redirect};
+use poem::{Error, IntoResponse, Response, Route, get, handler, post};
+use shared::context::Dep;
+use shared::csrf::{CsrfTokenHtml, CsrfVerifierError};
+use shared::error::{ExtraResultExt, FromErrorStack, LogItExt};
+use shared::flash::{Flash, FlashMessage};
+use shared::htmx::HtmxHeader;
+use shared::locale::LocaleExt;
+use shared::query_string::form::FormQs;
+use std::sync::Arc;
+
+pub const TWO_FACTOR_ROUTE: &str = "/two-factor";
+
+fn two_factor_path(path: &str) -> String {
+    format!("{}{}{}", USER_ROUTE, TWO_FACTOR_ROUTE, path)
+}
+
+fn code_form(csrf_token: &CsrfToken, lc: &TwoFactorLocale, path: &str, button: &str) -> Markup {
+    html! {
+        form hx-boost="true" hx-target="#main-content" .form method="post" action=(two_factor_path(path)) {
+            (csrf_token.as_html())
+            div .form-group {
+                input .form-item type="text" name="code" placeholder=(lc.code)
+                    autocomplete="one-time-code" inputmode="numeric" {}
+            }
+            div .form-group {
+                input .btn .btn-sky-blue type="submit" value=(button) {}
+            }
+        }
+    }
+}
+
+async fn two_factor_page(
+    context_html_builder: &ContextHtmlBuilder,
+    two_factor_service: &TwoFactorService,
+    user_id_context: &UserPointer,
+    csrf_token: &CsrfToken,
+    recovery_codes: Option<Arc<[String]>>,
+) -> poem::Result<Markup> {
+    let status = two_factor_service
+        .status(user_id_context.id, &user_id_context.username)
+        .await
+        .map_err(Error::from_error_stack)?;
+    let l = &context_html_builder.locale;
+    let lc = TwoFactorLocale::new(l);
+    let title = lc.title.as_str();
+
+    Ok(context_html_builder
+        .attach_title(title)
+        .set_current_tag("id-tag-user")
+        .attach_content(html! {
+            h1 { (title) }
+            @if let Some(recovery_codes) = recovery_codes {
+                div .flash-message .flash-message-success {
+                    p { (lc.recovery_codes_notice) }
+                    pre .pre {
+                        @for code in recovery_codes.iter() {
+                            (code) "\n"
+                        }
+                    }
+                }
+            }
+            @match status {
+                TwoFactorStatus::Disabled => {
+                    p { (lc.disabled_notice) }
+                    form hx-boost="true" hx-target="#main-content" .form method="post" action=(two_factor_path("/setup")) {
+                        (csrf_token.as_html())
+                        input .btn .btn-sky-blue type="submit" value=(lc.setup_button) {}
+                    }
+                }
+                TwoFactorStatus::Pending(setup) => {
+                    p { (lc.pending_notice) }
+                    p { a href=(setup.otpauth_uri) { (lc.open_in_app) } }
+                    p { (lc.secret) }
+                    pre .pre { (setup.secret) }
+                    (code_form(csrf_token, &lc, "/confirm", &lc.confirm_button))
+                    form hx-boost="true" hx-target="#main-content" .form method="post" action=(two_factor_path("/setup")) {
+                        (csrf_token.as_html())
+                        input .btn type="submit" value=(lc.restart_button) {}
+                    }
+                }
+                TwoFactorStatus::Enabled { recovery_codes_left } => {
+                    p { (two_factor_enabled_notice(l, recovery_codes_left)) }
+                    (code_form(csrf_token, &lc, "/recovery-codes", &lc.regenerate_button))
+                    (code_form(csrf_token, &lc, "/disable", &lc.disable_button))
+                }
+            }
+        })
+        .build())
+}
+
+/// A wrong code goes back to the page with a flash, anything else is a real error.
+fn invalid_code_or_error(
+    err: Report<TwoFactorServiceError>,
+    session: &Session,
+    l: &Locale,
+    htmx_header: &HtmxHeader,
+) -> poem::Result<Response> {
+    if !matches!(err.current_context(), TwoFactorServiceError::InvalidCode) {
+        return Err(Error::from_error_stack(err.log_it()));
+    }
+    session.flash(Flash::Error {
+        msg: l.text_with_default("user-route-two-factor-flash-invalid-code", "Invalid code"),
+    });
+    Ok(htmx_header.do_location(Redirect::see_other(two_factor_path("/")), "#main-content"))
+}
+
+#[handler]
+async fn two_factor_get(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(two_factor_service): Dep<TwoFactorService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    csrf_token: &CsrfToken,
+) -> poem::Result<Markup> {
+    two_factor_page(
+        &context_html_builder,
+        &two_factor_service,
+        &user_id_context,
+        csrf_token,
+        None,
+    )
+    .await
+}
+
+#[handler]
+async fn setup_post(
+    Dep(two_factor_service): Dep<TwoFactorService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    FormQs(form): FormQs<TwoFactorCodeForm>,
+    csrf_verifier: &CsrfVerifier,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    csrf_verifier
+        .verify(form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    two_factor_service
+        .start_setup(user_id_context.id, &user_id_context.username)
+        .await
+        .log_it()
+        .map_err(Error::from_error_stack)?;
+    Ok(htmx_header.do_location(Redirect::see_other(two_factor_path("/")), "#main-content"))
+}
+
+#[handler]
+#[allow(clippy::too_many_arguments)]
+async fn confirm_post(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(two_factor_service): Dep<TwoFactorService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    FormQs(form): FormQs<TwoFactorCodeForm>,
+    csrf_token: &CsrfToken,
+    csrf_verifier: &CsrfVerifier,
+    session: &Session,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    csrf_verifier
+        .verify(form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    let l = &context_html_builder.locale;
+    match two_factor_service
+        .confirm_setup(user_id_context.id, &form.code, Utc::now())
+        .await
+    {
+        Ok(recovery_codes) => {
+            context_html_builder.attach_flash(Flash::Success {
+                msg: l.text_with_default(
+                    "user-route-two-factor-flash-enabled",
+                    "Two-factor is now on",
+                ),
+            });
+            Ok(two_factor_page(
+                &context_html_builder,
+                &two_factor_service,
+                &user_id_context,
+                csrf_token,
+                Some(recovery_codes),
+            )
+            .await?
+            .into_response())
+        }
+        Err(err) => invalid_code_or_error(err, session, l, &htmx_header),
+    }
+}
+
+#[handler]
+#[allow(clippy::too_many_arguments)]
+async fn recovery_codes_post(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(two_factor_service): Dep<TwoFactorService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    FormQs(form): FormQs<TwoFactorCodeForm>,
+    csrf_token: &CsrfToken,
+    csrf_verifier: &CsrfVerifier,
+    session: &Session,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    csrf_verifier
+        .verify(form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    match two_factor_service
+        .regenerate_recovery_codes(user_id_context.id, &form.code, Utc::now())
+        .await
+    {
+        Ok(recovery_codes) => Ok(two_factor_page(
+            &context_html_builder,
+            &two_factor_service,
+            &user_id_context,
+            csrf_token,
+            Some(recovery_codes),
+        )
+        .await?
+        .into_response()),
+        Err(err) => invalid_code_or_error(err, session, &context_html_builder.locale, &htmx_header),
+    }
+}
+
+#[handler]
+async fn disable_post(
+    Dep(two_factor_service): Dep<TwoFactorService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    FormQs(form): FormQs<TwoFactorCodeForm>,
+    csrf_verifier: &CsrfVerifier,
+    session: &Session,
+    l: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    csrf_verifier
+        .verify(form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    match two_factor_service
+        .disable(user_id_context.id, &form.code, Utc::now())
+        .await
+    {
+        Ok(()) => {
+            session.flash(Flash::Success {
+                msg: l.text_with_default(
+                    "user-route-two-factor-flash-disabled",
+                    "Two-factor is now off",
+                ),
+            });
+            Ok(htmx_header.do_location(Redirect::see_other(two_factor_path("/")), "#main-content"))
+        }
+        Err(err) => invalid_code_or_error(err, session, &l, &htmx_header),
+    }
+}
+
+pub fn two_factor_route() -> Route {
+    Route::new()
+        .at("/", must_be_user(get(two_factor_get)))
+        .at("/setup", must_be_user(post(setup_post)))
+        .at("/confirm", must_be_user(post(confirm_post)))
+        .at("/recovery-codes", must_be_user(post(recovery_codes_post)))
+        .at("/disable", must_be_user(post(disable_post)))
+}
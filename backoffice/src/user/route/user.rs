@@ -1,43 +1,58 @@
 use crate::common::html::context_html::ContextHtmlBuilder;
-use crate::common::icon::{flag_icon, key_icon, pencil_square_icon, plus_icon};
+use crate::common::icon::{
+    command_line_icon, computer_desktop_icon, flag_icon, key_icon, no_symbol_icon,
+    pencil_square_icon, plus_icon, shield_check_icon, shield_exclamation_icon,
+};
 use crate::user::form::add_user::AddUserForm;
-use crate::user::form::edit_password_manager::EditPasswordManagerForm;
+use crate::user::form::edit_password_manager::{
+    EditPasswordManagerForm, EditPasswordManagerMessage,
+};
 use crate::user::form::edit_user::EditUserForm;
+use crate::user::locale::login_failure::LoginFailureLocale;
+use crate::user::locale::two_factor::two_factor_reset_confirm_message;
 use crate::user::locale::user::{UserLocale, user_logout_confirm_message};
 use crate::user::pointer::user_pointer::UserPointer;
-use crate::user::repository::user_manager_repository::UserManagerRepository;
+use crate::user::repository::login_failure_repository::LoginFailureRepository;
 use crate::user::role::Role;
 use crate::user::role::user_role_check::{must_be_root, must_be_user};
+use crate::user::route::api_token::{API_TOKEN_ROUTE, api_token_route};
+use crate::user::route::session::{SESSION_ROUTE, session_route};
+use crate::user::route::two_factor::{TWO_FACTOR_ROUTE, two_factor_route};
+use crate::user::service::two_factor_service::TwoFactorService;
 use crate::user::service::user_manager_service::add_user_service::AddUserService;
 use crate::user::service::user_manager_service::edit_password_service::EditPasswordService;
 use crate::user::service::user_manager_service::edit_service::EditUserService;
 use crate::user::service::user_manager_service::list_service::ListUserService;
+use crate::user::{CHANGE_PASSWORD_PATH, USER_ROUTE};
 use maud::{Markup, html};
 use poem::http::StatusCode;
 use poem::i18n::{I18NArgs, Locale};
 use poem::session::Session;
 use poem::web::{CsrfToken, CsrfVerifier, Path, Redirect};
-use poem::{Error, IntoResponse, Response, Route, get, handler};
+use poem::{Error, IntoResponse, Response, Route, get, handler, post};
 use shared::context::Dep;
-use shared::csrf::{CsrfTokenHtml, CsrfVerifierError};
+use shared::csrf::{CsrfTokenHtml, CsrfVerifierError, csrf_header_check};
 use shared::error::{ExtraResultExt, FromErrorStack};
 use shared::flash::{Flash, FlashMessage};
 use shared::htmx::HtmxHeader;
 use shared::locale::LocaleExt;
 use shared::query_string::form::FormQs;
+use std::sync::Arc;
 
-pub const USER_ROUTE: &str = "/user";
+const LOGIN_FAILURE_LIMIT: i64 = 200;
 
 #[handler]
 async fn list_users(
     Dep(list_user_service): Dep<ListUserService>,
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
     Dep(user_id_context): Dep<UserPointer>,
+    csrf_token: &CsrfToken,
 ) -> Markup {
-    let list_user = list_user_service.list_users();
+    let list_user = list_user_service.list_users().await;
     let edit_icon = pencil_square_icon();
     let password_icon = key_icon();
     let flag_icon = flag_icon();
+    let reset_two_factor_icon = shield_exclamation_icon();
 
     let user_locale = UserLocale::new(&context_html_builder.locale);
 
@@ -74,14 +89,28 @@ async fn list_users(
                                     a .icon hx-confirm=(user_logout_confirm_message(&context_html_builder.locale, &user.username))
                                         href=(format!("{}/sign-out/{}", USER_ROUTE, user.id)) title=(&user_locale.user_list_action_sign_out)
                                         hx-get=(format!("{}/sign-out/{}", USER_ROUTE, user.id)) hx-push-url="true" hx-target="#main-content" { (flag_icon) }
+                                    " "
+                                    button .icon type="button" hx-confirm=(two_factor_reset_confirm_message(&context_html_builder.locale, &user.username))
+                                        title=(&user_locale.user_list_action_reset_two_factor) hx-post=(format!("{}/reset-two-factor/{}", USER_ROUTE, user.id))
+                                        hx-headers=(csrf_token.as_hx_headers()) hx-target="#main-content" { (reset_two_factor_icon) }
                                 }
                             }
                         }
                     }
                 }
             }
-            @if user_id_context.role == Role::Root {
-                div .text-right .mt-3 {
+            div .text-right .mt-3 {
+                a .inline-block href=(format!("{}{}", USER_ROUTE, CHANGE_PASSWORD_PATH)) title=(&user_locale.user_list_action_change_password)
+                    hx-get=(format!("{}{}", USER_ROUTE, CHANGE_PASSWORD_PATH)) hx-push-url="true" hx-target="#main-content" { (key_icon()) }
+                a .inline-block href=(format!("{}{}/", USER_ROUTE, API_TOKEN_ROUTE)) title=(&user_locale.user_list_action_api_tokens)
+                    hx-get=(format!("{}{}/", USER_ROUTE, API_TOKEN_ROUTE)) hx-push-url="true" hx-target="#main-content" { (command_line_icon()) }
+                a .inline-block href=(format!("{}{}/", USER_ROUTE, TWO_FACTOR_ROUTE)) title=(&user_locale.user_list_action_two_factor)
+                    hx-get=(format!("{}{}/", USER_ROUTE, TWO_FACTOR_ROUTE)) hx-push-url="true" hx-target="#main-content" { (shield_check_icon()) }
+                a .inline-block href=(format!("{}{}/", USER_ROUTE, SESSION_ROUTE)) title=(&user_locale.user_list_action_sessions)
+                    hx-get=(format!("{}{}/", USER_ROUTE, SESSION_ROUTE)) hx-push-url="true" hx-target="#main-content" { (computer_desktop_icon()) }
+                @if user_id_context.role == Role::Root {
+                    a .inline-block href=(format!("{}/login-failures", USER_ROUTE)) title=(&user_locale.user_list_action_login_failures)
+                        hx-get=(format!("{}/login-failures", USER_ROUTE)) hx-push-url="true" hx-target="#main-content" { (no_symbol_icon()) }
                     a .inline-block href=(format!("{}/add-user", USER_ROUTE)) title=(&user_locale.user_list_action_add_user)
                         hx-get=(format!("{}/add-user", USER_ROUTE)) hx-push-url="true" hx-target="#main-content" { (plus_icon()) }
                 }
@@ -113,11 +142,14 @@ async fn edit_user_get(
 ) -> poem::Result<Markup> {
     let subject_user = edit_user_service
         .fetch_user(user_id)
+        .await
         .map_err(Error::from_error_stack)?;
 
-    let mut edit_user = EditUserForm::default();
-    edit_user.username = subject_user.username.to_string();
-    edit_user.role = subject_user.role;
+    let edit_user = EditUserForm {
+        username: subject_user.username.to_string(),
+        role: subject_user.role,
+        ..Default::default()
+    };
 
     Ok(edit_user
         .as_form_html(
@@ -130,6 +162,7 @@ async fn edit_user_get(
 }
 
 #[handler]
+#[allow(clippy::too_many_arguments)]
 async fn edit_user_post(
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
     Dep(edit_user_service): Dep<EditUserService>,
@@ -142,6 +175,7 @@ async fn edit_user_post(
 ) -> poem::Result<Response> {
     let subject_user = edit_user_service
         .fetch_user(user_id)
+        .await
         .map_err(Error::from_error_stack)?;
     csrf_verifier
         .verify(edit_user_form.csrf_token.as_str())
@@ -155,6 +189,7 @@ async fn edit_user_post(
         Ok(validated) => {
             edit_user_service
                 .edit_user_submit(user_id, &validated)
+                .await
                 .log_it()
                 .map_err(Error::from_error_stack)?;
             session.flash(Flash::Success {
@@ -196,6 +231,7 @@ async fn edit_user_password_get(
 ) -> poem::Result<Markup> {
     let subject_user = edit_password_service
         .fetch_user(user_id)
+        .await
         .map_err(Error::from_error_stack)?;
 
     let edit_password_form = EditPasswordManagerForm::default();
@@ -206,11 +242,13 @@ async fn edit_user_password_get(
             None,
             Some(csrf_token.as_html()),
             Some(subject_user.username),
+            false,
         )
         .await)
 }
 
 #[handler]
+#[allow(clippy::too_many_arguments)]
 async fn edit_user_password_post(
     Dep(context_html_builder): Dep<ContextHtmlBuilder>,
     Dep(edit_password_service): Dep<EditPasswordService>,
@@ -223,6 +261,7 @@ async fn edit_user_password_post(
 ) -> poem::Result<Response> {
     let subject_user = edit_password_service
         .fetch_user(user_id)
+        .await
         .map_err(Error::from_error_stack)?;
     csrf_verifier
         .verify(edit_password_manager_form.csrf_token.as_str())
@@ -233,6 +272,7 @@ async fn edit_user_password_post(
         Ok(validated) => {
             edit_password_service
                 .edit_password_submit(user_id, &validated)
+                .await
                 .log_it()
                 .map_err(Error::from_error_stack)?;
             session.flash(Flash::Success {
@@ -257,6 +297,7 @@ async fn edit_user_password_post(
                         Some(errors),
                         Some(csrf_token.as_html()),
                         Some(subject_user.username),
+                        false,
                     )
                     .await,
             )
@@ -296,6 +337,7 @@ async fn add_user_password_post(
         Ok(validated) => {
             add_user_service
                 .add_user_submit(&validated)
+                .await
                 .log_it()
                 .map_err(Error::from_error_stack)?;
 
@@ -329,14 +371,151 @@ async fn add_user_password_post(
 }
 
 #[handler]
-fn sign_out_user(
-    Dep(user_manager_repository): Dep<UserManagerRepository>,
+async fn change_password_get(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(user_id_context): Dep<UserPointer>,
+    csrf_token: &CsrfToken,
+) -> Markup {
+    if user_id_context.must_change_password {
+        context_html_builder.attach_flash(Flash::Warning {
+            msg: context_html_builder.locale.text_with_default(
+                "user-route-change-password-required",
+                "Please choose a new password before continuing",
+            ),
+        });
+    }
+
+    EditPasswordManagerForm::default()
+        .as_form_html(
+            &context_html_builder,
+            None,
+            Some(csrf_token.as_html()),
+            Some(user_id_context.username.clone()),
+            !user_id_context.must_change_password,
+        )
+        .await
+}
+
+#[handler]
+#[allow(clippy::too_many_arguments)]
+async fn change_password_post(
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+    Dep(edit_password_service): Dep<EditPasswordService>,
+    Dep(user_id_context): Dep<UserPointer>,
+    FormQs(edit_password_manager_form): FormQs<EditPasswordManagerForm>,
+    csrf_token: &CsrfToken,
+    csrf_verifier: &CsrfVerifier,
+    session: &Session,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    csrf_verifier
+        .verify(edit_password_manager_form.csrf_token.as_str())
+        .map_err(Error::from_error_stack)?;
+    let validated_result = edit_password_manager_form.as_validated().await.0;
+    // The forced change after the first login comes straight from the login form.
+    let current_password_verified = user_id_context.must_change_password
+        || edit_password_service
+            .verify_current_password(
+                user_id_context.id,
+                edit_password_manager_form.current_password.as_str(),
+            )
+            .await
+            .log_it()
+            .map_err(Error::from_error_stack)?;
+    let l = &context_html_builder.locale;
+    match validated_result {
+        Ok(validated) if current_password_verified => {
+            edit_password_service
+                .edit_password_submit(user_id_context.id, &validated)
+                .await
+                .log_it()
+                .map_err(Error::from_error_stack)?;
+            session.flash(Flash::Success {
+                msg: l.text_with_default(
+                    "user-route-flash-change-password-success",
+                    "Your password has been changed",
+                ),
+            });
+            Ok(htmx_header.do_location(Redirect::see_other("/"), "#main-content"))
+        }
+        validated_result => {
+            let mut errors = match validated_result {
+                Ok(_) => EditPasswordManagerMessage::default(),
+                Err(error) => error.as_message(l),
+            };
+            if !current_password_verified {
+                errors.current_password = Arc::new([l.text_with_default(
+                    "user-route-change-password-current-wrong",
+                    "The current password is wrong",
+                )]);
+            }
+            context_html_builder.attach_form_flash_error();
+            Ok(PostResponse::Validation(
+                edit_password_manager_form
+                    .as_form_html(
+                        &context_html_builder,
+                        Some(errors),
+                        Some(csrf_token.as_html()),
+                        Some(user_id_context.username.clone()),
+                        !user_id_context.must_change_password,
+                    )
+                    .await,
+            )
+            .into_response())
+        }
+    }
+}
+
+#[handler]
+async fn list_login_failures(
+    Dep(login_failure_repository): Dep<LoginFailureRepository>,
+    Dep(context_html_builder): Dep<ContextHtmlBuilder>,
+) -> poem::Result<Markup> {
+    let login_failures = login_failure_repository
+        .list_login_failures(LOGIN_FAILURE_LIMIT)
+        .await
+        .map_err(Error::from_error_stack)?;
+
+    let lc = LoginFailureLocale::new(&context_html_builder.locale);
+
+    Ok(context_html_builder
+        .attach_title(&lc.title)
+        .set_current_tag("id-tag-user")
+        .attach_content(html! {
+            h1 { (&lc.title) }
+            table .table-full {
+                thead {
+                    tr {
+                        th { (&lc.head_username) }
+                        th { (&lc.head_client_ip) }
+                        th { (&lc.head_failed_at) }
+                        th { (&lc.head_throttled) }
+                    }
+                }
+                tbody {
+                    @for login_failure in login_failures.iter() {
+                        tr {
+                            td { (&login_failure.username) }
+                            td { (login_failure.client_ip.as_deref().unwrap_or_default()) }
+                            td .js-date-local { (login_failure.failed_at.to_rfc3339()) }
+                            td { @if login_failure.throttled { (&lc.yes) } @else { (&lc.no) } }
+                        }
+                    }
+                }
+            }
+        })
+        .build())
+}
+
+#[handler]
+async fn sign_out_user(
+    Dep(edit_user_service): Dep<EditUserService>,
     Path(user_id): Path<i64>,
     session: &Session,
     locale: Locale,
     htmx_header: HtmxHeader,
 ) -> Response {
-    let result = user_manager_repository.revoke_all_token_by_id(user_id);
+    let result = edit_user_service.sign_out_user(user_id).await;
     let l = &locale;
     if result.is_err() {
         session.flash(Flash::Error {
@@ -364,6 +543,43 @@ fn sign_out_user(
     )
 }
 
+#[handler]
+async fn reset_two_factor(
+    Dep(two_factor_service): Dep<TwoFactorService>,
+    Path(user_id): Path<i64>,
+    session: &Session,
+    locale: Locale,
+    htmx_header: HtmxHeader,
+) -> poem::Result<Response> {
+    let reset = two_factor_service
+        .reset(user_id)
+        .await
+        .log_it()
+        .map_err(Error::from_error_stack)?;
+    let l = &locale;
+    if reset {
+        session.flash(Flash::Success {
+            msg: l.text_with_default_args(
+                "user-route-flash-reset-two-factor-success",
+                format!("Two-factor was reset for user id: {}", user_id).as_str(),
+                I18NArgs::from((("user_id", user_id),)),
+            ),
+        });
+    } else {
+        session.flash(Flash::Warning {
+            msg: l.text_with_default_args(
+                "user-route-flash-reset-two-factor-none",
+                format!("User id: {} does not have two-factor set up", user_id).as_str(),
+                I18NArgs::from((("user_id", user_id),)),
+            ),
+        });
+    }
+    Ok(htmx_header.do_location(
+        Redirect::see_other(USER_ROUTE.to_owned() + "/"),
+        "#main-content",
+    ))
+}
+
 pub fn user_route() -> Route {
     Route::new()
         .at("/", get(must_be_user(list_users)))
@@ -380,4 +596,37 @@ pub fn user_route() -> Route {
             must_be_root(get(add_user_password_get).post(add_user_password_post)),
         )
         .at("/sign-out/:user_id", must_be_root(get(sign_out_user)))
+        .at("/login-failures", must_be_root(get(list_login_failures)))
+        .at(
+            "/reset-two-factor/:user_id",
+            post(must_be_root(csrf_header_check(reset_two_factor))),
+        )
+        .at(
+            CHANGE_PASSWORD_PATH,
+            must_be_user(get(change_password_get).post(change_password_post)),
+        )
+        .nest(API_TOKEN_ROUTE, api_token_route())
+        .nest(TWO_FACTOR_ROUTE, two_factor_route())
+        .nest(SESSION_ROUTE, session_route())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::Endpoint;
+    use poem::http::Method;
+
+    #[tokio::test]
+    async fn test_reset_two_factor_refuses_get() {
+        let response = user_route()
+            .get_response(
+                poem::Request::builder()
+                    .method(Method::GET)
+                    .uri_str("/reset-two-factor/2")
+                    .finish(),
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
 }
@@ -0,0 +1,185 @@
+use crate::api::{ApiErrorModel, ApiValidationErrorModel, api_error, api_validation_error};
+use crate::user::form::add_user::AddUserMessage;
+use crate::user::form::add_user_json::AddUserJson;
+use crate::user::form::edit_password_manager::EditPasswordManagerMessage;
+use crate::user::form::edit_password_manager_json::EditPasswordManagerJson;
+use crate::user::form::edit_user::EditUserMessage;
+use crate::user::form::edit_user_json::EditUserJson;
+use crate::user::model::user_manager_model::ListUser;
+use crate::user::role::user_role_check::{must_be_root, must_be_user};
+use crate::user::service::user_manager_service::add_user_service::AddUserService;
+use crate::user::service::user_manager_service::edit_password_service::EditPasswordService;
+use crate::user::service::user_manager_service::edit_service::EditUserService;
+use crate::user::service::user_manager_service::list_service::ListUserService;
+use poem::http::StatusCode;
+use poem::i18n::Locale;
+use poem::web::{Json, Path};
+use poem::{IntoResponse, Response, Route, delete, get, handler, put};
+use shared::context::Dep;
+use shared::error::ExtraResultExt;
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+pub const USER_API_ROUTE: &str = "/users";
+
+#[derive(OpenApi)]
+#[openapi(paths(list_users, add_user, edit_user, edit_user_password, sign_out_user))]
+pub struct UserApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "users",
+    responses(
+        (status = 200, description = "All users", body = [ListUser]),
+        (status = 401, description = "Not signed in", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn list_users(Dep(list_user_service): Dep<ListUserService>) -> Json<Arc<[ListUser]>> {
+    Json(list_user_service.list_users().await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "users",
+    request_body = AddUserJson,
+    responses(
+        (status = 201, description = "User created"),
+        (status = 401, description = "Not signed in as a root user", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+        (status = 422, description = "Validation failed", body = ApiValidationErrorModel<AddUserMessage>),
+    )
+)]
+#[handler]
+async fn add_user(
+    Dep(add_user_service): Dep<AddUserService>,
+    Json(body): Json<AddUserJson>,
+    l: Locale,
+) -> poem::Result<Response> {
+    let validated = match body.as_form().as_validated(&add_user_service).await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Ok(api_validation_error(error.as_message(&l))),
+    };
+    add_user_service
+        .add_user_submit(&validated)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(StatusCode::CREATED.into_response())
+}
+
+#[utoipa::path(
+    put,
+    path = "/{user_id}",
+    tag = "users",
+    params(("user_id" = i64, Path)),
+    request_body = EditUserJson,
+    responses(
+        (status = 204, description = "User updated"),
+        (status = 401, description = "Not signed in as a root user", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such user", body = ApiErrorModel),
+        (status = 422, description = "Validation failed", body = ApiValidationErrorModel<EditUserMessage>),
+    )
+)]
+#[handler]
+async fn edit_user(
+    Dep(edit_user_service): Dep<EditUserService>,
+    Path(user_id): Path<i64>,
+    Json(body): Json<EditUserJson>,
+    l: Locale,
+) -> poem::Result<Response> {
+    let subject_user = edit_user_service
+        .fetch_user(user_id)
+        .await
+        .map_err(api_error)?;
+    let validated = match body
+        .as_form()
+        .as_validated(&edit_user_service, &subject_user.username)
+        .await
+        .0
+    {
+        Ok(validated) => validated,
+        Err(error) => return Ok(api_validation_error(error.as_message(&l))),
+    };
+    edit_user_service
+        .edit_user_submit(user_id, &validated)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[utoipa::path(
+    put,
+    path = "/{user_id}/password",
+    tag = "users",
+    params(("user_id" = i64, Path)),
+    request_body = EditPasswordManagerJson,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 401, description = "Not signed in as a root user", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+        (status = 404, description = "No such user", body = ApiErrorModel),
+        (status = 422, description = "Validation failed", body = ApiValidationErrorModel<EditPasswordManagerMessage>),
+    )
+)]
+#[handler]
+async fn edit_user_password(
+    Dep(edit_password_service): Dep<EditPasswordService>,
+    Path(user_id): Path<i64>,
+    Json(body): Json<EditPasswordManagerJson>,
+    l: Locale,
+) -> poem::Result<Response> {
+    edit_password_service
+        .fetch_user(user_id)
+        .await
+        .map_err(api_error)?;
+    let validated = match body.as_form().as_validated().await.0 {
+        Ok(validated) => validated,
+        Err(error) => return Ok(api_validation_error(error.as_message(&l))),
+    };
+    edit_password_service
+        .edit_password_submit(user_id, &validated)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{user_id}/sessions",
+    tag = "users",
+    params(("user_id" = i64, Path)),
+    responses(
+        (status = 204, description = "All of the user's login sessions were ended"),
+        (status = 401, description = "Not signed in as a root user", body = ApiErrorModel),
+        (status = 403, description = "Token is read only", body = ApiErrorModel),
+    )
+)]
+#[handler]
+async fn sign_out_user(
+    Dep(edit_user_service): Dep<EditUserService>,
+    Path(user_id): Path<i64>,
+) -> poem::Result<StatusCode> {
+    edit_user_service
+        .sign_out_user(user_id)
+        .await
+        .log_it()
+        .map_err(api_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn user_api_route() -> Route {
+    Route::new()
+        .at(
+            "/",
+            get(must_be_user(list_users)).post(must_be_root(add_user)),
+        )
+        .at("/:user_id", must_be_root(put(edit_user)))
+        .at("/:user_id/password", must_be_root(put(edit_user_password)))
+        .at("/:user_id/sessions", must_be_root(delete(sign_out_user)))
+}
@@ -0,0 +1,108 @@
+use crate::user::model::api_token_model::ApiTokenScope;
+use crate::user::role::Role;
+use cjtoolkit_structured_validator::common::locale::{
+    LocaleData, LocaleMessage, ValidateErrorCollector, ValidateErrorStore,
+};
+use cjtoolkit_structured_validator::common::validation_check::ValidationCheck;
+use cjtoolkit_structured_validator::types::name::name_alias::{Field, FieldError, FieldRules};
+use std::sync::Arc;
+
+fn api_token_name_rule() -> FieldRules {
+    FieldRules {
+        is_mandatory: true,
+        min_length: Some(1),
+        max_length: Some(100),
+    }
+}
+
+pub trait ApiTokenNameRulesExt {
+    fn parse_api_token_name(name: Option<&str>) -> Result<Field, FieldError>;
+}
+
+impl ApiTokenNameRulesExt for Field {
+    fn parse_api_token_name(name: Option<&str>) -> Result<Field, FieldError> {
+        Field::parse_custom(name, api_token_name_rule())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ApiTokenScopeError(pub ValidateErrorStore);
+
+impl ValidationCheck for ApiTokenScopeError {
+    fn validate_new(messages: ValidateErrorStore) -> Self {
+        Self(messages)
+    }
+}
+
+impl From<&ApiTokenScopeError> for ValidateErrorStore {
+    fn from(error: &ApiTokenScopeError) -> Self {
+        error.0.clone()
+    }
+}
+
+struct InvalidApiTokenScopeLocale;
+
+impl LocaleMessage for InvalidApiTokenScopeLocale {
+    fn get_locale_data(&self) -> Arc<LocaleData> {
+        LocaleData::new("validate-invalid-api-token-scope")
+    }
+}
+
+struct ApiTokenScopeNotAllowedLocale;
+
+impl LocaleMessage for ApiTokenScopeNotAllowedLocale {
+    fn get_locale_data(&self) -> Arc<LocaleData> {
+        LocaleData::new("validate-api-token-scope-not-allowed")
+    }
+}
+
+pub trait ApiTokenScopeRulesExt {
+    fn parse_api_token_scope(
+        scope: Option<&str>,
+        role: &Role,
+    ) -> Result<ApiTokenScope, ApiTokenScopeError>;
+}
+
+impl ApiTokenScopeRulesExt for ApiTokenScope {
+    fn parse_api_token_scope(
+        scope: Option<&str>,
+        role: &Role,
+    ) -> Result<ApiTokenScope, ApiTokenScopeError> {
+        let mut messages = ValidateErrorCollector::new();
+        match scope.and_then(|scope| ApiTokenScope::try_from(scope).ok()) {
+            Some(ApiTokenScope::Root) if *role < Role::Root => {
+                messages.push((
+                    "Scope is not allowed".to_string(),
+                    Box::new(ApiTokenScopeNotAllowedLocale),
+                ));
+            }
+            Some(scope) => return Ok(scope),
+            None => {
+                messages.push((
+                    "Invalid scope".to_string(),
+                    Box::new(InvalidApiTokenScopeLocale),
+                ));
+            }
+        }
+        Err(ApiTokenScopeError::validate_new(messages.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_api_token_scope() {
+        assert_eq!(
+            ApiTokenScope::parse_api_token_scope(Some("read-write"), &Role::User),
+            Ok(ApiTokenScope::ReadWrite)
+        );
+        assert_eq!(
+            ApiTokenScope::parse_api_token_scope(Some("root"), &Role::Root),
+            Ok(ApiTokenScope::Root)
+        );
+        assert!(ApiTokenScope::parse_api_token_scope(Some("root"), &Role::User).is_err());
+        assert!(ApiTokenScope::parse_api_token_scope(Some("write"), &Role::User).is_err());
+    }
+}
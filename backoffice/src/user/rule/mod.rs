@@ -1,2 +1,3 @@
+pub mod api_token;
 pub mod login;
 pub mod user_manager;
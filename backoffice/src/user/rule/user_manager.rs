@@ -44,10 +44,10 @@ impl UsernameUserManagerRulesExt for Username {
     ) -> Result<Username, UsernameError> {
         let mut username = Username::parse(username);
         if let Ok(username_ref) = username.as_ref() {
-            if let Some(current_user_name) = current_user_name {
-                if current_user_name == username_ref.as_str() {
-                    return username;
-                }
+            if let Some(current_user_name) = current_user_name
+                && current_user_name == username_ref.as_str()
+            {
+                return username;
             }
             check_username_is_reserved(username_ref.as_str())?;
             username = username_ref.check_username_taken_async(service).await;
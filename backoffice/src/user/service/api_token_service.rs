@@ -0,0 +1,173 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::user::form::add_api_token::AddApiTokenValidated;
+use crate::user::model::api_token_model::ApiTokenModel;
+use crate::user::repository::api_token_repository::ApiTokenRepository;
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use shared::context::{Context, ContextError, FromContext};
+use std::sync::Arc;
+
+pub const API_TOKEN_PREFIX: &str = "rsk_";
+const API_TOKEN_LENGTH: usize = 40;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiTokenServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Not found")]
+    NotFound,
+}
+
+pub fn hash_api_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn generate_api_token() -> String {
+    let random: String = rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(API_TOKEN_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{}{}", API_TOKEN_PREFIX, random)
+}
+
+pub struct ApiTokenService {
+    api_token_repository: ApiTokenRepository,
+    audit_service: AuditService,
+}
+
+impl ApiTokenService {
+    pub fn new(api_token_repository: ApiTokenRepository, audit_service: AuditService) -> Self {
+        Self {
+            api_token_repository,
+            audit_service,
+        }
+    }
+
+    pub async fn list_tokens(&self, user_id: i64) -> Arc<[ApiTokenModel]> {
+        self.api_token_repository
+            .list_api_tokens(user_id)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Returns the plain token, which is only ever shown once; just the hash is kept.
+    pub async fn create_token(
+        &self,
+        user_id: i64,
+        form: &AddApiTokenValidated,
+    ) -> Result<String, Report<ApiTokenServiceError>> {
+        let token = generate_api_token();
+        let id = self
+            .api_token_repository
+            .add_api_token(
+                user_id,
+                form.name.as_str(),
+                &hash_api_token(&token),
+                form.scope,
+                form.expires_at(),
+            )
+            .await
+            .change_context(ApiTokenServiceError::DbError)?;
+        self.audit_service
+            .record(
+                AuditAction::ApiTokenAdd,
+                Some(id),
+                None::<&()>,
+                Some(&json!({
+                    "user_id": user_id,
+                    "name": form.name.as_str(),
+                    "scope": form.scope.as_stringed(),
+                    "expires_at": form.expires_at(),
+                })),
+            )
+            .await;
+        Ok(token)
+    }
+
+    pub async fn revoke_token(
+        &self,
+        user_id: i64,
+        id: i64,
+    ) -> Result<(), Report<ApiTokenServiceError>> {
+        let revoked = self
+            .api_token_repository
+            .revoke_api_token(id, user_id)
+            .await
+            .change_context(ApiTokenServiceError::DbError)?;
+        if revoked == 0 {
+            return Err(Report::new(ApiTokenServiceError::NotFound).attach(StatusCode::NOT_FOUND));
+        }
+        self.audit_service
+            .record(
+                AuditAction::ApiTokenRevoke,
+                Some(id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+        Ok(())
+    }
+}
+
+impl FromContext for ApiTokenService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::form::add_api_token::AddApiTokenForm;
+    use crate::user::model::api_token_model::ApiTokenScope;
+    use crate::user::role::Role;
+    use mry::Any;
+
+    #[test]
+    fn test_generate_api_token() {
+        let token = generate_api_token();
+        assert!(token.starts_with(API_TOKEN_PREFIX));
+        assert_eq!(token.len(), API_TOKEN_PREFIX.len() + API_TOKEN_LENGTH);
+        assert_ne!(token, generate_api_token());
+    }
+
+    #[tokio::test]
+    async fn test_create_token_stores_hash() {
+        let mut api_token_repository = ApiTokenRepository::new_mock();
+        api_token_repository
+            .mock_add_api_token(1, "deploy", Any, ApiTokenScope::ReadWrite, None)
+            .returns_once(Ok(3));
+
+        let form = AddApiTokenForm {
+            name: "deploy".to_string(),
+            scope: "read-write".to_string(),
+            ..Default::default()
+        };
+        let validated = form.as_validated(&Role::User).await.0.unwrap();
+
+        let api_token_service =
+            ApiTokenService::new(api_token_repository, AuditService::new_mock());
+        let token = api_token_service.create_token(1, &validated).await.unwrap();
+        assert!(token.starts_with(API_TOKEN_PREFIX));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_token_not_found() {
+        let mut api_token_repository = ApiTokenRepository::new_mock();
+        api_token_repository
+            .mock_revoke_api_token(2, 1)
+            .returns_once(Ok(0));
+
+        let api_token_service =
+            ApiTokenService::new(api_token_repository, AuditService::new_mock());
+        let error = api_token_service.revoke_token(1, 2).await.unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+}
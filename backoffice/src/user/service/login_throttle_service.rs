@@ -0,0 +1,293 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use error_stack::Report;
+use shared::config::ConfigPointer;
+use shared::config::login::LoginConfig;
+use shared::context::{Context, ContextError, FromContext};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::OnceCell;
+
+/// Past this, keys that have gone quiet are dropped before tracking a new one.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum ThrottleKey {
+    Username(String),
+    Ip(IpAddr),
+}
+
+struct AttemptState {
+    failures: u32,
+    last_failure: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct Attempts {
+    states: HashMap<ThrottleKey, AttemptState>,
+    swept_at: Option<DateTime<Utc>>,
+}
+
+/// Failed logins per username and per client IP, locking either with exponential backoff.
+/// Checked before the password, so a locked login costs no Argon2 work.
+#[derive(Clone)]
+pub struct LoginThrottleService {
+    attempts: Arc<Mutex<Attempts>>,
+    username_free_attempts: u32,
+    ip_free_attempts: u32,
+    backoff_base_seconds: u64,
+    max_lockout_seconds: u64,
+    reset_after: TimeDelta,
+}
+
+impl LoginThrottleService {
+    pub fn new(config: &LoginConfig) -> Self {
+        Self {
+            attempts: Arc::new(Mutex::new(Attempts::default())),
+            username_free_attempts: config.username_free_attempts,
+            ip_free_attempts: config.ip_free_attempts,
+            backoff_base_seconds: config.backoff_base_seconds,
+            max_lockout_seconds: config.max_lockout_seconds,
+            reset_after: TimeDelta::seconds(config.reset_after_seconds as i64),
+        }
+    }
+
+    /// How long until the login may be tried again, `None` when neither the username nor
+    /// the IP is locked.
+    pub fn retry_after(
+        &self,
+        username: &str,
+        client_ip: Option<IpAddr>,
+        now: DateTime<Utc>,
+    ) -> Option<TimeDelta> {
+        let attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+        self.keys(username, client_ip)
+            .iter()
+            .filter_map(|(key, _)| attempts.states.get(key)?.locked_until)
+            .map(|locked_until| locked_until - now)
+            .filter(|retry_after| *retry_after > TimeDelta::zero())
+            .max()
+    }
+
+    /// Whether this failure locked the username or the IP.
+    pub fn record_failure(
+        &self,
+        username: &str,
+        client_ip: Option<IpAddr>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let mut attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+        // Once per quiet period, or sooner when a spray of new keys fills the map.
+        if attempts.states.len() >= MAX_TRACKED_KEYS
+            || attempts
+                .swept_at
+                .is_none_or(|swept_at| now - swept_at >= self.reset_after)
+        {
+            attempts
+                .states
+                .retain(|_, state| !self.is_expired(state, now));
+            attempts.swept_at = Some(now);
+        }
+        let mut locked = false;
+        for (key, free_attempts) in self.keys(username, client_ip) {
+            let state = attempts.states.entry(key).or_insert(AttemptState {
+                failures: 0,
+                last_failure: now,
+                locked_until: None,
+            });
+            if now - state.last_failure >= self.reset_after {
+                state.failures = 0;
+            }
+            state.failures = state.failures.saturating_add(1);
+            state.last_failure = now;
+            state.locked_until = self
+                .lockout(state.failures, free_attempts)
+                .map(|lockout| now + lockout);
+            locked |= state.locked_until.is_some();
+        }
+        locked
+    }
+
+    /// Only the username is cleared; the IP is left to cool down, so signing in to one
+    /// account does not reset the count for guessing at others.
+    pub fn record_success(&self, username: &str) {
+        let mut attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+        attempts
+            .states
+            .remove(&ThrottleKey::Username(normalise_username(username)));
+    }
+
+    fn is_expired(&self, state: &AttemptState, now: DateTime<Utc>) -> bool {
+        now - state.last_failure >= self.reset_after
+            && state
+                .locked_until
+                .is_none_or(|locked_until| locked_until <= now)
+    }
+
+    fn keys(&self, username: &str, client_ip: Option<IpAddr>) -> Vec<(ThrottleKey, u32)> {
+        let mut keys = vec![(
+            ThrottleKey::Username(normalise_username(username)),
+            self.username_free_attempts,
+        )];
+        if let Some(client_ip) = client_ip {
+            keys.push((
+                ThrottleKey::Ip(client_ip.to_canonical()),
+                self.ip_free_attempts,
+            ));
+        }
+        keys
+    }
+
+    fn lockout(&self, failures: u32, free_attempts: u32) -> Option<TimeDelta> {
+        let doublings = failures.checked_sub(free_attempts)?;
+        let seconds = 1u64
+            .checked_shl(doublings)
+            .map_or(u64::MAX, |factor| {
+                self.backoff_base_seconds.saturating_mul(factor)
+            })
+            .min(self.max_lockout_seconds);
+        Some(TimeDelta::seconds(seconds as i64))
+    }
+}
+
+fn normalise_username(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+static LOGIN_THROTTLE: OnceCell<LoginThrottleService> = OnceCell::const_new();
+
+impl FromContext for LoginThrottleService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let login_throttle_service: Result<&Self, Report<ContextError>> = LOGIN_THROTTLE
+            .get_or_try_init(|| async {
+                let config: ConfigPointer = ctx.inject().await?;
+                Ok(Self::new(&config.login))
+            })
+            .await;
+        Ok(login_throttle_service?.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> LoginThrottleService {
+        LoginThrottleService::new(&LoginConfig {
+            username_free_attempts: 3,
+            ip_free_attempts: 5,
+            backoff_base_seconds: 2,
+            max_lockout_seconds: 60,
+            reset_after_seconds: 3600,
+        })
+    }
+
+    fn ip(ip: &str) -> Option<IpAddr> {
+        Some(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn test_free_attempts_then_exponential_backoff() {
+        let service = service();
+        let now = Utc::now();
+
+        service.record_failure("admin", None, now);
+        service.record_failure("admin", None, now);
+        assert_eq!(service.retry_after("admin", None, now), None);
+
+        service.record_failure("admin", None, now);
+        assert_eq!(
+            service.retry_after("admin", None, now),
+            Some(TimeDelta::seconds(2))
+        );
+        service.record_failure("admin", None, now);
+        assert_eq!(
+            service.retry_after("admin", None, now),
+            Some(TimeDelta::seconds(4))
+        );
+        for _ in 0..40 {
+            service.record_failure("admin", None, now);
+        }
+        assert_eq!(
+            service.retry_after("admin", None, now),
+            Some(TimeDelta::seconds(60))
+        );
+        assert_eq!(
+            service.retry_after("admin", None, now + TimeDelta::seconds(60)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_username_is_case_insensitive() {
+        let service = service();
+        let now = Utc::now();
+        for _ in 0..3 {
+            service.record_failure("Admin", None, now);
+        }
+        assert!(service.retry_after(" admin", None, now).is_some());
+    }
+
+    #[test]
+    fn test_ip_locks_across_usernames() {
+        let service = service();
+        let now = Utc::now();
+        for username in ["a", "b", "c", "d", "e"] {
+            service.record_failure(username, ip("10.0.0.1"), now);
+        }
+        assert!(service.retry_after("f", ip("10.0.0.1"), now).is_some());
+        assert!(service.retry_after("f", ip("10.0.0.2"), now).is_none());
+    }
+
+    #[test]
+    fn test_success_clears_username_but_not_ip() {
+        let service = service();
+        let now = Utc::now();
+        for _ in 0..5 {
+            service.record_failure("admin", ip("10.0.0.1"), now);
+        }
+        service.record_success("admin");
+        assert!(service.retry_after("admin", None, now).is_none());
+        assert!(service.retry_after("admin", ip("10.0.0.1"), now).is_some());
+    }
+
+    #[test]
+    fn test_record_failure_reports_lock() {
+        let service = service();
+        let now = Utc::now();
+        assert!(!service.record_failure("admin", None, now));
+        assert!(!service.record_failure("admin", None, now));
+        assert!(service.record_failure("admin", None, now));
+    }
+
+    #[test]
+    fn test_quiet_keys_are_swept() {
+        let service = service();
+        let now = Utc::now();
+        for username in ["a", "b", "c"] {
+            service.record_failure(username, ip("10.0.0.1"), now);
+        }
+        service.record_failure("d", None, now + TimeDelta::hours(2));
+
+        let attempts = service.attempts.lock().unwrap();
+        assert_eq!(attempts.states.len(), 1);
+        assert!(
+            attempts
+                .states
+                .contains_key(&ThrottleKey::Username("d".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_failures_reset_after_quiet_period() {
+        let service = service();
+        let now = Utc::now();
+        for _ in 0..3 {
+            service.record_failure("admin", None, now);
+        }
+        let later = now + TimeDelta::hours(2);
+        service.record_failure("admin", None, later);
+        assert!(service.retry_after("admin", None, later).is_none());
+    }
+}
@@ -1,3 +1,7 @@
+pub mod api_token_service;
+pub mod login_throttle_service;
+pub mod session_service;
+pub mod two_factor_service;
 pub mod user_check_service;
 pub mod user_login_service;
 pub mod user_manager_service;
@@ -0,0 +1,141 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::user::LOGIN_TOKEN_COOKIE_NAME;
+use crate::user::model::session_model::SessionModel;
+use crate::user::repository::user_repository::UserRepository;
+use chrono::{SubsecRound, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use shared::config::ConfigPointer;
+use shared::config::session::SessionConfig;
+use shared::context::{Context, ContextError, FromContext};
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Not found")]
+    NotFound,
+}
+
+pub struct SessionService {
+    user_repository: UserRepository,
+    session_config: Arc<SessionConfig>,
+    token_cookie: Option<String>,
+    audit_service: AuditService,
+}
+
+impl SessionService {
+    pub fn new(
+        user_repository: UserRepository,
+        session_config: Arc<SessionConfig>,
+        token_cookie: Option<String>,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            user_repository,
+            session_config,
+            token_cookie,
+            audit_service,
+        }
+    }
+
+    /// Only the sessions that would still let the user in.
+    pub async fn list_sessions(&self, user_id: i64) -> Arc<[SessionModel]> {
+        let now = Utc::now().trunc_subsecs(0);
+        self.user_repository
+            .list_sessions(
+                user_id,
+                self.token_cookie.clone(),
+                now,
+                self.session_config
+                    .idle_timeout()
+                    .map(|idle_timeout| now - idle_timeout),
+            )
+            .await
+            .unwrap_or_default()
+    }
+
+    pub async fn revoke_session(
+        &self,
+        user_id: i64,
+        id: i64,
+    ) -> Result<(), Report<SessionServiceError>> {
+        let revoked = self
+            .user_repository
+            .revoke_session(id, user_id)
+            .await
+            .change_context(SessionServiceError::DbError)?;
+        if revoked == 0 {
+            return Err(Report::new(SessionServiceError::NotFound).attach(StatusCode::NOT_FOUND));
+        }
+        self.audit_service
+            .record(
+                AuditAction::SessionRevoke,
+                Some(id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+        Ok(())
+    }
+}
+
+impl FromContext for SessionService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let req = ctx.req_result()?;
+        let config: ConfigPointer = ctx.inject().await?;
+        Ok(Self::new(
+            ctx.inject().await?,
+            config.session.clone(),
+            req.cookie()
+                .get(LOGIN_TOKEN_COOKIE_NAME)
+                .map(|v| v.value_str().to_string()),
+            ctx.inject().await?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mry::Any;
+
+    #[tokio::test]
+    async fn test_list_sessions_without_idle_timeout() {
+        let mut user_repository = UserRepository::new_mock();
+        user_repository
+            .mock_list_sessions(1, Some("hello".to_string()), Any, None)
+            .returns_once(Ok(Arc::new([])));
+
+        let session_service = SessionService::new(
+            user_repository,
+            Arc::new(SessionConfig {
+                idle_timeout_seconds: 0,
+                ..SessionConfig::default()
+            }),
+            Some("hello".to_string()),
+            AuditService::new_mock(),
+        );
+        assert!(session_service.list_sessions(1).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_not_found() {
+        let mut user_repository = UserRepository::new_mock();
+        user_repository
+            .mock_revoke_session(2, 1)
+            .returns_once(Ok(0));
+
+        let session_service = SessionService::new(
+            user_repository,
+            Arc::new(SessionConfig::default()),
+            None,
+            AuditService::new_mock(),
+        );
+        let error = session_service.revoke_session(1, 2).await.unwrap_err();
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::NOT_FOUND);
+    }
+}
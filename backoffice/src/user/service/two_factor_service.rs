@@ -0,0 +1,568 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
+use crate::user::model::two_factor_model::LoginChallengeModel;
+use crate::user::repository::two_factor_repository::TwoFactorRepository;
+use chrono::{DateTime, TimeDelta, Utc};
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use sha2::{Digest, Sha256};
+use shared::config::ConfigPointer;
+use shared::context::{Context, ContextError, FromContext};
+use shared::error::ExtraResultExt;
+use shared::totp::{TotpCipher, TotpSecret};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+pub const LOGIN_CHALLENGE_COOKIE_NAME: &str = "login_challenge";
+pub const LOGIN_CHALLENGE_MINUTES: i64 = 5;
+const LOGIN_CHALLENGE_MAX_ATTEMPTS: i64 = 5;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_HALF_LENGTH: usize = 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TwoFactorServiceError {
+    #[error("Database error")]
+    DbError,
+    #[error("Cipher error")]
+    CipherError,
+    #[error("Two-factor is already enabled")]
+    AlreadyEnabled,
+    #[error("Two-factor is not enabled")]
+    NotEnabled,
+    #[error("Invalid code")]
+    InvalidCode,
+}
+
+/// What an authenticator app needs to be set up.
+pub struct TotpSetup {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+pub enum TwoFactorStatus {
+    Disabled,
+    /// Waiting for the first code, to prove the app was set up.
+    Pending(TotpSetup),
+    Enabled {
+        recovery_codes_left: i64,
+    },
+}
+
+/// Spaces, dashes and case are ignored, as the codes get typed in by hand.
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+fn hash_login_challenge(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = rand::rng()
+                .sample_iter(Alphanumeric)
+                .take(RECOVERY_CODE_HALF_LENGTH * 2)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect();
+            format!(
+                "{}-{}",
+                &code[..RECOVERY_CODE_HALF_LENGTH],
+                &code[RECOVERY_CODE_HALF_LENGTH..]
+            )
+        })
+        .collect()
+}
+
+pub struct TwoFactorService {
+    two_factor_repository: TwoFactorRepository,
+    totp_cipher: Arc<TotpCipher>,
+    issuer: String,
+    audit_service: AuditService,
+}
+
+impl TwoFactorService {
+    pub fn new(
+        two_factor_repository: TwoFactorRepository,
+        totp_cipher: Arc<TotpCipher>,
+        issuer: String,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            two_factor_repository,
+            totp_cipher,
+            issuer,
+            audit_service,
+        }
+    }
+
+    fn setup(&self, secret: &TotpSecret, username: &str) -> TotpSetup {
+        TotpSetup {
+            secret: secret.as_base32(),
+            otpauth_uri: secret.otpauth_uri(&self.issuer, username),
+        }
+    }
+
+    pub async fn status(
+        &self,
+        user_id: i64,
+        username: &str,
+    ) -> Result<TwoFactorStatus, Report<TwoFactorServiceError>> {
+        let totp = self
+            .two_factor_repository
+            .get_totp(user_id)
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        match totp {
+            None => Ok(TwoFactorStatus::Disabled),
+            Some(totp) if totp.enabled => Ok(TwoFactorStatus::Enabled {
+                recovery_codes_left: self
+                    .two_factor_repository
+                    .count_recovery_codes(user_id)
+                    .await
+                    .change_context(TwoFactorServiceError::DbError)?,
+            }),
+            Some(totp) => {
+                let secret = self
+                    .totp_cipher
+                    .decrypt(&totp.secret)
+                    .change_context(TwoFactorServiceError::CipherError)?;
+                Ok(TwoFactorStatus::Pending(self.setup(&secret, username)))
+            }
+        }
+    }
+
+    pub async fn is_enabled(&self, user_id: i64) -> Result<bool, Report<TwoFactorServiceError>> {
+        Ok(self
+            .two_factor_repository
+            .get_totp(user_id)
+            .await
+            .change_context(TwoFactorServiceError::DbError)?
+            .is_some_and(|totp| totp.enabled))
+    }
+
+    /// A fresh secret every time, replacing one that was never confirmed.
+    pub async fn start_setup(
+        &self,
+        user_id: i64,
+        username: &str,
+    ) -> Result<TotpSetup, Report<TwoFactorServiceError>> {
+        if self.is_enabled(user_id).await? {
+            return Err(
+                Report::new(TwoFactorServiceError::AlreadyEnabled).attach(StatusCode::BAD_REQUEST)
+            );
+        }
+        let secret = TotpSecret::generate();
+        let encrypted = self
+            .totp_cipher
+            .encrypt(&secret)
+            .change_context(TwoFactorServiceError::CipherError)?;
+        self.two_factor_repository
+            .save_pending_totp(user_id, encrypted, Utc::now())
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        Ok(self.setup(&secret, username))
+    }
+
+    /// Enables two-factor once the app produces a valid code, returning the recovery codes
+    /// in plain text; only their hashes are kept.
+    pub async fn confirm_setup(
+        &self,
+        user_id: i64,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Arc<[String]>, Report<TwoFactorServiceError>> {
+        let totp = self
+            .two_factor_repository
+            .get_totp(user_id)
+            .await
+            .change_context(TwoFactorServiceError::DbError)?
+            .filter(|totp| !totp.enabled)
+            .ok_or_else(|| {
+                Report::new(TwoFactorServiceError::NotEnabled).attach(StatusCode::BAD_REQUEST)
+            })?;
+        let secret = self
+            .totp_cipher
+            .decrypt(&totp.secret)
+            .change_context(TwoFactorServiceError::CipherError)?;
+        let step = secret
+            .verify(code, now, totp.last_used_step)
+            .ok_or_else(|| Report::new(TwoFactorServiceError::InvalidCode))?;
+
+        let codes = generate_recovery_codes();
+        let enabled = self
+            .two_factor_repository
+            .enable_totp(
+                user_id,
+                step,
+                codes.iter().map(|code| hash_recovery_code(code)).collect(),
+            )
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        if !enabled {
+            return Err(Report::new(TwoFactorServiceError::InvalidCode));
+        }
+        self.audit_service
+            .record(
+                AuditAction::TwoFactorEnable,
+                Some(user_id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+        Ok(codes.into())
+    }
+
+    /// Accepts either a code from the app or an unused recovery code, which is then spent.
+    pub async fn verify_code(
+        &self,
+        user_id: i64,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool, Report<TwoFactorServiceError>> {
+        let Some(totp) = self
+            .two_factor_repository
+            .get_totp(user_id)
+            .await
+            .change_context(TwoFactorServiceError::DbError)?
+            .filter(|totp| totp.enabled)
+        else {
+            return Ok(false);
+        };
+        let secret = self
+            .totp_cipher
+            .decrypt(&totp.secret)
+            .change_context(TwoFactorServiceError::CipherError)?;
+
+        match secret.verify(code, now, totp.last_used_step) {
+            Some(step) => self
+                .two_factor_repository
+                .use_totp_step(user_id, step)
+                .await
+                .change_context(TwoFactorServiceError::DbError),
+            None => self
+                .two_factor_repository
+                .use_recovery_code(user_id, hash_recovery_code(code), now)
+                .await
+                .change_context(TwoFactorServiceError::DbError),
+        }
+    }
+
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: i64,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Arc<[String]>, Report<TwoFactorServiceError>> {
+        if !self.verify_code(user_id, code, now).await? {
+            return Err(Report::new(TwoFactorServiceError::InvalidCode));
+        }
+        let codes = generate_recovery_codes();
+        self.two_factor_repository
+            .replace_recovery_codes(
+                user_id,
+                codes.iter().map(|code| hash_recovery_code(code)).collect(),
+            )
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        self.audit_service
+            .record(
+                AuditAction::TwoFactorRecoveryCodes,
+                Some(user_id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+        Ok(codes.into())
+    }
+
+    pub async fn disable(
+        &self,
+        user_id: i64,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), Report<TwoFactorServiceError>> {
+        if !self.verify_code(user_id, code, now).await? {
+            return Err(Report::new(TwoFactorServiceError::InvalidCode));
+        }
+        self.two_factor_repository
+            .delete_totp(user_id)
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        self.audit_service
+            .record(
+                AuditAction::TwoFactorDisable,
+                Some(user_id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+        Ok(())
+    }
+
+    /// For a root user to let someone back in who lost both their app and recovery codes.
+    /// False when the user never had two-factor set up.
+    pub async fn reset(&self, user_id: i64) -> Result<bool, Report<TwoFactorServiceError>> {
+        let reset = self
+            .two_factor_repository
+            .delete_totp(user_id)
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        if reset {
+            self.audit_service
+                .record(
+                    AuditAction::UserResetTwoFactor,
+                    Some(user_id),
+                    None::<&()>,
+                    None::<&()>,
+                )
+                .await;
+        }
+        Ok(reset)
+    }
+
+    /// Issued once the password checks out, the plain token goes in a cookie.
+    pub async fn start_login_challenge(
+        &self,
+        user_id: i64,
+        username: String,
+        now: DateTime<Utc>,
+    ) -> Result<String, Report<TwoFactorServiceError>> {
+        let token = Uuid::new_v4().to_string();
+        self.two_factor_repository
+            .add_login_challenge(
+                hash_login_challenge(&token),
+                user_id,
+                username,
+                now + TimeDelta::minutes(LOGIN_CHALLENGE_MINUTES),
+                now,
+            )
+            .await
+            .change_context(TwoFactorServiceError::DbError)?;
+        Ok(token)
+    }
+
+    /// `None` once the challenge has expired or used up its attempts, the password then has
+    /// to be entered again.
+    pub async fn login_challenge(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Option<LoginChallengeModel> {
+        self.two_factor_repository
+            .find_login_challenge(hash_login_challenge(token), now)
+            .await
+            .ok()
+            .flatten()
+            .filter(|challenge| challenge.attempts < LOGIN_CHALLENGE_MAX_ATTEMPTS)
+    }
+
+    /// The challenge is spent on success, otherwise the attempt is counted against it.
+    pub async fn complete_login_challenge(
+        &self,
+        token: &str,
+        challenge: &LoginChallengeModel,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let token_hash = hash_login_challenge(token);
+        if let Ok(true) = self.verify_code(challenge.user_id, code, now).await {
+            let _ = self
+                .two_factor_repository
+                .delete_login_challenge(token_hash)
+                .await
+                .log_it();
+            return true;
+        }
+
+        let _ = self
+            .two_factor_repository
+            .add_login_challenge_attempt(token_hash)
+            .await
+            .log_it();
+        false
+    }
+}
+
+static TOTP_CIPHER: OnceCell<Arc<TotpCipher>> = OnceCell::const_new();
+
+impl FromContext for TwoFactorService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let config: ConfigPointer = ctx.inject().await?;
+        let totp_cipher: Result<&Arc<TotpCipher>, Report<ContextError>> = TOTP_CIPHER
+            .get_or_try_init(|| async {
+                let totp_cipher =
+                    TotpCipher::load(&config.totp).change_context(ContextError::ConfigError)?;
+                Ok(Arc::new(totp_cipher))
+            })
+            .await;
+        Ok(Self::new(
+            ctx.inject().await?,
+            totp_cipher?.clone(),
+            config.totp.issuer.clone(),
+            ctx.inject().await?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::model::two_factor_model::TotpModel;
+    use chrono::TimeZone;
+    use mry::Any;
+    use shared::totp::step_at;
+
+    /// Fixed, so a made up code cannot happen to be valid.
+    fn fixed() -> (TotpSecret, DateTime<Utc>) {
+        (
+            TotpSecret::from_bytes(b"12345678901234567890"),
+            Utc.timestamp_opt(1111111109, 0).unwrap(),
+        )
+    }
+
+    fn cipher() -> Arc<TotpCipher> {
+        Arc::new(TotpCipher::new(&[7u8; 32]).unwrap())
+    }
+
+    fn service(two_factor_repository: TwoFactorRepository) -> TwoFactorService {
+        TwoFactorService::new(
+            two_factor_repository,
+            cipher(),
+            "Rusty Shorty".to_string(),
+            AuditService::new_mock(),
+        )
+    }
+
+    fn totp(secret: &TotpSecret, enabled: bool, last_used_step: i64) -> TotpModel {
+        TotpModel {
+            secret: cipher().encrypt(secret).unwrap(),
+            enabled,
+            last_used_step,
+        }
+    }
+
+    #[test]
+    fn test_recovery_code_hash_ignores_formatting() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(codes[0].len(), RECOVERY_CODE_HALF_LENGTH * 2 + 1);
+        assert_eq!(
+            hash_recovery_code("ab12c-d34ef"),
+            hash_recovery_code(" AB12C D34EF ")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_setup_enables_with_valid_code() {
+        let secret = TotpSecret::generate();
+        let now = Utc::now();
+        let step = step_at(now);
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        let pending = totp(&secret, false, 0);
+        two_factor_repository
+            .mock_get_totp(1)
+            .returns_once(Ok(Some(pending)));
+        two_factor_repository
+            .mock_enable_totp(1, step, Any)
+            .returns_once(Ok(true));
+
+        let codes = service(two_factor_repository)
+            .confirm_setup(1, &secret.code_at_step(step), now)
+            .await
+            .unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_setup_rejects_wrong_code() {
+        let (secret, now) = fixed();
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        let pending = totp(&secret, false, 0);
+        two_factor_repository
+            .mock_get_totp(1)
+            .returns_once(Ok(Some(pending)));
+
+        let wrong = secret.code_at_step(step_at(now) + 5);
+        let result = service(two_factor_repository)
+            .confirm_setup(1, &wrong, now)
+            .await;
+        assert!(matches!(
+            result.unwrap_err().current_context(),
+            TwoFactorServiceError::InvalidCode
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_code_falls_back_to_recovery_code() {
+        let secret = TotpSecret::generate();
+        let now = Utc::now();
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        let enabled = totp(&secret, true, 0);
+        two_factor_repository
+            .mock_get_totp(1)
+            .returns_once(Ok(Some(enabled)));
+        two_factor_repository
+            .mock_use_recovery_code(1, hash_recovery_code("ab12c-d34ef"), now)
+            .returns_once(Ok(true));
+
+        let valid = service(two_factor_repository)
+            .verify_code(1, "ab12c-d34ef", now)
+            .await
+            .unwrap();
+        assert!(valid);
+    }
+
+    #[tokio::test]
+    async fn test_complete_login_challenge_counts_failed_attempts() {
+        let (secret, now) = fixed();
+        let token_hash = hash_login_challenge("token");
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        let enabled = totp(&secret, true, 0);
+        two_factor_repository
+            .mock_get_totp(1)
+            .returns_once(Ok(Some(enabled)));
+        two_factor_repository
+            .mock_use_recovery_code(1, Any, now)
+            .returns_once(Ok(false));
+        two_factor_repository
+            .mock_add_login_challenge_attempt(token_hash)
+            .returns_once(Ok(()));
+
+        let challenge = LoginChallengeModel {
+            user_id: 1,
+            username: "hello".to_string(),
+            attempts: 0,
+        };
+        let completed = service(two_factor_repository)
+            .complete_login_challenge("token", &challenge, "000000", now)
+            .await;
+        assert!(!completed);
+    }
+
+    #[tokio::test]
+    async fn test_login_challenge_expires_after_max_attempts() {
+        let now = Utc::now();
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        two_factor_repository
+            .mock_find_login_challenge(hash_login_challenge("token"), now)
+            .returns_once(Ok(Some(LoginChallengeModel {
+                user_id: 1,
+                username: "hello".to_string(),
+                attempts: LOGIN_CHALLENGE_MAX_ATTEMPTS,
+            })));
+
+        let challenge = service(two_factor_repository)
+            .login_challenge("token", now)
+            .await;
+        assert!(challenge.is_none());
+    }
+}
@@ -1,41 +1,110 @@
-use crate::user::LOGIN_TOKEN_COOKIE_NAME;
 use crate::user::model::user_model::UserIdContext;
+use crate::user::repository::api_token_repository::ApiTokenRepository;
 use crate::user::repository::user_repository::UserRepository;
 use crate::user::role::Role;
+use crate::user::service::api_token_service::hash_api_token;
+use crate::user::{AllowApiToken, LOGIN_TOKEN_COOKIE_NAME};
+use chrono::{SubsecRound, TimeDelta, Utc};
 use error_stack::Report;
+use poem::http::header;
+use shared::config::ConfigPointer;
+use shared::config::session::SessionConfig;
 use shared::context::{Context, ContextError, FromContext};
+use std::sync::Arc;
+
+const LAST_USED_RESOLUTION_MINUTES: i64 = 1;
 
 pub struct UserCheckService {
     user_repository: UserRepository,
+    api_token_repository: ApiTokenRepository,
+    session_config: Arc<SessionConfig>,
     token_cookie: Option<String>,
+    bearer_token: Option<String>,
 }
 
 impl UserCheckService {
-    pub fn new(user_repository: UserRepository, token_cookie: Option<String>) -> Self {
+    pub fn new(
+        user_repository: UserRepository,
+        api_token_repository: ApiTokenRepository,
+        session_config: Arc<SessionConfig>,
+        token_cookie: Option<String>,
+        bearer_token: Option<String>,
+    ) -> Self {
         Self {
             user_repository,
+            api_token_repository,
+            session_config,
             token_cookie,
+            bearer_token,
         }
     }
 
-    pub fn get_user_context(&self) -> UserIdContext {
-        if let Some(user_context) = self.is_logged_in() {
+    pub async fn get_user_context(&self) -> UserIdContext {
+        let user_context = match self.bearer_token.as_ref() {
+            Some(bearer_token) => self.is_api_token_valid(bearer_token).await,
+            None => self.is_logged_in().await,
+        };
+        if let Some(user_context) = user_context {
             user_context
         } else {
             UserIdContext {
                 id: 0,
                 username: "visitor".to_string(),
                 role: Role::Visitor,
+                read_only: true,
+                must_change_password: false,
             }
         }
     }
 
-    fn is_logged_in(&self) -> Option<UserIdContext> {
-        if let Some(token) = self.token_cookie.as_ref() {
-            self.user_repository.find_by_token(token.to_string()).ok()
-        } else {
-            None
-        }
+    async fn is_logged_in(&self) -> Option<UserIdContext> {
+        let token = self.token_cookie.as_ref()?;
+        let now = Utc::now().trunc_subsecs(0);
+        let user_context = self
+            .user_repository
+            .find_by_token(
+                token.to_string(),
+                now,
+                self.session_config
+                    .idle_timeout()
+                    .map(|idle_timeout| now - idle_timeout),
+            )
+            .await
+            .ok()?;
+        let _ = self
+            .user_repository
+            .touch_token(
+                token.to_string(),
+                now,
+                now - TimeDelta::minutes(LAST_USED_RESOLUTION_MINUTES),
+            )
+            .await;
+        Some(user_context)
+    }
+
+    async fn is_api_token_valid(&self, bearer_token: &str) -> Option<UserIdContext> {
+        let now = Utc::now().trunc_subsecs(0);
+        let token_user = self
+            .api_token_repository
+            .find_by_api_token(hash_api_token(bearer_token), now)
+            .await
+            .ok()
+            .flatten()?;
+        let _ = self
+            .api_token_repository
+            .touch_api_token(
+                token_user.token_id,
+                now,
+                now - TimeDelta::minutes(LAST_USED_RESOLUTION_MINUTES),
+            )
+            .await;
+        Some(UserIdContext {
+            id: token_user.id,
+            username: token_user.username,
+            role: token_user.scope.effective_role(token_user.role),
+            read_only: token_user.scope.is_read_only(),
+            must_change_password: false,
+        })
     }
 }
 
@@ -43,11 +112,21 @@ impl FromContext for UserCheckService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
         let req = ctx.req_result()?;
         let cookie = req.cookie();
+        let bearer_token = req
+            .data::<AllowApiToken>()
+            .and_then(|_| req.headers().get(header::AUTHORIZATION))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|value| value.trim().to_string());
+        let config: ConfigPointer = ctx.inject().await?;
         Ok(Self::new(
             ctx.inject().await?,
+            ctx.inject().await?,
+            config.session.clone(),
             cookie
                 .get(LOGIN_TOKEN_COOKIE_NAME)
                 .map(|v| v.value_str().to_string()),
+            bearer_token,
         ))
     }
 }
@@ -55,35 +134,103 @@ impl FromContext for UserCheckService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::user::model::api_token_model::{ApiTokenScope, ApiTokenUserModel};
     use crate::user::repository::user_repository::UserRepositoryError;
+    use mry::Any;
 
-    #[test]
-    fn test_get_user_context_user() {
+    #[tokio::test]
+    async fn test_get_user_context_user() {
         let mut user_repository = UserRepository::new_mock();
 
         user_repository
-            .mock_find_by_token("hello".to_string())
+            .mock_find_by_token("hello".to_string(), Any, Any)
             .returns_once(Ok(UserIdContext {
                 id: 5,
                 username: "".to_string(),
                 role: Default::default(),
+                read_only: false,
+                must_change_password: false,
             }));
+        user_repository
+            .mock_touch_token("hello".to_string(), Any, Any)
+            .returns_once(Ok(()));
 
-        let service = UserCheckService::new(user_repository, Some("hello".to_string()));
-        let result = service.get_user_context();
+        let service = UserCheckService::new(
+            user_repository,
+            ApiTokenRepository::new_mock(),
+            Arc::new(SessionConfig::default()),
+            Some("hello".to_string()),
+            None,
+        );
+        let result = service.get_user_context().await;
         assert_eq!(result.id, 5);
     }
 
-    #[test]
-    fn test_get_user_context_visitor() {
+    #[tokio::test]
+    async fn test_get_user_context_visitor() {
         let mut user_repository = UserRepository::new_mock();
 
         user_repository
-            .mock_find_by_token("hello".to_string())
+            .mock_find_by_token("hello".to_string(), Any, Any)
             .returns_once(Err(Report::new(UserRepositoryError::QueryError)));
 
-        let service = UserCheckService::new(user_repository, Some("hello".to_string()));
-        let result = service.get_user_context();
+        let service = UserCheckService::new(
+            user_repository,
+            ApiTokenRepository::new_mock(),
+            Arc::new(SessionConfig::default()),
+            Some("hello".to_string()),
+            None,
+        );
+        let result = service.get_user_context().await;
         assert_eq!(result.id, 0);
     }
+
+    #[tokio::test]
+    async fn test_get_user_context_api_token() {
+        let mut api_token_repository = ApiTokenRepository::new_mock();
+
+        api_token_repository
+            .mock_find_by_api_token(hash_api_token("secret"), Any)
+            .returns_once(Ok(Some(ApiTokenUserModel {
+                token_id: 3,
+                id: 5,
+                username: "bot".to_string(),
+                role: Role::Root,
+                scope: ApiTokenScope::Read,
+            })));
+        api_token_repository
+            .mock_touch_api_token(3, Any, Any)
+            .returns_once(Ok(()));
+
+        let service = UserCheckService::new(
+            UserRepository::new_mock(),
+            api_token_repository,
+            Arc::new(SessionConfig::default()),
+            None,
+            Some("secret".to_string()),
+        );
+        let result = service.get_user_context().await;
+        assert_eq!(result.id, 5);
+        assert_eq!(result.role, Role::User);
+        assert!(result.read_only);
+    }
+
+    #[tokio::test]
+    async fn test_get_user_context_api_token_unknown() {
+        let mut api_token_repository = ApiTokenRepository::new_mock();
+
+        api_token_repository
+            .mock_find_by_api_token(hash_api_token("secret"), Any)
+            .returns_once(Ok(None));
+
+        let service = UserCheckService::new(
+            UserRepository::new_mock(),
+            api_token_repository,
+            Arc::new(SessionConfig::default()),
+            Some("hello".to_string()),
+            Some("secret".to_string()),
+        );
+        let result = service.get_user_context().await;
+        assert_eq!(result.role, Role::Visitor);
+    }
 }
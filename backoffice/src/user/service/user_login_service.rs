@@ -1,13 +1,45 @@
 use crate::user::LOGIN_TOKEN_COOKIE_NAME;
 use crate::user::layer::password_layer::PasswordLayer;
+use crate::user::repository::login_failure_repository::LoginFailureRepository;
 use crate::user::repository::user_repository::UserRepository;
+use crate::user::service::login_throttle_service::LoginThrottleService;
+use crate::user::service::two_factor_service::TwoFactorService;
+use chrono::{DateTime, SubsecRound, TimeDelta, Utc};
 use error_stack::Report;
+use shared::config::ConfigPointer;
+use shared::config::session::SessionConfig;
 use shared::context::{Context, ContextError, FromContext};
+use shared::error::ExtraResultExt;
+use std::net::IpAddr;
+use std::sync::Arc;
 use uuid::Uuid;
 
+const LOGIN_FAILURE_RETENTION_DAYS: i64 = 30;
+const USER_AGENT_MAX_LENGTH: usize = 512;
+
+pub enum LoginResult {
+    Success(String),
+    /// The password was right, the token is for the challenge that asks for the code.
+    SecondFactor(String),
+    Failed,
+    /// Too many failures, the password was not checked.
+    Throttled(TimeDelta),
+    /// The challenge is gone, the password has to be entered again.
+    ChallengeExpired,
+}
+
+/// Rounded up, so a client that waits this long is let through.
+pub fn retry_after_seconds(retry_after: TimeDelta) -> i64 {
+    (retry_after.num_milliseconds() + 999) / 1000
+}
+
 pub struct UserLoginService {
     user_repository: UserRepository,
     password_layer: PasswordLayer,
+    login_throttle_service: LoginThrottleService,
+    login_failure_repository: LoginFailureRepository,
+    two_factor_service: TwoFactorService,
+    session_config: Arc<SessionConfig>,
     token_cookie: Option<String>,
 }
 
@@ -15,42 +47,180 @@ impl UserLoginService {
     pub fn new(
         user_repository: UserRepository,
         password_layer: PasswordLayer,
+        login_throttle_service: LoginThrottleService,
+        login_failure_repository: LoginFailureRepository,
+        two_factor_service: TwoFactorService,
+        session_config: Arc<SessionConfig>,
         token_cookie: Option<String>,
     ) -> Self {
         Self {
             user_repository,
             password_layer,
+            login_throttle_service,
+            login_failure_repository,
+            two_factor_service,
+            session_config,
             token_cookie,
         }
     }
 
-    pub fn validate_login(&self, username: String, password: String) -> Option<String> {
-        if let Ok(id_password) = self.user_repository.get_user_password(username) {
+    /// For the login cookie, which should not outlive the session.
+    pub fn session_lifetime(&self) -> TimeDelta {
+        self.session_config.lifetime()
+    }
+
+    pub async fn validate_login(
+        &self,
+        username: String,
+        password: String,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> LoginResult {
+        let now = Utc::now();
+        if let Some(retry_after) = self
+            .login_throttle_service
+            .retry_after(&username, client_ip, now)
+        {
+            // Already rejected, so no write for it; the failure that locked it is on record.
+            return LoginResult::Throttled(retry_after);
+        }
+
+        if let Ok(id_password) = self
+            .user_repository
+            .get_user_password(username.clone())
+            .await
+        {
             let password_status = self
                 .password_layer
                 .verify_password(id_password.password, password.as_str());
-            if let Ok(password_state) = password_status {
-                if password_state.is_valid() {
-                    let uuid = Uuid::new_v4().to_string();
-
-                    if self
-                        .user_repository
-                        .add_token(uuid.clone(), id_password.id)
-                        .is_err()
-                    {
-                        return None;
+            if let Ok(password_state) = password_status
+                && password_state.is_valid()
+            {
+                // The throttle is only cleared once the second factor is through as well,
+                // so knowing the password does not buy unlimited guesses at the code.
+                return match self.two_factor_service.is_enabled(id_password.id).await {
+                    Ok(false) => {
+                        self.login_throttle_service.record_success(&username);
+                        self.add_token(id_password.id, client_ip, user_agent, now)
+                            .await
                     }
-
-                    return Some(uuid);
-                }
+                    Ok(true) => match self
+                        .two_factor_service
+                        .start_login_challenge(id_password.id, username, now)
+                        .await
+                    {
+                        Ok(challenge) => LoginResult::SecondFactor(challenge),
+                        Err(_) => LoginResult::Failed,
+                    },
+                    Err(_) => LoginResult::Failed,
+                };
             }
         }
-        None
+
+        let locked = self
+            .login_throttle_service
+            .record_failure(&username, client_ip, now);
+        self.add_login_failure(username, client_ip, locked, now)
+            .await;
+        LoginResult::Failed
     }
 
-    pub fn logout(&self) -> bool {
+    /// Takes either a code from the authenticator app or a recovery code.
+    pub async fn validate_second_factor(
+        &self,
+        challenge_token: String,
+        code: String,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> LoginResult {
+        let now = Utc::now();
+        let Some(challenge) = self
+            .two_factor_service
+            .login_challenge(&challenge_token, now)
+            .await
+        else {
+            return LoginResult::ChallengeExpired;
+        };
+        if let Some(retry_after) =
+            self.login_throttle_service
+                .retry_after(&challenge.username, client_ip, now)
+        {
+            return LoginResult::Throttled(retry_after);
+        }
+
+        if self
+            .two_factor_service
+            .complete_login_challenge(&challenge_token, &challenge, &code, now)
+            .await
+        {
+            self.login_throttle_service
+                .record_success(&challenge.username);
+            return self
+                .add_token(challenge.user_id, client_ip, user_agent, now)
+                .await;
+        }
+
+        let locked =
+            self.login_throttle_service
+                .record_failure(&challenge.username, client_ip, now);
+        self.add_login_failure(challenge.username, client_ip, locked, now)
+            .await;
+        LoginResult::Failed
+    }
+
+    async fn add_token(
+        &self,
+        user_id: i64,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+        now: DateTime<Utc>,
+    ) -> LoginResult {
+        let uuid = Uuid::new_v4().to_string();
+        let now = now.trunc_subsecs(0);
+        match self
+            .user_repository
+            .add_token(
+                uuid.clone(),
+                user_id,
+                client_ip.map(|client_ip| client_ip.to_canonical().to_string()),
+                user_agent
+                    .map(|user_agent| user_agent.chars().take(USER_AGENT_MAX_LENGTH).collect()),
+                now,
+                now + self.session_config.lifetime(),
+            )
+            .await
+        {
+            Ok(()) => LoginResult::Success(uuid),
+            Err(_) => LoginResult::Failed,
+        }
+    }
+
+    async fn add_login_failure(
+        &self,
+        username: String,
+        client_ip: Option<IpAddr>,
+        throttled: bool,
+        now: DateTime<Utc>,
+    ) {
+        let _ = self
+            .login_failure_repository
+            .add_login_failure(
+                username,
+                client_ip.map(|client_ip| client_ip.to_canonical().to_string()),
+                throttled,
+                now,
+                now - TimeDelta::days(LOGIN_FAILURE_RETENTION_DAYS),
+            )
+            .await
+            .log_it();
+    }
+
+    pub async fn logout(&self) -> bool {
         if let Some(token) = self.token_cookie.as_ref() {
-            self.user_repository.delete_token(token.to_string()).is_ok()
+            self.user_repository
+                .delete_token(token.to_string())
+                .await
+                .is_ok()
         } else {
             false
         }
@@ -61,9 +231,14 @@ impl FromContext for UserLoginService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
         let req = ctx.req_result()?;
         let cookie = req.cookie();
+        let config: ConfigPointer = ctx.inject().await?;
         Ok(Self::new(
             ctx.inject().await?,
             ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+            config.session.clone(),
             cookie
                 .get(LOGIN_TOKEN_COOKIE_NAME)
                 .map(|v| v.value_str().to_string()),
@@ -74,13 +249,80 @@ impl FromContext for UserLoginService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audit::service::audit_service::AuditService;
+    use crate::user::model::two_factor_model::{LoginChallengeModel, TotpModel};
     use crate::user::model::user_model::IdPassword;
+    use crate::user::repository::two_factor_repository::TwoFactorRepository;
     use crate::user::repository::user_repository::UserRepositoryError;
     use mry::Any;
+    use shared::config::login::LoginConfig;
     use shared::password::PasswordState;
+    use shared::totp::{TotpCipher, TotpSecret, step_at};
 
-    #[test]
-    fn test_validate_login_success() {
+    fn cipher() -> TotpCipher {
+        TotpCipher::new(&[7u8; 32]).unwrap()
+    }
+
+    fn service(
+        user_repository: UserRepository,
+        password_layer: PasswordLayer,
+        login_failure_repository: LoginFailureRepository,
+        two_factor_repository: TwoFactorRepository,
+        token_cookie: Option<String>,
+    ) -> UserLoginService {
+        UserLoginService::new(
+            user_repository,
+            password_layer,
+            LoginThrottleService::new(&LoginConfig::default()),
+            login_failure_repository,
+            TwoFactorService::new(
+                two_factor_repository,
+                Arc::new(cipher()),
+                "Rusty Shorty".to_string(),
+                AuditService::new_mock(),
+            ),
+            Arc::new(SessionConfig::default()),
+            token_cookie,
+        )
+    }
+
+    fn two_factor(secret: Option<&TotpSecret>) -> TwoFactorRepository {
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        let secret = secret.map(|secret| cipher().encrypt(secret).unwrap());
+        two_factor_repository
+            .mock_get_totp(1)
+            .returns_with(move |_| {
+                Ok(secret.clone().map(|secret| TotpModel {
+                    secret,
+                    enabled: true,
+                    last_used_step: 0,
+                }))
+            });
+        two_factor_repository
+    }
+
+    fn challenge(two_factor_repository: &mut TwoFactorRepository) {
+        two_factor_repository
+            .mock_find_login_challenge(Any, Any)
+            .returns_with(|_, _| {
+                Ok(Some(LoginChallengeModel {
+                    user_id: 1,
+                    username: "hello".to_string(),
+                    attempts: 0,
+                }))
+            });
+    }
+
+    fn failure_recorded(throttled: bool) -> LoginFailureRepository {
+        let mut login_failure_repository = LoginFailureRepository::new_mock();
+        login_failure_repository
+            .mock_add_login_failure("hello".to_string(), Any, throttled, Any, Any)
+            .returns_with(|_, _, _, _, _| Ok(()));
+        login_failure_repository
+    }
+
+    #[tokio::test]
+    async fn test_validate_login_success() {
         let mut user_repository = UserRepository::new_mock();
         let mut password_layer = PasswordLayer::new_mock();
 
@@ -95,15 +337,37 @@ mod tests {
             .mock_verify_password(Any, "password")
             .returns_once(Ok(PasswordState::Valid));
 
-        user_repository.mock_add_token(Any, 1).returns_once(Ok(()));
+        user_repository
+            .mock_add_token(
+                Any,
+                1,
+                Some("127.0.0.1".to_string()),
+                Some("curl/8.0".to_string()),
+                Any,
+                Any,
+            )
+            .returns_once(Ok(()));
 
-        let service = UserLoginService::new(user_repository, password_layer, None);
-        let str = service.validate_login("hello".to_string(), "password".to_string());
-        assert!(str.is_some());
+        let service = service(
+            user_repository,
+            password_layer,
+            LoginFailureRepository::new_mock(),
+            two_factor(None),
+            None,
+        );
+        let result = service
+            .validate_login(
+                "hello".to_string(),
+                "password".to_string(),
+                Some("::ffff:127.0.0.1".parse().unwrap()),
+                Some("curl/8.0".to_string()),
+            )
+            .await;
+        assert!(matches!(result, LoginResult::Success(_)));
     }
 
-    #[test]
-    fn test_validate_login_username_error() {
+    #[tokio::test]
+    async fn test_validate_login_username_error() {
         let mut user_repository = UserRepository::new_mock();
         let password_layer = PasswordLayer::new_mock();
 
@@ -111,13 +375,21 @@ mod tests {
             .mock_get_user_password("hello".to_string())
             .returns_once(Err(Report::new(UserRepositoryError::QueryError)));
 
-        let service = UserLoginService::new(user_repository, password_layer, None);
-        let str = service.validate_login("hello".to_string(), "password".to_string());
-        assert!(str.is_none());
+        let service = service(
+            user_repository,
+            password_layer,
+            failure_recorded(false),
+            two_factor(None),
+            None,
+        );
+        let result = service
+            .validate_login("hello".to_string(), "password".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::Failed));
     }
 
-    #[test]
-    fn test_validate_login_password_verify_fail() {
+    #[tokio::test]
+    async fn test_validate_login_password_verify_fail() {
         let mut user_repository = UserRepository::new_mock();
         let mut password_layer = PasswordLayer::new_mock();
 
@@ -132,13 +404,21 @@ mod tests {
             .mock_verify_password(Any, "password")
             .returns_once(Ok(PasswordState::Invalid));
 
-        let service = UserLoginService::new(user_repository, password_layer, None);
-        let str = service.validate_login("hello".to_string(), "password".to_string());
-        assert!(str.is_none());
+        let service = service(
+            user_repository,
+            password_layer,
+            failure_recorded(false),
+            two_factor(None),
+            None,
+        );
+        let result = service
+            .validate_login("hello".to_string(), "password".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::Failed));
     }
 
-    #[test]
-    fn test_validate_login_add_token_fail() {
+    #[tokio::test]
+    async fn test_validate_login_add_token_fail() {
         let mut user_repository = UserRepository::new_mock();
         let mut password_layer = PasswordLayer::new_mock();
 
@@ -154,16 +434,24 @@ mod tests {
             .returns_once(Ok(PasswordState::Valid));
 
         user_repository
-            .mock_add_token(Any, 1)
+            .mock_add_token(Any, 1, Any, Any, Any, Any)
             .returns_once(Err(Report::new(UserRepositoryError::QueryError)));
 
-        let service = UserLoginService::new(user_repository, password_layer, None);
-        let str = service.validate_login("hello".to_string(), "password".to_string());
-        assert!(str.is_none());
+        let service = service(
+            user_repository,
+            password_layer,
+            failure_recorded(false),
+            two_factor(None),
+            None,
+        );
+        let result = service
+            .validate_login("hello".to_string(), "password".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::Failed));
     }
 
-    #[test]
-    fn test_logout_success() {
+    #[tokio::test]
+    async fn test_logout_success() {
         let mut user_repository = UserRepository::new_mock();
         let password_layer = PasswordLayer::new_mock();
 
@@ -171,14 +459,19 @@ mod tests {
             .mock_delete_token("hello".to_string())
             .returns_once(Ok(()));
 
-        let service =
-            UserLoginService::new(user_repository, password_layer, Some("hello".to_string()));
-        let result = service.logout();
-        assert_eq!(result, true);
+        let service = service(
+            user_repository,
+            password_layer,
+            LoginFailureRepository::new_mock(),
+            two_factor(None),
+            Some("hello".to_string()),
+        );
+        let result = service.logout().await;
+        assert!(result);
     }
 
-    #[test]
-    fn test_logout_fail() {
+    #[tokio::test]
+    async fn test_logout_fail() {
         let mut user_repository = UserRepository::new_mock();
         let password_layer = PasswordLayer::new_mock();
 
@@ -186,9 +479,172 @@ mod tests {
             .mock_delete_token("hello".to_string())
             .returns_once(Err(Report::new(UserRepositoryError::QueryError)));
 
-        let service =
-            UserLoginService::new(user_repository, password_layer, Some("hello".to_string()));
-        let result = service.logout();
-        assert_eq!(result, false);
+        let service = service(
+            user_repository,
+            password_layer,
+            LoginFailureRepository::new_mock(),
+            two_factor(None),
+            Some("hello".to_string()),
+        );
+        let result = service.logout().await;
+        assert!(!result);
+    }
+
+    #[tokio::test]
+    async fn test_validate_login_throttled_skips_password_check() {
+        let mut user_repository = UserRepository::new_mock();
+        let mut password_layer = PasswordLayer::new_mock();
+
+        user_repository
+            .mock_get_user_password("hello".to_string())
+            .returns_with(|_| {
+                Ok(IdPassword {
+                    id: 1,
+                    password: Default::default(),
+                })
+            });
+        password_layer
+            .mock_verify_password(Any, "password")
+            .returns_with(|_, _| Ok(PasswordState::Invalid));
+
+        let mut login_failure_repository = failure_recorded(false);
+        login_failure_repository
+            .mock_add_login_failure("hello".to_string(), Any, true, Any, Any)
+            .returns_once(Ok(()));
+
+        let mut service = service(
+            user_repository,
+            password_layer,
+            login_failure_repository,
+            two_factor(None),
+            None,
+        );
+        for _ in 0..LoginConfig::default().username_free_attempts {
+            let result = service
+                .validate_login("hello".to_string(), "password".to_string(), None, None)
+                .await;
+            assert!(matches!(result, LoginResult::Failed));
+        }
+        let result = service
+            .validate_login("hello".to_string(), "password".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::Throttled(_)));
+        service
+            .password_layer
+            .mock_verify_password(Any, "password")
+            .assert_called(LoginConfig::default().username_free_attempts as usize);
+        // Only the failure that locked it is written, not the rejected attempt.
+        service
+            .login_failure_repository
+            .mock_add_login_failure("hello".to_string(), Any, true, Any, Any)
+            .assert_called(1);
+    }
+
+    #[tokio::test]
+    async fn test_validate_login_with_two_factor_starts_challenge() {
+        let mut user_repository = UserRepository::new_mock();
+        let mut password_layer = PasswordLayer::new_mock();
+
+        user_repository
+            .mock_get_user_password("hello".to_string())
+            .returns_once(Ok(IdPassword {
+                id: 1,
+                password: Default::default(),
+            }));
+        password_layer
+            .mock_verify_password(Any, "password")
+            .returns_once(Ok(PasswordState::Valid));
+
+        let mut two_factor_repository = two_factor(Some(&TotpSecret::generate()));
+        two_factor_repository
+            .mock_add_login_challenge(Any, 1, "hello".to_string(), Any, Any)
+            .returns_once(Ok(()));
+
+        let service = service(
+            user_repository,
+            password_layer,
+            LoginFailureRepository::new_mock(),
+            two_factor_repository,
+            None,
+        );
+        let result = service
+            .validate_login("hello".to_string(), "password".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::SecondFactor(_)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_second_factor_success() {
+        let mut user_repository = UserRepository::new_mock();
+        user_repository
+            .mock_add_token(Any, 1, Any, Any, Any, Any)
+            .returns_once(Ok(()));
+
+        let secret = TotpSecret::generate();
+        let mut two_factor_repository = two_factor(Some(&secret));
+        challenge(&mut two_factor_repository);
+        two_factor_repository
+            .mock_use_totp_step(1, Any)
+            .returns_once(Ok(true));
+        two_factor_repository
+            .mock_delete_login_challenge(Any)
+            .returns_once(Ok(()));
+
+        let service = service(
+            user_repository,
+            PasswordLayer::new_mock(),
+            LoginFailureRepository::new_mock(),
+            two_factor_repository,
+            None,
+        );
+        let code = secret.code_at_step(step_at(Utc::now()));
+        let result = service
+            .validate_second_factor("challenge".to_string(), code, None, None)
+            .await;
+        assert!(matches!(result, LoginResult::Success(_)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_second_factor_wrong_code_is_a_failure() {
+        let mut two_factor_repository = two_factor(Some(&TotpSecret::generate()));
+        challenge(&mut two_factor_repository);
+        two_factor_repository
+            .mock_use_recovery_code(1, Any, Any)
+            .returns_once(Ok(false));
+        two_factor_repository
+            .mock_add_login_challenge_attempt(Any)
+            .returns_once(Ok(()));
+
+        let service = service(
+            UserRepository::new_mock(),
+            PasswordLayer::new_mock(),
+            failure_recorded(false),
+            two_factor_repository,
+            None,
+        );
+        let result = service
+            .validate_second_factor("challenge".to_string(), "wrong".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::Failed));
+    }
+
+    #[tokio::test]
+    async fn test_validate_second_factor_expired() {
+        let mut two_factor_repository = TwoFactorRepository::new_mock();
+        two_factor_repository
+            .mock_find_login_challenge(Any, Any)
+            .returns_once(Ok(None));
+
+        let service = service(
+            UserRepository::new_mock(),
+            PasswordLayer::new_mock(),
+            LoginFailureRepository::new_mock(),
+            two_factor_repository,
+            None,
+        );
+        let result = service
+            .validate_second_factor("challenge".to_string(), "123456".to_string(), None, None)
+            .await;
+        assert!(matches!(result, LoginResult::ChallengeExpired));
     }
 }
@@ -1,5 +1,8 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
 use crate::user::form::add_user::AddUserValidated;
 use crate::user::layer::password_layer::PasswordLayer;
+use crate::user::model::user_manager_model::FetchUser;
 use crate::user::repository::user_manager_repository::UserManagerRepository;
 use cjtoolkit_structured_validator::types::username::IsUsernameTakenAsync;
 use error_stack::{Report, ResultExt};
@@ -22,24 +25,28 @@ pub enum AddUserServiceError {
 pub struct AddUserService {
     user_manager_repository: UserManagerRepository,
     password_layer: PasswordLayer,
+    audit_service: AuditService,
 }
 
 impl AddUserService {
     pub fn new(
         user_manager_repository: UserManagerRepository,
         password_layer: PasswordLayer,
+        audit_service: AuditService,
     ) -> Self {
         Self {
             user_manager_repository,
             password_layer,
+            audit_service,
         }
     }
 
-    pub fn add_user_submit(
+    pub async fn add_user_submit(
         &self,
         add_user_validated: &AddUserValidated,
     ) -> Result<(), Report<AddUserServiceError>> {
-        self.user_manager_repository
+        let id = self
+            .user_manager_repository
             .add_user(
                 add_user_validated.username.as_str().to_string(),
                 self.hash_password(add_user_validated.password.as_str())?
@@ -49,7 +56,19 @@ impl AddUserService {
                     .attach(StatusCode::INTERNAL_SERVER_ERROR)?,
                 &add_user_validated.role,
             )
+            .await
             .change_context(AddUserServiceError::SubmitFailed)?;
+        self.audit_service
+            .record(
+                AuditAction::UserAdd,
+                Some(id),
+                None::<&FetchUser>,
+                Some(&FetchUser {
+                    username: add_user_validated.username.as_str().to_string(),
+                    role: add_user_validated.role.clone(),
+                }),
+            )
+            .await;
         Ok(())
     }
 
@@ -65,6 +84,7 @@ impl IsUsernameTakenAsync for AddUserService {
     async fn is_username_taken_async(&self, username: &str) -> bool {
         self.user_manager_repository
             .username_taken(username.to_string())
+            .await
             .ok()
             .unwrap_or_default()
     }
@@ -72,7 +92,11 @@ impl IsUsernameTakenAsync for AddUserService {
 
 impl FromContext for AddUserService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+        ))
     }
 }
 
@@ -83,8 +107,8 @@ mod tests {
     use mry::Any;
     use shared::password::PasswordError;
 
-    #[test]
-    fn test_add_user_success() {
+    #[tokio::test]
+    async fn test_add_user_success() {
         let add_user_validated = AddUserValidated::new_test_data();
 
         let mut user_manager_repository = UserManagerRepository::new_mock();
@@ -102,15 +126,19 @@ mod tests {
                 Any,
                 add_user_validated.role.clone(),
             )
-            .returns_once(Ok(()));
+            .returns_once(Ok(2));
 
-        let service = AddUserService::new(user_manager_repository, password_layer);
-        let result = service.add_user_submit(&add_user_validated);
+        let service = AddUserService::new(
+            user_manager_repository,
+            password_layer,
+            AuditService::new_mock(),
+        );
+        let result = service.add_user_submit(&add_user_validated).await;
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_add_user_hash_fail() {
+    #[tokio::test]
+    async fn test_add_user_hash_fail() {
         let add_user_validated = AddUserValidated::new_test_data();
 
         let user_manager_repository = UserManagerRepository::new_mock();
@@ -120,13 +148,17 @@ mod tests {
             .mock_hash_password(add_user_validated.password.as_str())
             .returns_once(Err(Report::new(PasswordError("Failed".to_string()))));
 
-        let service = AddUserService::new(user_manager_repository, password_layer);
-        let result = service.add_user_submit(&add_user_validated);
+        let service = AddUserService::new(
+            user_manager_repository,
+            password_layer,
+            AuditService::new_mock(),
+        );
+        let result = service.add_user_submit(&add_user_validated).await;
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_add_user_db_fail() {
+    #[tokio::test]
+    async fn test_add_user_db_fail() {
         let add_user_validated = AddUserValidated::new_test_data();
 
         let mut user_manager_repository = UserManagerRepository::new_mock();
@@ -146,8 +178,12 @@ mod tests {
             )
             .returns_once(Err(Report::new(UserManagerRepositoryError::QueryError)));
 
-        let service = AddUserService::new(user_manager_repository, password_layer);
-        let result = service.add_user_submit(&add_user_validated);
+        let service = AddUserService::new(
+            user_manager_repository,
+            password_layer,
+            AuditService::new_mock(),
+        );
+        let result = service.add_user_submit(&add_user_validated).await;
         assert!(result.is_err());
     }
 }
@@ -1,3 +1,5 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
 use crate::user::form::edit_password_manager::EditPasswordManagerValidated;
 use crate::user::layer::password_layer::PasswordLayer;
 use crate::user::model::user_manager_model::FetchUser;
@@ -24,20 +26,23 @@ pub enum EditPasswordServiceError {
 pub struct EditPasswordService {
     user_manager_repository: UserManagerRepository,
     password_layer: PasswordLayer,
+    audit_service: AuditService,
 }
 
 impl EditPasswordService {
     pub fn new(
         user_manager_repository: UserManagerRepository,
         password_layer: PasswordLayer,
+        audit_service: AuditService,
     ) -> Self {
         Self {
             user_manager_repository,
             password_layer,
+            audit_service,
         }
     }
 
-    pub fn edit_password_submit(
+    pub async fn edit_password_submit(
         &self,
         user_id: i64,
         password: &EditPasswordManagerValidated,
@@ -51,14 +56,48 @@ impl EditPasswordService {
                     .log_it()
                     .attach(StatusCode::INTERNAL_SERVER_ERROR)?,
             )
+            .await
             .change_context(EditPasswordServiceError::DbError)?;
+        // Only that it changed, never the hash.
+        self.audit_service
+            .record(
+                AuditAction::UserPassword,
+                Some(user_id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
 
         Ok(())
     }
 
-    pub fn fetch_user(&self, user_id: i64) -> Result<FetchUser, Report<EditPasswordServiceError>> {
+    /// A missing password never matches, so the hash is not fetched for it.
+    pub async fn verify_current_password(
+        &self,
+        user_id: i64,
+        current_password: &str,
+    ) -> Result<bool, Report<EditPasswordServiceError>> {
+        if current_password.is_empty() {
+            return Ok(false);
+        }
+        let fetch_password = self
+            .user_manager_repository
+            .fetch_password(user_id)
+            .await
+            .change_context(EditPasswordServiceError::DbError)?;
+        Ok(self
+            .password_layer
+            .verify_password(fetch_password.password, current_password)
+            .is_ok_and(|password_state| password_state.is_valid()))
+    }
+
+    pub async fn fetch_user(
+        &self,
+        user_id: i64,
+    ) -> Result<FetchUser, Report<EditPasswordServiceError>> {
         self.user_manager_repository
             .fetch_user(user_id)
+            .await
             .change_context(EditPasswordServiceError::UserNotFound)?
             .ok_or_else(|| {
                 Report::new(EditPasswordServiceError::UserNotFound).attach(StatusCode::NOT_FOUND)
@@ -75,7 +114,11 @@ impl EditPasswordService {
 
 impl FromContext for EditPasswordService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
+        Ok(Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            ctx.inject().await?,
+        ))
     }
 }
 
@@ -89,8 +132,8 @@ mod tests {
         use mry::Any;
         use shared::password::PasswordError;
 
-        #[test]
-        fn test_submit_success() {
+        #[tokio::test]
+        async fn test_submit_success() {
             let password = EditPasswordManagerValidated::new_test_data();
             let mut user_manager_repository = UserManagerRepository::new_mock();
             let mut password_layer = PasswordLayer::new_mock();
@@ -105,13 +148,17 @@ mod tests {
                 .mock_edit_password(1, Any)
                 .returns_once(Ok(()));
 
-            let service = EditPasswordService::new(user_manager_repository, password_layer);
-            let result = service.edit_password_submit(1, &password);
+            let service = EditPasswordService::new(
+                user_manager_repository,
+                password_layer,
+                AuditService::new_mock(),
+            );
+            let result = service.edit_password_submit(1, &password).await;
             assert!(result.is_ok());
         }
 
-        #[test]
-        fn test_password_hash_fail() {
+        #[tokio::test]
+        async fn test_password_hash_fail() {
             let password = EditPasswordManagerValidated::new_test_data();
             let user_manager_repository = UserManagerRepository::new_mock();
             let mut password_layer = PasswordLayer::new_mock();
@@ -120,13 +167,17 @@ mod tests {
                 .mock_hash_password(password.password.as_str())
                 .returns_once(Err(Report::new(PasswordError("Failed".to_string()))));
 
-            let service = EditPasswordService::new(user_manager_repository, password_layer);
-            let result = service.edit_password_submit(1, &password);
+            let service = EditPasswordService::new(
+                user_manager_repository,
+                password_layer,
+                AuditService::new_mock(),
+            );
+            let result = service.edit_password_submit(1, &password).await;
             assert!(result.is_err());
         }
 
-        #[test]
-        fn test_submit_fail() {
+        #[tokio::test]
+        async fn test_submit_fail() {
             let password = EditPasswordManagerValidated::new_test_data();
             let mut user_manager_repository = UserManagerRepository::new_mock();
             let mut password_layer = PasswordLayer::new_mock();
@@ -141,18 +192,80 @@ mod tests {
                 .mock_edit_password(1, Any)
                 .returns_once(Err(Report::new(UserManagerRepositoryError::QueryError)));
 
-            let service = EditPasswordService::new(user_manager_repository, password_layer);
-            let result = service.edit_password_submit(1, &password);
+            let service = EditPasswordService::new(
+                user_manager_repository,
+                password_layer,
+                AuditService::new_mock(),
+            );
+            let result = service.edit_password_submit(1, &password).await;
             assert!(result.is_err());
         }
     }
 
+    mod test_verify_current_password {
+        use super::*;
+        use crate::user::model::user_manager_model::FetchPassword;
+        use mry::Any;
+        use shared::password::PasswordState;
+
+        fn fetch_password() -> UserManagerRepository {
+            let mut user_manager_repository = UserManagerRepository::new_mock();
+            user_manager_repository
+                .mock_fetch_password(1)
+                .returns_once(Ok(FetchPassword {
+                    password: Default::default(),
+                }));
+            user_manager_repository
+        }
+
+        #[tokio::test]
+        async fn test_current_password_matches() {
+            let mut password_layer = PasswordLayer::new_mock();
+            password_layer
+                .mock_verify_password(Any, "current")
+                .returns_once(Ok(PasswordState::Valid));
+
+            let service = EditPasswordService::new(
+                fetch_password(),
+                password_layer,
+                AuditService::new_mock(),
+            );
+            assert!(service.verify_current_password(1, "current").await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn test_current_password_wrong() {
+            let mut password_layer = PasswordLayer::new_mock();
+            password_layer
+                .mock_verify_password(Any, "wrong")
+                .returns_once(Ok(PasswordState::Invalid));
+
+            let service = EditPasswordService::new(
+                fetch_password(),
+                password_layer,
+                AuditService::new_mock(),
+            );
+            assert!(!service.verify_current_password(1, "wrong").await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn test_current_password_missing() {
+            // Neither the repository nor the password layer is touched.
+            let service = EditPasswordService::new(
+                UserManagerRepository::new_mock(),
+                PasswordLayer::new_mock(),
+                AuditService::new_mock(),
+            );
+            assert!(!service.verify_current_password(1, "").await.unwrap());
+        }
+    }
+
     mod test_fetch_user {
         use super::*;
         use crate::user::repository::user_manager_repository::UserManagerRepositoryError;
 
-        #[test]
-        fn test_fetch_user_success() {
+        #[tokio::test]
+        async fn test_fetch_user_success() {
             let mut user_manager_repository = UserManagerRepository::new_mock();
             let password_layer = PasswordLayer::new_mock();
             user_manager_repository
@@ -162,37 +275,49 @@ mod tests {
                     role: Default::default(),
                 })));
 
-            let service = EditPasswordService::new(user_manager_repository, password_layer);
-            let result = service.fetch_user(1);
+            let service = EditPasswordService::new(
+                user_manager_repository,
+                password_layer,
+                AuditService::new_mock(),
+            );
+            let result = service.fetch_user(1).await;
             assert!(result.is_ok());
         }
 
-        #[test]
-        fn test_fetch_user_not_found() {
+        #[tokio::test]
+        async fn test_fetch_user_not_found() {
             let mut user_manager_repository = UserManagerRepository::new_mock();
             let password_layer = PasswordLayer::new_mock();
             user_manager_repository
                 .mock_fetch_user(1)
                 .returns_once(Ok(None));
 
-            let service = EditPasswordService::new(user_manager_repository, password_layer);
-            let result = service.fetch_user(1);
+            let service = EditPasswordService::new(
+                user_manager_repository,
+                password_layer,
+                AuditService::new_mock(),
+            );
+            let result = service.fetch_user(1).await;
             assert!(result.is_err());
             let result = result.err().unwrap();
             let status_code = result.downcast_ref::<StatusCode>().unwrap();
             assert_eq!(*status_code, StatusCode::NOT_FOUND);
         }
 
-        #[test]
-        fn test_fetch_user_error() {
+        #[tokio::test]
+        async fn test_fetch_user_error() {
             let mut user_manager_repository = UserManagerRepository::new_mock();
             let password_layer = PasswordLayer::new_mock();
             user_manager_repository
                 .mock_fetch_user(1)
                 .returns_once(Err(Report::new(UserManagerRepositoryError::QueryError)));
 
-            let service = EditPasswordService::new(user_manager_repository, password_layer);
-            let result = service.fetch_user(1);
+            let service = EditPasswordService::new(
+                user_manager_repository,
+                password_layer,
+                AuditService::new_mock(),
+            );
+            let result = service.fetch_user(1).await;
             assert!(result.is_err());
         }
     }
@@ -1,3 +1,5 @@
+use crate::audit::model::audit_model::AuditAction;
+use crate::audit::service::audit_service::AuditService;
 use crate::user::form::edit_user::EditUserValidated;
 use crate::user::model::user_manager_model::FetchUser;
 use crate::user::repository::user_manager_repository::UserManagerRepository;
@@ -13,37 +15,82 @@ pub enum EditUserServiceError {
     SubmitFailed,
     #[error("User not found")]
     UserNotFound,
+    #[error("Database error")]
+    DbError,
 }
 
 pub struct EditUserService {
     user_manager_repository: UserManagerRepository,
+    audit_service: AuditService,
 }
 
 impl EditUserService {
-    pub fn new(user_manager_repository: UserManagerRepository) -> Self {
+    pub fn new(
+        user_manager_repository: UserManagerRepository,
+        audit_service: AuditService,
+    ) -> Self {
         Self {
             user_manager_repository,
+            audit_service,
         }
     }
 
-    pub fn edit_user_submit(
+    pub async fn edit_user_submit(
         &self,
         user_id: i64,
         edit_user_validated: &EditUserValidated,
     ) -> Result<(), Report<EditUserServiceError>> {
+        let before = self
+            .user_manager_repository
+            .fetch_user(user_id)
+            .await
+            .change_context(EditUserServiceError::SubmitFailed)?;
         self.user_manager_repository
             .edit_user(
                 user_id,
                 edit_user_validated.username.as_str().to_string(),
                 &edit_user_validated.role,
             )
+            .await
             .change_context(EditUserServiceError::SubmitFailed)?;
+        self.audit_service
+            .record(
+                AuditAction::UserEdit,
+                Some(user_id),
+                before.as_ref(),
+                Some(&FetchUser {
+                    username: edit_user_validated.username.as_str().to_string(),
+                    role: edit_user_validated.role.clone(),
+                }),
+            )
+            .await;
         Ok(())
     }
 
-    pub fn fetch_user(&self, user_id: i64) -> Result<FetchUser, Report<EditUserServiceError>> {
+    /// Ends every login session the user has, their API tokens keep working.
+    pub async fn sign_out_user(&self, user_id: i64) -> Result<(), Report<EditUserServiceError>> {
+        self.user_manager_repository
+            .revoke_all_token_by_id(user_id)
+            .await
+            .change_context(EditUserServiceError::DbError)?;
+        self.audit_service
+            .record(
+                AuditAction::UserSignOut,
+                Some(user_id),
+                None::<&()>,
+                None::<&()>,
+            )
+            .await;
+        Ok(())
+    }
+
+    pub async fn fetch_user(
+        &self,
+        user_id: i64,
+    ) -> Result<FetchUser, Report<EditUserServiceError>> {
         self.user_manager_repository
             .fetch_user(user_id)
+            .await
             .change_context(EditUserServiceError::UserNotFound)?
             .ok_or_else(|| {
                 Report::new(EditUserServiceError::UserNotFound).attach(StatusCode::NOT_FOUND)
@@ -55,6 +102,7 @@ impl IsUsernameTakenAsync for EditUserService {
     async fn is_username_taken_async(&self, username: &str) -> bool {
         self.user_manager_repository
             .username_taken(username.to_string())
+            .await
             .ok()
             .unwrap_or_default()
     }
@@ -62,23 +110,32 @@ impl IsUsernameTakenAsync for EditUserService {
 
 impl FromContext for EditUserService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?))
+        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audit::model::audit_model::AuditActor;
+    use crate::audit::repository::audit_repository::AuditRepository;
+    use mry::Any;
 
     mod test_edit_user_submit {
         use super::*;
         use crate::user::form::edit_user::EditUserValidated;
         use crate::user::repository::user_manager_repository::UserManagerRepositoryError;
 
-        #[test]
-        fn test_submit_success() {
+        #[tokio::test]
+        async fn test_submit_success() {
             let edit_user_validated = EditUserValidated::new_test_data();
             let mut user_manager_repository = UserManagerRepository::new_mock();
+            user_manager_repository
+                .mock_fetch_user(1)
+                .returns_once(Ok(Some(FetchUser {
+                    username: "before".to_string(),
+                    role: Default::default(),
+                })));
             user_manager_repository
                 .mock_edit_user(
                     1,
@@ -87,15 +144,21 @@ mod tests {
                 )
                 .returns_once(Ok(()));
 
-            let service = EditUserService::new(user_manager_repository);
-            let result = service.edit_user_submit(1, &edit_user_validated);
+            let service = EditUserService::new(user_manager_repository, AuditService::new_mock());
+            let result = service.edit_user_submit(1, &edit_user_validated).await;
             assert!(result.is_ok());
         }
 
-        #[test]
-        fn test_submit_fail() {
+        #[tokio::test]
+        async fn test_submit_fail() {
             let edit_user_validated = EditUserValidated::new_test_data();
             let mut user_manager_repository = UserManagerRepository::new_mock();
+            user_manager_repository
+                .mock_fetch_user(1)
+                .returns_once(Ok(Some(FetchUser {
+                    username: "before".to_string(),
+                    role: Default::default(),
+                })));
             user_manager_repository
                 .mock_edit_user(
                     1,
@@ -104,18 +167,42 @@ mod tests {
                 )
                 .returns_once(Err(Report::new(UserManagerRepositoryError::QueryError)));
 
-            let service = EditUserService::new(user_manager_repository);
-            let result = service.edit_user_submit(1, &edit_user_validated);
+            let service = EditUserService::new(user_manager_repository, AuditService::new_mock());
+            let result = service.edit_user_submit(1, &edit_user_validated).await;
             assert!(result.is_err());
         }
     }
 
+    #[tokio::test]
+    async fn test_sign_out_user_is_recorded() {
+        let mut user_manager_repository = UserManagerRepository::new_mock();
+        user_manager_repository
+            .mock_revoke_all_token_by_id(1)
+            .returns_once(Ok(()));
+        let mut audit_repository = AuditRepository::new_mock();
+        audit_repository
+            .mock_add_audit_log(
+                AuditActor::default(),
+                AuditAction::UserSignOut,
+                Some(1),
+                "{}".to_string(),
+                Any,
+            )
+            .returns_once(Ok(()));
+
+        let service = EditUserService::new(
+            user_manager_repository,
+            AuditService::new(audit_repository, AuditActor::default()),
+        );
+        assert!(service.sign_out_user(1).await.is_ok());
+    }
+
     mod test_fetch_user {
         use super::*;
         use crate::user::repository::user_manager_repository::UserManagerRepositoryError;
 
-        #[test]
-        fn test_fetch_user_success() {
+        #[tokio::test]
+        async fn test_fetch_user_success() {
             let mut user_manager_repository = UserManagerRepository::new_mock();
             user_manager_repository
                 .mock_fetch_user(1)
@@ -124,35 +211,35 @@ mod tests {
                     role: Default::default(),
                 })));
 
-            let service = EditUserService::new(user_manager_repository);
-            let result = service.fetch_user(1);
+            let service = EditUserService::new(user_manager_repository, AuditService::new_mock());
+            let result = service.fetch_user(1).await;
             assert!(result.is_ok());
         }
 
-        #[test]
-        fn test_fetch_user_not_found() {
+        #[tokio::test]
+        async fn test_fetch_user_not_found() {
             let mut user_manager_repository = UserManagerRepository::new_mock();
             user_manager_repository
                 .mock_fetch_user(1)
                 .returns_once(Ok(None));
 
-            let service = EditUserService::new(user_manager_repository);
-            let result = service.fetch_user(1);
+            let service = EditUserService::new(user_manager_repository, AuditService::new_mock());
+            let result = service.fetch_user(1).await;
             assert!(result.is_err());
             let result = result.err().unwrap();
             let status_code = result.downcast_ref::<StatusCode>().unwrap();
             assert_eq!(*status_code, StatusCode::NOT_FOUND);
         }
 
-        #[test]
-        fn test_fetch_user_error() {
+        #[tokio::test]
+        async fn test_fetch_user_error() {
             let mut user_manager_repository = UserManagerRepository::new_mock();
             user_manager_repository
                 .mock_fetch_user(1)
                 .returns_once(Err(Report::new(UserManagerRepositoryError::QueryError)));
 
-            let service = EditUserService::new(user_manager_repository);
-            let result = service.fetch_user(1);
+            let service = EditUserService::new(user_manager_repository, AuditService::new_mock());
+            let result = service.fetch_user(1).await;
             assert!(result.is_err());
         }
     }
@@ -15,9 +15,10 @@ impl ListUserService {
         }
     }
 
-    pub fn list_users(&self) -> Arc<[ListUser]> {
+    pub async fn list_users(&self) -> Arc<[ListUser]> {
         self.user_manager_repository
             .list_users()
+            .await
             .unwrap_or_default()
     }
 }
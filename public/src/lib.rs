@@ -4,14 +4,25 @@ use error_stack::{Report, ResultExt};
 use poem::middleware::CatchPanic;
 use poem::{EndpointExt, IntoResponse, Server};
 use shared::config::Config;
+use shared::context::fetch_context;
+use shared::db::SqliteClient;
 use shared::error::boot_error::MainError;
 use shared::log::log_poem_error;
+use shared::shutdown::shutdown_signal;
 use shorty::route::shorty::shorty_route;
+use shorty::service::gone_service::GoneService;
+use shorty::service::hit_recorder_service::flush_hit_recorder;
 
 pub async fn boot() -> Result<(), Report<MainError>> {
     let config = Config::fetch()
         .await
         .change_context(MainError::ConfigError)?;
+    SqliteClient::init()
+        .await
+        .change_context(MainError::DatabaseError)?;
+    fetch_context::<GoneService>()
+        .await
+        .change_context(MainError::ConfigError)?;
 
     let route = shorty_route();
 
@@ -25,12 +36,19 @@ pub async fn boot() -> Result<(), Report<MainError>> {
                 "Public Listening on http://{}",
                 config.poem_public.parse_address()
             );
-            Server::new(poem::listener::TcpListener::bind(
+            let result = Server::new(poem::listener::TcpListener::bind(
                 &config.poem_public.parse_address(),
             ))
-            .run(route)
+            .run_with_graceful_shutdown(
+                route,
+                shutdown_signal(),
+                Some(config.poem_public.shutdown_timeout()),
+            )
             .await
-            .change_context(MainError::IoError)
+            .change_context(MainError::IoError);
+            flush_hit_recorder().await;
+            println!("Public stopped");
+            result
         }
         None => Err(Report::new(MainError::ConfigError)),
     }
@@ -1,3 +1,32 @@
+use chrono::{DateTime, Utc};
+use shared::redirect::RedirectType;
+
+#[derive(Clone)]
 pub struct UrlRedirect {
+    pub id: i64,
     pub url_redirect: String,
+    pub redirect_type: RedirectType,
+    pub hit_count: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_clicks: Option<i64>,
+}
+
+impl UrlRedirect {
+    pub fn is_gone(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+            || self
+                .max_clicks
+                .is_some_and(|max_clicks| self.hit_count >= max_clicks)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlHit {
+    pub url_redirect_id: i64,
+    pub hit_at: DateTime<Utc>,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+    pub client_ip: Option<String>,
+    /// Already added to the hit count, when the link has a click limit.
+    pub hit_counted: bool,
 }
@@ -0,0 +1,3 @@
+insert into url_redirect_hit (url_redirect_id, hit_at, referer, user_agent, client_ip)
+select :url_redirect_id, :hit_at, :referer, :user_agent, :client_ip
+where exists (select 1 from url_redirect where id = :url_redirect_id)
\ No newline at end of file
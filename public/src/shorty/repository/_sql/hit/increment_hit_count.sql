@@ -0,0 +1,3 @@
+update url_redirect
+set hit_count = hit_count + 1
+where id = :url_redirect_id
\ No newline at end of file
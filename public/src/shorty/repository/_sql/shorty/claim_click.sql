@@ -0,0 +1,5 @@
+update url_redirect
+set hit_count = hit_count + 1
+where id = :id
+  and hit_count < max_clicks
+returning hit_count
\ No newline at end of file
@@ -1,3 +1,10 @@
-select url_redirect
+select id, url_redirect, redirect_type, hit_count, expires_at, max_clicks
 from url_redirect
-where url_path = :path
\ No newline at end of file
+where url_path = :path
+  and deleted_at is null
+union all
+select ur.id, ur.url_redirect, ur.redirect_type, ur.hit_count, ur.expires_at, ur.max_clicks
+from url_redirect_alias as ura
+         inner join url_redirect ur on ur.id = ura.url_redirect_id
+where ura.url_path = :path
+  and ur.deleted_at is null
\ No newline at end of file
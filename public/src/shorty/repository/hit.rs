@@ -0,0 +1,109 @@
+use crate::shorty::model::url::UrlHit;
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use rusqlite::{Connection, named_params};
+use shared::context::{Context, ContextError, FromContext};
+use shared::db::{RunConnectionExt, SqliteClient};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum HitRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Transaction error")]
+    TransactionError,
+    #[error("Borrow Conn error")]
+    BorrowConnError,
+}
+
+#[mry::mry]
+pub struct HitRepository {
+    sqlite_client: Option<SqliteClient>,
+}
+
+impl HitRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self {
+            sqlite_client: Some(sqlite_client),
+            mry: Default::default(),
+        }
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<HitRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<HitRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(HitRepositoryError::BorrowConnError, f)
+            .await
+    }
+}
+
+#[mry::mry]
+impl HitRepository {
+    pub async fn add_hits(&self, hits: Vec<UrlHit>) -> Result<(), Report<HitRepositoryError>> {
+        self.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .change_context(HitRepositoryError::TransactionError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            {
+                let mut add_stmt = tx
+                    .prepare_cached(include_str!("_sql/hit/add_hit.sql"))
+                    .change_context(HitRepositoryError::QueryError)
+                    .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                let mut increment_stmt = tx
+                    .prepare_cached(include_str!("_sql/hit/increment_hit_count.sql"))
+                    .change_context(HitRepositoryError::QueryError)
+                    .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+                for hit in hits.iter() {
+                    add_stmt
+                        .execute(named_params! {
+                            ":url_redirect_id": hit.url_redirect_id,
+                            ":hit_at": hit.hit_at,
+                            ":referer": hit.referer,
+                            ":user_agent": hit.user_agent,
+                            ":client_ip": hit.client_ip,
+                        })
+                        .change_context(HitRepositoryError::QueryError)
+                        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                    if hit.hit_counted {
+                        continue;
+                    }
+                    increment_stmt
+                        .execute(named_params! {
+                            ":url_redirect_id": hit.url_redirect_id,
+                        })
+                        .change_context(HitRepositoryError::QueryError)
+                        .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+                }
+            }
+
+            tx.commit()
+                .change_context(HitRepositoryError::TransactionError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+impl HitRepository {
+    pub fn new_mock() -> Self {
+        mry::new!(Self {
+            sqlite_client: None
+        })
+    }
+}
+
+impl FromContext for HitRepository {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
@@ -1 +1,2 @@
+pub mod hit;
 pub mod shorty;
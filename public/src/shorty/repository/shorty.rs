@@ -3,11 +3,10 @@ use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
 use rusqlite::{Connection, OptionalExtension, named_params};
 use shared::context::{Context, ContextError, FromContext};
-use shared::db::SqliteClient;
-use shared::error::LogItExt;
-use std::sync::MutexGuard;
+use shared::db::{RunConnectionExt, SqliteClient};
 use thiserror::Error;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum ShortyRepositoryError {
     #[error("Query error")]
@@ -31,52 +30,87 @@ impl ShortyRepository {
         }
     }
 
-    fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<ShortyRepositoryError>> {
-        let guard = self
-            .sqlite_client
-            .as_ref()
-            .expect("Client")
-            .get_conn()
-            .lock()
-            .map_err(|err| {
-                Report::new(ShortyRepositoryError::LockError)
-                    .attach(err.to_string())
-                    .log_it()
-                    .attach(StatusCode::INTERNAL_SERVER_ERROR)
-            })?;
-        Ok(guard)
+    async fn read<F, R>(&self, f: F) -> Result<R, Report<ShortyRepositoryError>>
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<ShortyRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .read(ShortyRepositoryError::LockError, f)
+            .await
+    }
+
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<ShortyRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<ShortyRepositoryError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.sqlite_client
+            .write(ShortyRepositoryError::LockError, f)
+            .await
     }
 }
 
 #[mry::mry]
 impl ShortyRepository {
-    pub fn fetch_url(
+    pub async fn fetch_url(
         &self,
         path: &str,
     ) -> Result<Option<UrlRedirect>, Report<ShortyRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        let path = path.to_owned();
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/shorty/fetch_url.sql"))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/shorty/fetch_url.sql"))
-            .change_context(ShortyRepositoryError::QueryError)
-            .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+            let row = stmt
+                .query_row(
+                    named_params! {
+                        ":path": path,
+                    },
+                    |row| {
+                        Ok(UrlRedirect {
+                            id: row.get("id")?,
+                            url_redirect: row.get("url_redirect")?,
+                            redirect_type: row.get("redirect_type")?,
+                            hit_count: row.get("hit_count")?,
+                            expires_at: row.get("expires_at")?,
+                            max_clicks: row.get("max_clicks")?,
+                        })
+                    },
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::RowValueError)
+                .attach(StatusCode::UNPROCESSABLE_ENTITY)?;
 
-        let row = stmt
-            .query_row(
-                named_params! {
-                    ":path": path,
-                },
-                |row| {
-                    Ok(UrlRedirect {
-                        url_redirect: row.get("url_redirect")?,
-                    })
-                },
-            )
-            .optional()
-            .change_context(ShortyRepositoryError::RowValueError)
-            .attach(StatusCode::UNPROCESSABLE_ENTITY)?;
+            Ok(row)
+        })
+        .await
+    }
 
-        Ok(row)
+    /// Counts the click straight away, false once the link has used up its clicks.
+    pub async fn claim_click(&self, id: i64) -> Result<bool, Report<ShortyRepositoryError>> {
+        self.write(move |conn| {
+            let mut stmt = conn
+                .prepare_cached(include_str!("_sql/shorty/claim_click.sql"))
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            let hit_count: Option<i64> = stmt
+                .query_row(
+                    named_params! {
+                        ":id": id,
+                    },
+                    |row| row.get("hit_count"),
+                )
+                .optional()
+                .change_context(ShortyRepositoryError::QueryError)
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            Ok(hit_count.is_some())
+        })
+        .await
     }
 }
 
@@ -1,23 +1,46 @@
 use crate::shorty::rule::shorty_path::ShortyPathRuleExt;
-use crate::shorty::service::fetch_url_service::FetchUrlService;
+use crate::shorty::service::fetch_url_service::{FetchUrlService, FetchUrlServiceError};
+use crate::shorty::service::gone_service::GoneService;
+use crate::shorty::service::hit_recorder_service::HitRecorderService;
+use chrono::Utc;
 use cjtoolkit_structured_validator::types::name::name_alias::Field;
-use poem::http::StatusCode;
-use poem::web::{Path, Redirect};
-use poem::{Error, Route, get, handler};
+use poem::http::header::{REFERER, USER_AGENT};
+use poem::http::{HeaderMap, StatusCode};
+use poem::web::{Path, RealIp};
+use poem::{Error, Response, Route, get, handler};
 use shared::context::Dep;
 use shared::error::FromErrorStack;
 
 #[handler]
 async fn fetch_url(
     Dep(fetch_url_service): Dep<FetchUrlService>,
+    Dep(hit_recorder_service): Dep<HitRecorderService>,
+    Dep(gone_service): Dep<GoneService>,
     Path(path): Path<String>,
-) -> poem::Result<Redirect> {
+    RealIp(client_ip): RealIp,
+    headers: &HeaderMap,
+) -> poem::Result<Response> {
     let path = Field::parse_shorty_path(Some(&path))
         .map_err(|err| Error::from_string(err.to_string(), StatusCode::NOT_FOUND))?;
-    let url = fetch_url_service
-        .fetch_url(path.as_str())
-        .map_err(Error::from_error_stack)?;
-    Ok(Redirect::see_other(url.url_redirect))
+    let url = match fetch_url_service.fetch_url(path.as_str(), Utc::now()).await {
+        Ok(url) => url,
+        Err(err) if matches!(err.current_context(), FetchUrlServiceError::Gone) => {
+            return gone_service
+                .gone_response()
+                .ok_or_else(|| Error::from_error_stack(err));
+        }
+        Err(err) => return Err(Error::from_error_stack(err)),
+    };
+    hit_recorder_service.record(
+        url.id,
+        headers.get(REFERER).and_then(|value| value.to_str().ok()),
+        headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok()),
+        client_ip,
+        url.max_clicks.is_some(),
+    );
+    Ok(url.redirect_type.redirect(url.url_redirect))
 }
 
 pub fn shorty_route() -> Route {
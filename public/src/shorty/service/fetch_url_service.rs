@@ -1,5 +1,7 @@
 use crate::shorty::model::url::UrlRedirect;
 use crate::shorty::repository::shorty::ShortyRepository;
+use crate::shorty::service::redirect_cache_service::RedirectCacheService;
+use chrono::{DateTime, Utc};
 use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
 use shared::context::{Context, ContextError, FromContext};
@@ -12,35 +14,78 @@ pub enum FetchUrlServiceError {
     DbError,
     #[error("Not Found")]
     NotFound,
+    #[error("Gone")]
+    Gone,
 }
 
 pub struct FetchUrlService {
     shorty_repository: ShortyRepository,
+    redirect_cache_service: RedirectCacheService,
 }
 
 impl FetchUrlService {
-    pub fn new(shorty_repository: ShortyRepository) -> Self {
-        Self { shorty_repository }
+    pub fn new(
+        shorty_repository: ShortyRepository,
+        redirect_cache_service: RedirectCacheService,
+    ) -> Self {
+        Self {
+            shorty_repository,
+            redirect_cache_service,
+        }
     }
 
-    pub fn fetch_url(&self, path: &str) -> Result<UrlRedirect, Report<FetchUrlServiceError>> {
+    async fn lookup(
+        &self,
+        path: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<UrlRedirect>, Report<FetchUrlServiceError>> {
+        if let Some(url_redirect) = self.redirect_cache_service.get(path, now) {
+            return Ok(url_redirect);
+        }
+        let generation = self.redirect_cache_service.generation();
         let url_redirect = self
             .shorty_repository
             .fetch_url(path)
+            .await
             .change_context(FetchUrlServiceError::DbError)
-            .log_it()?
-            .ok_or_else(|| {
-                Report::new(FetchUrlServiceError::NotFound)
-                    .attach(format!("Path: {}", path))
-                    .attach(StatusCode::NOT_FOUND)
-            })?;
+            .log_it()?;
+        self.redirect_cache_service
+            .insert(path, url_redirect.as_ref(), generation, now);
+        Ok(url_redirect)
+    }
+
+    pub async fn fetch_url(
+        &self,
+        path: &str,
+        now: DateTime<Utc>,
+    ) -> Result<UrlRedirect, Report<FetchUrlServiceError>> {
+        let url_redirect = self.lookup(path, now).await?.ok_or_else(|| {
+            Report::new(FetchUrlServiceError::NotFound)
+                .attach(format!("Path: {}", path))
+                .attach(StatusCode::NOT_FOUND)
+        })?;
+        // The cached hit count lags behind, so a limited link claims its click in the
+        // database before redirecting, and concurrent hits cannot go over the limit.
+        if url_redirect.is_gone(now)
+            || (url_redirect.max_clicks.is_some()
+                && !self
+                    .shorty_repository
+                    .claim_click(url_redirect.id)
+                    .await
+                    .change_context(FetchUrlServiceError::DbError)
+                    .log_it()?)
+        {
+            return Err(Report::new(FetchUrlServiceError::Gone)
+                .attach(format!("Path: {}", path))
+                .attach(StatusCode::GONE));
+        }
         Ok(url_redirect)
     }
 }
 
 impl FromContext for FetchUrlService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?))
+        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
     }
 }
 
@@ -48,44 +93,182 @@ impl FromContext for FetchUrlService {
 mod tests {
     use super::*;
     use crate::shorty::repository::shorty::ShortyRepositoryError;
+    use chrono::TimeDelta;
+    use shared::redirect::RedirectType;
 
-    #[test]
-    fn test_fetch_url_success() {
+    fn redirect_cache_service() -> RedirectCacheService {
+        RedirectCacheService::new(10, TimeDelta::seconds(60))
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_success() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_fetch_url("hello")
             .returns_once(Ok(Some(UrlRedirect {
+                id: 1,
                 url_redirect: "hi".to_string(),
+                redirect_type: RedirectType::default(),
+                hit_count: 0,
+                expires_at: None,
+                max_clicks: None,
             })));
 
-        let fetch_url_service = FetchUrlService::new(shorty_repository);
-        let url_redirect = fetch_url_service.fetch_url("hello").unwrap();
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        let url_redirect = fetch_url_service
+            .fetch_url("hello", Utc::now())
+            .await
+            .unwrap();
         assert_eq!(url_redirect.url_redirect, "hi");
     }
 
-    #[test]
-    fn test_fetch_url_not_found() {
+    #[tokio::test]
+    async fn test_fetch_url_not_found() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_fetch_url("hello")
             .returns_once(Ok(None));
 
-        let fetch_url_service = FetchUrlService::new(shorty_repository);
-        let url_redirect = fetch_url_service.fetch_url("hello");
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        let url_redirect = fetch_url_service.fetch_url("hello", Utc::now()).await;
         assert!(url_redirect.is_err());
         let error = url_redirect.as_ref().err().unwrap();
         let http_code = error.downcast_ref::<StatusCode>().unwrap();
         assert_eq!(http_code, &StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_fetch_url_db_error() {
+    #[tokio::test]
+    async fn test_fetch_url_db_error() {
         let mut shorty_repository = ShortyRepository::new_mock();
         shorty_repository
             .mock_fetch_url("hello")
             .returns_once(Err(Report::new(ShortyRepositoryError::RowValueError)));
-        let fetch_url_service = FetchUrlService::new(shorty_repository);
-        let url_redirect = fetch_url_service.fetch_url("hello");
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        let url_redirect = fetch_url_service.fetch_url("hello", Utc::now()).await;
         assert!(url_redirect.is_err());
     }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-03-10T12:00:00Z")
+            .unwrap()
+            .to_utc()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_gone_expired() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_fetch_url("hello")
+            .returns_once(Ok(Some(UrlRedirect {
+                id: 1,
+                url_redirect: "hi".to_string(),
+                redirect_type: RedirectType::default(),
+                hit_count: 0,
+                expires_at: Some(now()),
+                max_clicks: None,
+            })));
+
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        let url_redirect = fetch_url_service.fetch_url("hello", now()).await;
+        let error = url_redirect.err().unwrap();
+        assert!(matches!(
+            error.current_context(),
+            FetchUrlServiceError::Gone
+        ));
+        let http_code = error.downcast_ref::<StatusCode>().unwrap();
+        assert_eq!(http_code, &StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_gone_max_clicks() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_fetch_url("hello")
+            .returns_once(Ok(Some(UrlRedirect {
+                id: 1,
+                url_redirect: "hi".to_string(),
+                redirect_type: RedirectType::default(),
+                hit_count: 5,
+                expires_at: None,
+                max_clicks: Some(5),
+            })));
+
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        let url_redirect = fetch_url_service.fetch_url("hello", now()).await;
+        let error = url_redirect.err().unwrap();
+        assert!(matches!(
+            error.current_context(),
+            FetchUrlServiceError::Gone
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_within_limits() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_fetch_url("hello")
+            .returns_once(Ok(Some(UrlRedirect {
+                id: 1,
+                url_redirect: "hi".to_string(),
+                redirect_type: RedirectType::default(),
+                hit_count: 4,
+                expires_at: Some(now() + chrono::TimeDelta::minutes(1)),
+                max_clicks: Some(5),
+            })));
+        shorty_repository.mock_claim_click(1).returns_once(Ok(true));
+
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        assert!(fetch_url_service.fetch_url("hello", now()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_gone_when_click_not_claimed() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_fetch_url("hello")
+            .returns_once(Ok(Some(UrlRedirect {
+                id: 1,
+                url_redirect: "hi".to_string(),
+                redirect_type: RedirectType::default(),
+                hit_count: 4,
+                expires_at: None,
+                max_clicks: Some(5),
+            })));
+        // Another request took the last click since the link was cached.
+        shorty_repository
+            .mock_claim_click(1)
+            .returns_once(Ok(false));
+
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        let error = fetch_url_service
+            .fetch_url("hello", now())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.current_context(),
+            FetchUrlServiceError::Gone
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_served_from_cache() {
+        let mut shorty_repository = ShortyRepository::new_mock();
+        shorty_repository
+            .mock_fetch_url("hello")
+            .returns_once(Ok(None));
+
+        let fetch_url_service = FetchUrlService::new(shorty_repository, redirect_cache_service());
+        for _ in 0..2 {
+            let error = fetch_url_service
+                .fetch_url("hello", now())
+                .await
+                .err()
+                .unwrap();
+            assert!(matches!(
+                error.current_context(),
+                FetchUrlServiceError::NotFound
+            ));
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use error_stack::{Report, ResultExt};
+use poem::http::StatusCode;
+use poem::web::{Html, Redirect};
+use poem::{IntoResponse, Response};
+use shared::config::ConfigPointer;
+use shared::context::{Context, ContextError, FromContext};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// What a link that has expired or run out of clicks answers with. The page is read once.
+#[derive(Clone, Default)]
+pub struct GoneService {
+    gone_redirect_url: Option<Arc<str>>,
+    gone_page: Option<Arc<str>>,
+}
+
+impl GoneService {
+    pub fn new(gone_redirect_url: Option<&str>, gone_page: Option<&str>) -> Self {
+        Self {
+            gone_redirect_url: gone_redirect_url.map(Arc::from),
+            gone_page: gone_page.map(Arc::from),
+        }
+    }
+
+    /// The redirect takes priority over the page, none leaves the plain error.
+    pub fn gone_response(&self) -> Option<Response> {
+        if let Some(gone_redirect_url) = &self.gone_redirect_url {
+            return Some(Redirect::see_other(gone_redirect_url).into_response());
+        }
+        self.gone_page.as_ref().map(|gone_page| {
+            Html(gone_page.to_string())
+                .with_status(StatusCode::GONE)
+                .into_response()
+        })
+    }
+}
+
+static GONE_SERVICE: OnceCell<GoneService> = OnceCell::const_new();
+
+impl FromContext for GoneService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let gone_service: Result<&Self, Report<ContextError>> = GONE_SERVICE
+            .get_or_try_init(|| async {
+                let config: ConfigPointer = ctx.inject().await?;
+                let gone_page = match &config.shorty.gone_page_path {
+                    Some(gone_page_path) => Some(
+                        tokio::fs::read_to_string(gone_page_path)
+                            .await
+                            .change_context(ContextError::ConfigError)
+                            .attach_with(|| format!("Gone page: {}", gone_page_path))?,
+                    ),
+                    None => None,
+                };
+                Ok(Self::new(
+                    config.shorty.gone_redirect_url.as_deref(),
+                    gone_page.as_deref(),
+                ))
+            })
+            .await;
+        Ok(gone_service?.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gone_response_prefers_redirect() {
+        let gone_service = GoneService::new(Some("https://example.com/ended"), Some("<p>Gone</p>"));
+        let response = gone_service.gone_response().unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+        let gone_service = GoneService::new(None, Some("<p>Gone</p>"));
+        let response = gone_service.gone_response().unwrap();
+        assert_eq!(response.status(), StatusCode::GONE);
+
+        assert!(GoneService::default().gone_response().is_none());
+    }
+}
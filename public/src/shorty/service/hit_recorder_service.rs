@@ -0,0 +1,205 @@
+use crate::shorty::model::url::UrlHit;
+use crate::shorty::service::hit_service::HitService;
+use chrono::Utc;
+use error_stack::Report;
+use log::{error, warn};
+use shared::context::{Context, ContextError, FromContext};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+const HIT_BUFFER_SIZE: usize = 10_000;
+const HIT_BATCH_SIZE: usize = 500;
+const HEADER_MAX_LENGTH: usize = 512;
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Queues hits for the background writer, so the redirect never waits on the database.
+#[derive(Clone)]
+pub struct HitRecorderService {
+    sender: Sender<UrlHit>,
+}
+
+impl HitRecorderService {
+    pub fn new(sender: Sender<UrlHit>) -> Self {
+        Self { sender }
+    }
+
+    pub fn record(
+        &self,
+        url_redirect_id: i64,
+        referer: Option<&str>,
+        user_agent: Option<&str>,
+        client_ip: Option<IpAddr>,
+        hit_counted: bool,
+    ) {
+        let hit = UrlHit {
+            url_redirect_id,
+            hit_at: Utc::now(),
+            referer: referer.map(truncate_header),
+            user_agent: user_agent.map(truncate_header),
+            client_ip: client_ip.map(anonymise_ip),
+            hit_counted,
+        };
+        match self.sender.try_send(hit) {
+            Ok(_) => {}
+            Err(TrySendError::Full(_)) => warn!("Hit buffer is full, dropping hit"),
+            Err(TrySendError::Closed(_)) => error!("Hit writer has stopped, dropping hit"),
+        }
+    }
+}
+
+/// Zeroes the host part of the address: the last octet for IPv4, everything past the /48 for IPv6.
+pub fn anonymise_ip(ip: IpAddr) -> String {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            IpAddr::from([a, b, c, 0]).to_string()
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            IpAddr::from([a, b, c, 0, 0, 0, 0, 0]).to_string()
+        }
+    }
+}
+
+fn truncate_header(value: &str) -> String {
+    value.chars().take(HEADER_MAX_LENGTH).collect()
+}
+
+async fn hit_writer(mut receiver: Receiver<UrlHit>, hit_service: Arc<HitService>) {
+    let mut buffer = Vec::with_capacity(HIT_BATCH_SIZE);
+    while receiver.recv_many(&mut buffer, HIT_BATCH_SIZE).await > 0 {
+        let hits = std::mem::replace(&mut buffer, Vec::with_capacity(HIT_BATCH_SIZE));
+        if let Err(err) = hit_service.save_hits(hits).await {
+            error!("{:?}", err);
+        }
+    }
+}
+
+struct HitRecorder {
+    service: Option<HitRecorderService>,
+    writer: Option<JoinHandle<()>>,
+}
+
+static HIT_RECORDER_CACHE: OnceCell<Mutex<HitRecorder>> = OnceCell::const_new();
+
+impl FromContext for HitRecorderService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let hit_recorder: Result<&Mutex<HitRecorder>, Report<ContextError>> = HIT_RECORDER_CACHE
+            .get_or_try_init(|| async {
+                let hit_service: HitService = ctx.inject().await?;
+                let (sender, receiver) = channel(HIT_BUFFER_SIZE);
+                let writer = tokio::spawn(hit_writer(receiver, Arc::new(hit_service)));
+                Ok(Mutex::new(HitRecorder {
+                    service: Some(Self::new(sender)),
+                    writer: Some(writer),
+                }))
+            })
+            .await;
+        hit_recorder?
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .service
+            .clone()
+            .ok_or_else(|| Report::new(ContextError::Other).attach("Hit recorder has been flushed"))
+    }
+}
+
+/// Closes the hit queue and waits for the writer to save what is left in it.
+/// Called once the public server has drained, so no request still holds a sender.
+pub async fn flush_hit_recorder() {
+    let Some(hit_recorder) = HIT_RECORDER_CACHE.get() else {
+        return;
+    };
+    let writer = {
+        let mut hit_recorder = hit_recorder.lock().unwrap_or_else(PoisonError::into_inner);
+        hit_recorder.service = None;
+        hit_recorder.writer.take()
+    };
+    if let Some(writer) = writer
+        && timeout(FLUSH_TIMEOUT, writer).await.is_err()
+    {
+        warn!("Timed out saving queued hits");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shorty::repository::hit::HitRepository;
+
+    #[test]
+    fn test_anonymise_ipv4() {
+        let ip: IpAddr = "192.168.10.123".parse().unwrap();
+        assert_eq!(anonymise_ip(ip), "192.168.10.0");
+    }
+
+    #[test]
+    fn test_anonymise_ipv6() {
+        let ip: IpAddr = "2001:db8:85a3:8d3:1319:8a2e:370:7348".parse().unwrap();
+        assert_eq!(anonymise_ip(ip), "2001:db8:85a3::");
+    }
+
+    #[test]
+    fn test_anonymise_ipv4_mapped_ipv6() {
+        let ip: IpAddr = "::ffff:10.0.0.7".parse().unwrap();
+        assert_eq!(anonymise_ip(ip), "10.0.0.0");
+    }
+
+    #[test]
+    fn test_record_queues_hit() {
+        let (sender, mut receiver) = channel(1);
+        let hit_recorder_service = HitRecorderService::new(sender);
+        hit_recorder_service.record(
+            7,
+            Some("https://example.com"),
+            Some("curl/8.0"),
+            Some("10.1.2.3".parse().unwrap()),
+            false,
+        );
+
+        let hit = receiver.try_recv().unwrap();
+        assert_eq!(hit.url_redirect_id, 7);
+        assert_eq!(hit.referer.as_deref(), Some("https://example.com"));
+        assert_eq!(hit.user_agent.as_deref(), Some("curl/8.0"));
+        assert_eq!(hit.client_ip.as_deref(), Some("10.1.2.0"));
+    }
+
+    #[test]
+    fn test_record_drops_hit_when_buffer_full() {
+        let (sender, mut receiver) = channel(1);
+        let hit_recorder_service = HitRecorderService::new(sender);
+        hit_recorder_service.record(1, None, None, None, false);
+        hit_recorder_service.record(2, None, None, None, false);
+
+        assert_eq!(receiver.try_recv().unwrap().url_redirect_id, 1);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hit_writer_saves_queued_hits_once_senders_are_dropped() {
+        let saved = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let saved_in_mock = saved.clone();
+        let mut hit_repository = HitRepository::new_mock();
+        hit_repository
+            .mock_add_hits(mry::Any)
+            .returns_with(move |hits: Vec<UrlHit>| {
+                saved_in_mock.fetch_add(hits.len(), std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            });
+
+        let (sender, receiver) = channel(10);
+        let hit_recorder_service = HitRecorderService::new(sender);
+        hit_recorder_service.record(1, None, None, None, false);
+        hit_recorder_service.record(2, None, None, None, false);
+        drop(hit_recorder_service);
+
+        hit_writer(receiver, Arc::new(HitService::new(hit_repository))).await;
+        assert_eq!(saved.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::shorty::model::url::UrlHit;
+use crate::shorty::repository::hit::HitRepository;
+use error_stack::{Report, ResultExt};
+use shared::context::{Context, ContextError, FromContext};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum HitServiceError {
+    #[error("Db error")]
+    DbError,
+}
+
+pub struct HitService {
+    hit_repository: HitRepository,
+}
+
+impl HitService {
+    pub fn new(hit_repository: HitRepository) -> Self {
+        Self { hit_repository }
+    }
+
+    pub async fn save_hits(&self, hits: Vec<UrlHit>) -> Result<(), Report<HitServiceError>> {
+        if hits.is_empty() {
+            return Ok(());
+        }
+        let count = hits.len();
+        self.hit_repository
+            .add_hits(hits)
+            .await
+            .change_context(HitServiceError::DbError)
+            .attach(format!("Hits dropped: {}", count))
+    }
+}
+
+impl FromContext for HitService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject().await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shorty::repository::hit::HitRepositoryError;
+    use chrono::Utc;
+
+    fn hit() -> UrlHit {
+        UrlHit {
+            url_redirect_id: 1,
+            hit_at: Utc::now(),
+            referer: None,
+            user_agent: None,
+            client_ip: None,
+            hit_counted: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_hits_success() {
+        let hits = vec![hit()];
+        let mut hit_repository = HitRepository::new_mock();
+        hit_repository
+            .mock_add_hits(hits.clone())
+            .returns_once(Ok(()));
+
+        let hit_service = HitService::new(hit_repository);
+        assert!(hit_service.save_hits(hits).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_save_hits_empty_skips_repository() {
+        let hit_service = HitService::new(HitRepository::new_mock());
+        assert!(hit_service.save_hits(vec![]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_save_hits_db_error() {
+        let hits = vec![hit()];
+        let mut hit_repository = HitRepository::new_mock();
+        hit_repository
+            .mock_add_hits(hits.clone())
+            .returns_once(Err(Report::new(HitRepositoryError::QueryError)));
+
+        let hit_service = HitService::new(hit_repository);
+        assert!(hit_service.save_hits(hits).await.is_err());
+    }
+}
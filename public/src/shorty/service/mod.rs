@@ -1 +1,5 @@
 pub mod fetch_url_service;
+pub mod gone_service;
+pub mod hit_recorder_service;
+pub mod hit_service;
+pub mod redirect_cache_service;
@@ -0,0 +1,287 @@
+use crate::shorty::model::url::UrlRedirect;
+use chrono::{DateTime, TimeDelta, Utc};
+use error_stack::Report;
+use shared::config::ConfigPointer;
+use shared::context::{Context, ContextError, FromContext};
+use shared::redirect::invalidation::{RedirectInvalidation, RedirectInvalidator};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, PoisonError, RwLock};
+use tokio::sync::OnceCell;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::error::RecvError;
+
+struct CacheEntry {
+    url_redirect: Option<UrlRedirect>,
+    cached_at: DateTime<Utc>,
+    sequence: u64,
+}
+
+#[derive(Default)]
+struct RedirectCache {
+    entries: HashMap<String, CacheEntry>,
+    insert_order: VecDeque<(u64, String)>,
+    sequence: u64,
+    generation: u64,
+}
+
+impl RedirectCache {
+    fn evict_oldest(&mut self) {
+        while let Some((sequence, path)) = self.insert_order.pop_front() {
+            if self
+                .entries
+                .get(&path)
+                .is_some_and(|entry| entry.sequence == sequence)
+            {
+                self.entries.remove(&path);
+                return;
+            }
+        }
+    }
+
+    fn compact_insert_order(&mut self) {
+        let entries = &self.entries;
+        self.insert_order.retain(|(sequence, path)| {
+            entries
+                .get(path)
+                .is_some_and(|entry| entry.sequence == *sequence)
+        });
+    }
+}
+
+/// Path to redirect lookups, including unknown paths, kept for a short while so hot links
+/// skip the database.
+#[derive(Clone)]
+pub struct RedirectCacheService {
+    cache: Arc<RwLock<RedirectCache>>,
+    capacity: usize,
+    ttl: TimeDelta,
+}
+
+impl RedirectCacheService {
+    pub fn new(capacity: usize, ttl: TimeDelta) -> Self {
+        Self {
+            cache: Arc::new(RwLock::new(RedirectCache::default())),
+            capacity,
+            ttl,
+        }
+    }
+
+    /// `None` is a miss, `Some(None)` is a cached unknown path.
+    pub fn get(&self, path: &str, now: DateTime<Utc>) -> Option<Option<UrlRedirect>> {
+        let cache = self.cache.read().unwrap_or_else(PoisonError::into_inner);
+        cache
+            .entries
+            .get(path)
+            .filter(|entry| now - entry.cached_at < self.ttl)
+            .map(|entry| entry.url_redirect.clone())
+    }
+
+    /// Taken before going to the database, so an invalidation that lands during the query
+    /// stops the stale result from being cached.
+    pub fn generation(&self) -> u64 {
+        self.cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .generation
+    }
+
+    pub fn insert(
+        &self,
+        path: &str,
+        url_redirect: Option<&UrlRedirect>,
+        generation: u64,
+        now: DateTime<Utc>,
+    ) {
+        // Click limits depend on the live hit count, so those links always go to the database.
+        if self.capacity == 0 || url_redirect.is_some_and(|url| url.max_clicks.is_some()) {
+            return;
+        }
+        let mut cache = self.cache.write().unwrap_or_else(PoisonError::into_inner);
+        if cache.generation != generation {
+            return;
+        }
+        if !cache.entries.contains_key(path) && cache.entries.len() >= self.capacity {
+            cache.evict_oldest();
+        }
+        cache.sequence += 1;
+        let sequence = cache.sequence;
+        cache.entries.insert(
+            path.to_string(),
+            CacheEntry {
+                url_redirect: url_redirect.cloned(),
+                cached_at: now,
+                sequence,
+            },
+        );
+        cache.insert_order.push_back((sequence, path.to_string()));
+        if cache.insert_order.len() > self.capacity * 2 {
+            cache.compact_insert_order();
+        }
+    }
+
+    pub fn invalidate(&self, invalidation: &RedirectInvalidation) {
+        let mut cache = self.cache.write().unwrap_or_else(PoisonError::into_inner);
+        cache.generation += 1;
+        match invalidation {
+            RedirectInvalidation::Id(id) => cache.entries.retain(|_, entry| {
+                entry
+                    .url_redirect
+                    .as_ref()
+                    .is_none_or(|url_redirect| url_redirect.id != *id)
+            }),
+            RedirectInvalidation::Path(path) => {
+                cache.entries.remove(path);
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut cache = self.cache.write().unwrap_or_else(PoisonError::into_inner);
+        cache.generation += 1;
+        cache.entries.clear();
+        cache.insert_order.clear();
+    }
+}
+
+async fn invalidation_listener(
+    mut receiver: Receiver<RedirectInvalidation>,
+    redirect_cache_service: RedirectCacheService,
+) {
+    loop {
+        match receiver.recv().await {
+            Ok(invalidation) => redirect_cache_service.invalidate(&invalidation),
+            // Missed some changes, so nothing cached can be trusted.
+            Err(RecvError::Lagged(_)) => redirect_cache_service.clear(),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+static REDIRECT_CACHE: OnceCell<RedirectCacheService> = OnceCell::const_new();
+
+impl FromContext for RedirectCacheService {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let redirect_cache_service: Result<&Self, Report<ContextError>> = REDIRECT_CACHE
+            .get_or_try_init(|| async {
+                let config: ConfigPointer = ctx.inject().await?;
+                let redirect_invalidator: RedirectInvalidator = ctx.inject().await?;
+                let redirect_cache_service = Self::new(
+                    config.shorty.redirect_cache_capacity,
+                    TimeDelta::seconds(config.shorty.redirect_cache_ttl_seconds as i64),
+                );
+                tokio::spawn(invalidation_listener(
+                    redirect_invalidator.subscribe(),
+                    redirect_cache_service.clone(),
+                ));
+                Ok(redirect_cache_service)
+            })
+            .await;
+        Ok(redirect_cache_service?.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::redirect::RedirectType;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-03-10T12:00:00Z")
+            .unwrap()
+            .to_utc()
+    }
+
+    fn url_redirect(id: i64) -> UrlRedirect {
+        UrlRedirect {
+            id,
+            url_redirect: format!("https://example.com/{}", id),
+            redirect_type: RedirectType::default(),
+            hit_count: 0,
+            expires_at: None,
+            max_clicks: None,
+        }
+    }
+
+    #[test]
+    fn test_get_returns_fresh_entries_only() {
+        let redirect_cache_service = RedirectCacheService::new(10, TimeDelta::seconds(60));
+        let generation = redirect_cache_service.generation();
+        redirect_cache_service.insert("hello", Some(&url_redirect(1)), generation, now());
+        redirect_cache_service.insert("missing", None, generation, now());
+
+        let cached = redirect_cache_service.get("hello", now()).unwrap().unwrap();
+        assert_eq!(cached.id, 1);
+        assert!(
+            redirect_cache_service
+                .get("missing", now())
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            redirect_cache_service
+                .get("hello", now() + TimeDelta::seconds(60))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_invalidate_by_id_and_path() {
+        let redirect_cache_service = RedirectCacheService::new(10, TimeDelta::seconds(60));
+        let generation = redirect_cache_service.generation();
+        redirect_cache_service.insert("hello", Some(&url_redirect(1)), generation, now());
+        redirect_cache_service.insert("other", Some(&url_redirect(2)), generation, now());
+        redirect_cache_service.insert("missing", None, generation, now());
+
+        redirect_cache_service.invalidate(&RedirectInvalidation::Id(1));
+        redirect_cache_service.invalidate(&RedirectInvalidation::Path("missing".to_string()));
+
+        assert!(redirect_cache_service.get("hello", now()).is_none());
+        assert!(redirect_cache_service.get("missing", now()).is_none());
+        assert!(redirect_cache_service.get("other", now()).is_some());
+    }
+
+    #[test]
+    fn test_insert_ignored_after_invalidation() {
+        let redirect_cache_service = RedirectCacheService::new(10, TimeDelta::seconds(60));
+        let generation = redirect_cache_service.generation();
+        redirect_cache_service.invalidate(&RedirectInvalidation::Id(1));
+        redirect_cache_service.insert("hello", Some(&url_redirect(1)), generation, now());
+
+        assert!(redirect_cache_service.get("hello", now()).is_none());
+    }
+
+    #[test]
+    fn test_insert_evicts_oldest_when_full() {
+        let redirect_cache_service = RedirectCacheService::new(2, TimeDelta::seconds(60));
+        let generation = redirect_cache_service.generation();
+        redirect_cache_service.insert("a", Some(&url_redirect(1)), generation, now());
+        redirect_cache_service.insert("b", Some(&url_redirect(2)), generation, now());
+        redirect_cache_service.insert("c", Some(&url_redirect(3)), generation, now());
+
+        assert!(redirect_cache_service.get("a", now()).is_none());
+        assert!(redirect_cache_service.get("b", now()).is_some());
+        assert!(redirect_cache_service.get("c", now()).is_some());
+    }
+
+    #[test]
+    fn test_click_limited_links_are_not_cached() {
+        let redirect_cache_service = RedirectCacheService::new(10, TimeDelta::seconds(60));
+        let generation = redirect_cache_service.generation();
+        let limited = UrlRedirect {
+            max_clicks: Some(5),
+            ..url_redirect(1)
+        };
+        redirect_cache_service.insert("hello", Some(&limited), generation, now());
+
+        assert!(redirect_cache_service.get("hello", now()).is_none());
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_cache() {
+        let redirect_cache_service = RedirectCacheService::new(0, TimeDelta::seconds(60));
+        let generation = redirect_cache_service.generation();
+        redirect_cache_service.insert("hello", Some(&url_redirect(1)), generation, now());
+
+        assert!(redirect_cache_service.get("hello", now()).is_none());
+    }
+}
@@ -0,0 +1,121 @@
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "URL shortener with a public redirect server and a backoffice"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the servers enabled in the config (the default)
+    Serve,
+    /// Run only the public redirect server, whatever the config says
+    ServePublic,
+    /// Run only the backoffice server, whatever the config says
+    ServeBackoffice,
+    /// Manage backoffice users
+    #[command(subcommand)]
+    User(UserCommand),
+    /// Manage links
+    #[command(subcommand)]
+    Link(LinkCommand),
+    /// Database maintenance
+    #[command(subcommand)]
+    Db(DbCommand),
+    /// Configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+pub enum UserCommand {
+    /// Create a user, asking for the password when it is not given
+    Add {
+        username: String,
+        #[arg(long, default_value = "user", value_parser = ["root", "user"])]
+        role: String,
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Set a new password and sign the user out everywhere
+    ResetPassword {
+        username: String,
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Change a user's role
+    SetRole {
+        username: String,
+        #[arg(value_parser = ["root", "user"])]
+        role: String,
+    },
+    /// Turn off two-factor login and drop the recovery codes, for a user locked out of it
+    ResetTwoFactor { username: String },
+}
+
+#[derive(Subcommand)]
+pub enum LinkCommand {
+    /// Add a link
+    Add(LinkAddArgs),
+    /// List links, optionally filtered by a search or a tag
+    List {
+        #[arg(long)]
+        search: Option<String>,
+        #[arg(long)]
+        tag: Option<String>,
+        #[arg(long)]
+        page: Option<i64>,
+        #[arg(long)]
+        per_page: Option<i64>,
+    },
+    /// Move a link to the trash by id
+    Delete { id: i64 },
+    /// Restore a link from the trash by id
+    Restore { id: i64 },
+    /// Purge the links that have been in the trash longer than the retention
+    PurgeTrash,
+}
+
+#[derive(Args)]
+pub struct LinkAddArgs {
+    /// Where the link redirects to
+    pub url_redirect: String,
+    /// Short path, a code is generated when left out
+    #[arg(long)]
+    pub path: Option<String>,
+    /// HTTP status used for the redirect
+    #[arg(long)]
+    pub redirect_type: Option<u16>,
+    /// Local date and time, e.g. 2030-01-01T10:00
+    #[arg(long)]
+    pub expires_at: Option<String>,
+    #[arg(long)]
+    pub max_clicks: Option<i64>,
+    #[arg(long)]
+    pub description: Option<String>,
+    /// Repeat for more than one tag
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    /// Username the link belongs to, defaults to the first root user
+    #[arg(long)]
+    pub owner: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Apply pending schema migrations
+    Migrate,
+    /// Write a consistent copy of the database to a new file
+    Backup { path: String },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Load the config and print the merged result
+    Check,
+}
@@ -1,21 +1,127 @@
-use backoffice::export::{MainError, init_log};
-use error_stack::Report;
+mod cli;
+
+use crate::cli::{Cli, Command, ConfigCommand, DbCommand, LinkCommand, UserCommand};
+use backoffice::cli::{CliError, config, db, link, user};
+use backoffice::export::{Config, MainError, init_log, trigger_shutdown};
+use clap::Parser;
 use error_stack::fmt::ColorMode;
+use error_stack::{Report, ResultExt};
+use std::future::Future;
 use tokio::task::JoinHandle;
 
 #[tokio::main]
 async fn main() -> Result<(), Report<MainError>> {
+    let cli = Cli::parse();
     init_log();
     Report::set_color_mode(ColorMode::None);
 
-    let backoffice_handle = tokio::spawn(backoffice::boot());
-    let public_handle = tokio::spawn(public::boot());
-    match tokio::try_join!(flatten(backoffice_handle), flatten(public_handle)) {
-        Ok(_) => Ok(()),
-        Err(err) => Err(err),
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve().await,
+        Command::ServePublic => public::boot().await,
+        Command::ServeBackoffice => backoffice::boot().await,
+        Command::User(command) => run_user(command).await.map_err(command_error),
+        Command::Link(command) => run_link(command).await.map_err(command_error),
+        Command::Db(command) => run_db(command).await.map_err(command_error),
+        Command::Config(ConfigCommand::Check) => config::check().await.map_err(command_error),
+    }
+}
+
+async fn serve() -> Result<(), Report<MainError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(MainError::ConfigError)?
+        .upgrade()
+        .ok_or_else(|| Report::new(MainError::ConfigError))?;
+
+    let mut handles = Vec::new();
+    if config.poem_backoffice.enabled {
+        handles.push(spawn_server(backoffice::boot()));
+    }
+    if config.poem_public.enabled {
+        handles.push(spawn_server(public::boot()));
+    }
+    if handles.is_empty() {
+        return Err(Report::new(MainError::ConfigError)
+            .attach("Both poem_public and poem_backoffice are disabled"));
+    }
+
+    let mut result = Ok(());
+    for handle in handles {
+        if let Err(err) = flatten(handle).await
+            && result.is_ok()
+        {
+            result = Err(err);
+        }
+    }
+    result
+}
+
+/// A server that fails takes the other one down with it, but lets it drain first.
+fn spawn_server(
+    boot: impl Future<Output = Result<(), Report<MainError>>> + Send + 'static,
+) -> JoinHandle<Result<(), Report<MainError>>> {
+    tokio::spawn(async move {
+        let result = boot.await;
+        if result.is_err() {
+            trigger_shutdown();
+        }
+        result
+    })
+}
+
+async fn run_user(command: UserCommand) -> Result<(), Report<CliError>> {
+    match command {
+        UserCommand::Add {
+            username,
+            role,
+            password,
+        } => user::add(&username, &role, password).await,
+        UserCommand::ResetPassword { username, password } => {
+            user::reset_password(&username, password).await
+        }
+        UserCommand::SetRole { username, role } => user::set_role(&username, &role).await,
+        UserCommand::ResetTwoFactor { username } => user::reset_two_factor(&username).await,
+    }
+}
+
+async fn run_link(command: LinkCommand) -> Result<(), Report<CliError>> {
+    match command {
+        LinkCommand::Add(args) => {
+            link::add(link::NewLink {
+                url_redirect: args.url_redirect,
+                url_path: args.path,
+                redirect_type: args.redirect_type,
+                expires_at: args.expires_at,
+                max_clicks: args.max_clicks,
+                description: args.description,
+                tags: args.tags,
+                owner: args.owner,
+            })
+            .await
+        }
+        LinkCommand::List {
+            search,
+            tag,
+            page,
+            per_page,
+        } => link::list(search, tag, page, per_page).await,
+        LinkCommand::Delete { id } => link::delete(id).await,
+        LinkCommand::Restore { id } => link::restore(id).await,
+        LinkCommand::PurgeTrash => link::purge_trash().await,
     }
 }
 
+async fn run_db(command: DbCommand) -> Result<(), Report<CliError>> {
+    match command {
+        DbCommand::Migrate => db::migrate().await,
+        DbCommand::Backup { path } => db::backup(&path).await,
+    }
+}
+
+fn command_error(err: Report<CliError>) -> Report<MainError> {
+    err.change_context(MainError::CommandError)
+}
+
 async fn flatten(
     handle: JoinHandle<Result<(), Report<MainError>>>,
 ) -> Result<(), Report<MainError>> {
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::env::var;
+
+/// The root user created on first run, when the database has no users yet.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BootstrapConfig {
+    pub root_username: String,
+    /// A random password is generated and printed to the console when left out.
+    #[serde(skip_serializing)]
+    pub root_password: Option<String>,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            root_username: "admin".to_string(),
+            root_password: None,
+        }
+    }
+}
+
+impl BootstrapConfig {
+    /// `RUSTY_SHORTY_ROOT_USERNAME` takes priority over the config file.
+    pub fn root_username(&self) -> String {
+        var("RUSTY_SHORTY_ROOT_USERNAME")
+            .ok()
+            .filter(|username| !username.trim().is_empty())
+            .unwrap_or_else(|| self.root_username.clone())
+    }
+
+    /// `RUSTY_SHORTY_ROOT_PASSWORD` takes priority over the config file.
+    pub fn root_password(&self) -> Option<String> {
+        var("RUSTY_SHORTY_ROOT_PASSWORD")
+            .ok()
+            .or_else(|| self.root_password.clone())
+            .filter(|password| !password.is_empty())
+    }
+}
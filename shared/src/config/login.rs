@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Login throttling, tracked separately per username and per client IP.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginConfig {
+    /// Failed attempts allowed for a username before it is locked.
+    pub username_free_attempts: u32,
+    /// Failed attempts allowed from one IP before it is locked, higher as an office
+    /// may share an address.
+    pub ip_free_attempts: u32,
+    /// The first lock, doubled with every failure after it.
+    pub backoff_base_seconds: u64,
+    pub max_lockout_seconds: u64,
+    /// Failures are forgotten after this long without another one.
+    pub reset_after_seconds: u64,
+}
+
+impl Default for LoginConfig {
+    fn default() -> Self {
+        Self {
+            username_free_attempts: 5,
+            ip_free_attempts: 20,
+            backoff_base_seconds: 2,
+            max_lockout_seconds: 900,
+            reset_after_seconds: 3600,
+        }
+    }
+}
@@ -1,23 +1,35 @@
 use crate::context::{Context, ContextError, FromContext};
+use bootstrap::BootstrapConfig;
 use error_stack::{FutureExt, Report, ResultExt};
 use figment::providers::{Format, Serialized, Toml};
 use figment::{Figment, Profile};
+use login::LoginConfig;
 use poem::PoemConfig;
 use serde::{Deserialize, Serialize};
+use session::SessionConfig;
+use shorty::ShortyConfig;
 use sqlite::SqliteConfig;
 use std::env::var;
 use std::ops::Deref;
 use std::sync::{Arc, Weak};
 use thiserror::Error;
 use tokio::sync::OnceCell;
+use totp::TotpConfig;
 
+pub mod bootstrap;
+pub mod login;
 pub mod poem;
+pub mod session;
+pub mod shorty;
 pub mod sqlite;
+pub mod totp;
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("Config did not parse")]
     ParseError,
+    #[error("Config is not valid")]
+    Invalid,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -25,6 +37,11 @@ pub struct Config {
     pub poem_public: Arc<PoemConfig>,
     pub poem_backoffice: Arc<PoemConfig>,
     pub sqlite: Arc<SqliteConfig>,
+    pub shorty: Arc<ShortyConfig>,
+    pub bootstrap: Arc<BootstrapConfig>,
+    pub login: Arc<LoginConfig>,
+    pub totp: Arc<TotpConfig>,
+    pub session: Arc<SessionConfig>,
 }
 
 impl Default for Config {
@@ -34,8 +51,14 @@ impl Default for Config {
             poem_backoffice: Arc::new(PoemConfig {
                 address: "127.0.0.1".to_string(),
                 port: 8001,
+                ..PoemConfig::default()
             }),
             sqlite: Arc::new(SqliteConfig::default()),
+            shorty: Arc::new(ShortyConfig::default()),
+            bootstrap: Arc::new(BootstrapConfig::default()),
+            login: Arc::new(LoginConfig::default()),
+            totp: Arc::new(TotpConfig::default()),
+            session: Arc::new(SessionConfig::default()),
         }
     }
 }
@@ -58,9 +81,11 @@ impl Config {
     }
 
     fn parse() -> Result<Self, Report<ConfigError>> {
-        Self::build_figment()
+        let config = Self::build_figment()
             .extract::<Self>()
-            .change_context(ConfigError::ParseError)
+            .change_context(ConfigError::ParseError)?;
+        config.shorty.validate()?;
+        Ok(config)
     }
 
     pub async fn fetch() -> Result<Weak<Config>, Report<ConfigError>> {
@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PoemConfig {
     pub address: String,
     pub port: u16,
+    /// Whether `rusty-shorty serve` starts this listener.
+    pub enabled: bool,
+    /// How long in-flight requests get to finish once a shutdown signal arrives.
+    pub shutdown_timeout_seconds: u64,
 }
 
 impl Default for PoemConfig {
@@ -11,6 +16,8 @@ impl Default for PoemConfig {
         Self {
             address: "127.0.0.1".to_string(),
             port: 8000,
+            enabled: true,
+            shutdown_timeout_seconds: 30,
         }
     }
 }
@@ -19,4 +26,8 @@ impl PoemConfig {
     pub fn parse_address(&self) -> String {
         format!("{}:{}", self.address, self.port)
     }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_seconds)
+    }
 }
@@ -0,0 +1,31 @@
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+/// Backoffice login sessions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// How long a session lasts from login, however active it is.
+    pub lifetime_seconds: u64,
+    /// A session not seen for this long is signed out, 0 to turn it off.
+    pub idle_timeout_seconds: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            lifetime_seconds: 30 * 24 * 60 * 60,
+            idle_timeout_seconds: 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn lifetime(&self) -> TimeDelta {
+        TimeDelta::seconds(self.lifetime_seconds as i64)
+    }
+
+    pub fn idle_timeout(&self) -> Option<TimeDelta> {
+        (self.idle_timeout_seconds > 0)
+            .then(|| TimeDelta::seconds(self.idle_timeout_seconds as i64))
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::ConfigError;
+use chrono::TimeDelta;
+use error_stack::{Report, ResultExt};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShortyConfig {
+    pub gone_redirect_url: Option<String>,
+    pub gone_page_path: Option<String>,
+    pub short_code_alphabet: String,
+    pub short_code_length: usize,
+    pub redirect_cache_capacity: usize,
+    pub redirect_cache_ttl_seconds: u64,
+    /// Deleted links are kept this long before they are purged, 0 keeps them forever.
+    pub trash_retention_days: u32,
+    /// Refuses a destination that another link already points at.
+    pub unique_url_redirect: bool,
+}
+
+impl Default for ShortyConfig {
+    fn default() -> Self {
+        Self {
+            gone_redirect_url: None,
+            gone_page_path: None,
+            // No 0/o, 1/l/i, so codes survive being read aloud or retyped.
+            short_code_alphabet: "23456789abcdefghjkmnpqrstuvwxyz".to_string(),
+            short_code_length: 7,
+            redirect_cache_capacity: 10_000,
+            redirect_cache_ttl_seconds: 60,
+            trash_retention_days: 30,
+            unique_url_redirect: true,
+        }
+    }
+}
+
+impl ShortyConfig {
+    pub fn validate(&self) -> Result<(), Report<ConfigError>> {
+        if let Some(gone_page_path) = &self.gone_page_path {
+            File::open(gone_page_path)
+                .change_context(ConfigError::Invalid)
+                .attach_with(|| format!("Gone page '{}' cannot be read", gone_page_path))?;
+        }
+        Ok(())
+    }
+
+    pub fn trash_retention(&self) -> Option<TimeDelta> {
+        (self.trash_retention_days > 0).then(|| TimeDelta::days(self.trash_retention_days as i64))
+    }
+}
@@ -3,12 +3,15 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SqliteConfig {
     pub path: String,
+    /// Read only connections used alongside the single writer.
+    pub read_connections: usize,
 }
 
 impl Default for SqliteConfig {
     fn default() -> Self {
         Self {
             path: "./sqlite.db".to_string(),
+            read_connections: 4,
         }
     }
 }
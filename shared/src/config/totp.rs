@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Two-factor login with authenticator apps.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TotpConfig {
+    /// Shown as the account's label in the authenticator app.
+    pub issuer: String,
+    /// The key that encrypts stored secrets, generated on first use when the file is
+    /// missing. `RUSTY_SHORTY_TOTP_KEY` (32 bytes in base64) takes priority.
+    pub key_path: String,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "Rusty Shorty".to_string(),
+            key_path: "./totp.key".to_string(),
+        }
+    }
+}
@@ -8,10 +8,13 @@ use serde_json::{Value, json};
 use std::error::Error;
 use thiserror::Error;
 
-pub const CSRF_PATH: &'static str = "/csrf/";
+pub const CSRF_PATH: &str = "/csrf/";
+pub const CSRF_HEADER: &str = "X-Csrf-Token";
 
 pub trait CsrfTokenHtml {
     fn as_html(&self) -> Markup;
+    /// For `hx-headers`, on buttons that post without a form.
+    fn as_hx_headers(&self) -> String;
 }
 
 impl CsrfTokenHtml for CsrfToken {
@@ -20,6 +23,10 @@ impl CsrfTokenHtml for CsrfToken {
             input type="hidden" name="csrf_token" value=(self.0);
         }
     }
+
+    fn as_hx_headers(&self) -> String {
+        json!({ CSRF_HEADER: self.0 }).to_string()
+    }
 }
 
 #[derive(Debug, Error)]
@@ -57,7 +64,7 @@ impl<E: Endpoint> Endpoint for CsrfTokenChecker<E> {
     type Output = Response;
 
     async fn call(&self, req: Request) -> poem::Result<Self::Output> {
-        let token = req.header("X-Csrf-Token").ok_or_else(|| CsrfError)?;
+        let token = req.header(CSRF_HEADER).ok_or(CsrfError)?;
 
         match req.data::<CsrfVerifier>() {
             None => Ok(self.0.call(req).await?.into_response()),
@@ -0,0 +1,2 @@
+insert into schema_version (version, name, applied_at)
+values (:version, :name, datetime())
\ No newline at end of file
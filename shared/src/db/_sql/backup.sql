@@ -0,0 +1 @@
+vacuum into :path
\ No newline at end of file
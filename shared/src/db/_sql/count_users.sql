@@ -0,0 +1,2 @@
+select count(*)
+from backoffice_users
\ No newline at end of file
@@ -0,0 +1,2 @@
+select max(version)
+from schema_version
\ No newline at end of file
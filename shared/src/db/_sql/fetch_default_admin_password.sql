@@ -0,0 +1,3 @@
+select id, password
+from backoffice_users
+where username = 'admin'
\ No newline at end of file
@@ -0,0 +1,3 @@
+update backoffice_users
+set must_change_password = 1
+where id = :id
\ No newline at end of file
@@ -0,0 +1,8 @@
+select case
+           when exists(select 1 from sqlite_master where name = 'url_redirect_search') then 6
+           when exists(select 1 from sqlite_master where name = 'user_api_tokens') then 5
+           when exists(select 1 from pragma_table_info('url_redirect') where name = 'redirect_type') then 4
+           when exists(select 1 from pragma_table_info('url_redirect') where name = 'max_clicks') then 3
+           when exists(select 1 from pragma_table_info('url_redirect') where name = 'hit_count') then 2
+           else 1
+           end
\ No newline at end of file
@@ -1,5 +1,3 @@
-PRAGMA foreign_keys = ON;
-
 create table backoffice_users
 (
     id       integer primary key autoincrement not null,
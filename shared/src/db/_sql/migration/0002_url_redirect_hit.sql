@@ -0,0 +1,16 @@
+alter table url_redirect
+    add column hit_count integer default 0 not null;
+
+create table url_redirect_hit
+(
+    id              integer primary key autoincrement not null,
+    url_redirect_id integer                           not null,
+    hit_at          text                              not null,
+    referer         text,
+    user_agent      text,
+    client_ip       text,
+    foreign key (url_redirect_id) references url_redirect (id) on delete cascade
+);
+
+create index url_redirect_hit_url_redirect_id_hit_at
+    on url_redirect_hit (url_redirect_id, hit_at);
\ No newline at end of file
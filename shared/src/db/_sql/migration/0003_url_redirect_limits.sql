@@ -0,0 +1,5 @@
+alter table url_redirect
+    add column expires_at text;
+
+alter table url_redirect
+    add column max_clicks integer;
\ No newline at end of file
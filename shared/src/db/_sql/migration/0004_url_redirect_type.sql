@@ -0,0 +1,2 @@
+alter table url_redirect
+    add column redirect_type integer default 303 not null;
\ No newline at end of file
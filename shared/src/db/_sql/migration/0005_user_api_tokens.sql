@@ -0,0 +1,12 @@
+create table user_api_tokens
+(
+    id           integer primary key autoincrement not null,
+    user_id      integer                           not null,
+    name         text                              not null,
+    token_hash   text unique                       not null,
+    scope        text                              not null,
+    created_at   text                              not null,
+    last_used_at text,
+    expires_at   text,
+    foreign key (user_id) references backoffice_users (id) on delete cascade
+);
\ No newline at end of file
@@ -0,0 +1,40 @@
+alter table url_redirect
+    add column description text;
+
+create virtual table url_redirect_search using fts5
+(
+    url_path,
+    url_redirect,
+    description,
+    content = 'url_redirect',
+    content_rowid = 'id'
+);
+
+create trigger url_redirect_search_insert
+    after insert
+    on url_redirect
+begin
+    insert into url_redirect_search (rowid, url_path, url_redirect, description)
+    values (new.id, new.url_path, new.url_redirect, new.description);
+end;
+
+create trigger url_redirect_search_delete
+    after delete
+    on url_redirect
+begin
+    insert into url_redirect_search (url_redirect_search, rowid, url_path, url_redirect, description)
+    values ('delete', old.id, old.url_path, old.url_redirect, old.description);
+end;
+
+create trigger url_redirect_search_update
+    after update of url_path, url_redirect, description
+    on url_redirect
+begin
+    insert into url_redirect_search (url_redirect_search, rowid, url_path, url_redirect, description)
+    values ('delete', old.id, old.url_path, old.url_redirect, old.description);
+    insert into url_redirect_search (rowid, url_path, url_redirect, description)
+    values (new.id, new.url_path, new.url_redirect, new.description);
+end;
+
+insert into url_redirect_search (url_redirect_search)
+values ('rebuild');
\ No newline at end of file
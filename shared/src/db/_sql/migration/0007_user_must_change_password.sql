@@ -0,0 +1,2 @@
+alter table backoffice_users
+    add column must_change_password integer not null default 0;
\ No newline at end of file
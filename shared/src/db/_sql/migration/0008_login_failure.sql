@@ -0,0 +1,10 @@
+create table login_failure
+(
+    id         integer primary key autoincrement not null,
+    username   text                              not null,
+    client_ip  text,
+    throttled  integer                           not null,
+    failed_at  text                              not null
+);
+
+create index login_failure_failed_at on login_failure (failed_at);
\ No newline at end of file
@@ -0,0 +1,30 @@
+create table user_totp
+(
+    user_id        integer primary key not null,
+    secret         blob                not null,
+    enabled        integer             not null default 0,
+    last_used_step integer             not null default 0,
+    created_at     text                not null,
+    foreign key (user_id) references backoffice_users (id) on delete cascade
+);
+
+create table user_totp_recovery_code
+(
+    id        integer primary key autoincrement not null,
+    user_id   integer                           not null,
+    code_hash text                              not null,
+    used_at   text,
+    foreign key (user_id) references backoffice_users (id) on delete cascade
+);
+
+create index user_totp_recovery_code_user_id on user_totp_recovery_code (user_id);
+
+create table login_challenge
+(
+    token_hash text unique not null,
+    user_id    integer     not null,
+    username   text        not null,
+    attempts   integer     not null default 0,
+    expires_at text        not null,
+    foreign key (user_id) references backoffice_users (id) on delete cascade
+);
\ No newline at end of file
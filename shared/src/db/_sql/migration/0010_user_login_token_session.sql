@@ -0,0 +1,23 @@
+create table user_login_tokens_session
+(
+    id           integer primary key autoincrement not null,
+    user_id      integer                           not null,
+    token        text unique                       not null,
+    created_at   text                              not null,
+    last_seen_at text                              not null,
+    expire_after text                              not null,
+    client_ip    text,
+    user_agent   text,
+    foreign key (user_id) references backoffice_users (id) on delete cascade
+);
+
+insert into user_login_tokens_session (user_id, token, created_at, last_seen_at, expire_after)
+select user_id, token, datetime(), datetime(), expire_after
+from user_login_tokens;
+
+drop table user_login_tokens;
+
+alter table user_login_tokens_session
+    rename to user_login_tokens;
+
+create index user_login_tokens_user_id on user_login_tokens (user_id);
\ No newline at end of file
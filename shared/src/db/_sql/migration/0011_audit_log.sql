@@ -0,0 +1,30 @@
+create table audit_log
+(
+    id             integer primary key autoincrement not null,
+    actor_user_id  integer,
+    actor_username text,
+    action         text                              not null,
+    target_type    text                              not null,
+    target_id      integer,
+    diff           text                              not null,
+    client_ip      text,
+    created_at     text                              not null
+);
+
+create index audit_log_created_at on audit_log (created_at);
+create index audit_log_actor_user_id on audit_log (actor_user_id);
+create index audit_log_action on audit_log (action);
+
+create trigger audit_log_no_update
+    before update
+    on audit_log
+begin
+    select raise(abort, 'audit_log is append only');
+end;
+
+create trigger audit_log_no_delete
+    before delete
+    on audit_log
+begin
+    select raise(abort, 'audit_log is append only');
+end;
\ No newline at end of file
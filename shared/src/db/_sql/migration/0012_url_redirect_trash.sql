@@ -0,0 +1,4 @@
+alter table url_redirect
+    add column deleted_at text;
+
+create index url_redirect_deleted_at on url_redirect (deleted_at);
\ No newline at end of file
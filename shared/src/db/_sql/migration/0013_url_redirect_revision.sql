@@ -0,0 +1,17 @@
+create table url_redirect_revision
+(
+    id                 integer primary key autoincrement not null,
+    url_redirect_id    integer                           not null,
+    url_path           text                              not null,
+    url_redirect       text                              not null,
+    changed_by_user_id integer,
+    created_at         text                              not null,
+    foreign key (url_redirect_id) references url_redirect (id) on delete cascade,
+    foreign key (changed_by_user_id) references backoffice_users (id) on delete set null
+);
+
+create index url_redirect_revision_url_redirect_id on url_redirect_revision (url_redirect_id, id);
+
+insert into url_redirect_revision (url_redirect_id, url_path, url_redirect, changed_by_user_id, created_at)
+select id, url_path, url_redirect, created_by_user_id, created_at
+from url_redirect;
\ No newline at end of file
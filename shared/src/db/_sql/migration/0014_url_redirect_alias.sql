@@ -0,0 +1,106 @@
+create table url_redirect_new
+(
+    id                 integer primary key autoincrement not null,
+    url_path           text unique                       not null,
+    url_redirect       text                              not null,
+    created_at         text                              not null,
+    created_by_user_id integer                           not null,
+    hit_count          integer default 0                 not null,
+    expires_at         text,
+    max_clicks         integer,
+    redirect_type      integer default 303               not null,
+    description        text,
+    deleted_at         text,
+    foreign key (created_by_user_id) references backoffice_users (id) on delete cascade
+);
+
+insert into url_redirect_new (id, url_path, url_redirect, created_at, created_by_user_id, hit_count, expires_at,
+                              max_clicks, redirect_type, description, deleted_at)
+select id,
+       url_path,
+       url_redirect,
+       created_at,
+       created_by_user_id,
+       hit_count,
+       expires_at,
+       max_clicks,
+       redirect_type,
+       description,
+       deleted_at
+from url_redirect;
+
+delete
+from sqlite_sequence
+where name = 'url_redirect_new';
+insert into sqlite_sequence (name, seq)
+select 'url_redirect_new', seq
+from sqlite_sequence
+where name = 'url_redirect';
+
+drop table url_redirect;
+alter table url_redirect_new
+    rename to url_redirect;
+
+create index url_redirect_url_redirect on url_redirect (url_redirect);
+create index url_redirect_deleted_at on url_redirect (deleted_at);
+
+create trigger url_redirect_search_insert
+    after insert
+    on url_redirect
+begin
+    insert into url_redirect_search (rowid, url_path, url_redirect, description)
+    values (new.id, new.url_path, new.url_redirect, new.description);
+end;
+
+create trigger url_redirect_search_delete
+    after delete
+    on url_redirect
+begin
+    insert into url_redirect_search (url_redirect_search, rowid, url_path, url_redirect, description)
+    values ('delete', old.id, old.url_path, old.url_redirect, old.description);
+end;
+
+create trigger url_redirect_search_update
+    after update of url_path, url_redirect, description
+    on url_redirect
+begin
+    insert into url_redirect_search (url_redirect_search, rowid, url_path, url_redirect, description)
+    values ('delete', old.id, old.url_path, old.url_redirect, old.description);
+    insert into url_redirect_search (rowid, url_path, url_redirect, description)
+    values (new.id, new.url_path, new.url_redirect, new.description);
+end;
+
+create table url_redirect_alias
+(
+    id              integer primary key autoincrement not null,
+    url_redirect_id integer                           not null,
+    url_path        text unique                       not null,
+    created_at      text                              not null,
+    foreign key (url_redirect_id) references url_redirect (id) on delete cascade
+);
+
+create index url_redirect_alias_url_redirect_id on url_redirect_alias (url_redirect_id);
+
+create trigger url_redirect_alias_path_taken
+    before insert
+    on url_redirect_alias
+    when exists(select 1 from url_redirect where url_path = new.url_path)
+begin
+    select raise(abort, 'url_path is taken');
+end;
+
+create trigger url_redirect_path_taken_insert
+    before insert
+    on url_redirect
+    when exists(select 1 from url_redirect_alias where url_path = new.url_path)
+begin
+    select raise(abort, 'url_path is taken');
+end;
+
+create trigger url_redirect_path_taken_update
+    before update of url_path
+    on url_redirect
+    when exists(select 1 from url_redirect_alias where url_path = new.url_path)
+begin
+    select raise(abort, 'url_path is taken');
+end;
\ No newline at end of file
@@ -0,0 +1,9 @@
+create table url_redirect_tag
+(
+    url_redirect_id integer not null,
+    tag             text    not null,
+    primary key (url_redirect_id, tag),
+    foreign key (url_redirect_id) references url_redirect (id) on delete cascade
+) without rowid;
+
+create index url_redirect_tag_tag on url_redirect_tag (tag, url_redirect_id);
\ No newline at end of file
@@ -0,0 +1,161 @@
+use crate::config::bootstrap::BootstrapConfig;
+use crate::db::{BorrowConnectionExt, ConnectionMarker, SqliteClient, SqliteClientError};
+use crate::error::ExtraResultExt;
+use crate::password::Password;
+use error_stack::{Report, ResultExt};
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use rusqlite::{Connection, OptionalExtension, named_params};
+
+const GENERATED_PASSWORD_LENGTH: usize = 24;
+
+/// The schema version that added `must_change_password`.
+pub(crate) const MUST_CHANGE_PASSWORD_VERSION: i64 = 7;
+
+impl<T: ConnectionMarker> SqliteClient<T> {
+    /// Creates the first root user when there are no users, with the credentials from
+    /// [`BootstrapConfig`] or a generated password that is printed once. Either way the
+    /// password has to be changed on first login.
+    pub fn bootstrap_root_user(
+        &self,
+        config: &BootstrapConfig,
+    ) -> Result<(), Report<SqliteClientError>> {
+        let conn = self.borrow_conn()?;
+        let users: i64 = conn
+            .query_one(include_str!("_sql/count_users.sql"), [], |row| row.get(0))
+            .change_context(SqliteClientError::InitFailed)
+            .attach_critical("Failed to count users".to_string())?;
+        if users > 0 {
+            return Ok(());
+        }
+
+        let username = config.root_username();
+        let (password, generated) = match config.root_password() {
+            Some(password) => (password, false),
+            None => (generate_password(), true),
+        };
+        let password_hash = Password::hash_password(password.clone())
+            .change_context(SqliteClientError::InitFailed)
+            .attach_critical("Failed to hash password".to_string())?
+            .encode_to_msg_pack()
+            .change_context(SqliteClientError::InitFailed)
+            .attach_critical("Failed to encode password".to_string())?;
+        conn.execute(
+            include_str!("_sql/add_user.sql"),
+            named_params! {
+                ":username": username,
+                ":password": password_hash.to_vec(),
+            },
+        )
+        .change_context(SqliteClientError::InitFailed)
+        .attach_critical("Failed to create root user".to_string())?;
+
+        if generated {
+            println!("Created the root user '{}' with the password:", username);
+            println!();
+            println!("    {}", password);
+            println!();
+            println!("It is only shown once and must be changed on first login.");
+        } else {
+            println!("Created the root user '{}' from the config", username);
+        }
+        Ok(())
+    }
+}
+
+/// Databases created before bootstrapping existed still have `admin`/`banana`; if that
+/// password was never changed, it has to be on the next login.
+pub(crate) fn flag_default_admin_password(
+    conn: &Connection,
+) -> Result<(), Report<SqliteClientError>> {
+    let admin: Option<(i64, Box<[u8]>)> = conn
+        .query_one(
+            include_str!("_sql/fetch_default_admin_password.sql"),
+            [],
+            |row| Ok((row.get("id")?, row.get("password")?)),
+        )
+        .optional()
+        .change_context(SqliteClientError::MigrationFailed)
+        .attach_critical("Failed to fetch the default admin".to_string())?;
+    let Some((id, password)) = admin else {
+        return Ok(());
+    };
+    let is_default = Password::verify_password(password, "banana".to_string())
+        .map(|state| state.is_valid())
+        .unwrap_or_default();
+    if is_default {
+        conn.execute(
+            include_str!("_sql/flag_must_change_password.sql"),
+            named_params! {
+                ":id": id,
+            },
+        )
+        .change_context(SqliteClientError::MigrationFailed)
+        .attach_critical("Failed to flag the default admin".to_string())?;
+        log::warn!("The admin user still has the default password, it must be changed on login");
+    }
+    Ok(())
+}
+
+fn generate_password() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(GENERATED_PASSWORD_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migration::migrate;
+
+    fn must_change_password(conn: &Connection, username: &str) -> bool {
+        conn.query_one(
+            "select must_change_password from backoffice_users where username = ?1",
+            [username],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn add_admin(conn: &Connection, password: &str) {
+        let password = Password::hash_password(password.to_string())
+            .unwrap()
+            .encode_to_msg_pack()
+            .unwrap();
+        conn.execute(
+            "insert into backoffice_users (username, password, role) values ('admin', ?1, 'root')",
+            [password.to_vec()],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_generate_password() {
+        let password = generate_password();
+        assert_eq!(password.len(), GENERATED_PASSWORD_LENGTH);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(password, generate_password());
+    }
+
+    #[test]
+    fn test_flag_default_admin_password() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        add_admin(&conn, "banana");
+
+        flag_default_admin_password(&conn).unwrap();
+        assert!(must_change_password(&conn, "admin"));
+    }
+
+    #[test]
+    fn test_flag_default_admin_password_leaves_changed_password() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        add_admin(&conn, "something else");
+
+        flag_default_admin_password(&conn).unwrap();
+        assert!(!must_change_password(&conn, "admin"));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::db::SqliteClientError;
+use crate::error::{ExtraResultExt, FromIntoStackError};
+use error_stack::{Report, ResultExt};
+use rusqlite::{Connection, named_params};
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Append only, never edit a migration that has shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        sql: include_str!("_sql/migration/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "url_redirect_hit",
+        sql: include_str!("_sql/migration/0002_url_redirect_hit.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "url_redirect_limits",
+        sql: include_str!("_sql/migration/0003_url_redirect_limits.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "url_redirect_type",
+        sql: include_str!("_sql/migration/0004_url_redirect_type.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "user_api_tokens",
+        sql: include_str!("_sql/migration/0005_user_api_tokens.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "url_redirect_search",
+        sql: include_str!("_sql/migration/0006_url_redirect_search.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "user_must_change_password",
+        sql: include_str!("_sql/migration/0007_user_must_change_password.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "login_failure",
+        sql: include_str!("_sql/migration/0008_login_failure.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "user_totp",
+        sql: include_str!("_sql/migration/0009_user_totp.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "user_login_token_session",
+        sql: include_str!("_sql/migration/0010_user_login_token_session.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "audit_log",
+        sql: include_str!("_sql/migration/0011_audit_log.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "url_redirect_trash",
+        sql: include_str!("_sql/migration/0012_url_redirect_trash.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "url_redirect_revision",
+        sql: include_str!("_sql/migration/0013_url_redirect_revision.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "url_redirect_alias",
+        sql: include_str!("_sql/migration/0014_url_redirect_alias.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "url_redirect_tag",
+        sql: include_str!("_sql/migration/0015_url_redirect_tag.sql"),
+    },
+];
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS
+        .last()
+        .map(|migration| migration.version)
+        .unwrap_or_default()
+}
+
+fn add_schema_version(
+    conn: &Connection,
+    migration: &Migration,
+) -> Result<(), Report<SqliteClientError>> {
+    conn.execute(
+        include_str!("_sql/add_schema_version.sql"),
+        named_params! {
+            ":version": migration.version,
+            ":name": migration.name,
+        },
+    )
+    .change_context(SqliteClientError::MigrationFailed)
+    .attach_critical_lazy(|| format!("Failed to record migration {}", migration.version))?;
+    Ok(())
+}
+
+fn current_version(conn: &Connection) -> Result<i64, Report<SqliteClientError>> {
+    let version: Option<i64> = conn
+        .query_one(include_str!("_sql/current_schema_version.sql"), [], |row| {
+            row.get(0)
+        })
+        .change_context(SqliteClientError::MigrationFailed)
+        .attach_critical("Failed to read schema version".to_string())?;
+    if let Some(version) = version {
+        return Ok(version);
+    }
+
+    let is_legacy: bool = conn
+        .query_one(include_str!("_sql/legacy_schema_check.sql"), [], |row| {
+            row.get(0)
+        })
+        .change_context(SqliteClientError::MigrationFailed)
+        .attach_critical("Failed to check for an existing schema".to_string())?;
+    if !is_legacy {
+        return Ok(0);
+    }
+
+    // Databases created before migrations existed got whatever init.sql had at the time,
+    // so the migrations already covered by it are recorded as applied.
+    let legacy_version: i64 = conn
+        .query_one(include_str!("_sql/legacy_schema_version.sql"), [], |row| {
+            row.get(0)
+        })
+        .change_context(SqliteClientError::MigrationFailed)
+        .attach_critical("Failed to check the existing schema version".to_string())?;
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version <= legacy_version)
+    {
+        add_schema_version(conn, migration)?;
+    }
+    Ok(legacy_version)
+}
+
+/// Applies every pending migration, each in its own transaction, and returns the
+/// version the database was at beforehand (`0` for a new database).
+///
+/// Runs before foreign keys are switched on, so a migration can rebuild a table without
+/// cascading deletes into the tables that reference it.
+pub fn migrate(conn: &mut Connection) -> Result<i64, Report<SqliteClientError>> {
+    conn.execute_batch(include_str!("_sql/create_schema_version.sql"))
+        .change_context(SqliteClientError::MigrationFailed)
+        .attach_critical("Failed to create schema version table".to_string())?;
+
+    let previous_version = current_version(conn)?;
+    let latest_version = latest_version();
+    if previous_version > latest_version {
+        return Err(
+            SqliteClientError::SchemaTooNew(previous_version, latest_version)
+                .into_stack_error_critical(
+                    "Refusing to start on a database migrated by a newer release".to_string(),
+                ),
+        );
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > previous_version)
+    {
+        let tx = conn
+            .transaction()
+            .change_context(SqliteClientError::MigrationFailed)
+            .attach_critical("Failed to start migration transaction".to_string())?;
+        tx.execute_batch(migration.sql)
+            .change_context(SqliteClientError::MigrationFailed)
+            .attach_critical_lazy(|| {
+                format!(
+                    "Migration {} ({}) failed",
+                    migration.version, migration.name
+                )
+            })?;
+        add_schema_version(&tx, migration)?;
+        tx.commit()
+            .change_context(SqliteClientError::MigrationFailed)
+            .attach_critical_lazy(|| format!("Failed to commit migration {}", migration.version))?;
+        log::info!(
+            "Applied migration {} ({})",
+            migration.version,
+            migration.name
+        );
+    }
+
+    Ok(previous_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_exists(conn: &Connection, name: &str) -> bool {
+        conn.query_one(
+            "select exists(select 1 from sqlite_master where name = ?1)",
+            [name],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i64 + 1);
+        }
+    }
+
+    #[test]
+    fn test_migrate_new_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), 0);
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(table_exists(&conn, "url_redirect_search"));
+
+        assert_eq!(migrate(&mut conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn test_migrate_legacy_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute(
+            "insert into backoffice_users (username, password, role) values ('admin', x'00', 'root')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "insert into url_redirect (url_path, url_redirect, created_at, created_by_user_id) \
+             values ('spring-sale', 'https://example.com/sale', datetime(), 1)",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), 1);
+
+        let found: i64 = conn
+            .query_one(
+                "select count(*) from url_redirect_search where url_redirect_search match 'sale'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(found, 1);
+    }
+
+    #[test]
+    fn test_migrate_legacy_database_with_hits() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute_batch(MIGRATIONS[1].sql).unwrap();
+        conn.execute_batch(MIGRATIONS[2].sql).unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), 3);
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn test_migrate_url_redirect_rebuild_keeps_hits() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("_sql/create_schema_version.sql"))
+            .unwrap();
+        for migration in MIGRATIONS.iter().filter(|migration| migration.version < 14) {
+            conn.execute_batch(migration.sql).unwrap();
+            add_schema_version(&conn, migration).unwrap();
+        }
+        conn.execute_batch(
+            "insert into backoffice_users (username, password, role) values ('admin', x'00', 'root'); \
+             insert into url_redirect (url_path, url_redirect, created_at, created_by_user_id) \
+             values ('spring-sale', 'https://example.com/sale', datetime(), 1); \
+             insert into url_redirect_hit (url_redirect_id, hit_at) values (1, datetime());",
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), 13);
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+
+        let hits: i64 = conn
+            .query_one("select count(*) from url_redirect_hit", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(hits, 1);
+        // The destination no longer has to be unique, the path still does, aliases included.
+        conn.execute(
+            "insert into url_redirect (url_path, url_redirect, created_at, created_by_user_id) \
+             values ('summer-sale', 'https://example.com/sale', datetime(), 1)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(conn.last_insert_rowid(), 2);
+        assert!(
+            conn.execute(
+                "insert into url_redirect_alias (url_redirect_id, url_path, created_at) \
+                 values (2, 'spring-sale', datetime())",
+                [],
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_migrate_refuses_newer_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn.execute(
+            "insert into schema_version (version, name, applied_at) values (?1, 'future', datetime())",
+            [latest_version() + 1],
+        )
+        .unwrap();
+
+        let err = migrate(&mut conn).unwrap_err();
+        assert!(matches!(
+            err.current_context(),
+            SqliteClientError::SchemaTooNew(..)
+        ));
+    }
+}
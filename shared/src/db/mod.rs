@@ -1,15 +1,20 @@
-use crate::config::ConfigPointer;
+use crate::config::Config;
 use crate::context::{Context, ContextError, FromContext};
 use crate::error::{ExtraResultExt, FromIntoStackError, LogItExt};
-use crate::password::Password;
 use error_stack::{Report, ResultExt};
 use poem::http::StatusCode;
-use rusqlite::{Connection, named_params};
+use rusqlite::{Connection, OpenFlags, named_params};
+use std::error::Error;
 use std::marker::PhantomData;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::OnceCell;
 
+pub mod bootstrap;
+pub mod migration;
+
 pub trait ConnectionMarker: Send + Sync {}
 
 pub struct DefaultConnection;
@@ -24,57 +29,108 @@ pub enum SqliteClientError {
     Connection,
     #[error("Init failed")]
     InitFailed,
+    #[error("Migration failed")]
+    MigrationFailed,
+    #[error("Database schema version {0} is newer than the supported version {1}")]
+    SchemaTooNew(i64, i64),
     #[error("Connection Option Empty error")]
     OptionEmpty,
     #[error("Lock error: {0}")]
     LockError(String),
+    #[error("Backup failed")]
+    BackupFailed,
 }
 
 impl FromIntoStackError for SqliteClientError {}
 
-pub struct SqliteClient<T = DefaultConnection>(Arc<Mutex<Connection>>, PhantomData<T>)
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One connection for writes and a set of read only connections, which WAL lets read
+/// alongside the writer without waiting on it.
+pub struct SqlitePool {
+    writer: Mutex<Connection>,
+    readers: Box<[Mutex<Connection>]>,
+    next_reader: AtomicUsize,
+}
+
+pub struct SqliteClient<T = DefaultConnection>(Arc<SqlitePool>, PhantomData<T>)
 where
     T: ConnectionMarker;
 
 impl<T: ConnectionMarker> SqliteClient<T> {
-    pub fn new(sqlite_path: String) -> Result<Self, Report<SqliteClientError>> {
+    pub fn new(
+        sqlite_path: String,
+        read_connections: usize,
+    ) -> Result<Self, Report<SqliteClientError>> {
         if sqlite_path.is_empty() {
             return Err(SqliteClientError::SqliteFileEmpty
                 .into_stack_error_critical("Sqlite file path is empty".to_string()));
         }
-        let file_exist = std::fs::metadata(&sqlite_path).is_ok();
-
-        let conn = Connection::open(sqlite_path)
+        let mut conn = Connection::open(&sqlite_path)
             .change_context(SqliteClientError::Connection)
             .attach_critical("Sqlite Connection failed".to_string())?;
-        if !file_exist {
-            conn.execute_batch(include_str!("_sql/init.sql"))
-                .change_context(SqliteClientError::InitFailed)
-                .attach_critical("Init failed".to_string())?;
-
-            let password = Password::hash_password("banana".to_string())
-                .change_context(SqliteClientError::InitFailed)
-                .attach_critical("Failed to hash password".to_string())?
-                .encode_to_msg_pack()
-                .change_context(SqliteClientError::InitFailed)
-                .attach_critical("Failed to encode password".to_string())?;
-
-            conn.execute(
-                include_str!("_sql/add_user.sql",),
-                named_params! {
-                    ":username": "admin",
-                    ":password": password.to_vec(),
-                },
-            )
-            .change_context(SqliteClientError::InitFailed)
-            .attach_critical("Failed to create default user".to_string())?;
+        conn.pragma_update(None, "journal_mode", "wal")
+            .and_then(|_| conn.pragma_update(None, "synchronous", "normal"))
+            .and_then(|_| conn.busy_timeout(BUSY_TIMEOUT))
+            .change_context(SqliteClientError::Connection)
+            .attach_critical("Failed to enable WAL mode".to_string())?;
+        let previous_version = migration::migrate(&mut conn)?;
+        conn.pragma_update(None, "foreign_keys", true)
+            .change_context(SqliteClientError::Connection)
+            .attach_critical("Failed to enable foreign keys".to_string())?;
+        if (1..bootstrap::MUST_CHANGE_PASSWORD_VERSION).contains(&previous_version) {
+            bootstrap::flag_default_admin_password(&conn)?;
         }
 
-        Ok(SqliteClient(Arc::new(Mutex::new(conn)), PhantomData))
+        let readers = (0..read_connections.max(1))
+            .map(|_| Self::open_reader(&sqlite_path).map(Mutex::new))
+            .collect::<Result<_, _>>()?;
+
+        Ok(SqliteClient(
+            Arc::new(SqlitePool {
+                writer: Mutex::new(conn),
+                readers,
+                next_reader: AtomicUsize::new(0),
+            }),
+            PhantomData,
+        ))
+    }
+
+    fn open_reader(sqlite_path: &str) -> Result<Connection, Report<SqliteClientError>> {
+        let conn = Connection::open_with_flags(
+            sqlite_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX
+                | OpenFlags::SQLITE_OPEN_URI,
+        )
+        .change_context(SqliteClientError::Connection)
+        .attach_critical("Sqlite read connection failed".to_string())?;
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .change_context(SqliteClientError::Connection)
+            .attach_critical("Failed to set busy timeout".to_string())?;
+        Ok(conn)
     }
 
     pub fn get_conn(&self) -> &Mutex<Connection> {
-        self.0.as_ref()
+        &self.0.writer
+    }
+
+    /// Prefers an idle reader, and only waits when every reader is busy.
+    pub fn get_read_conn(
+        &self,
+    ) -> Result<MutexGuard<'_, Connection>, TryLockError<MutexGuard<'_, Connection>>> {
+        let readers = &self.0.readers;
+        let start = self.0.next_reader.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..readers.len() {
+            match readers[(start + offset) % readers.len()].try_lock() {
+                Ok(guard) => return Ok(guard),
+                Err(TryLockError::WouldBlock) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        readers[start % readers.len()]
+            .lock()
+            .map_err(TryLockError::Poisoned)
     }
 }
 
@@ -86,25 +142,69 @@ impl<T: ConnectionMarker> Clone for SqliteClient<T> {
 
 static SQLITE_CLIENT_CACHE: OnceCell<SqliteClient> = OnceCell::const_new();
 
-impl FromContext for SqliteClient {
-    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        let sqlite_client: Result<&Self, Report<ContextError>> = SQLITE_CLIENT_CACHE
+impl SqliteClient {
+    /// Opens the database, runs the migrations and creates the first root user, called at
+    /// boot so a bad schema stops the server before it starts listening.
+    pub async fn init() -> Result<Self, Report<SqliteClientError>> {
+        let sqlite_client: Result<&Self, Report<SqliteClientError>> = SQLITE_CLIENT_CACHE
             .get_or_try_init(|| async {
-                let config: ConfigPointer = ctx.inject().await?;
-                Ok(Self::new(config.sqlite.path.clone()).change_context(ContextError::Other)?)
+                let config = Config::fetch()
+                    .await
+                    .change_context(SqliteClientError::Connection)?
+                    .upgrade()
+                    .ok_or_else(|| {
+                        Report::new(SqliteClientError::Connection).attach("Config not found")
+                    })?;
+                let sqlite_client =
+                    Self::new(config.sqlite.path.clone(), config.sqlite.read_connections)?;
+                sqlite_client.bootstrap_root_user(&config.bootstrap)?;
+                Ok(sqlite_client)
             })
             .await;
         Ok(sqlite_client?.clone())
     }
 }
 
+impl<T: ConnectionMarker + 'static> SqliteClient<T> {
+    /// Writes a consistent copy of the database to `path`, which must not exist yet.
+    pub async fn backup(&self, path: String) -> Result<(), Report<SqliteClientError>> {
+        self.write(SqliteClientError::BackupFailed, move |conn| {
+            conn.execute(
+                include_str!("_sql/backup.sql"),
+                named_params! {
+                    ":path": path,
+                },
+            )
+            .change_context(SqliteClientError::BackupFailed)?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl FromContext for SqliteClient {
+    async fn from_context(_ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Self::init().await.change_context(ContextError::Other)
+    }
+}
+
 pub trait BorrowConnectionExt {
     fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<SqliteClientError>>;
+
+    fn borrow_read_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<SqliteClientError>>;
 }
 
 impl<T: ConnectionMarker> BorrowConnectionExt for SqliteClient<T> {
     fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<SqliteClientError>> {
-        self.0.lock().map_err(|err| {
+        self.get_conn().lock().map_err(|err| {
+            Report::new(SqliteClientError::LockError(err.to_string()))
+                .attach(StatusCode::INTERNAL_SERVER_ERROR)
+                .log_it()
+        })
+    }
+
+    fn borrow_read_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<SqliteClientError>> {
+        self.get_read_conn().map_err(|err| {
             Report::new(SqliteClientError::LockError(err.to_string()))
                 .attach(StatusCode::INTERNAL_SERVER_ERROR)
                 .log_it()
@@ -122,4 +222,259 @@ impl<T: ConnectionMarker> BorrowConnectionExt for Option<SqliteClient<T>> {
             })?
             .borrow_conn()
     }
+
+    fn borrow_read_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<SqliteClientError>> {
+        self.as_ref()
+            .ok_or_else(|| {
+                Report::new(SqliteClientError::OptionEmpty)
+                    .attach(StatusCode::INTERNAL_SERVER_ERROR)
+                    .log_it()
+            })?
+            .borrow_read_conn()
+    }
+}
+
+/// Runs rusqlite work on the blocking thread pool, so a query never stalls an async worker.
+///
+/// Failing to get a connection is reported under `context`, like the repositories'
+/// own `BorrowConnError`.
+pub trait RunConnectionExt {
+    fn read<F, R, C>(&self, context: C, f: F) -> impl Future<Output = Result<R, Report<C>>> + Send
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<C>> + Send + 'static,
+        R: Send + 'static,
+        C: Error + Send + Sync + 'static;
+
+    fn write<F, R, C>(&self, context: C, f: F) -> impl Future<Output = Result<R, Report<C>>> + Send
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<C>> + Send + 'static,
+        R: Send + 'static,
+        C: Error + Send + Sync + 'static;
+}
+
+async fn run_blocking<T, F, R, C>(
+    sqlite_client: Option<SqliteClient<T>>,
+    context: C,
+    f: F,
+) -> Result<R, Report<C>>
+where
+    T: ConnectionMarker + 'static,
+    F: FnOnce(&Option<SqliteClient<T>>) -> Result<Result<R, Report<C>>, Report<SqliteClientError>>
+        + Send
+        + 'static,
+    R: Send + 'static,
+    C: Error + Send + Sync + 'static,
+{
+    match tokio::task::spawn_blocking(move || f(&sqlite_client)).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(err)) => Err(err.change_context(context)),
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(Report::new(err)
+            .change_context(context)
+            .attach(StatusCode::INTERNAL_SERVER_ERROR)),
+    }
+}
+
+impl<T: ConnectionMarker + 'static> RunConnectionExt for Option<SqliteClient<T>> {
+    fn read<F, R, C>(&self, context: C, f: F) -> impl Future<Output = Result<R, Report<C>>> + Send
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<C>> + Send + 'static,
+        R: Send + 'static,
+        C: Error + Send + Sync + 'static,
+    {
+        run_blocking(self.clone(), context, move |sqlite_client| {
+            let conn = sqlite_client.borrow_read_conn()?;
+            Ok(f(&conn))
+        })
+    }
+
+    fn write<F, R, C>(&self, context: C, f: F) -> impl Future<Output = Result<R, Report<C>>> + Send
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<C>> + Send + 'static,
+        R: Send + 'static,
+        C: Error + Send + Sync + 'static,
+    {
+        run_blocking(self.clone(), context, move |sqlite_client| {
+            let mut conn = sqlite_client.borrow_conn()?;
+            Ok(f(&mut conn))
+        })
+    }
+}
+
+impl<T: ConnectionMarker + 'static> RunConnectionExt for SqliteClient<T> {
+    fn read<F, R, C>(&self, context: C, f: F) -> impl Future<Output = Result<R, Report<C>>> + Send
+    where
+        F: FnOnce(&Connection) -> Result<R, Report<C>> + Send + 'static,
+        R: Send + 'static,
+        C: Error + Send + Sync + 'static,
+    {
+        run_blocking(Some(self.clone()), context, move |sqlite_client| {
+            let conn = sqlite_client.borrow_read_conn()?;
+            Ok(f(&conn))
+        })
+    }
+
+    fn write<F, R, C>(&self, context: C, f: F) -> impl Future<Output = Result<R, Report<C>>> + Send
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<C>> + Send + 'static,
+        R: Send + 'static,
+        C: Error + Send + Sync + 'static,
+    {
+        run_blocking(Some(self.clone()), context, move |sqlite_client| {
+            let mut conn = sqlite_client.borrow_conn()?;
+            Ok(f(&mut conn))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::bootstrap::BootstrapConfig;
+    use crate::password::Password;
+
+    struct TempDb(String);
+
+    impl TempDb {
+        fn new() -> Self {
+            let path =
+                std::env::temp_dir().join(format!("rusty-shorty-{}.db", uuid::Uuid::new_v4()));
+            Self(path.to_string_lossy().to_string())
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", self.0, suffix));
+            }
+        }
+    }
+
+    fn count_users(conn: &Connection) -> i64 {
+        conn.query_one("select count(*) from backoffice_users", [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_readers_do_not_wait_on_writer() {
+        let temp_db = TempDb::new();
+        let sqlite_client: SqliteClient = SqliteClient::new(temp_db.0.clone(), 2).unwrap();
+
+        let mut writer = sqlite_client.borrow_conn().unwrap();
+        let tx = writer.transaction().unwrap();
+        tx.execute(
+            "insert into backoffice_users (username, password, role) values ('pending', x'00', 'user')",
+            [],
+        )
+        .unwrap();
+
+        let reader = sqlite_client.borrow_read_conn().unwrap();
+        assert_eq!(count_users(&reader), 0);
+        let other_reader = sqlite_client.borrow_read_conn().unwrap();
+        assert_eq!(count_users(&other_reader), 0);
+        drop((reader, other_reader));
+
+        tx.commit().unwrap();
+        drop(writer);
+        assert_eq!(count_users(&sqlite_client.borrow_read_conn().unwrap()), 1);
+    }
+
+    #[test]
+    fn test_readers_are_read_only() {
+        let temp_db = TempDb::new();
+        let sqlite_client: SqliteClient = SqliteClient::new(temp_db.0.clone(), 1).unwrap();
+
+        let reader = sqlite_client.borrow_read_conn().unwrap();
+        assert!(reader.execute("delete from backoffice_users", []).is_err());
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("Test repository error")]
+    struct TestError;
+
+    #[tokio::test]
+    async fn test_write_then_read_on_blocking_pool() {
+        let temp_db = TempDb::new();
+        let sqlite_client: SqliteClient = SqliteClient::new(temp_db.0.clone(), 1).unwrap();
+
+        sqlite_client
+            .write(TestError, |conn| {
+                conn.execute(
+                    "insert into backoffice_users (username, password, role) values ('async', x'00', 'user')",
+                    [],
+                )
+                .change_context(TestError)
+            })
+            .await
+            .unwrap();
+
+        let total = sqlite_client
+            .read(TestError, |conn| Ok(count_users(conn)))
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_client_reports_context() {
+        let sqlite_client: Option<SqliteClient> = None;
+
+        let error = sqlite_client.read(TestError, |_| Ok(())).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<StatusCode>(),
+            Some(&StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backup_copies_database() {
+        let temp_db = TempDb::new();
+        let backup_db = TempDb::new();
+        let sqlite_client: SqliteClient = SqliteClient::new(temp_db.0.clone(), 1).unwrap();
+        sqlite_client
+            .borrow_conn()
+            .unwrap()
+            .execute(
+                "insert into backoffice_users (username, password, role) values ('kept', x'00', 'user')",
+                [],
+            )
+            .unwrap();
+
+        sqlite_client.backup(backup_db.0.clone()).await.unwrap();
+        let backup = Connection::open(&backup_db.0).unwrap();
+        assert_eq!(count_users(&backup), 1);
+        assert!(sqlite_client.backup(backup_db.0.clone()).await.is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_root_user_only_on_empty_database() {
+        let temp_db = TempDb::new();
+        let sqlite_client: SqliteClient = SqliteClient::new(temp_db.0.clone(), 1).unwrap();
+        let config = BootstrapConfig {
+            root_username: "owner".to_string(),
+            root_password: Some("correct horse".to_string()),
+        };
+
+        sqlite_client.bootstrap_root_user(&config).unwrap();
+        sqlite_client.bootstrap_root_user(&config).unwrap();
+
+        let conn = sqlite_client.borrow_conn().unwrap();
+        assert_eq!(count_users(&conn), 1);
+        let (must_change_password, password): (bool, Box<[u8]>) = conn
+            .query_one(
+                "select must_change_password, password from backoffice_users where username = 'owner'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(must_change_password);
+        assert!(
+            Password::verify_password(password, "correct horse".to_string())
+                .unwrap()
+                .is_valid()
+        );
+    }
 }
@@ -4,10 +4,14 @@ use thiserror::Error;
 pub enum MainError {
     #[error("Config error")]
     ConfigError,
+    #[error("Database error")]
+    DatabaseError,
     #[error("IO error")]
     IoError,
     #[error("Locale error")]
     LocaleError,
     #[error("Thread error")]
     ThreadError,
+    #[error("Command failed")]
+    CommandError,
 }
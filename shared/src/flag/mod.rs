@@ -2,19 +2,14 @@ pub mod path_edit;
 
 use poem::{Endpoint, FromRequest, IntoEndpoint, Request, RequestBody};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Flag {
+    #[default]
     Add,
     Edit,
     Delete,
 }
 
-impl Default for Flag {
-    fn default() -> Self {
-        Self::Add
-    }
-}
-
 impl Flag {
     pub fn is_add(&self) -> bool {
         *self == Self::Add
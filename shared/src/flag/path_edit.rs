@@ -1,8 +1,8 @@
+use crate::flag::Flag;
 use poem::web::Path;
 use poem::{FromRequest, Request, RequestBody};
 use serde::de::DeserializeOwned;
 use std::ops::{Deref, DerefMut};
-use crate::flag::Flag;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct PathEdit<T: Default + DeserializeOwned>(pub T);
@@ -23,7 +23,10 @@ impl<T: Default + DeserializeOwned> DerefMut for PathEdit<T> {
 
 impl<'a, T: Default + DeserializeOwned> FromRequest<'a> for PathEdit<T> {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
-        let edit = req.data::<Flag>().map(|flag| flag.is_edit()).unwrap_or(false);
+        let edit = req
+            .data::<Flag>()
+            .map(|flag| flag.is_edit())
+            .unwrap_or(false);
         if edit {
             let path = Path::<T>::from_request_without_body(req).await?;
             return Ok(Self(path.0));
@@ -80,9 +80,10 @@ pub trait HtmxResponseExt {
 
 impl<T: IntoResponse> HtmxResponseExt for T {
     fn htmx_response(self) -> HtmxResponse {
-        let mut htmx_response = HtmxResponse::default();
-        htmx_response.response = self.into_response();
-        htmx_response
+        HtmxResponse {
+            response: self.into_response(),
+            ..Default::default()
+        }
     }
 }
 
@@ -13,3 +13,6 @@ pub mod locale;
 pub mod log;
 pub mod password;
 pub mod query_string;
+pub mod redirect;
+pub mod shutdown;
+pub mod totp;
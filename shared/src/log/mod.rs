@@ -14,7 +14,7 @@ pub async fn log_poem_error(err: &poem::Error) {
     if let Some(log_data) = err.data::<LogData>() {
         error!("{} - {}", err.status(), &log_data.summary);
         if let Ok(error_stack_log_service) = fetch_context::<ErrorStackLogService>().await {
-            _ = error_stack_log_service.log_data(log_data);
+            _ = error_stack_log_service.log_data(log_data).await;
         }
     }
 }
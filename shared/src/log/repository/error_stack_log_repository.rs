@@ -1,8 +1,7 @@
 use crate::context::{Context, ContextError, FromContext};
-use crate::db::{BorrowConnectionExt, SqliteClient};
+use crate::db::{RunConnectionExt, SqliteClient};
 use error_stack::{Report, ResultExt};
 use rusqlite::{Connection, named_params};
-use std::sync::MutexGuard;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -26,36 +25,44 @@ impl ErrorStackLogRepository {
         }
     }
 
-    fn borrow_conn(
-        &'_ self,
-    ) -> Result<MutexGuard<'_, Connection>, Report<ErrorStackLogRepositoryError>> {
+    async fn write<F, R>(&self, f: F) -> Result<R, Report<ErrorStackLogRepositoryError>>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, Report<ErrorStackLogRepositoryError>>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
         self.sqlite_client
-            .borrow_conn()
-            .change_context(ErrorStackLogRepositoryError::BorrowConnError)
+            .write(ErrorStackLogRepositoryError::BorrowConnError, f)
+            .await
     }
 }
 
 #[mry::mry]
 impl ErrorStackLogRepository {
-    pub fn add_to_log(
+    pub async fn add_to_log(
         &self,
         error_name: &str,
         error_summary: &str,
         error_stack: &str,
     ) -> Result<(), Report<ErrorStackLogRepositoryError>> {
-        let conn = self.borrow_conn()?;
+        let error_name = error_name.to_owned();
+        let error_summary = error_summary.to_owned();
+        let error_stack = error_stack.to_owned();
+        self.write(move |conn| {
+            conn.execute(
+                include_str!("_sql/error_stack_log_repository/add_to_log.sql"),
+                named_params! {
+                    ":error_name": error_name,
+                    ":error_summary": error_summary,
+                    ":error_stack": error_stack,
+                },
+            )
+            .change_context(ErrorStackLogRepositoryError::QueryError)?;
 
-        conn.execute(
-            include_str!("_sql/error_stack_log_repository/add_to_log.sql"),
-            named_params! {
-                ":error_name": error_name,
-                ":error_summary": error_summary,
-                ":error_stack": error_stack,
-            },
-        )
-        .change_context(ErrorStackLogRepositoryError::QueryError)?;
-
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 }
 
@@ -19,9 +19,13 @@ impl ErrorStackLogService {
         }
     }
 
-    pub fn log_data(&self, log_data: &LogData) -> Result<(), Report<ErrorStackLogServiceError>> {
+    pub async fn log_data(
+        &self,
+        log_data: &LogData,
+    ) -> Result<(), Report<ErrorStackLogServiceError>> {
         self.error_stack_log_repository
             .add_to_log(&log_data.name, &log_data.summary, &log_data.details)
+            .await
             .change_context(ErrorStackLogServiceError)
     }
 }
@@ -37,8 +41,8 @@ mod tests {
     use super::*;
     use crate::log::repository::error_stack_log_repository::ErrorStackLogRepositoryError;
 
-    #[test]
-    fn test_error_stack_log_service_success() {
+    #[tokio::test]
+    async fn test_error_stack_log_service_success() {
         let mut error_stack_log_repository = ErrorStackLogRepository::new_mock();
         let log_data = LogData {
             name: "abc".to_string(),
@@ -54,12 +58,12 @@ mod tests {
             .returns_once(Ok(()));
 
         let service = ErrorStackLogService::new(error_stack_log_repository);
-        let result = service.log_data(&log_data);
+        let result = service.log_data(&log_data).await;
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_error_stack_log_service_error() {
+    #[tokio::test]
+    async fn test_error_stack_log_service_error() {
         let mut error_stack_log_repository = ErrorStackLogRepository::new_mock();
         let log_data = LogData {
             name: "abc".to_string(),
@@ -75,7 +79,7 @@ mod tests {
             .returns_once(Err(Report::new(ErrorStackLogRepositoryError::QueryError)));
 
         let service = ErrorStackLogService::new(error_stack_log_repository);
-        let result = service.log_data(&log_data);
+        let result = service.log_data(&log_data).await;
         assert!(result.is_err());
     }
 }
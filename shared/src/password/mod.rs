@@ -29,10 +29,7 @@ impl PasswordState {
     }
 
     pub fn is_valid_rehashed(&self) -> bool {
-        match self {
-            PasswordState::ValidRehashed(_) => true,
-            _ => false,
-        }
+        matches!(self, PasswordState::ValidRehashed(_))
     }
 }
 
@@ -81,12 +78,7 @@ impl Password {
 
     pub fn encode_to_msg_pack(&self) -> Result<Box<[u8]>, Report<PasswordError>> {
         Ok(rmp_serde::to_vec_named(self)
-            .map_err(|e| {
-                PasswordError(format!(
-                    "Failed to serialize password hash: {}",
-                    e.to_string()
-                ))
-            })?
+            .map_err(|e| PasswordError(format!("Failed to serialize password hash: {}", e)))?
             .into())
     }
 }
@@ -56,10 +56,7 @@ impl<T> DerefMut for FormQs<T> {
 
 impl<'a, T: DeserializeOwned> FromRequest<'a> for FormQs<T> {
     async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
-        let config = req
-            .data::<serde_qs::Config>()
-            .map(|v| v.clone())
-            .unwrap_or_default();
+        let config = req.data::<serde_qs::Config>().copied().unwrap_or_default();
 
         if req.method() == Method::GET {
             Ok(config
@@ -7,7 +7,7 @@ impl<E: Endpoint> Endpoint for SerdeQsConfig<E> {
     type Output = E::Output;
 
     async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
-        req.set_data(self.0.clone());
+        req.set_data(self.0);
 
         self.1.call(req).await
     }
@@ -0,0 +1,73 @@
+use crate::context::{Context, ContextError, FromContext};
+use error_stack::Report;
+use std::sync::LazyLock;
+use tokio::sync::broadcast::{Receiver, Sender, channel};
+
+const INVALIDATION_BUFFER_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedirectInvalidation {
+    Id(i64),
+    Path(String),
+}
+
+static INVALIDATION_SENDER: LazyLock<Sender<RedirectInvalidation>> =
+    LazyLock::new(|| channel(INVALIDATION_BUFFER_SIZE).0);
+
+/// Tells the public redirect cache that a link was added, edited or deleted.
+#[derive(Clone)]
+pub struct RedirectInvalidator {
+    sender: Sender<RedirectInvalidation>,
+}
+
+impl RedirectInvalidator {
+    pub fn new(sender: Sender<RedirectInvalidation>) -> Self {
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> Receiver<RedirectInvalidation> {
+        self.sender.subscribe()
+    }
+
+    pub fn invalidate_id(&self, id: i64) {
+        // Nobody listening just means nothing is cached yet.
+        let _ = self.sender.send(RedirectInvalidation::Id(id));
+    }
+
+    pub fn invalidate_path(&self, path: &str) {
+        let _ = self
+            .sender
+            .send(RedirectInvalidation::Path(path.to_string()));
+    }
+}
+
+impl Default for RedirectInvalidator {
+    fn default() -> Self {
+        Self::new(INVALIDATION_SENDER.clone())
+    }
+}
+
+impl FromContext for RedirectInvalidator {
+    async fn from_context(_ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalidations_reach_subscribers() {
+        let redirect_invalidator = RedirectInvalidator::new(channel(4).0);
+        let mut receiver = redirect_invalidator.subscribe();
+        redirect_invalidator.invalidate_id(3);
+        redirect_invalidator.invalidate_path("hello");
+
+        assert_eq!(receiver.try_recv().unwrap(), RedirectInvalidation::Id(3));
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RedirectInvalidation::Path("hello".to_string())
+        );
+    }
+}
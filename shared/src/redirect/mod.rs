@@ -0,0 +1,142 @@
+use poem::http::{StatusCode, header};
+use poem::web::Redirect;
+use poem::{IntoResponse, Response};
+use rusqlite::ToSql;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+pub mod invalidation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub enum RedirectType {
+    MovedPermanently,
+    Found,
+    #[default]
+    SeeOther,
+    TemporaryRedirect,
+    PermanentRedirect,
+}
+
+impl RedirectType {
+    pub const ALL: [RedirectType; 5] = [
+        RedirectType::MovedPermanently,
+        RedirectType::Found,
+        RedirectType::SeeOther,
+        RedirectType::TemporaryRedirect,
+        RedirectType::PermanentRedirect,
+    ];
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.code() == code)
+    }
+
+    pub fn code(&self) -> u16 {
+        self.status().as_u16()
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::MovedPermanently => StatusCode::MOVED_PERMANENTLY,
+            Self::Found => StatusCode::FOUND,
+            Self::SeeOther => StatusCode::SEE_OTHER,
+            Self::TemporaryRedirect => StatusCode::TEMPORARY_REDIRECT,
+            Self::PermanentRedirect => StatusCode::PERMANENT_REDIRECT,
+        }
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        matches!(self, Self::MovedPermanently | Self::PermanentRedirect)
+    }
+
+    pub fn redirect(&self, url: impl AsRef<str>) -> Response {
+        let url = url.as_ref();
+        let response = match self {
+            Self::MovedPermanently => Redirect::moved_permanent(url).into_response(),
+            Self::Found => Response::builder()
+                .status(StatusCode::FOUND)
+                .header(header::LOCATION, url)
+                .finish(),
+            Self::SeeOther => Redirect::see_other(url).into_response(),
+            Self::TemporaryRedirect => Redirect::temporary(url).into_response(),
+            Self::PermanentRedirect => Redirect::permanent(url).into_response(),
+        };
+        if self.is_permanent() {
+            response
+        } else {
+            response
+                .with_header(header::CACHE_CONTROL, "no-store")
+                .into_response()
+        }
+    }
+}
+
+impl TryFrom<u16> for RedirectType {
+    type Error = String;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        Self::from_code(code).ok_or_else(|| format!("Unsupported redirect code: {code}"))
+    }
+}
+
+impl From<RedirectType> for u16 {
+    fn from(redirect_type: RedirectType) -> Self {
+        redirect_type.code()
+    }
+}
+
+impl ToSql for RedirectType {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.code() as i64))
+    }
+}
+
+impl FromSql for RedirectType {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let code = value.as_i64()?;
+        u16::try_from(code)
+            .ok()
+            .and_then(Self::from_code)
+            .ok_or(FromSqlError::OutOfRange(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(
+            RedirectType::from_code(301),
+            Some(RedirectType::MovedPermanently)
+        );
+        assert_eq!(RedirectType::from_code(302), Some(RedirectType::Found));
+        assert_eq!(RedirectType::from_code(200), None);
+    }
+
+    #[test]
+    fn test_redirect_permanent_is_cacheable() {
+        let response = RedirectType::PermanentRedirect.redirect("https://example.com");
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers().get(header::LOCATION).unwrap(),
+            "https://example.com"
+        );
+        assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+    }
+
+    #[test]
+    fn test_redirect_found_is_not_cacheable() {
+        let response = RedirectType::Found.redirect("https://example.com");
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(
+            response.headers().get(header::LOCATION).unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "no-store"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use log::{error, info};
+use std::sync::{LazyLock, Once};
+use tokio::sync::watch;
+
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+static SIGNAL_LISTENER: Once = Once::new();
+
+/// Completes once the process receives SIGINT or SIGTERM, or [`trigger_shutdown`] is called.
+/// Every server waiting on it stops accepting connections together.
+pub async fn shutdown_signal() {
+    SIGNAL_LISTENER.call_once(|| {
+        tokio::spawn(async {
+            wait_for_signal().await;
+            info!("Shutdown signal received, draining in-flight requests");
+            trigger_shutdown();
+        });
+    });
+    let mut receiver = SHUTDOWN.subscribe();
+    let _ = receiver.wait_for(|shutdown| *shutdown).await;
+}
+
+pub fn trigger_shutdown() {
+    SHUTDOWN.send_replace(true);
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+        }
+        Err(err) => {
+            error!("Unable to listen for SIGTERM: {}", err);
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_trigger_shutdown_releases_waiters() {
+        let first = tokio::spawn(shutdown_signal());
+        let second = tokio::spawn(shutdown_signal());
+        tokio::task::yield_now().await;
+        assert!(!first.is_finished());
+
+        trigger_shutdown();
+
+        timeout(Duration::from_secs(1), first)
+            .await
+            .unwrap()
+            .unwrap();
+        timeout(Duration::from_secs(1), second)
+            .await
+            .unwrap()
+            .unwrap();
+        // Anyone arriving late returns straight away.
+        timeout(Duration::from_secs(1), shutdown_signal())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,267 @@
+use crate::config::totp::TotpConfig;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use chrono::{DateTime, Utc};
+use data_encoding::{BASE32_NOPAD, BASE64};
+use error_stack::{Report, ResultExt};
+use hmac::{Hmac, Mac};
+use log::info;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use rand::Rng;
+use sha1::Sha1;
+use std::env::var;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use thiserror::Error;
+
+const SECRET_LENGTH: usize = 20;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+/// Codes from the step either side of now are accepted as well, for clock drift.
+const DRIFT_STEPS: i64 = 1;
+
+#[derive(Debug, Error)]
+pub enum TotpError {
+    #[error("Key error")]
+    KeyError,
+    #[error("Encrypt error")]
+    EncryptError,
+    #[error("Decrypt error")]
+    DecryptError,
+}
+
+pub fn step_at(now: DateTime<Utc>) -> i64 {
+    now.timestamp().div_euclid(STEP_SECONDS)
+}
+
+/// An RFC 6238 secret, using HMAC-SHA1, six digits and 30 second steps as authenticator
+/// apps expect by default.
+pub struct TotpSecret(Box<[u8]>);
+
+impl TotpSecret {
+    pub fn generate() -> Self {
+        let mut secret = [0u8; SECRET_LENGTH];
+        rand::rng().fill(&mut secret);
+        Self(secret.into())
+    }
+
+    pub fn from_bytes(secret: &[u8]) -> Self {
+        Self(secret.into())
+    }
+
+    /// For typing into an app by hand.
+    pub fn as_base32(&self) -> String {
+        BASE32_NOPAD.encode(&self.0)
+    }
+
+    /// The key URI apps import, usually from a QR code.
+    pub fn otpauth_uri(&self, issuer: &str, account: &str) -> String {
+        let issuer = utf8_percent_encode(issuer, NON_ALPHANUMERIC).to_string();
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            issuer,
+            utf8_percent_encode(account, NON_ALPHANUMERIC),
+            self.as_base32(),
+            issuer,
+            DIGITS,
+            STEP_SECONDS
+        )
+    }
+
+    pub fn code_at_step(&self, step: i64) -> String {
+        let mut mac =
+            <Hmac<Sha1> as Mac>::new_from_slice(&self.0).expect("HMAC takes keys of any length");
+        mac.update(&(step as u64).to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        format!(
+            "{:0width$}",
+            binary % 10u32.pow(DIGITS),
+            width = DIGITS as usize
+        )
+    }
+
+    /// The step the code belongs to, only when it is newer than `last_used_step`, so an
+    /// observed code cannot be used a second time.
+    pub fn verify(&self, code: &str, now: DateTime<Utc>, last_used_step: i64) -> Option<i64> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        if code.len() != DIGITS as usize {
+            return None;
+        }
+        let current = step_at(now);
+        (current - DRIFT_STEPS..=current + DRIFT_STEPS)
+            .filter(|step| *step > last_used_step)
+            .find(|step| constant_time_eq(self.code_at_step(*step).as_bytes(), code.as_bytes()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Keeps the secrets encrypted at rest with AES-256-GCM, so a copy of the database alone
+/// is not enough to generate codes.
+pub struct TotpCipher(Aes256Gcm);
+
+impl TotpCipher {
+    pub fn new(key: &[u8]) -> Result<Self, Report<TotpError>> {
+        Aes256Gcm::new_from_slice(key)
+            .map(Self)
+            .map_err(|_| Report::new(TotpError::KeyError).attach("The key must be 32 bytes"))
+    }
+
+    /// `RUSTY_SHORTY_TOTP_KEY` first, then the key file, which is generated when missing.
+    pub fn load(config: &TotpConfig) -> Result<Self, Report<TotpError>> {
+        let encoded = match var("RUSTY_SHORTY_TOTP_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+        {
+            Some(key) => key,
+            None => read_or_create_key_file(&config.key_path)?,
+        };
+        let key = BASE64
+            .decode(encoded.trim().as_bytes())
+            .change_context(TotpError::KeyError)
+            .attach("The key must be base64")?;
+        Self::new(&key)
+    }
+
+    /// The nonce is kept in front of the ciphertext.
+    pub fn encrypt(&self, secret: &TotpSecret) -> Result<Box<[u8]>, Report<TotpError>> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::rng().fill(&mut nonce);
+        let ciphertext = self
+            .0
+            .encrypt(Nonce::from_slice(&nonce), secret.0.as_ref())
+            .map_err(|_| Report::new(TotpError::EncryptError))?;
+        Ok([nonce.as_slice(), &ciphertext].concat().into())
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<TotpSecret, Report<TotpError>> {
+        if data.len() < NONCE_LENGTH {
+            return Err(Report::new(TotpError::DecryptError).attach("Secret is too short"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        let secret = self
+            .0
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                Report::new(TotpError::DecryptError).attach("Wrong key or corrupted secret")
+            })?;
+        Ok(TotpSecret(secret.into()))
+    }
+}
+
+fn read_or_create_key_file(path: &str) -> Result<String, Report<TotpError>> {
+    match fs::read_to_string(path) {
+        Ok(key) => return Ok(key),
+        Err(err) if err.kind() != ErrorKind::NotFound => {
+            return Err(Report::new(err)
+                .change_context(TotpError::KeyError)
+                .attach(format!("Key path: {}", path)));
+        }
+        Err(_) => {}
+    }
+
+    let mut key = [0u8; KEY_LENGTH];
+    rand::rng().fill(&mut key);
+    let encoded = BASE64.encode(&key);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    match options.open(path) {
+        Ok(mut file) => {
+            file.write_all(encoded.as_bytes())
+                .change_context(TotpError::KeyError)
+                .attach_with(|| format!("Key path: {}", path))?;
+            info!(
+                "Generated a new TOTP key at {}, back it up with the database",
+                path
+            );
+            Ok(encoded)
+        }
+        // Another process got there first.
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => fs::read_to_string(path)
+            .change_context(TotpError::KeyError)
+            .attach_with(|| format!("Key path: {}", path)),
+        Err(err) => Err(Report::new(err)
+            .change_context(TotpError::KeyError)
+            .attach(format!("Key path: {}", path))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret::from_bytes(b"12345678901234567890")
+    }
+
+    #[test]
+    fn test_code_matches_rfc_6238_vectors() {
+        let secret = rfc_secret();
+        for (timestamp, code) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            let now = Utc.timestamp_opt(timestamp, 0).unwrap();
+            assert_eq!(secret.code_at_step(step_at(now)), code);
+        }
+    }
+
+    #[test]
+    fn test_verify_allows_drift_and_rejects_replay() {
+        let secret = rfc_secret();
+        let now = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let step = step_at(now);
+
+        assert_eq!(secret.verify("081804", now, 0), Some(step));
+        assert_eq!(secret.verify("081 804", now, 0), Some(step));
+        assert_eq!(
+            secret.verify(&secret.code_at_step(step - 1), now, 0),
+            Some(step - 1)
+        );
+        assert_eq!(secret.verify(&secret.code_at_step(step - 2), now, 0), None);
+        assert_eq!(secret.verify("081804", now, step), None);
+        assert_eq!(secret.verify("000000", now, 0), None);
+    }
+
+    #[test]
+    fn test_otpauth_uri_escapes_labels() {
+        let uri = rfc_secret().otpauth_uri("Rusty Shorty", "ann@example");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Rusty%20Shorty:ann%40example?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Rusty%20Shorty&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_cipher_round_trip() {
+        let cipher = TotpCipher::new(&[7u8; KEY_LENGTH]).unwrap();
+        let encrypted = cipher.encrypt(&rfc_secret()).unwrap();
+        let decrypted = cipher.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted.as_base32(), rfc_secret().as_base32());
+
+        let other = TotpCipher::new(&[8u8; KEY_LENGTH]).unwrap();
+        assert!(other.decrypt(&encrypted).is_err());
+    }
+}